@@ -1,6 +1,13 @@
 use chrono::NaiveDateTime;
-use marco_polo_rs_core::database::models::channel::Channel;
+use lazy_static::lazy_static;
+use marco_polo_rs_core::database::models::{channel::Channel, glossary_term::GlossaryTerm};
+use regex::Regex;
 use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+lazy_static! {
+    static ref SINGLE_LINE: Regex = Regex::new(r"^[^\t\r\n]*$").unwrap();
+}
 
 #[derive(Debug, Deserialize)]
 pub struct OauthQueryParams {
@@ -9,6 +16,55 @@ pub struct OauthQueryParams {
     pub scope: String,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SetSubtitlesStyle {
+    pub style_id: Option<i32>,
+}
+
+/// Terms without a translation are kept as they are, on every language
+#[derive(Debug, Validate, Serialize, Deserialize)]
+pub struct CreateGlossaryTerm {
+    #[validate(
+        length(min = 1, max = 255, message = "Term must have 1 to 255 characters"),
+        regex(path = "SINGLE_LINE", message = "Term must be a single line")
+    )]
+    pub term: String,
+    /// The language of the translation
+    pub language: Option<String>,
+    #[validate(
+        length(
+            min = 1,
+            max = 255,
+            message = "Translation must have 1 to 255 characters"
+        ),
+        regex(path = "SINGLE_LINE", message = "Translation must be a single line")
+    )]
+    pub translation: Option<String>,
+}
+
+#[derive(Serialize, Debug, PartialEq, Deserialize)]
+pub struct GlossaryTermDTO {
+    pub id: i32,
+    pub term: String,
+    pub language: Option<String>,
+    pub translation: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+impl From<GlossaryTerm> for GlossaryTermDTO {
+    fn from(value: GlossaryTerm) -> Self {
+        return Self {
+            id: value.id,
+            term: value.term,
+            language: value.language,
+            translation: value.translation,
+            created_at: value.created_at,
+            updated_at: value.updated_at,
+        };
+    }
+}
+
 #[derive(Serialize, Debug, PartialEq, Deserialize)]
 pub struct ChannelDTO {
     pub id: i32,
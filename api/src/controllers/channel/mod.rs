@@ -1,17 +1,24 @@
+use std::str::FromStr;
+
 use actix_web::{
-    get,
+    delete, get, post, put,
     web::{self, Json},
-    Responder, Scope,
+    HttpResponse, Responder, Scope,
 };
 use marco_polo_rs_core::{
     database::{
         models::{channel::Channel, user::UserRole},
-        queries::{self, filter::Filter, pagination::Pagination},
+        queries::{self, filter::Filter, glossary::CreateGlossaryTermDto, pagination::Pagination},
     },
-    internals::video_platform::youtube::{
-        client::YoutubeClient, traits::YoutubeClient as YoutubeClientTrait,
+    internals::{
+        translator::language::Language,
+        video_platform::youtube::{
+            client::YoutubeClient, traits::YoutubeClient as YoutubeClientTrait,
+        },
     },
 };
+use sqlx::PgPool;
+use validator::Validate;
 
 mod dto;
 #[cfg(test)]
@@ -19,10 +26,24 @@ mod test;
 mod youtube;
 
 use crate::{
-    controllers::channel::dto::ChannelDTO, middleware::jwt_token::TokenClaims,
-    models::error::AppError, AppPool,
+    controllers::channel::dto::{
+        ChannelDTO, CreateGlossaryTerm, GlossaryTermDTO, SetSubtitlesStyle,
+    },
+    middleware::jwt_token::TokenClaims,
+    models::error::AppError,
+    AppPool,
 };
 
+/// Channels of other users are not found, unless for admins
+async fn find_owned(pool: &PgPool, id: i32, jwt: &TokenClaims) -> Result<Channel, AppError> {
+    let channel = queries::channel::find_by_id(pool, id).await?;
+    if jwt.role != UserRole::Admin && channel.creator_id != jwt.id {
+        return Err(AppError::not_found("Channel not found".to_string()));
+    }
+
+    return Ok(channel);
+}
+
 #[get("/{id}")]
 async fn find_by_id(
     id: web::Path<i32>,
@@ -62,12 +83,107 @@ async fn find_all(
     return Ok(Json(dto));
 }
 
+/// The style the videos of the channel get when they don't pick one, `null` clears it
+#[put("/{id}/subtitles-style")]
+async fn set_subtitles_style(
+    id: web::Path<i32>,
+    pool: web::Data<AppPool>,
+    body: Json<SetSubtitlesStyle>,
+    jwt: TokenClaims,
+) -> Result<impl Responder, AppError> {
+    let pool = &pool.pool;
+
+    let channel = find_owned(pool, id.into_inner(), &jwt).await?;
+
+    // only the styles of the owner of the channel
+    if let Some(style_id) = body.style_id {
+        queries::subtitles_style::find_by_id_and_user(pool, style_id, channel.creator_id).await?;
+    }
+
+    queries::subtitles_style::set_channel_style(pool.as_ref(), channel.id, body.style_id).await?;
+
+    return Ok(HttpResponse::Ok().finish());
+}
+
+#[get("/{id}/glossary")]
+async fn find_glossary(
+    id: web::Path<i32>,
+    pool: web::Data<AppPool>,
+    jwt: TokenClaims,
+) -> Result<impl Responder, AppError> {
+    let pool = &pool.pool;
+
+    let channel = find_owned(pool, id.into_inner(), &jwt).await?;
+    let terms = queries::glossary::find_all_by_channel(pool, channel.id).await?;
+    let dto: Vec<GlossaryTermDTO> = terms.into_iter().map(|t| t.into()).collect();
+
+    return Ok(Json(dto));
+}
+
+/// Every term is boosted on the transcriptions of the channel, the translated ones are
+/// always translated that way to their language and the others are not translated
+#[post("/{id}/glossary")]
+async fn create_glossary_term(
+    id: web::Path<i32>,
+    pool: web::Data<AppPool>,
+    body: Json<CreateGlossaryTerm>,
+    jwt: TokenClaims,
+) -> Result<impl Responder, AppError> {
+    body.validate()?;
+    let pool = &pool.pool;
+
+    let language = match (&body.language, &body.translation) {
+        (None, None) => None,
+        (Some(language), Some(_)) => {
+            let language = Language::from_str(language).map_err(AppError::bad_request)?;
+            Some(language.code())
+        }
+        _ => {
+            return Err(AppError::bad_request(
+                "Language and translation must be set together".to_string(),
+            ))
+        }
+    };
+
+    let channel = find_owned(pool, id.into_inner(), &jwt).await?;
+    let dto = CreateGlossaryTermDto {
+        channel_id: channel.id,
+        term: body.term.trim(),
+        language,
+        translation: body.translation.as_deref().map(|t| t.trim()),
+    };
+
+    let term = queries::glossary::create(pool, dto).await?;
+    let dto: GlossaryTermDTO = term.into();
+
+    return Ok(HttpResponse::Created().json(dto));
+}
+
+#[delete("/{id}/glossary/{term_id}")]
+async fn delete_glossary_term(
+    path: web::Path<(i32, i32)>,
+    pool: web::Data<AppPool>,
+    jwt: TokenClaims,
+) -> Result<impl Responder, AppError> {
+    let pool = &pool.pool;
+    let (id, term_id) = path.into_inner();
+
+    let channel = find_owned(pool, id, &jwt).await?;
+    queries::glossary::delete(pool, channel.id, term_id).await?;
+
+    return Ok(HttpResponse::Ok().finish());
+}
+
 fn create_scope<YC: YoutubeClientTrait + 'static>() -> Scope {
     let youtube_scope = youtube::create_scope::<YC>();
 
     let channel_scope = web::scope("/channel")
         .service(find_by_id)
         .service(find_all)
+        .service(set_subtitles_style)
+        .service(find_glossary)
+        .service(create_glossary_term)
+        .service(delete_glossary_term)
         .service(youtube_scope);
 
     return channel_scope;
@@ -8,16 +8,20 @@ use actix_web::{
     web::{self},
 };
 use chrono::NaiveDate;
-use marco_polo_rs_core::database::models::{
-    channel::{auth::AuthType, platform::Platform, Channel},
-    user::UserRole,
+use marco_polo_rs_core::database::{
+    models::{
+        channel::{auth::AuthType, platform::Platform, Channel},
+        subtitles_style::SubtitlesPosition,
+        user::UserRole,
+    },
+    queries::{self, subtitles_style::CreateSubtitlesStyleDto},
 };
 use reqwest::StatusCode;
 use sqlx::PgPool;
 
 use crate::{
     controllers::{
-        channel::dto::ChannelDTO,
+        channel::dto::{ChannelDTO, CreateGlossaryTerm, GlossaryTermDTO, SetSubtitlesStyle},
         test::{
             create_test_app,
             mock::video_platform::youtube::{YoutubeClientMock, CSRF_TOKEN},
@@ -414,6 +418,130 @@ async fn user_can_not_resign_on_channel_if_not_owner(pool: PgPool) {
     assert_eq!(response.status().as_u16(), StatusCode::NOT_FOUND);
 }
 
+#[sqlx::test(
+    migrations = "../migrations",
+    fixtures("../../../test/fixtures/videos")
+)]
+async fn test_set_subtitles_style(pool: PgPool) {
+    let pool = Arc::new(pool);
+    let token = get_token!(pool.as_ref(), 456);
+
+    let dto = CreateSubtitlesStyleDto {
+        user_id: 456,
+        name: "Landscape",
+        font_name: "Roboto",
+        font_size: 48,
+        primary_color: "#FFFFFF",
+        outline_color: "#000000",
+        outline: 2,
+        background_color: None,
+        position: SubtitlesPosition::Bottom,
+        margin: 60,
+        max_line_width: None,
+    };
+    let style = queries::subtitles_style::create(pool.as_ref(), dto)
+        .await
+        .unwrap();
+
+    let test_app = innit_test_app(pool.clone()).await;
+
+    // channel 678 belongs to another user
+    for (channel_id, status) in [(666, StatusCode::OK), (678, StatusCode::NOT_FOUND)] {
+        let request = test::TestRequest::put()
+            .uri(&format!("/channel/{}/subtitles-style", channel_id))
+            .insert_header(ContentType::json())
+            .insert_header(("Authorization", token.clone()))
+            .set_json(SetSubtitlesStyle {
+                style_id: Some(style.id),
+            })
+            .to_request();
+
+        let response = test::call_service(&test_app, request).await;
+        assert_eq!(response.status(), status);
+    }
+
+    let channel_style = sqlx::query!("SELECT subtitles_style_id FROM channels WHERE id = 666")
+        .fetch_one(pool.as_ref())
+        .await
+        .unwrap()
+        .subtitles_style_id;
+    assert_eq!(channel_style, Some(style.id));
+}
+
+#[sqlx::test(
+    migrations = "../migrations",
+    fixtures("../../../test/fixtures/videos")
+)]
+async fn test_glossary(pool: PgPool) {
+    let pool = Arc::new(pool);
+    let token = get_token!(pool.as_ref(), 456);
+    let test_app = innit_test_app(pool.clone()).await;
+
+    let term = |term: &str, language: Option<&str>, translation: Option<&str>| CreateGlossaryTerm {
+        term: term.to_string(),
+        language: language.map(|l| l.to_string()),
+        translation: translation.map(|t| t.to_string()),
+    };
+
+    // channel 678 belongs to another user
+    let requests = [
+        (666, term("FFmpeg", None, None), StatusCode::CREATED),
+        (
+            666,
+            term("pull request", Some("pt_BR"), Some("PR")),
+            StatusCode::CREATED,
+        ),
+        (
+            666,
+            term("pull request", None, Some("PR")),
+            StatusCode::BAD_REQUEST,
+        ),
+        (
+            666,
+            term("pull request", Some("xx"), Some("PR")),
+            StatusCode::BAD_REQUEST,
+        ),
+        (
+            666,
+            term("pull\trequest", None, None),
+            StatusCode::BAD_REQUEST,
+        ),
+        (678, term("FFmpeg", None, None), StatusCode::NOT_FOUND),
+    ];
+    for (channel_id, body, status) in requests {
+        let request = test::TestRequest::post()
+            .uri(&format!("/channel/{}/glossary", channel_id))
+            .insert_header(ContentType::json())
+            .insert_header(("Authorization", token.clone()))
+            .set_json(body)
+            .to_request();
+
+        let response = test::call_service(&test_app, request).await;
+        assert_eq!(response.status(), status);
+    }
+
+    let request = test::TestRequest::get()
+        .uri("/channel/666/glossary")
+        .insert_header(("Authorization", token.clone()))
+        .to_request();
+    let terms: Vec<GlossaryTermDTO> = test::call_and_read_body_json(&test_app, request).await;
+    assert_eq!(terms.len(), 2);
+    assert_eq!(terms[1].language.as_deref(), Some("pt-br"));
+
+    let request = test::TestRequest::delete()
+        .uri(&format!("/channel/666/glossary/{}", terms[0].id))
+        .insert_header(("Authorization", token.clone()))
+        .to_request();
+    let response = test::call_service(&test_app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let terms = queries::glossary::find_all_by_channel(pool.as_ref(), 666)
+        .await
+        .unwrap();
+    assert_eq!(terms.len(), 1);
+    assert_eq!(terms[0].term, "pull request");
+}
+
 async fn innit_test_app(
     pool: Arc<PgPool>,
 ) -> impl actix_web::dev::Service<Request, Response = ServiceResponse, Error = actix_web::Error> {
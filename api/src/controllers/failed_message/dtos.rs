@@ -0,0 +1,60 @@
+use chrono::NaiveDateTime;
+use marco_polo_rs_core::database::models::{
+    failed_message::FailedMessage, video::stage::VideoStage,
+};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::utils::validation::validate_time;
+
+#[derive(Serialize, Debug, PartialEq, Deserialize)]
+pub struct FailedMessageDTO {
+    pub id: i32,
+    pub payload: serde_json::Value,
+    pub video_ids: Vec<Uuid>,
+    pub error: String,
+    pub stage: Option<VideoStage>,
+    pub attempts: i32,
+    pub replayed_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+impl From<FailedMessage> for FailedMessageDTO {
+    fn from(value: FailedMessage) -> Self {
+        // bodies that are not json are still shown, as a plain string
+        let payload = match serde_json::from_str(&value.payload) {
+            Ok(payload) => payload,
+            Err(_) => serde_json::Value::String(value.payload),
+        };
+
+        FailedMessageDTO {
+            id: value.id,
+            payload,
+            video_ids: value.video_ids,
+            error: value.error,
+            stage: value.stage,
+            attempts: value.attempts,
+            replayed_at: value.replayed_at,
+            created_at: value.created_at,
+            updated_at: value.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Default, Validate, Deserialize, Serialize, Clone)]
+pub struct Replay {
+    /// Replaces the stored payload before sending it again
+    pub payload: Option<serde_json::Value>,
+    #[validate(custom(
+        function = "validate_time",
+        message = "Invalid Time Format (HH:MM:SS)\n"
+    ))]
+    pub start_time: Option<String>,
+    #[validate(custom(
+        function = "validate_time",
+        message = "Invalid Time Format (HH:MM:SS)\n"
+    ))]
+    pub end_time: Option<String>,
+}
@@ -0,0 +1,86 @@
+use actix_web::{
+    get, post,
+    web::{self, Json},
+    HttpResponse, Responder, Scope,
+};
+use marco_polo_rs_core::database::{models::user::UserRole, queries};
+use validator::Validate;
+
+use crate::{middleware::jwt_token::TokenClaims, models::error::AppError, AppPool};
+
+use self::dtos::{FailedMessageDTO, Replay};
+
+mod dtos;
+mod service;
+#[cfg(test)]
+mod test;
+
+fn check_admin(jwt: &TokenClaims) -> Result<(), AppError> {
+    match jwt.role {
+        UserRole::Admin => Ok(()),
+        UserRole::User => Err(AppError::forbidden(
+            "Only admins can manage failed messages".to_string(),
+        )),
+    }
+}
+
+#[get("")]
+async fn find_all(pool: web::Data<AppPool>, jwt: TokenClaims) -> Result<impl Responder, AppError> {
+    check_admin(&jwt)?;
+    let pool = &pool.pool;
+
+    let messages = queries::failed_message::find_all(pool).await?;
+    let dto: Vec<FailedMessageDTO> = messages.into_iter().map(|m| m.into()).collect();
+
+    return Ok(Json(dto));
+}
+
+#[get("/{id}")]
+async fn find_by_id(
+    id: web::Path<i32>,
+    pool: web::Data<AppPool>,
+    jwt: TokenClaims,
+) -> Result<impl Responder, AppError> {
+    check_admin(&jwt)?;
+    let pool = &pool.pool;
+
+    let message = queries::failed_message::find_by_id(pool, id.into_inner()).await?;
+    let dto: FailedMessageDTO = message.into();
+
+    return Ok(Json(dto));
+}
+
+#[post("/{id}/replay")]
+async fn replay(
+    id: web::Path<i32>,
+    pool: web::Data<AppPool>,
+    jwt: TokenClaims,
+    body: Json<Replay>,
+) -> Result<impl Responder, AppError> {
+    check_admin(&jwt)?;
+    body.validate()?;
+    let pool = pool.pool.as_ref();
+
+    let message = queries::failed_message::find_by_id(pool, id.into_inner()).await?;
+    let message_id = message.id;
+    service::replay(pool, message, body.into_inner()).await?;
+
+    let message = queries::failed_message::find_by_id(pool, message_id).await?;
+    let dto: FailedMessageDTO = message.into();
+
+    return Ok(HttpResponse::Ok().json(dto));
+}
+
+fn create_scope() -> Scope {
+    let scope = web::scope("/failed-message")
+        .service(find_all)
+        .service(find_by_id)
+        .service(replay);
+
+    return scope;
+}
+
+pub fn init_routes(config: &mut web::ServiceConfig) {
+    let scope = create_scope();
+    config.service(scope);
+}
@@ -0,0 +1,52 @@
+use marco_polo_rs_core::{
+    database::{models::failed_message::FailedMessage, queries},
+    internals::cloud::models::payload::PayloadType,
+};
+use sqlx::PgPool;
+
+use crate::models::error::AppError;
+
+use super::dtos::Replay;
+
+/// Enqueues the message again, applying the edits of the admin first.
+/// The replay is claimed on the same transaction, concurrent replays conflict
+pub async fn replay(pool: &PgPool, message: FailedMessage, body: Replay) -> Result<(), AppError> {
+    let payload = match &body.payload {
+        Some(payload) => payload.to_string(),
+        None => message.payload.clone(),
+    };
+
+    let payload_type = PayloadType::from_json(&payload)
+        .map_err(|e| AppError::bad_request(format!("Invalid payload: {}", e)))?;
+
+    let video_ids = payload_type.video_ids();
+
+    let mut trx = pool.begin().await?;
+
+    let claimed =
+        queries::failed_message::set_replayed(&mut *trx, message.id, &payload_type.to_json())
+            .await?;
+    if !claimed {
+        return Err(AppError::conflict(
+            "Message was already replayed".to_string(),
+        ));
+    }
+
+    if body.start_time.is_some() || body.end_time.is_some() {
+        queries::video::update_times(
+            &mut *trx,
+            &video_ids,
+            body.start_time.as_deref(),
+            body.end_time.as_deref(),
+        )
+        .await?;
+    }
+
+    queries::video::clear_errors(&mut *trx, &video_ids).await?;
+
+    queries::outbox::enqueue(&mut *trx, &payload_type).await?;
+
+    trx.commit().await?;
+
+    return Ok(());
+}
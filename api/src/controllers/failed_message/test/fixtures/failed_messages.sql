@@ -0,0 +1,9 @@
+INSERT INTO failed_messages (id, payload, video_ids, error, stage, attempts)
+VALUES (
+    1,
+    '{"type":"BatukaDownloadVideo","payload":{"original_video_id":999,"video_ids":["806b57d2-f221-11ed-a05b-0242ac120003"]}}',
+    '{806b57d2-f221-11ed-a05b-0242ac120003}',
+    'Error downloading video',
+    'DOWNLOADING',
+    3
+  );
@@ -0,0 +1,211 @@
+use std::sync::Arc;
+
+use actix_http::Request;
+use actix_web::{
+    dev::ServiceResponse,
+    http::header::ContentType,
+    test,
+    web::{self},
+};
+use marco_polo_rs_core::database::queries;
+use reqwest::StatusCode;
+use sqlx::PgPool;
+
+use crate::{controllers::test::create_test_app, utils::test::get_token, AppPool};
+
+use super::{
+    create_scope,
+    dtos::{FailedMessageDTO, Replay},
+};
+
+async fn innit_test_app(
+    pool: Arc<PgPool>,
+) -> impl actix_web::dev::Service<Request, Response = ServiceResponse, Error = actix_web::Error> {
+    let pool = AppPool { pool };
+    let web_data = web::Data::new(pool);
+
+    let app = create_test_app();
+    let scope = create_scope();
+
+    let app = app.app_data(web_data).service(scope);
+
+    let test_app = test::init_service(app).await;
+
+    return test_app;
+}
+
+#[sqlx::test(
+    migrations = "../migrations",
+    fixtures(
+        "../../../test/fixtures/admin",
+        "../../../test/fixtures/videos",
+        "failed_messages"
+    )
+)]
+async fn test_find_all_ok(pool: PgPool) {
+    let pool = Arc::new(pool);
+    let token = get_token!(pool.as_ref(), 1000);
+
+    let test_app = innit_test_app(pool.clone()).await;
+
+    let request = test::TestRequest::get()
+        .uri("/failed-message")
+        .insert_header(ContentType::json())
+        .insert_header(("Authorization", token))
+        .to_request();
+
+    let response = test::call_service(&test_app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let messages: Vec<FailedMessageDTO> = test::read_body_json(response).await;
+    assert_eq!(messages.len(), 1);
+    assert_eq!(messages[0].attempts, 3);
+    assert_eq!(messages[0].payload["type"], "BatukaDownloadVideo");
+}
+
+#[sqlx::test(
+    migrations = "../migrations",
+    fixtures(
+        "../../../test/fixtures/user",
+        "../../../test/fixtures/videos",
+        "failed_messages"
+    )
+)]
+async fn test_find_all_forbidden_for_users(pool: PgPool) {
+    let pool = Arc::new(pool);
+    let token = get_token!(pool.as_ref(), 666);
+
+    let test_app = innit_test_app(pool.clone()).await;
+
+    let request = test::TestRequest::get()
+        .uri("/failed-message")
+        .insert_header(ContentType::json())
+        .insert_header(("Authorization", token))
+        .to_request();
+
+    let response = test::call_service(&test_app, request).await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+}
+
+#[sqlx::test(
+    migrations = "../migrations",
+    fixtures(
+        "../../../test/fixtures/admin",
+        "../../../test/fixtures/videos",
+        "failed_messages"
+    )
+)]
+async fn test_replay_ok(pool: PgPool) {
+    let pool = Arc::new(pool);
+    let token = get_token!(pool.as_ref(), 1000);
+
+    let test_app = innit_test_app(pool.clone()).await;
+
+    let body = Replay {
+        start_time: Some("00:01:00".to_string()),
+        ..Default::default()
+    };
+
+    let request = test::TestRequest::post()
+        .uri("/failed-message/1/replay")
+        .insert_header(ContentType::json())
+        .insert_header(("Authorization", token))
+        .set_json(body)
+        .to_request();
+
+    let response = test::call_service(&test_app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let message = queries::failed_message::find_by_id(pool.as_ref(), 1)
+        .await
+        .unwrap();
+    assert!(message.replayed_at.is_some());
+
+    let pending = queries::outbox::find_unsent(pool.as_ref()).await.unwrap();
+    assert_eq!(pending.len(), 1);
+
+    let video_id = message.video_ids[0];
+    let video = queries::video::find_by_id(pool.as_ref(), &video_id)
+        .await
+        .unwrap();
+    assert_eq!(video.start_time, "00:01:00");
+}
+
+#[sqlx::test(
+    migrations = "../migrations",
+    fixtures(
+        "../../../test/fixtures/admin",
+        "../../../test/fixtures/videos",
+        "failed_messages"
+    )
+)]
+async fn test_replay_twice_conflicts(pool: PgPool) {
+    let pool = Arc::new(pool);
+    let token = get_token!(pool.as_ref(), 1000);
+
+    let test_app = innit_test_app(pool.clone()).await;
+
+    let mut statuses = vec![];
+    for _ in 0..2 {
+        let request = test::TestRequest::post()
+            .uri("/failed-message/1/replay")
+            .insert_header(ContentType::json())
+            .insert_header(("Authorization", token.clone()))
+            .set_json(Replay::default())
+            .to_request();
+
+        let response = test::call_service(&test_app, request).await;
+        statuses.push(response.status());
+    }
+
+    assert_eq!(statuses, vec![StatusCode::OK, StatusCode::CONFLICT]);
+
+    let pending = queries::outbox::find_unsent(pool.as_ref()).await.unwrap();
+    assert_eq!(pending.len(), 1);
+}
+
+#[sqlx::test(
+    migrations = "../migrations",
+    fixtures(
+        "../../../test/fixtures/admin",
+        "../../../test/fixtures/videos",
+        "failed_messages"
+    )
+)]
+async fn test_replay_invalid_payload(pool: PgPool) {
+    let pool = Arc::new(pool);
+    let token = get_token!(pool.as_ref(), 1000);
+
+    let test_app = innit_test_app(pool.clone()).await;
+
+    let payloads = [
+        serde_json::json!({"type": "Unknown", "payload": {}}),
+        // an edited key that no longer starts with the video id
+        serde_json::json!({
+            "type": "BatukaVideoRawUpload",
+            "payload": {"s3VideoURI": "videos/raw/video.mkv"}
+        }),
+    ];
+
+    for payload in payloads {
+        let body = Replay {
+            payload: Some(payload),
+            ..Default::default()
+        };
+
+        let request = test::TestRequest::post()
+            .uri("/failed-message/1/replay")
+            .insert_header(ContentType::json())
+            .insert_header(("Authorization", token.clone()))
+            .set_json(body)
+            .to_request();
+
+        let response = test::call_service(&test_app, request).await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    let message = queries::failed_message::find_by_id(pool.as_ref(), 1)
+        .await
+        .unwrap();
+    assert!(message.replayed_at.is_none());
+}
@@ -1,19 +1,25 @@
 use actix_web::web;
 
-mod assembly_ai;
 mod channel;
+mod failed_message;
 mod storage;
+mod subtitles_style;
+mod transcription;
 mod user;
 mod video;
+mod webhook;
 
 #[cfg(test)]
 mod test;
 
 pub fn init_routes(config: &mut web::ServiceConfig) {
-    println!("Initializing routes...");
+    tracing::info!("Initializing routes...");
     config.configure(storage::init_routes);
-    config.configure(assembly_ai::init_routes);
+    config.configure(transcription::init_routes);
     config.configure(user::init_routes);
     config.configure(video::init_routes);
     config.configure(channel::init_routes);
+    config.configure(failed_message::init_routes);
+    config.configure(webhook::init_routes);
+    config.configure(subtitles_style::init_routes);
 }
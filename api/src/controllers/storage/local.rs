@@ -0,0 +1,87 @@
+use actix_web::{
+    web::{self, Data, Payload, Query},
+    HttpResponse, Responder, Scope,
+};
+use futures::StreamExt;
+use marco_polo_rs_core::{
+    database::queries,
+    internals::cloud::{local::LocalCloudService, traits::CloudService},
+};
+use serde::Deserialize;
+use tokio::io::AsyncWriteExt;
+use tokio_util::io::ReaderStream;
+
+use crate::{models::error::AppError, AppCloudService, AppPool};
+
+#[derive(Debug, Deserialize)]
+pub struct SignedQuery {
+    pub expires: i64,
+    pub signature: String,
+}
+
+/// Serves the objects of the local bucket through the urls it signs
+async fn download(
+    key: web::Path<String>,
+    query: Query<SignedQuery>,
+    cloud_service: Data<AppCloudService<LocalCloudService>>,
+) -> Result<impl Responder, AppError> {
+    let bucket_client = cloud_service.client.bucket_client();
+    let key = key.into_inner();
+
+    if !bucket_client.verify("GET", &key, query.expires, &query.signature) {
+        return Err(AppError::unauthorized("Invalid signature".to_string()));
+    }
+
+    let path = bucket_client.object_path(&key)?;
+    let file = match tokio::fs::File::open(path).await {
+        Ok(file) => file,
+        Err(_) => return Err(AppError::not_found("File not found".to_string())),
+    };
+
+    return Ok(HttpResponse::Ok().streaming(ReaderStream::new(file)));
+}
+
+async fn upload(
+    key: web::Path<String>,
+    query: Query<SignedQuery>,
+    mut payload: Payload,
+    pool: Data<AppPool>,
+    cloud_service: Data<AppCloudService<LocalCloudService>>,
+) -> Result<impl Responder, AppError> {
+    let bucket_client = cloud_service.client.bucket_client();
+    let key = key.into_inner();
+
+    if !bucket_client.verify("PUT", &key, query.expires, &query.signature) {
+        return Err(AppError::unauthorized("Invalid signature".to_string()));
+    }
+
+    let path = bucket_client.object_path(&key)?;
+    if let Some(parent) = path.parent() {
+        tokio::fs::create_dir_all(parent)
+            .await
+            .map_err(|_| AppError::internal_server_error())?;
+    }
+
+    let mut file = tokio::fs::File::create(path)
+        .await
+        .map_err(|_| AppError::internal_server_error())?;
+
+    while let Some(chunk) = payload.next().await {
+        let chunk = chunk.map_err(|e| AppError::bad_request(e.to_string()))?;
+        file.write_all(&chunk)
+            .await
+            .map_err(|_| AppError::internal_server_error())?;
+    }
+
+    queries::outbox::enqueue_upload_event(pool.pool.as_ref(), bucket_client, &key).await?;
+
+    return Ok(HttpResponse::Ok().finish());
+}
+
+pub fn create_scope() -> Scope {
+    let scope = web::scope("/storage/local")
+        .route("/{key:.*}", web::get().to(download))
+        .route("/{key:.*}", web::put().to(upload));
+
+    return scope;
+}
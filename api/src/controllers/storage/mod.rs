@@ -3,8 +3,8 @@ use actix_web::{
     Responder, Scope,
 };
 use marco_polo_rs_core::internals::cloud::{
-    aws::AwsCloudService,
     traits::{BucketClient, CloudService},
+    DefaultCloudService,
 };
 
 use crate::{
@@ -12,6 +12,8 @@ use crate::{
     AppCloudService,
 };
 
+#[cfg(any(feature = "local", test))]
+mod local;
 #[cfg(test)]
 mod test;
 
@@ -36,7 +38,11 @@ fn create_scope<CS: CloudService + 'static>() -> Scope {
 }
 
 pub fn init_routes(config: &mut web::ServiceConfig) {
-    let scope = create_scope::<AwsCloudService>();
+    // the local bucket signs urls that point back to the api
+    #[cfg(feature = "local")]
+    config.service(local::create_scope());
+
+    let scope = create_scope::<DefaultCloudService>();
 
     config.service(scope);
 }
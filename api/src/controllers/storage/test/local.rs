@@ -0,0 +1,106 @@
+use std::sync::Arc;
+
+use actix_web::{test, web};
+use marco_polo_rs_core::{
+    database::queries,
+    internals::cloud::{
+        local::{bucket::LocalBucketClient, queue::LocalQueueClient, LocalCloudService},
+        traits::BucketClient,
+    },
+};
+use sqlx::PgPool;
+
+use crate::{
+    controllers::{storage::local::create_scope, test::create_test_app},
+    AppCloudService, AppPool,
+};
+
+fn local_cloud_service(pool: PgPool) -> LocalCloudService {
+    let root = std::env::temp_dir().join(uuid::Uuid::new_v4().to_string());
+    let bucket_client = LocalBucketClient::new(
+        root,
+        "http://localhost:8080/storage/local".to_string(),
+        "secret".to_string(),
+    );
+
+    LocalCloudService {
+        bucket_client,
+        queue_client: LocalQueueClient::new(pool),
+    }
+}
+
+fn path_of(url: &str) -> String {
+    url.trim_start_matches("http://localhost:8080").to_string()
+}
+
+#[sqlx::test(migrations = "../migrations")]
+async fn test_upload_and_download_signed_url(pool: PgPool) {
+    let cloud_service = local_cloud_service(pool.clone());
+    let bucket_client = cloud_service.bucket_client.clone();
+
+    let app_data = web::Data::new(AppCloudService {
+        client: Arc::new(cloud_service),
+    });
+    let app = create_test_app()
+        .app_data(web::Data::new(AppPool {
+            pool: Arc::new(pool.clone()),
+        }))
+        .app_data(app_data)
+        .service(create_scope());
+    let app = test::init_service(app).await;
+
+    // the upload event is only written for the keys of a video
+    let key = format!("videos/raw/{}.mkv", uuid::Uuid::new_v4());
+    let upload_url = bucket_client
+        .create_signed_upload_url_with_uri(&key, 60)
+        .await
+        .unwrap();
+
+    let request = test::TestRequest::put()
+        .uri(&path_of(&upload_url))
+        .set_payload("video")
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert!(response.status().is_success());
+
+    let messages = queries::outbox::find_unsent(&pool).await.unwrap();
+    assert_eq!(messages.len(), 1);
+
+    let download_url = bucket_client
+        .create_signed_download_url(&key, Some(60))
+        .await
+        .unwrap();
+
+    let request = test::TestRequest::get()
+        .uri(&path_of(&download_url))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert!(response.status().is_success());
+
+    let body = test::read_body(response).await;
+    assert_eq!(body, "video");
+}
+
+#[sqlx::test(migrations = "../migrations")]
+async fn test_download_with_upload_signature(pool: PgPool) {
+    let cloud_service = local_cloud_service(pool);
+    let bucket_client = cloud_service.bucket_client.clone();
+
+    let app_data = web::Data::new(AppCloudService {
+        client: Arc::new(cloud_service),
+    });
+    let app = create_test_app().app_data(app_data).service(create_scope());
+    let app = test::init_service(app).await;
+
+    let upload_url = bucket_client
+        .create_signed_upload_url_with_uri("videos/raw/test.mkv", 60)
+        .await
+        .unwrap();
+
+    let request = test::TestRequest::get()
+        .uri(&path_of(&upload_url))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+
+    assert_eq!(response.status().as_u16(), 401);
+}
@@ -5,6 +5,8 @@ use crate::controllers::test::{create_test_app, mock::cloud_service::CloudServic
 use super::*;
 use actix_web::{http::header::ContentType, test, web};
 
+mod local;
+
 #[actix_web::test]
 async fn test_create_signed_upload_url() {
     let test_cloud_service = CloudServiceMock::new();
@@ -0,0 +1,104 @@
+use chrono::NaiveDateTime;
+use lazy_static::lazy_static;
+use marco_polo_rs_core::database::{
+    models::subtitles_style::{SubtitlesPosition, SubtitlesStyle},
+    queries::subtitles_style::CreateSubtitlesStyleDto,
+};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+lazy_static! {
+    static ref COLOR: Regex = Regex::new(r"^#([0-9A-Fa-f]{6}|[0-9A-Fa-f]{8})$").unwrap();
+    /// Commas and line breaks would split the style line of the ass file
+    static ref FONT_NAME: Regex = Regex::new(r"^[^,\r\n]+$").unwrap();
+}
+
+/// Sizes are in pixels of the video, colors are `#RRGGBB` or `#RRGGBBAA`
+#[derive(Debug, Clone, Validate, Deserialize, Serialize)]
+pub struct SaveSubtitlesStyle {
+    #[validate(length(min = 1, max = 255, message = "Name must have 1 to 255 characters"))]
+    pub name: String,
+    #[validate(
+        length(min = 1, max = 255, message = "Font must have 1 to 255 characters"),
+        regex(path = "FONT_NAME", message = "Font can't have commas or line breaks")
+    )]
+    pub font_name: String,
+    #[validate(range(min = 8, max = 400, message = "Font size must be between 8 and 400"))]
+    pub font_size: i32,
+    #[validate(regex(path = "COLOR", message = "Invalid color, use #RRGGBB or #RRGGBBAA"))]
+    pub primary_color: String,
+    #[validate(regex(path = "COLOR", message = "Invalid color, use #RRGGBB or #RRGGBBAA"))]
+    pub outline_color: String,
+    #[validate(range(min = 0, max = 50, message = "Outline must be between 0 and 50"))]
+    pub outline: i32,
+    /// Draws a box of this color behind the text, instead of the outline
+    #[validate(regex(path = "COLOR", message = "Invalid color, use #RRGGBB or #RRGGBBAA"))]
+    pub background_color: Option<String>,
+    #[serde(default)]
+    pub position: SubtitlesPosition,
+    #[validate(range(min = 0, max = 2000, message = "Margin must be between 0 and 2000"))]
+    pub margin: i32,
+    /// Characters per line, the lines of the subtitles are broken again when set
+    #[validate(range(
+        min = 8,
+        max = 200,
+        message = "Max line width must be between 8 and 200"
+    ))]
+    pub max_line_width: Option<i32>,
+}
+
+impl SaveSubtitlesStyle {
+    pub fn to_dto(&self, user_id: i32) -> CreateSubtitlesStyleDto<'_> {
+        return CreateSubtitlesStyleDto {
+            user_id,
+            name: &self.name,
+            font_name: &self.font_name,
+            font_size: self.font_size,
+            primary_color: &self.primary_color,
+            outline_color: &self.outline_color,
+            outline: self.outline,
+            background_color: self.background_color.as_deref(),
+            position: self.position,
+            margin: self.margin,
+            max_line_width: self.max_line_width,
+        };
+    }
+}
+
+#[derive(Serialize, Debug, PartialEq, Deserialize)]
+pub struct SubtitlesStyleDTO {
+    pub id: i32,
+    pub name: String,
+    pub font_name: String,
+    pub font_size: i32,
+    pub primary_color: String,
+    pub outline_color: String,
+    pub outline: i32,
+    pub background_color: Option<String>,
+    pub position: SubtitlesPosition,
+    pub margin: i32,
+    pub max_line_width: Option<i32>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+impl From<SubtitlesStyle> for SubtitlesStyleDTO {
+    fn from(value: SubtitlesStyle) -> Self {
+        return Self {
+            id: value.id,
+            name: value.name,
+            font_name: value.font_name,
+            font_size: value.font_size,
+            primary_color: value.primary_color,
+            outline_color: value.outline_color,
+            outline: value.outline,
+            background_color: value.background_color,
+            position: value.position,
+            margin: value.margin,
+            max_line_width: value.max_line_width,
+            created_at: value.created_at,
+            updated_at: value.updated_at,
+        };
+    }
+}
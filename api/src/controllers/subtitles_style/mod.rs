@@ -0,0 +1,119 @@
+use actix_web::{
+    delete, get, post, put,
+    web::{self, Json},
+    HttpResponse, Responder, Scope,
+};
+use marco_polo_rs_core::database::queries;
+use validator::Validate;
+
+use crate::{middleware::jwt_token::TokenClaims, models::error::AppError, AppPool};
+
+use self::dtos::{SaveSubtitlesStyle, SubtitlesStyleDTO};
+
+mod dtos;
+#[cfg(test)]
+mod test;
+
+/// Names are unique per user, a taken one is a bad request instead of a database error
+fn name_taken(error: sqlx::Error, name: &str) -> AppError {
+    match &error {
+        sqlx::Error::Database(e) if e.is_unique_violation() => {
+            AppError::bad_request(format!("A style named {} already exists", name))
+        }
+        _ => error.into(),
+    }
+}
+
+#[post("")]
+async fn create_style(
+    pool: web::Data<AppPool>,
+    body: Json<SaveSubtitlesStyle>,
+    jwt: TokenClaims,
+) -> Result<impl Responder, AppError> {
+    body.validate()?;
+    let pool = &pool.pool;
+
+    let style = queries::subtitles_style::create(pool, body.to_dto(jwt.id))
+        .await
+        .map_err(|e| name_taken(e, &body.name))?;
+    let dto: SubtitlesStyleDTO = style.into();
+
+    return Ok(HttpResponse::Created().json(dto));
+}
+
+#[get("")]
+async fn find_all(pool: web::Data<AppPool>, jwt: TokenClaims) -> Result<impl Responder, AppError> {
+    let pool = &pool.pool;
+
+    let styles = queries::subtitles_style::find_all_by_user(pool, jwt.id).await?;
+    let dto: Vec<SubtitlesStyleDTO> = styles.into_iter().map(|s| s.into()).collect();
+
+    return Ok(Json(dto));
+}
+
+#[get("/{id}")]
+async fn find_by_id(
+    id: web::Path<i32>,
+    pool: web::Data<AppPool>,
+    jwt: TokenClaims,
+) -> Result<impl Responder, AppError> {
+    let pool = &pool.pool;
+
+    let style =
+        queries::subtitles_style::find_by_id_and_user(pool, id.into_inner(), jwt.id).await?;
+    let dto: SubtitlesStyleDTO = style.into();
+
+    return Ok(Json(dto));
+}
+
+/// Videos that were already subtitled keep the old look
+#[put("/{id}")]
+async fn update_style(
+    id: web::Path<i32>,
+    pool: web::Data<AppPool>,
+    body: Json<SaveSubtitlesStyle>,
+    jwt: TokenClaims,
+) -> Result<impl Responder, AppError> {
+    body.validate()?;
+    let pool = &pool.pool;
+
+    let style =
+        queries::subtitles_style::find_by_id_and_user(pool, id.into_inner(), jwt.id).await?;
+    let style = queries::subtitles_style::update(pool, style.id, body.to_dto(jwt.id))
+        .await
+        .map_err(|e| name_taken(e, &body.name))?;
+    let dto: SubtitlesStyleDTO = style.into();
+
+    return Ok(Json(dto));
+}
+
+#[delete("/{id}")]
+async fn delete_by_id(
+    id: web::Path<i32>,
+    pool: web::Data<AppPool>,
+    jwt: TokenClaims,
+) -> Result<impl Responder, AppError> {
+    let pool = &pool.pool;
+
+    let style =
+        queries::subtitles_style::find_by_id_and_user(pool, id.into_inner(), jwt.id).await?;
+    queries::subtitles_style::delete(pool, style.id).await?;
+
+    return Ok(HttpResponse::Ok().finish());
+}
+
+fn create_scope() -> Scope {
+    let scope = web::scope("/subtitles-style")
+        .service(create_style)
+        .service(find_all)
+        .service(find_by_id)
+        .service(update_style)
+        .service(delete_by_id);
+
+    return scope;
+}
+
+pub fn init_routes(config: &mut web::ServiceConfig) {
+    let scope = create_scope();
+    config.service(scope);
+}
@@ -0,0 +1,189 @@
+use std::sync::Arc;
+
+use actix_http::Request;
+use actix_web::{
+    dev::ServiceResponse,
+    http::header::ContentType,
+    test,
+    web::{self},
+};
+use marco_polo_rs_core::database::{models::subtitles_style::SubtitlesPosition, queries};
+use reqwest::StatusCode;
+use sqlx::PgPool;
+
+use crate::{controllers::test::create_test_app, utils::test::get_token, AppPool};
+
+use super::{
+    create_scope,
+    dtos::{SaveSubtitlesStyle, SubtitlesStyleDTO},
+};
+
+async fn innit_test_app(
+    pool: Arc<PgPool>,
+) -> impl actix_web::dev::Service<Request, Response = ServiceResponse, Error = actix_web::Error> {
+    let pool = AppPool { pool };
+    let web_data = web::Data::new(pool);
+
+    let app = create_test_app();
+    let scope = create_scope();
+
+    let app = app.app_data(web_data).service(scope);
+
+    let test_app = test::init_service(app).await;
+
+    return test_app;
+}
+
+fn style_body(name: &str) -> SaveSubtitlesStyle {
+    SaveSubtitlesStyle {
+        name: name.to_string(),
+        font_name: "Roboto".to_string(),
+        font_size: 64,
+        primary_color: "#FFFFFF".to_string(),
+        outline_color: "#000000".to_string(),
+        outline: 3,
+        background_color: Some("#00000080".to_string()),
+        position: SubtitlesPosition::Middle,
+        margin: 0,
+        max_line_width: Some(24),
+    }
+}
+
+async fn create_style(pool: &PgPool, user_id: i32, name: &str) -> i32 {
+    let body = style_body(name);
+    queries::subtitles_style::create(pool, body.to_dto(user_id))
+        .await
+        .unwrap()
+        .id
+}
+
+#[sqlx::test(
+    migrations = "../migrations",
+    fixtures("../../../test/fixtures/videos")
+)]
+async fn test_create_style_ok(pool: PgPool) {
+    let pool = Arc::new(pool);
+    let token = get_token!(pool.as_ref(), 456);
+
+    let test_app = innit_test_app(pool.clone()).await;
+
+    let request = test::TestRequest::post()
+        .uri("/subtitles-style")
+        .insert_header(ContentType::json())
+        .insert_header(("Authorization", token))
+        .set_json(style_body("Shorts"))
+        .to_request();
+
+    let response = test::call_service(&test_app, request).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let dto: SubtitlesStyleDTO = test::read_body_json(response).await;
+    assert_eq!(dto.name, "Shorts");
+    assert_eq!(dto.position, SubtitlesPosition::Middle);
+    assert_eq!(dto.background_color.as_deref(), Some("#00000080"));
+
+    let styles = queries::subtitles_style::find_all_by_user(pool.as_ref(), 456)
+        .await
+        .unwrap();
+    assert_eq!(styles.len(), 1);
+}
+
+#[sqlx::test(
+    migrations = "../migrations",
+    fixtures("../../../test/fixtures/videos")
+)]
+async fn test_create_style_bad_request(pool: PgPool) {
+    let pool = Arc::new(pool);
+    let token = get_token!(pool.as_ref(), 456);
+    create_style(pool.as_ref(), 456, "Shorts").await;
+
+    let test_app = innit_test_app(pool.clone()).await;
+
+    let mut invalid_color = style_body("Landscape");
+    invalid_color.primary_color = "white".to_string();
+
+    // would add fields to the style line of the ass file
+    let mut injected_font = style_body("Portrait");
+    injected_font.font_name = "Arial,200,&H000000FF".to_string();
+
+    let mut multiline_font = style_body("Square");
+    multiline_font.font_name = "Arial\nStyle: Evil,Arial".to_string();
+
+    let bodies = [
+        style_body("Shorts"),
+        invalid_color,
+        injected_font,
+        multiline_font,
+    ];
+    for body in bodies {
+        let request = test::TestRequest::post()
+            .uri("/subtitles-style")
+            .insert_header(ContentType::json())
+            .insert_header(("Authorization", token.clone()))
+            .set_json(body)
+            .to_request();
+
+        let response = test::call_service(&test_app, request).await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+}
+
+#[sqlx::test(
+    migrations = "../migrations",
+    fixtures("../../../test/fixtures/videos")
+)]
+async fn test_update_style_ok(pool: PgPool) {
+    let pool = Arc::new(pool);
+    let token = get_token!(pool.as_ref(), 456);
+    let id = create_style(pool.as_ref(), 456, "Shorts").await;
+
+    let test_app = innit_test_app(pool.clone()).await;
+
+    let mut body = style_body("Shorts");
+    body.background_color = None;
+    body.position = SubtitlesPosition::Bottom;
+
+    let request = test::TestRequest::put()
+        .uri(&format!("/subtitles-style/{}", id))
+        .insert_header(ContentType::json())
+        .insert_header(("Authorization", token))
+        .set_json(body)
+        .to_request();
+
+    let response = test::call_service(&test_app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let dto: SubtitlesStyleDTO = test::read_body_json(response).await;
+    assert_eq!(dto.id, id);
+    assert_eq!(dto.background_color, None);
+    assert_eq!(dto.position, SubtitlesPosition::Bottom);
+}
+
+#[sqlx::test(
+    migrations = "../migrations",
+    fixtures("../../../test/fixtures/videos")
+)]
+async fn test_styles_of_another_user_not_found(pool: PgPool) {
+    let pool = Arc::new(pool);
+    let token = get_token!(pool.as_ref(), 456);
+    let id = create_style(pool.as_ref(), 789, "Shorts").await;
+
+    let test_app = innit_test_app(pool.clone()).await;
+
+    let request = test::TestRequest::get()
+        .uri("/subtitles-style")
+        .insert_header(("Authorization", token.clone()))
+        .to_request();
+
+    let response = test::call_service(&test_app, request).await;
+    let styles: Vec<SubtitlesStyleDTO> = test::read_body_json(response).await;
+    assert!(styles.is_empty());
+
+    let request = test::TestRequest::delete()
+        .uri(&format!("/subtitles-style/{}", id))
+        .insert_header(("Authorization", token))
+        .to_request();
+
+    let response = test::call_service(&test_app, request).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
@@ -105,6 +105,7 @@ impl QueueMessage for TestMessage {
         Ok(PayloadType::BatukaSrtTranscriptionUpload(SrtPayload {
             srt_uri: String::from("test"),
             video_id: uuid::Uuid::new_v4(),
+            language: None,
         }))
     }
 }
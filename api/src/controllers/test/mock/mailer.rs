@@ -1,13 +1,10 @@
-use crate::mail::sender::{MailSender, SenderError};
+use marco_polo_rs_core::mail::sender::{MailSender, SendEmailOptions, SenderError};
 
 pub struct MailSenderMock;
 
 #[async_trait::async_trait]
 impl MailSender for MailSenderMock {
-    async fn send(
-        &self,
-        _option: crate::mail::sender::SendEmailOptions,
-    ) -> Result<(), SenderError> {
+    async fn send(&self, _option: SendEmailOptions) -> Result<(), SenderError> {
         Ok(())
     }
 }
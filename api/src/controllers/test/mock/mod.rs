@@ -1,3 +1,4 @@
 pub mod cloud_service;
 pub mod mailer;
+pub mod transcriber;
 pub mod video_platform;
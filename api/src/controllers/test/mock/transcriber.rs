@@ -0,0 +1,83 @@
+use async_trait::async_trait;
+use marco_polo_rs_core::{
+    internals::{
+        transcriber::traits::{
+            Sentence, TranscribeOptions, TranscriberClient, TranscriptionStatus,
+        },
+        ServiceProvider,
+    },
+    SyncError,
+};
+use serde::Deserialize;
+
+pub const WEBHOOK_TOKEN: &str = "transcriber_webhook_token";
+
+#[derive(Deserialize)]
+struct WebhookBody {
+    id: String,
+}
+
+pub struct TranscriberClientMock {
+    pub status: TranscriptionStatus,
+}
+
+impl TranscriberClientMock {
+    pub fn new(status: TranscriptionStatus) -> Self {
+        Self { status }
+    }
+}
+
+impl ServiceProvider for TranscriberClientMock {
+    fn id(&self) -> i32 {
+        return 3;
+    }
+}
+
+#[async_trait]
+impl TranscriberClient for TranscriberClientMock {
+    async fn transcribe(
+        &self,
+        _media_url: &str,
+        _options: &TranscribeOptions,
+    ) -> Result<String, SyncError> {
+        Ok(String::from("transcription"))
+    }
+
+    async fn transcribe_from_file(
+        &self,
+        _file_path: &str,
+        _options: &TranscribeOptions,
+    ) -> Result<String, SyncError> {
+        Ok(String::from("transcription"))
+    }
+
+    async fn get_transcription_sentences(
+        &self,
+        _transcription_id: &str,
+    ) -> Result<Vec<Sentence>, SyncError> {
+        Ok(vec![Sentence {
+            start_time: 0,
+            end_time: 1000,
+            text: String::from("Hello"),
+            words: None,
+            speaker: None,
+        }])
+    }
+
+    async fn status(&self, _transcription_id: &str) -> Result<TranscriptionStatus, SyncError> {
+        Ok(self.status.clone())
+    }
+
+    async fn pool(&self, _transcription_id: &str) -> Result<(), SyncError> {
+        Ok(())
+    }
+
+    fn authorize_webhook(&self, authorization: &str) -> bool {
+        authorization == WEBHOOK_TOKEN
+    }
+
+    fn webhook_transcription_id(&self, body: &[u8]) -> Result<String, SyncError> {
+        let body: WebhookBody = serde_json::from_slice(body)?;
+        Ok(body.id)
+    }
+}
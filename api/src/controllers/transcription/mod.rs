@@ -0,0 +1,140 @@
+use actix_web::{
+    dev::{ServiceFactory, ServiceRequest, ServiceResponse},
+    web::{self, post},
+    HttpRequest, HttpResponse, Responder, Scope,
+};
+use marco_polo_rs_core::internals::cloud::{traits::CloudService, DefaultCloudService};
+
+use crate::{models::error::AppError, AppCloudService, AppPool, AppTranscriberClients};
+
+mod service;
+#[cfg(test)]
+mod test;
+
+/// The provider the route from before `/transcriptions/{provider}/webhook` belongs to
+const LEGACY_PROVIDER: &str = "assemblyai";
+
+/// Every transcriber notifies the same route, `provider` picks the client that reads it
+async fn webhook<CS>(
+    req: HttpRequest,
+    provider: web::Path<String>,
+    body: web::Bytes,
+    pool: web::Data<AppPool>,
+    cloud_service: web::Data<AppCloudService<CS>>,
+    transcriber_clients: web::Data<AppTranscriberClients>,
+) -> Result<impl Responder, AppError>
+where
+    CS: CloudService,
+{
+    return receive(
+        &req,
+        &provider,
+        &body,
+        &pool,
+        &cloud_service,
+        &transcriber_clients,
+    )
+    .await;
+}
+
+/// `/assemblyai/transcriptions/webhook`, kept for the transcriptions requested while it was
+/// the webhook endpoint, AssemblyAI still notifies them there
+async fn legacy_webhook<CS>(
+    req: HttpRequest,
+    body: web::Bytes,
+    pool: web::Data<AppPool>,
+    cloud_service: web::Data<AppCloudService<CS>>,
+    transcriber_clients: web::Data<AppTranscriberClients>,
+) -> Result<impl Responder, AppError>
+where
+    CS: CloudService,
+{
+    return receive(
+        &req,
+        LEGACY_PROVIDER,
+        &body,
+        &pool,
+        &cloud_service,
+        &transcriber_clients,
+    )
+    .await;
+}
+
+async fn receive<CS>(
+    req: &HttpRequest,
+    provider: &str,
+    body: &[u8],
+    pool: &AppPool,
+    cloud_service: &AppCloudService<CS>,
+    transcriber_clients: &AppTranscriberClients,
+) -> Result<HttpResponse, AppError>
+where
+    CS: CloudService,
+{
+    let pool = &pool.pool;
+    let bucket_client = cloud_service.client.bucket_client();
+
+    let transcriber_client = match transcriber_clients.clients.get(provider) {
+        Some(client) => client.as_ref(),
+        None => {
+            return Err(AppError::not_found(format!(
+                "Transcription provider {} not found",
+                provider
+            )))
+        }
+    };
+
+    let authorization = match req.headers().get("Authorization") {
+        Some(header) => header.to_str().unwrap_or(""),
+        None => {
+            return Err(AppError::unauthorized(
+                "Missing Authorization header".to_string(),
+            ))
+        }
+    };
+
+    if !transcriber_client.authorize_webhook(authorization) {
+        return Err(AppError::unauthorized("Invalid API key".to_string()));
+    }
+
+    service::webhook(transcriber_client, body, pool, bucket_client).await?;
+
+    return Ok(HttpResponse::Ok().finish());
+}
+
+fn create_scope<CS: CloudService + 'static>() -> Scope<
+    impl ServiceFactory<
+        ServiceRequest,
+        Config = (),
+        Response = ServiceResponse,
+        Error = actix_web::Error,
+        InitError = (),
+    >,
+> {
+    let scope = web::scope("/transcriptions");
+    let scope = scope.route("/{provider}/webhook", post().to(webhook::<CS>));
+
+    return scope;
+}
+
+fn create_legacy_scope<CS: CloudService + 'static>() -> Scope<
+    impl ServiceFactory<
+        ServiceRequest,
+        Config = (),
+        Response = ServiceResponse,
+        Error = actix_web::Error,
+        InitError = (),
+    >,
+> {
+    let scope = web::scope("/assemblyai");
+    let scope = scope.route("/transcriptions/webhook", post().to(legacy_webhook::<CS>));
+
+    return scope;
+}
+
+pub fn init_routes(config: &mut web::ServiceConfig) {
+    let scope = create_scope::<DefaultCloudService>();
+    config.service(scope);
+    let legacy_scope = create_legacy_scope::<DefaultCloudService>();
+    config.service(legacy_scope);
+}
@@ -0,0 +1,77 @@
+use sqlx::PgPool;
+
+use marco_polo_rs_core::{
+    database::queries::{self, transcription::UpdateVideoTranscriptionDto, video::CreateErrorsDto},
+    internals::{
+        cloud::traits::BucketClient,
+        transcriber::traits::{TranscriberClient, TranscriptionStatus},
+    },
+    util::srt,
+};
+use uuid::Uuid;
+
+use crate::models::error::AppError;
+
+/// The webhook only tells which transcription changed, the client is asked for the rest.
+/// The srt uploaded to `srt_transcriptions/` is what starts the translation
+pub async fn webhook<C>(
+    transcriber_client: &dyn TranscriberClient,
+    body: &[u8],
+    pool: &PgPool,
+    bucket_client: &C,
+) -> Result<(), AppError>
+where
+    C: BucketClient,
+{
+    let transcription_id = match transcriber_client.webhook_transcription_id(body) {
+        Ok(transcription_id) => transcription_id,
+        Err(e) => return Err(AppError::bad_request(e.to_string())),
+    };
+
+    let video = queries::video::find_by_transcription_id(pool, &transcription_id).await?;
+
+    let sentences = match transcriber_client.status(&transcription_id).await? {
+        TranscriptionStatus::Processing => return Ok(()),
+        TranscriptionStatus::Failed(error) => {
+            let error = format!("Transcription error: {}", error);
+            transcription_error(pool, video.id, &error).await?;
+            return Ok(());
+        }
+        TranscriptionStatus::Completed => {
+            transcriber_client
+                .get_transcription_sentences(&transcription_id)
+                .await?
+        }
+    };
+
+    let speaker_labels = queries::video::find_speaker_labels(pool, &video.id).await?;
+    let body = srt::create_based_on_sentences(sentences, speaker_labels).into_bytes();
+
+    let file_name = format!("srt_transcriptions/{}.srt", video.id);
+
+    bucket_client.upload_file(&file_name, body).await?;
+
+    let mut trx = pool.begin().await?;
+    queries::outbox::enqueue_upload_event(&mut *trx, bucket_client, &file_name).await?;
+    queries::transcription::update(
+        &mut *trx,
+        UpdateVideoTranscriptionDto {
+            video_id: video.id,
+            storage_id: bucket_client.id(),
+            path: file_name,
+        },
+    )
+    .await?;
+    trx.commit().await?;
+
+    return Ok(());
+}
+
+async fn transcription_error(pool: &PgPool, video_id: Uuid, error: &str) -> Result<(), AppError> {
+    let dto: CreateErrorsDto = CreateErrorsDto {
+        video_ids: vec![video_id],
+        error,
+    };
+    queries::video::create_errors(pool, dto).await?;
+    Ok(())
+}
@@ -0,0 +1,184 @@
+use std::{collections::HashMap, str::FromStr, sync::Arc};
+
+use actix_http::Request;
+use actix_web::{
+    dev::ServiceResponse,
+    http::header::ContentType,
+    test,
+    web::{self},
+};
+use marco_polo_rs_core::{
+    database::queries::{self, transcription::CreateTranscriptionDto},
+    internals::transcriber::traits::{TranscriberClient, TranscriptionStatus},
+};
+use reqwest::StatusCode;
+use serde_json::json;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    controllers::test::{
+        create_test_app,
+        mock::{
+            cloud_service::CloudServiceMock,
+            transcriber::{TranscriberClientMock, WEBHOOK_TOKEN},
+        },
+    },
+    AppCloudService, AppPool, AppTranscriberClients,
+};
+
+use super::{create_legacy_scope, create_scope, LEGACY_PROVIDER};
+
+const VIDEO_ID: &str = "806b57d2-f221-11ed-a05b-0242ac120003";
+const TRANSCRIPTION_ID: &str = "transcription_test";
+
+async fn innit_test_app(
+    pool: Arc<PgPool>,
+    status: TranscriptionStatus,
+) -> impl actix_web::dev::Service<Request, Response = ServiceResponse, Error = actix_web::Error> {
+    let mut clients: HashMap<&'static str, Arc<dyn TranscriberClient + Send + Sync>> =
+        HashMap::new();
+    let client = Arc::new(TranscriberClientMock::new(status));
+    clients.insert("mock", client.clone());
+    clients.insert(LEGACY_PROVIDER, client);
+
+    let app = create_test_app();
+    let scope = create_scope::<CloudServiceMock>();
+    let legacy_scope = create_legacy_scope::<CloudServiceMock>();
+
+    let app = app
+        .app_data(web::Data::new(AppPool { pool }))
+        .app_data(web::Data::new(AppCloudService {
+            client: Arc::new(CloudServiceMock::new()),
+        }))
+        .app_data(web::Data::new(AppTranscriberClients { clients }))
+        .service(scope)
+        .service(legacy_scope);
+
+    let test_app = test::init_service(app).await;
+
+    return test_app;
+}
+
+async fn request_transcription(pool: &PgPool) -> Uuid {
+    let video_id = Uuid::from_str(VIDEO_ID).unwrap();
+    let dto = CreateTranscriptionDto {
+        video_id,
+        transcription_id: TRANSCRIPTION_ID.to_string(),
+        transcriber_id: 3,
+    };
+    queries::transcription::create(pool, dto).await.unwrap();
+    return video_id;
+}
+
+fn webhook_request(provider: &str, token: &str) -> Request {
+    return test::TestRequest::post()
+        .uri(&format!("/transcriptions/{}/webhook", provider))
+        .insert_header(ContentType::json())
+        .insert_header(("Authorization", token))
+        .set_json(json!({ "id": TRANSCRIPTION_ID }))
+        .to_request();
+}
+
+#[sqlx::test(
+    migrations = "../migrations",
+    fixtures("../../../test/fixtures/videos")
+)]
+async fn test_webhook_completed(pool: PgPool) {
+    let video_id = request_transcription(&pool).await;
+    let pool = Arc::new(pool);
+    let test_app = innit_test_app(pool.clone(), TranscriptionStatus::Completed).await;
+
+    let request = webhook_request("mock", WEBHOOK_TOKEN);
+    let response = test::call_service(&test_app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let transcription = queries::transcription::find_by_video_id(&pool, &video_id)
+        .await
+        .unwrap();
+    assert_eq!(
+        transcription.path,
+        Some(format!("srt_transcriptions/{}.srt", VIDEO_ID))
+    );
+    assert_eq!(transcription.storage_id, Some(1));
+}
+
+#[sqlx::test(
+    migrations = "../migrations",
+    fixtures("../../../test/fixtures/videos")
+)]
+async fn test_webhook_failed(pool: PgPool) {
+    let video_id = request_transcription(&pool).await;
+    let pool = Arc::new(pool);
+    let status = TranscriptionStatus::Failed("Audio too short".to_string());
+    let test_app = innit_test_app(pool.clone(), status).await;
+
+    let request = webhook_request("mock", WEBHOOK_TOKEN);
+    let response = test::call_service(&test_app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let transcription = queries::transcription::find_by_video_id(&pool, &video_id)
+        .await
+        .unwrap();
+    assert_eq!(transcription.path, None);
+
+    let errors = queries::video_error::find_by_video_id(&pool, &video_id)
+        .await
+        .unwrap();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].error, "Transcription error: Audio too short");
+}
+
+#[sqlx::test(
+    migrations = "../migrations",
+    fixtures("../../../test/fixtures/videos")
+)]
+async fn test_webhook_invalid_token(pool: PgPool) {
+    request_transcription(&pool).await;
+    let pool = Arc::new(pool);
+    let test_app = innit_test_app(pool.clone(), TranscriptionStatus::Completed).await;
+
+    let request = webhook_request("mock", "wrong_token");
+    let response = test::call_service(&test_app, request).await;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[sqlx::test(
+    migrations = "../migrations",
+    fixtures("../../../test/fixtures/videos")
+)]
+async fn test_webhook_unknown_provider(pool: PgPool) {
+    let pool = Arc::new(pool);
+    let test_app = innit_test_app(pool.clone(), TranscriptionStatus::Completed).await;
+
+    let request = webhook_request("unknown", WEBHOOK_TOKEN);
+    let response = test::call_service(&test_app, request).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[sqlx::test(
+    migrations = "../migrations",
+    fixtures("../../../test/fixtures/videos")
+)]
+async fn test_legacy_webhook_route(pool: PgPool) {
+    let video_id = request_transcription(&pool).await;
+    let pool = Arc::new(pool);
+    let test_app = innit_test_app(pool.clone(), TranscriptionStatus::Completed).await;
+
+    let request = test::TestRequest::post()
+        .uri("/assemblyai/transcriptions/webhook")
+        .insert_header(ContentType::json())
+        .insert_header(("Authorization", WEBHOOK_TOKEN))
+        .set_json(json!({ "id": TRANSCRIPTION_ID }))
+        .to_request();
+    let response = test::call_service(&test_app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let transcription = queries::transcription::find_by_video_id(&pool, &video_id)
+        .await
+        .unwrap();
+    assert_eq!(
+        transcription.path,
+        Some(format!("srt_transcriptions/{}.srt", VIDEO_ID))
+    );
+}
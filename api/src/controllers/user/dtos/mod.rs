@@ -2,6 +2,7 @@ pub mod create;
 pub mod find;
 pub mod forgot;
 pub mod login;
+pub mod notification;
 
 use lazy_static::lazy_static;
 use regex::Regex;
@@ -0,0 +1,27 @@
+use marco_polo_rs_core::database::models::notification::NotificationPreferences;
+use serde::{Deserialize, Serialize};
+
+/// Which emails the user receives, used both to read and to replace them
+#[derive(Serialize, Debug, PartialEq, Deserialize)]
+pub struct NotificationPreferencesDTO {
+    pub videos_finished: bool,
+    pub video_failed: bool,
+}
+
+impl Default for NotificationPreferencesDTO {
+    fn default() -> Self {
+        return Self {
+            videos_finished: true,
+            video_failed: true,
+        };
+    }
+}
+
+impl From<NotificationPreferences> for NotificationPreferencesDTO {
+    fn from(value: NotificationPreferences) -> Self {
+        return Self {
+            videos_finished: value.videos_finished,
+            video_failed: value.video_failed,
+        };
+    }
+}
@@ -7,7 +7,14 @@ use actix_web::{
 use marco_polo_rs_core::{
     database::{
         models::user::User,
-        queries::{self, filter::Filter, pagination::Pagination, user::CreateUserDto},
+        queries::{
+            self, filter::Filter, notification::UpdatePreferencesDto, pagination::Pagination,
+            user::CreateUserDto,
+        },
+    },
+    mail::{
+        engine::{handlebars::HandleBarsEngine, MailEngine},
+        sender::{lettre::LettreMailer, MailSender},
     },
     util::security,
 };
@@ -17,13 +24,10 @@ use validator::Validate;
 use self::dtos::{
     forgot::{ForgotPasswordDto, ForgotPasswordEmailParams, ResetPasswordDto},
     login::Login,
+    notification::NotificationPreferencesDTO,
 };
 use crate::{
     controllers::user::dtos::{create::CreateUser, find::UserDTO},
-    mail::{
-        engine::{handlebars::HandleBarsEngine, MailEngine},
-        sender::{lettre::LettreMailer, MailSender},
-    },
     AppMailer, AppPool,
 };
 use crate::{
@@ -96,8 +100,8 @@ async fn forgot_password<E, S>(
     body: Json<ForgotPasswordDto>,
 ) -> Result<impl Responder, AppError>
 where
-    E: crate::mail::engine::MailEngine,
-    S: crate::mail::sender::MailSender,
+    E: MailEngine,
+    S: MailSender,
 {
     let pool = &pool.pool;
     let mailer = &mailer.mailer;
@@ -163,6 +167,41 @@ async fn reset_password(
     return Ok(HttpResponse::Ok().finish());
 }
 
+#[get("/notifications")]
+async fn find_notification_preferences(
+    pool: web::Data<AppPool>,
+    jwt: TokenClaims,
+) -> Result<impl Responder, AppError> {
+    let pool = &pool.pool;
+
+    let preferences = queries::notification::find_preferences(pool, jwt.id).await?;
+    let dto: NotificationPreferencesDTO = match preferences {
+        Some(preferences) => preferences.into(),
+        None => NotificationPreferencesDTO::default(),
+    };
+
+    return Ok(Json(dto));
+}
+
+#[put("/notifications")]
+async fn update_notification_preferences(
+    pool: web::Data<AppPool>,
+    body: Json<NotificationPreferencesDTO>,
+    jwt: TokenClaims,
+) -> Result<impl Responder, AppError> {
+    let pool = &pool.pool;
+
+    let db_dto = UpdatePreferencesDto {
+        user_id: jwt.id,
+        videos_finished: body.videos_finished,
+        video_failed: body.video_failed,
+    };
+    let preferences = queries::notification::upsert_preferences(pool, db_dto).await?;
+    let dto: NotificationPreferencesDTO = preferences.into();
+
+    return Ok(Json(dto));
+}
+
 #[get("/{id}")]
 async fn find_by_id(
     id: web::Path<i32>,
@@ -200,6 +239,8 @@ fn create_scope<ME: MailEngine + 'static, MS: MailSender + 'static>() -> Scope {
         .route("/forgot-password", post().to(forgot_password::<ME, MS>))
         .service(create_user)
         .service(login)
+        .service(find_notification_preferences)
+        .service(update_notification_preferences)
         .service(find_by_id)
         .service(find_all)
         .service(reset_password);
@@ -7,7 +7,13 @@ use actix_web::{
     test,
     web::{self},
 };
-use marco_polo_rs_core::database::models::user::{User, UserRole};
+use marco_polo_rs_core::{
+    database::{
+        models::user::{User, UserRole},
+        queries,
+    },
+    mail::{engine::handlebars::HandleBarsEngine, Mailer},
+};
 use reqwest::StatusCode;
 use sqlx::PgPool;
 
@@ -18,14 +24,14 @@ use crate::{
         test::{create_test_app, mock::mailer::MailSenderMock},
         user::dtos::create::CreateUser,
     },
-    mail::Mailer,
+    models::error::AppErrorResponse,
     AppMailer,
 };
-use crate::{mail::engine::handlebars::HandleBarsEngine, models::error::AppErrorResponse};
 
 use crate::controllers::user::dtos::{
     find::UserDTO,
     forgot::{ForgotPasswordDto, ResetPasswordDto},
+    notification::NotificationPreferencesDTO,
 };
 use crate::utils::test::get_token;
 use crate::AppPool;
@@ -533,11 +539,60 @@ async fn test_reset_password_expired_token(pool: PgPool) {
     assert!(!bcrypt::verify(password, &user.password).unwrap());
 }
 
+#[sqlx::test(migrations = "../migrations", fixtures("../../../test/fixtures/user"))]
+async fn test_notification_preferences_default_to_all(pool: PgPool) {
+    let pool = Arc::new(pool);
+    let token = get_token!(pool.as_ref(), 666);
+
+    let test_app = innit_test_app(pool.clone()).await;
+
+    let request = test::TestRequest::get()
+        .uri("/user/notifications")
+        .insert_header(("Authorization", token))
+        .to_request();
+
+    let response = test::call_service(&test_app, request).await;
+    assert_eq!(response.status().as_u16(), StatusCode::OK);
+
+    let preferences: NotificationPreferencesDTO = test::read_body_json(response).await;
+    assert_eq!(preferences, NotificationPreferencesDTO::default());
+}
+
+#[sqlx::test(migrations = "../migrations", fixtures("../../../test/fixtures/user"))]
+async fn test_update_notification_preferences(pool: PgPool) {
+    let pool = Arc::new(pool);
+    let token = get_token!(pool.as_ref(), 666);
+
+    let test_app = innit_test_app(pool.clone()).await;
+
+    let body = NotificationPreferencesDTO {
+        videos_finished: false,
+        video_failed: true,
+    };
+
+    let request = test::TestRequest::put()
+        .uri("/user/notifications")
+        .insert_header(ContentType::json())
+        .insert_header(("Authorization", token))
+        .set_json(&body)
+        .to_request();
+
+    let response = test::call_service(&test_app, request).await;
+    assert_eq!(response.status().as_u16(), StatusCode::OK);
+
+    let preferences = queries::notification::find_preferences(pool.as_ref(), 666)
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(!preferences.videos_finished);
+    assert!(preferences.video_failed);
+}
+
 async fn innit_test_app(
     pool: Arc<PgPool>,
 ) -> impl actix_web::dev::Service<Request, Response = ServiceResponse, Error = actix_web::Error> {
     let pool = AppPool { pool };
-    let mailer = Mailer::new(HandleBarsEngine::new("./templates"), MailSenderMock);
+    let mailer = Mailer::new(HandleBarsEngine::new("../core/templates"), MailSenderMock);
     let mailer = AppMailer {
         mailer: Arc::new(mailer),
     };
@@ -1,26 +1,28 @@
+use std::str::FromStr;
+
 use lazy_static::lazy_static;
-use marco_polo_rs_core::database::models::video_storage::VideoFormat;
+use marco_polo_rs_core::{
+    database::models::{
+        video::{speaker_labels::SpeakerLabels, subtitles_mode::SubtitlesMode},
+        video_storage::VideoFormat,
+    },
+    internals::translator::language::Language,
+};
 use regex::Regex;
 use serde::{Deserialize, Serialize};
 
 use validator::{Validate, ValidationError};
 
+use crate::utils::validation::validate_time;
+
 use marco_polo_rs_core::MAX_NUMBER_OF_CUTS;
 
 lazy_static! {
     static ref YOUTUBE_URL: Regex = Regex::new(r#"^((?:https?:)?//)?((?:www|m)\.)?((?:youtube\.com|youtu.be))(/(?:[\w\-]+\?v=|embed/|v/)?)([\w\-]+)(\S+)?$"#).unwrap();
 }
 
-fn validate_time(time: &str) -> Result<(), ValidationError> {
-    let times = time.split(":").collect::<Vec<&str>>();
-    if times.len() != 3 {
-        return Err(ValidationError::new("Invalid Time Format"));
-    }
-
-    for time in times {
-        time.parse::<i32>()
-            .map_err(|_| ValidationError::new("Invalid Time Format"))?;
-    }
+fn validate_language(language: &str) -> Result<(), ValidationError> {
+    Language::from_str(language).map_err(|_| ValidationError::new("Unsupported Language"))?;
     return Ok(());
 }
 
@@ -28,8 +30,19 @@ fn validate_time(time: &str) -> Result<(), ValidationError> {
 pub struct Create {
     #[validate(regex(path = "YOUTUBE_URL", message = "Invalid Youtube URL"))]
     pub video_url: String,
+    #[validate(custom(function = "validate_language", message = "Unsupported language\n"))]
     pub language: Option<String>,
+    #[validate(custom(function = "validate_language", message = "Unsupported language\n"))]
+    pub target_language: Option<String>,
     pub format: Option<VideoFormat>,
+    /// Pauses every cut on `Reviewing` until its translated subtitles are approved
+    pub review: Option<bool>,
+    /// Defaults to burning the subtitles into the video
+    pub subtitles: Option<SubtitlesMode>,
+    /// Look of the burned subtitles, defaults to the style of each channel
+    pub subtitles_style_id: Option<i32>,
+    /// Marks who is speaking on the subtitles, with dashes or colors
+    pub speaker_labels: Option<SpeakerLabels>,
     #[validate]
     #[validate(length(min = 1, max = "MAX_NUMBER_OF_CUTS"))]
     pub cuts: Vec<Cut>,
@@ -51,4 +64,17 @@ pub struct Cut {
     ))]
     pub end_time: Option<String>,
     pub tags: Option<Vec<String>>,
+    /// Each language is translated, subtitled and uploaded on its own.
+    /// When missing, the cut is only published in the target language of the video
+    #[validate]
+    #[validate(length(min = 1))]
+    pub languages: Option<Vec<CutLanguage>>,
+}
+
+#[derive(Debug, Default, Validate, Deserialize, Serialize, Clone)]
+pub struct CutLanguage {
+    #[validate(custom(function = "validate_language", message = "Unsupported language\n"))]
+    pub language: String,
+    /// Defaults to the channel of the cut
+    pub channel_id: Option<i32>,
 }
@@ -1,8 +1,11 @@
 pub mod create;
+pub mod review;
+pub mod subtitles;
 use chrono::NaiveDateTime;
 use marco_polo_rs_core::database::models::{
     video::{stage::VideoStage, with::VideoWithOriginal},
     video_error::VideoError,
+    video_output::VideoOutput,
 };
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
@@ -16,6 +19,7 @@ pub struct VideoDTO {
     pub channel_id: i32,
     pub url: Option<String>,
     pub language: String,
+    pub target_language: String,
     pub stage: VideoStage,
     pub error: bool,
     pub original_duration: Option<String>,
@@ -46,6 +50,7 @@ impl From<VideoWithOriginal> for VideoDTO {
             channel_id: video.channel_id,
             url: video.url,
             language: video.language,
+            target_language: video.target_language,
             created_at: video.created_at,
             updated_at: video.updated_at,
             uploaded_at: video.uploaded_at,
@@ -80,3 +85,32 @@ impl From<VideoError> for VideoErrorDTO {
         }
     }
 }
+
+#[derive(Serialize, Debug, PartialEq, Deserialize)]
+pub struct VideoOutputDTO {
+    pub id: i32,
+    pub video_id: Uuid,
+    pub channel_id: i32,
+    pub language: String,
+    pub stage: VideoStage,
+    pub url: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+    pub uploaded_at: Option<NaiveDateTime>,
+}
+
+impl From<VideoOutput> for VideoOutputDTO {
+    fn from(value: VideoOutput) -> Self {
+        VideoOutputDTO {
+            id: value.id,
+            video_id: value.video_id,
+            channel_id: value.channel_id,
+            language: value.language,
+            stage: value.stage,
+            url: value.url,
+            created_at: value.created_at,
+            updated_at: value.updated_at,
+            uploaded_at: value.uploaded_at,
+        }
+    }
+}
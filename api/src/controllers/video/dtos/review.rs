@@ -0,0 +1,98 @@
+use chrono::NaiveDateTime;
+use marco_polo_rs_core::{
+    database::models::video_review::VideoReview, internals::transcriber::traits::Sentence,
+    util::srt,
+};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::models::error::AppError;
+
+/// A subtitle of the review, times use the `HH:MM:SS,mmm` format of the srt files
+#[derive(Serialize, Debug, PartialEq, Deserialize)]
+pub struct ReviewLineDTO {
+    pub index: usize,
+    pub start_time: String,
+    pub end_time: String,
+    pub text: String,
+}
+
+#[derive(Serialize, Debug, PartialEq, Deserialize)]
+pub struct ReviewDTO {
+    pub id: i32,
+    pub video_id: Uuid,
+    pub language: String,
+    pub approved: bool,
+    pub lines: Vec<ReviewLineDTO>,
+    pub approved_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+impl TryFrom<VideoReview> for ReviewDTO {
+    type Error = AppError;
+
+    fn try_from(value: VideoReview) -> Result<Self, Self::Error> {
+        let sentences = srt::parse(&value.srt).map_err(|e| {
+            tracing::error!("Review {} has an invalid srt: {}", value.id, e);
+            AppError::internal_server_error()
+        })?;
+
+        let lines = sentences
+            .into_iter()
+            .enumerate()
+            .map(|(index, sentence)| ReviewLineDTO {
+                index: index + 1,
+                start_time: srt::format_milliseconds(sentence.start_time as u32),
+                end_time: srt::format_milliseconds(sentence.end_time as u32),
+                text: sentence.text,
+            })
+            .collect();
+
+        return Ok(Self {
+            id: value.id,
+            video_id: value.video_id,
+            language: value.language,
+            approved: value.approved_at.is_some(),
+            lines,
+            approved_at: value.approved_at,
+            created_at: value.created_at,
+            updated_at: value.updated_at,
+        });
+    }
+}
+
+/// Fields left out keep their current value
+#[derive(Debug, Default, Validate, Deserialize, Serialize)]
+pub struct UpdateReviewLine {
+    #[validate(length(min = 1, message = "Text can't be empty"))]
+    pub text: Option<String>,
+    pub start_time: Option<String>,
+    pub end_time: Option<String>,
+}
+
+impl UpdateReviewLine {
+    pub fn apply(&self, sentence: &mut Sentence) -> Result<(), AppError> {
+        if let Some(text) = &self.text {
+            sentence.text = text.clone();
+        }
+
+        if let Some(start_time) = &self.start_time {
+            sentence.start_time =
+                srt::parse_milliseconds(start_time).map_err(AppError::bad_request)?;
+        }
+
+        if let Some(end_time) = &self.end_time {
+            sentence.end_time = srt::parse_milliseconds(end_time).map_err(AppError::bad_request)?;
+        }
+
+        if sentence.start_time >= sentence.end_time {
+            return Err(AppError::bad_request(
+                "Line must start before it ends".to_string(),
+            ));
+        }
+
+        return Ok(());
+    }
+}
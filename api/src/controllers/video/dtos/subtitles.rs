@@ -0,0 +1,90 @@
+use chrono::NaiveDateTime;
+use marco_polo_rs_core::{
+    database::models::video_subtitles_revision::VideoSubtitlesRevision,
+    util::{srt, subtitles::Cue},
+};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::models::error::AppError;
+
+/// A subtitle, times use the `HH:MM:SS,mmm` format of the srt files
+#[derive(Serialize, Debug, Clone, PartialEq, Deserialize)]
+pub struct CueDTO {
+    pub start_time: String,
+    pub end_time: String,
+    pub text: String,
+}
+
+impl From<Cue> for CueDTO {
+    fn from(value: Cue) -> Self {
+        return Self {
+            start_time: srt::format_milliseconds(value.start_time as u32),
+            end_time: srt::format_milliseconds(value.end_time as u32),
+            text: value.text,
+        };
+    }
+}
+
+impl TryFrom<&CueDTO> for Cue {
+    type Error = AppError;
+
+    fn try_from(value: &CueDTO) -> Result<Self, Self::Error> {
+        return Ok(Cue::new(
+            srt::parse_milliseconds(&value.start_time).map_err(AppError::bad_request)?,
+            srt::parse_milliseconds(&value.end_time).map_err(AppError::bad_request)?,
+            value.text.clone(),
+        ));
+    }
+}
+
+#[derive(Serialize, Debug, PartialEq, Deserialize)]
+pub struct SubtitlesDTO {
+    pub video_id: Uuid,
+    pub language: String,
+    /// 0 is the translated srt
+    pub revision: i32,
+    pub cues: Vec<CueDTO>,
+}
+
+/// Defaults to the latest revision in the target language of the video
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct SubtitlesQuery {
+    pub language: Option<String>,
+    pub revision: Option<i32>,
+}
+
+/// Same as `SubtitlesQuery`, for files in the srt (default), vtt or ass formats
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct SubtitlesFileQuery {
+    pub language: Option<String>,
+    pub revision: Option<i32>,
+    pub format: Option<String>,
+}
+
+#[derive(Debug, Default, Validate, Deserialize, Serialize)]
+pub struct UpdateSubtitles {
+    pub language: Option<String>,
+    #[validate(length(min = 1, message = "Subtitles must have at least one cue"))]
+    pub cues: Vec<CueDTO>,
+}
+
+#[derive(Serialize, Debug, PartialEq, Deserialize)]
+pub struct SubtitlesRevisionDTO {
+    pub revision: i32,
+    pub language: String,
+    pub user_id: i32,
+    pub created_at: NaiveDateTime,
+}
+
+impl From<VideoSubtitlesRevision> for SubtitlesRevisionDTO {
+    fn from(value: VideoSubtitlesRevision) -> Self {
+        return Self {
+            revision: value.revision,
+            language: value.language,
+            user_id: value.user_id,
+            created_at: value.created_at,
+        };
+    }
+}
@@ -1,30 +1,45 @@
+use std::convert::Infallible;
+
 use actix_web::{
-    get,
-    web::{self, post, Json},
+    get, patch, post,
+    web::{self, Bytes, Json},
     HttpResponse, Responder, Scope,
 };
+use futures::Stream;
 
 use marco_polo_rs_core::{
     database::{
-        models::{user::UserRole, video::Video},
+        models::{
+            user::UserRole,
+            video::Video,
+            video_event::{VideoEvent, VideoEventKind},
+        },
         queries::{self, filter::Filter, pagination::Pagination},
     },
     internals::{
-        cloud::{aws::AwsCloudService, traits::CloudService},
+        cloud::{traits::CloudService, DefaultCloudService},
         video_platform::youtube::{
             client::YoutubeClient, traits::YoutubeClient as YoutubeClientTrait,
         },
     },
+    util::subtitles::{Cue, Document},
 };
 
 use uuid::Uuid;
 use validator::Validate;
 
 use crate::{
-    controllers::video::dtos::{VideoDTO, VideoErrorDTO},
+    controllers::video::dtos::{
+        review::{ReviewDTO, UpdateReviewLine},
+        subtitles::{
+            SubtitlesDTO, SubtitlesFileQuery, SubtitlesQuery, SubtitlesRevisionDTO, UpdateSubtitles,
+        },
+        VideoDTO, VideoErrorDTO, VideoOutputDTO,
+    },
+    events,
     middleware::jwt_token::TokenClaims,
     models::error::AppError,
-    AppCloudService, AppPool, AppYoutubeClient,
+    AppCloudService, AppPool, AppVideoEvents, AppYoutubeClient,
 };
 
 use self::dtos::create::Create;
@@ -34,9 +49,8 @@ mod service;
 #[cfg(test)]
 mod test;
 
-async fn create_video<CS: CloudService, YC: YoutubeClientTrait>(
+async fn create_video<YC: YoutubeClientTrait>(
     pool: web::Data<AppPool>,
-    cloud_service: web::Data<AppCloudService<CS>>,
     youtube_client: web::Data<AppYoutubeClient<YC>>,
     jwt: TokenClaims,
     body: Json<Create>,
@@ -45,9 +59,8 @@ async fn create_video<CS: CloudService, YC: YoutubeClientTrait>(
     let pool = pool.pool.as_ref();
     let body = body.into_inner();
     let youtube_client = youtube_client.client.as_ref();
-    let queue_client = cloud_service.client.queue_client();
 
-    let ids = service::create_video(pool, body, queue_client, youtube_client, jwt).await?;
+    let ids = service::create_video(pool, body, youtube_client, jwt).await?;
 
     let videos = queries::video::with_original::find_all_with_original_by_ids(pool, ids).await?;
 
@@ -142,17 +155,441 @@ async fn find_video_errors(
     return Ok(Json(dto));
 }
 
-fn create_scope<CS: CloudService + 'static, YC: YoutubeClientTrait + 'static>() -> Scope {
+#[get("/{id}/outputs")]
+async fn find_video_outputs(
+    id: web::Path<Uuid>,
+    pool: web::Data<AppPool>,
+    jwt: TokenClaims,
+) -> Result<impl Responder, AppError> {
+    let pool = &pool.pool;
+    let id = id.into_inner();
+
+    let outputs = match jwt.role {
+        UserRole::Admin => queries::output::find_by_video_id(pool, &id).await?,
+        UserRole::User => {
+            let user_id = jwt.id;
+            queries::output::find_by_video_id_and_owner(pool, &id, user_id).await?
+        }
+    };
+
+    let dto: Vec<VideoOutputDTO> = outputs.into_iter().map(|c| c.into()).collect();
+
+    return Ok(Json(dto));
+}
+
+#[post("/{id}/retry")]
+async fn retry_video(
+    id: web::Path<Uuid>,
+    pool: web::Data<AppPool>,
+    jwt: TokenClaims,
+) -> Result<impl Responder, AppError> {
+    let id = id.into_inner();
+    let pool = pool.pool.as_ref();
+
+    let video = match jwt.role {
+        UserRole::Admin => queries::video::with_original::find_with_original(pool, &id).await?,
+        UserRole::User => {
+            let user_id = jwt.id;
+            queries::video::with_original::find_by_user_id_with_original(pool, &id, user_id).await?
+        }
+    };
+
+    service::retry_video(pool, &video.video).await?;
+
+    let video = queries::video::with_original::find_with_original(pool, &id).await?;
+    let dto: VideoDTO = video.into();
+
+    return Ok(HttpResponse::Ok().json(dto));
+}
+
+/// Translated subtitles waiting for approval, one per output of videos created with `review`
+#[get("/{id}/reviews")]
+async fn find_video_reviews(
+    id: web::Path<Uuid>,
+    pool: web::Data<AppPool>,
+    jwt: TokenClaims,
+) -> Result<impl Responder, AppError> {
+    let id = id.into_inner();
+    let pool = pool.pool.as_ref();
+
+    let video = match jwt.role {
+        UserRole::Admin => queries::video::with_original::find_with_original(pool, &id).await?,
+        UserRole::User => {
+            let user_id = jwt.id;
+            queries::video::with_original::find_by_user_id_with_original(pool, &id, user_id).await?
+        }
+    };
+
+    let reviews = queries::review::find_by_video_id(pool, &video.video.id).await?;
+
+    let mut dto: Vec<ReviewDTO> = vec![];
+    for review in reviews {
+        dto.push(review.try_into()?);
+    }
+
+    return Ok(Json(dto));
+}
+
+#[patch("/{id}/reviews/{language}/lines/{index}")]
+async fn update_review_line(
+    path: web::Path<(Uuid, String, usize)>,
+    pool: web::Data<AppPool>,
+    jwt: TokenClaims,
+    body: Json<UpdateReviewLine>,
+) -> Result<impl Responder, AppError> {
+    body.validate()?;
+    let (id, language, index) = path.into_inner();
+    let pool = pool.pool.as_ref();
+
+    let video = match jwt.role {
+        UserRole::Admin => queries::video::with_original::find_with_original(pool, &id).await?,
+        UserRole::User => {
+            let user_id = jwt.id;
+            queries::video::with_original::find_by_user_id_with_original(pool, &id, user_id).await?
+        }
+    };
+
+    let review =
+        service::update_review_line(pool, &video.video.id, &language, index, &body).await?;
+    let dto: ReviewDTO = review.try_into()?;
+
+    return Ok(Json(dto));
+}
+
+/// Resumes the output, it is subtitled with the reviewed lines
+#[post("/{id}/reviews/{language}/approve")]
+async fn approve_review(
+    path: web::Path<(Uuid, String)>,
+    pool: web::Data<AppPool>,
+    jwt: TokenClaims,
+) -> Result<impl Responder, AppError> {
+    let (id, language) = path.into_inner();
+    let pool = pool.pool.as_ref();
+
+    let video = match jwt.role {
+        UserRole::Admin => queries::video::with_original::find_with_original(pool, &id).await?,
+        UserRole::User => {
+            let user_id = jwt.id;
+            queries::video::with_original::find_by_user_id_with_original(pool, &id, user_id).await?
+        }
+    };
+
+    let review = service::approve_review(pool, &video.video.id, &language).await?;
+    let dto: ReviewDTO = review.try_into()?;
+
+    return Ok(Json(dto));
+}
+
+/// Cues of a revision of the subtitles, the translated srt is revision 0
+async fn find_video_subtitles<CS: CloudService>(
+    id: web::Path<Uuid>,
+    query: web::Query<SubtitlesQuery>,
+    pool: web::Data<AppPool>,
+    cloud_service: web::Data<AppCloudService<CS>>,
+    jwt: TokenClaims,
+) -> Result<impl Responder, AppError> {
+    let id = id.into_inner();
+    let query = query.into_inner();
+    let pool = pool.pool.as_ref();
+
+    let video = match jwt.role {
+        UserRole::Admin => queries::video::with_original::find_with_original(pool, &id).await?,
+        UserRole::User => {
+            let user_id = jwt.id;
+            queries::video::with_original::find_by_user_id_with_original(pool, &id, user_id).await?
+        }
+    };
+
+    let video = video.video;
+    let language = query.language.unwrap_or(video.target_language);
+    let bucket_client = cloud_service.client.bucket_client();
+
+    let (revision, document) =
+        service::find_subtitles(pool, bucket_client, &video.id, &language, query.revision).await?;
+
+    let dto = SubtitlesDTO {
+        video_id: video.id,
+        language,
+        revision,
+        cues: document.cues.into_iter().map(|c| c.into()).collect(),
+    };
+
+    return Ok(Json(dto));
+}
+
+/// Saves the edited cues as a new revision, the video is not rendered again until asked to
+async fn update_video_subtitles<CS: CloudService>(
+    id: web::Path<Uuid>,
+    pool: web::Data<AppPool>,
+    cloud_service: web::Data<AppCloudService<CS>>,
+    jwt: TokenClaims,
+    body: Json<UpdateSubtitles>,
+) -> Result<impl Responder, AppError> {
+    body.validate()?;
+    let id = id.into_inner();
+    let body = body.into_inner();
+    let pool = pool.pool.as_ref();
+
+    let video = match jwt.role {
+        UserRole::Admin => queries::video::with_original::find_with_original(pool, &id).await?,
+        UserRole::User => {
+            let user_id = jwt.id;
+            queries::video::with_original::find_by_user_id_with_original(pool, &id, user_id).await?
+        }
+    };
+
+    let video = video.video;
+    let language = body.language.unwrap_or(video.target_language);
+    let bucket_client = cloud_service.client.bucket_client();
+
+    let mut document = Document::default();
+    for cue in &body.cues {
+        document.cues.push(Cue::try_from(cue)?);
+    }
+
+    let revision =
+        service::save_subtitles(pool, bucket_client, &video.id, &language, &document, jwt.id)
+            .await?;
+
+    let dto = SubtitlesDTO {
+        video_id: video.id,
+        language,
+        revision,
+        cues: body.cues,
+    };
+
+    return Ok(HttpResponse::Created().json(dto));
+}
+
+/// A revision of the subtitles as a srt, vtt or ass file
+async fn download_video_subtitles<CS: CloudService>(
+    id: web::Path<Uuid>,
+    query: web::Query<SubtitlesFileQuery>,
+    pool: web::Data<AppPool>,
+    cloud_service: web::Data<AppCloudService<CS>>,
+    jwt: TokenClaims,
+) -> Result<impl Responder, AppError> {
+    let id = id.into_inner();
+    let query = query.into_inner();
+    let pool = pool.pool.as_ref();
+    let format = service::subtitles_format(query.format.as_deref())?;
+
+    let video = match jwt.role {
+        UserRole::Admin => queries::video::with_original::find_with_original(pool, &id).await?,
+        UserRole::User => {
+            let user_id = jwt.id;
+            queries::video::with_original::find_by_user_id_with_original(pool, &id, user_id).await?
+        }
+    };
+
+    let video = video.video;
+    let language = query.language.unwrap_or(video.target_language);
+    let bucket_client = cloud_service.client.bucket_client();
+
+    let (_, document) =
+        service::find_subtitles(pool, bucket_client, &video.id, &language, query.revision).await?;
+
+    let file_name = format!("{}.{}.{}", video.id, language, format.extension());
+
+    return Ok(HttpResponse::Ok()
+        .content_type(format.content_type())
+        .insert_header((
+            "Content-Disposition",
+            format!("attachment; filename=\"{}\"", file_name),
+        ))
+        .body(document.write(format)));
+}
+
+/// Saves a srt, vtt or ass file as a new revision, parse errors point to the lines of the file
+async fn upload_video_subtitles<CS: CloudService>(
+    id: web::Path<Uuid>,
+    query: web::Query<SubtitlesFileQuery>,
+    pool: web::Data<AppPool>,
+    cloud_service: web::Data<AppCloudService<CS>>,
+    jwt: TokenClaims,
+    body: String,
+) -> Result<impl Responder, AppError> {
+    let id = id.into_inner();
+    let query = query.into_inner();
+    let pool = pool.pool.as_ref();
+    let format = service::subtitles_format(query.format.as_deref())?;
+    let document = Document::parse(&body, format)?;
+
+    let video = match jwt.role {
+        UserRole::Admin => queries::video::with_original::find_with_original(pool, &id).await?,
+        UserRole::User => {
+            let user_id = jwt.id;
+            queries::video::with_original::find_by_user_id_with_original(pool, &id, user_id).await?
+        }
+    };
+
+    let video = video.video;
+    let language = query.language.unwrap_or(video.target_language);
+    let bucket_client = cloud_service.client.bucket_client();
+
+    let revision =
+        service::save_subtitles(pool, bucket_client, &video.id, &language, &document, jwt.id)
+            .await?;
+
+    let dto = SubtitlesDTO {
+        video_id: video.id,
+        language,
+        revision,
+        cues: document.cues.into_iter().map(|c| c.into()).collect(),
+    };
+
+    return Ok(HttpResponse::Created().json(dto));
+}
+
+#[get("/{id}/subtitles/revisions")]
+async fn find_subtitles_revisions(
+    id: web::Path<Uuid>,
+    query: web::Query<SubtitlesQuery>,
+    pool: web::Data<AppPool>,
+    jwt: TokenClaims,
+) -> Result<impl Responder, AppError> {
+    let id = id.into_inner();
+    let query = query.into_inner();
+    let pool = pool.pool.as_ref();
+
+    let video = match jwt.role {
+        UserRole::Admin => queries::video::with_original::find_with_original(pool, &id).await?,
+        UserRole::User => {
+            let user_id = jwt.id;
+            queries::video::with_original::find_by_user_id_with_original(pool, &id, user_id).await?
+        }
+    };
+
+    let video = video.video;
+    let language = query.language.unwrap_or(video.target_language);
+
+    let revisions =
+        queries::subtitles_revision::find_by_video_id_and_language(pool, &video.id, &language)
+            .await?;
+
+    let dto: Vec<SubtitlesRevisionDTO> = revisions.into_iter().map(|r| r.into()).collect();
+
+    return Ok(Json(dto));
+}
+
+/// Subtitles the video again with any revision, as long as it isn't published yet
+#[post("/{id}/subtitles/revisions/{revision}/render")]
+async fn render_subtitles_revision(
+    path: web::Path<(Uuid, i32)>,
+    query: web::Query<SubtitlesQuery>,
+    pool: web::Data<AppPool>,
+    jwt: TokenClaims,
+) -> Result<impl Responder, AppError> {
+    let (id, revision) = path.into_inner();
+    let query = query.into_inner();
+    let pool = pool.pool.as_ref();
+
+    let video = match jwt.role {
+        UserRole::Admin => queries::video::with_original::find_with_original(pool, &id).await?,
+        UserRole::User => {
+            let user_id = jwt.id;
+            queries::video::with_original::find_by_user_id_with_original(pool, &id, user_id).await?
+        }
+    };
+
+    let video = video.video;
+    let language = query.language.unwrap_or(video.target_language);
+
+    service::render_subtitles(pool, &video.id, &language, revision).await?;
+
+    return Ok(HttpResponse::Accepted().finish());
+}
+
+/// Stage transitions, errors and progress of the video, starting with its current stage
+#[get("/{id}/events")]
+async fn find_video_events(
+    id: web::Path<Uuid>,
+    pool: web::Data<AppPool>,
+    events: web::Data<AppVideoEvents>,
+    jwt: TokenClaims,
+) -> Result<impl Responder, AppError> {
+    let id = id.into_inner();
+    let pool = pool.pool.as_ref();
+
+    // subscribed first, so nothing happening while the video is read is missed
+    let receiver = events.sender.subscribe();
+
+    let video = match jwt.role {
+        UserRole::Admin => queries::video::with_original::find_with_original(pool, &id).await?,
+        UserRole::User => {
+            let user_id = jwt.id;
+            queries::video::with_original::find_by_user_id_with_original(pool, &id, user_id).await?
+        }
+    };
+
+    let current = VideoEvent {
+        video_id: video.video.id,
+        user_id: video.video.user_id,
+        kind: VideoEventKind::Stage {
+            stage: video.video.stage,
+        },
+    };
+
+    let stream = events::stream(vec![current], receiver, move |event| event.video_id == id);
+
+    return Ok(event_stream_response(stream));
+}
+
+/// Events of every video of the user, admins receive the events of all the videos
+#[get("/events")]
+async fn find_events(events: web::Data<AppVideoEvents>, jwt: TokenClaims) -> impl Responder {
+    let receiver = events.sender.subscribe();
+
+    let stream = events::stream(vec![], receiver, move |event| match jwt.role {
+        UserRole::Admin => true,
+        UserRole::User => event.user_id == jwt.id,
+    });
+
+    return event_stream_response(stream);
+}
+
+fn event_stream_response(
+    stream: impl Stream<Item = Result<Bytes, Infallible>> + 'static,
+) -> HttpResponse {
+    return HttpResponse::Ok()
+        .content_type("text/event-stream")
+        .insert_header(("Cache-Control", "no-cache"))
+        .streaming(stream);
+}
+
+fn create_scope<YC: YoutubeClientTrait + 'static, CS: CloudService + 'static>() -> Scope {
     let scope = web::scope("/video");
     let scope = scope
-        .route("", post().to(create_video::<CS, YC>))
+        .route("", web::post().to(create_video::<YC>))
+        .route("/{id}/subtitles", web::get().to(find_video_subtitles::<CS>))
+        .route(
+            "/{id}/subtitles",
+            web::put().to(update_video_subtitles::<CS>),
+        )
+        .route(
+            "/{id}/subtitles/file",
+            web::get().to(download_video_subtitles::<CS>),
+        )
+        .route(
+            "/{id}/subtitles/file",
+            web::put().to(upload_video_subtitles::<CS>),
+        )
+        .service(find_subtitles_revisions)
+        .service(render_subtitles_revision)
+        .service(retry_video)
+        .service(find_events)
         .service(find_by_id)
         .service(find_all)
-        .service(find_video_errors);
+        .service(find_video_errors)
+        .service(find_video_outputs)
+        .service(find_video_reviews)
+        .service(update_review_line)
+        .service(approve_review)
+        .service(find_video_events);
     return scope;
 }
 
 pub fn init_routes(config: &mut web::ServiceConfig) {
-    let scope = create_scope::<AwsCloudService, YoutubeClient>();
+    let scope = create_scope::<YoutubeClient, DefaultCloudService>();
     config.service(scope);
 }
@@ -1,30 +1,46 @@
-use std::collections::HashSet;
+use std::{collections::HashSet, str::FromStr};
 
 use futures::future::join_all;
 use marco_polo_rs_core::{
     database::{
-        models::{channel::auth::AuthType, user::UserRole},
-        queries::{self, video::CreateVideoDto},
+        models::{
+            channel::auth::AuthType,
+            user::UserRole,
+            video::{stage::VideoStage, Video},
+            video_review::VideoReview,
+            video_storage::StorageVideoStage,
+        },
+        queries::{
+            self, output::CreateOutputDto, subtitles_revision::CreateRevisionDto,
+            video::CreateVideoDto,
+        },
     },
     internals::{
         cloud::{
-            models::payload::{PayloadType, VideoDownloadPayload},
-            traits::QueueClient,
+            models::payload::{PayloadType, SrtPayload, VideoDownloadPayload, VideoPayload},
+            traits::BucketClient,
         },
+        translator::language::Language,
         video_platform::youtube::traits::YoutubeClient as YoutubeClientTrait,
     },
+    util::{
+        srt,
+        subtitles::{Document, Format},
+    },
 };
 use sqlx::PgPool;
 use uuid::Uuid;
 
 use crate::{middleware::jwt_token::TokenClaims, models::error::AppError};
 
-use super::dtos::create::{Create, Cut};
+use super::dtos::{
+    create::{Create, Cut},
+    review::UpdateReviewLine,
+};
 
-pub async fn create_video<QC: QueueClient, YC: YoutubeClientTrait>(
+pub async fn create_video<YC: YoutubeClientTrait>(
     pool: &PgPool,
     body: Create,
-    queue_client: &QC,
     youtube_client: &YC,
     jwt: TokenClaims,
 ) -> Result<Vec<Uuid>, AppError> {
@@ -33,10 +49,16 @@ pub async fn create_video<QC: QueueClient, YC: YoutubeClientTrait>(
 
     for cut in &body.cuts {
         channel_ids.insert(cut.channel_id);
+
+        for language in cut.languages.iter().flatten() {
+            if let Some(channel_id) = language.channel_id {
+                channel_ids.insert(channel_id);
+            }
+        }
     }
 
     check_channels_heath(pool, youtube_client, channel_ids, jwt).await?;
-    let ids = create_videos(pool, body, user_id, queue_client).await?;
+    let ids = create_videos(pool, body, user_id).await?;
     return Ok(ids);
 }
 
@@ -113,59 +135,116 @@ async fn check_channel_heath(
     Ok(())
 }
 
-async fn create_videos(
-    pool: &PgPool,
-    body: Create,
-    user_id: i32,
-    queue_client: &impl QueueClient,
-) -> Result<Vec<Uuid>, AppError> {
-    let language = match &body.language {
-        Some(language) => language,
-        None => "en",
-    };
+async fn create_videos(pool: &PgPool, body: Create, user_id: i32) -> Result<Vec<Uuid>, AppError> {
+    let language = parse_language(&body.language, Language::English)?;
+    let target_language = parse_language(&body.target_language, Language::PortugueseBrazil)?;
+
+    if let Some(style_id) = body.subtitles_style_id {
+        queries::subtitles_style::find_by_id_and_user(pool, style_id, user_id)
+            .await
+            .map_err(|_| AppError::not_found("Subtitles style not found".to_string()))?;
+    }
+
+    let mut outputs = vec![];
+    for cut in &body.cuts {
+        outputs.push(cut_outputs(cut, target_language)?);
+    }
+
     let mut trx = pool.begin().await?;
     let original_video_id = queries::original_video::create(&mut *trx, &body.video_url).await?;
 
-    let dtos = create_video_dtos(&body, original_video_id, user_id, &language).await;
+    let dtos = create_video_dtos(&body, original_video_id, user_id, language, &outputs).await;
     let video_ids: Vec<Uuid> = dtos.iter().map(|dto| dto.id).collect();
 
     queries::video::create_many(&mut *trx, dtos).await?;
 
+    let mut output_dtos = vec![];
+    for (video_id, video_outputs) in video_ids.iter().zip(&outputs) {
+        for (channel_id, language) in video_outputs {
+            output_dtos.push(CreateOutputDto {
+                video_id: *video_id,
+                channel_id: *channel_id,
+                language: language.code(),
+            });
+        }
+    }
+
+    queries::output::create_many(&mut *trx, output_dtos).await?;
+
     let payload: VideoDownloadPayload = VideoDownloadPayload {
         original_video_id,
         video_ids: video_ids.clone(),
     };
 
-    queue_client
-        .send_message(PayloadType::BatukaDownloadVideo(payload))
-        .await?;
+    queries::outbox::enqueue(&mut *trx, &PayloadType::BatukaDownloadVideo(payload)).await?;
 
     trx.commit().await?;
 
     return Ok(video_ids);
 }
 
+fn parse_language(language: &Option<String>, default: Language) -> Result<Language, AppError> {
+    let language = match language {
+        Some(language) => Language::from_str(language).map_err(AppError::bad_request)?,
+        None => default,
+    };
+    return Ok(language);
+}
+
+/// Returns the channel and language of every output of the cut,
+/// the first one is the primary language of the video
+fn cut_outputs(cut: &Cut, default: Language) -> Result<Vec<(i32, Language)>, AppError> {
+    let languages = match &cut.languages {
+        Some(languages) => languages,
+        None => return Ok(vec![(cut.channel_id, default)]),
+    };
+
+    let mut outputs = vec![];
+    let mut seen = HashSet::new();
+    for cut_language in languages {
+        let language = Language::from_str(&cut_language.language).map_err(AppError::bad_request)?;
+
+        if !seen.insert(language) {
+            return Err(AppError::bad_request(format!(
+                "Language {} was requested more than once",
+                language
+            )));
+        }
+
+        let channel_id = cut_language.channel_id.unwrap_or(cut.channel_id);
+        outputs.push((channel_id, language));
+    }
+
+    return Ok(outputs);
+}
+
 async fn create_video_dtos<'a>(
     body: &'a Create,
     original_video_id: i32,
     user_id: i32,
-    language: &'a str,
+    language: Language,
+    outputs: &[Vec<(i32, Language)>],
 ) -> Vec<CreateVideoDto<'a>> {
     let mut dtos = vec![];
-    for cut in &body.cuts {
-        let dto = create_video_dto(cut, original_video_id, user_id, language).await;
+    for (cut, video_outputs) in body.cuts.iter().zip(outputs) {
+        let (_, target_language) = video_outputs[0];
+        let languages = (language.code(), target_language.code());
+        let dto = create_video_dto(body, cut, original_video_id, user_id, languages).await;
         dtos.push(dto);
     }
     return dtos;
 }
 
+/// The settings of the body apply to every cut
 async fn create_video_dto<'a>(
+    body: &Create,
     cut: &'a Cut,
     original_video_id: i32,
     user_id: i32,
-    language: &'a str,
+    languages: (&'a str, &'a str),
 ) -> CreateVideoDto<'a> {
     let video_id = uuid::Uuid::new_v4();
+    let (language, target_language) = languages;
 
     let start_time = match &cut.start_time {
         Some(start_time) => start_time,
@@ -192,11 +271,371 @@ async fn create_video_dto<'a>(
         end_time,
         description: &cut.description,
         channel_id: cut.channel_id,
-        language: &language,
+        language,
+        target_language,
         original_id: original_video_id,
         tags,
         start_time,
+        review: body.review.unwrap_or(false),
+        subtitles_mode: body.subtitles.unwrap_or_default(),
+        subtitles_style_id: body.subtitles_style_id,
+        speaker_labels: body.speaker_labels.unwrap_or_default(),
     };
 
     return dto;
 }
+
+/// Clears the error of a failed video and enqueues the messages that resume it
+pub async fn retry_video(pool: &PgPool, video: &Video) -> Result<(), AppError> {
+    if !video.error {
+        return Err(AppError::bad_request(
+            "Only failed videos can be retried".to_string(),
+        ));
+    }
+
+    let payloads = find_resume_payloads(pool, video).await?;
+
+    let mut trx = pool.begin().await?;
+    if !queries::video::claim_retry(&mut *trx, &video.id).await? {
+        return Err(AppError::conflict(
+            "Video is already being retried".to_string(),
+        ));
+    }
+    // stages are resumed on purpose, the handlers must not skip them
+    queries::checkpoint::delete_unfinished_by_video_id(&mut *trx, &video.id).await?;
+
+    for payload in payloads {
+        queries::outbox::enqueue(&mut *trx, &payload).await?;
+    }
+
+    trx.commit().await?;
+
+    return Ok(());
+}
+
+/// Finds the last stage the video went through and builds the messages
+/// that start the pipeline again from there. Outputs are resumed one by one
+/// once the video is translated, each one may have stopped at a different stage
+pub async fn find_resume_payloads(
+    pool: &PgPool,
+    video: &Video,
+) -> Result<Vec<PayloadType>, AppError> {
+    let download = PayloadType::BatukaDownloadVideo(VideoDownloadPayload {
+        original_video_id: video.original_video_id,
+        video_ids: vec![video.id],
+    });
+
+    match video.stage {
+        // the downloaded file only lives in the worker, so both restart from the download
+        VideoStage::Downloading | VideoStage::Cutting => return Ok(vec![download]),
+        VideoStage::Done => return Err(AppError::bad_request("Video is already done".to_string())),
+        _ => {}
+    };
+
+    let raw_storage =
+        match queries::storage::find_by_video_id_and_stage(pool, &video.id, StorageVideoStage::Raw)
+            .await
+        {
+            Ok(storage) => storage,
+            Err(sqlx::Error::RowNotFound) => return Ok(vec![download]),
+            Err(e) => return Err(e.into()),
+        };
+
+    let raw_upload = PayloadType::BatukaVideoRawUpload(VideoPayload {
+        video_uri: raw_storage.video_path,
+        video_id: video.id,
+        language: None,
+    });
+
+    if video.stage == VideoStage::RawUploading {
+        return Ok(vec![raw_upload]);
+    }
+
+    let transcription = match queries::transcription::find_by_video_id(pool, &video.id).await {
+        Ok(transcription) => transcription,
+        Err(sqlx::Error::RowNotFound) => return Ok(vec![raw_upload]),
+        Err(e) => return Err(e.into()),
+    };
+
+    let transcription_upload = match transcription.path {
+        Some(srt_uri) => PayloadType::BatukaSrtTranscriptionUpload(SrtPayload {
+            video_id: video.id,
+            srt_uri,
+            language: None,
+        }),
+        None => return Ok(vec![raw_upload]),
+    };
+
+    if video.stage == VideoStage::Transcribing {
+        return Ok(vec![transcription_upload]);
+    }
+
+    let outputs = queries::output::find_by_video_id(pool, &video.id).await?;
+    let translations = queries::translation::find_by_video_id(pool, &video.id).await?;
+
+    let mut payloads = vec![];
+    for output in outputs {
+        if output.stage == VideoStage::Done || output.url.is_some() {
+            continue;
+        }
+
+        let translation = translations
+            .iter()
+            .find(|translation| translation.language == output.language);
+
+        let srt_uri = match translation.and_then(|translation| translation.path.clone()) {
+            Some(srt_uri) => srt_uri,
+            // translating runs for every output at once
+            None => return Ok(vec![transcription_upload]),
+        };
+
+        let processed = queries::storage::find_processed_by_video_id_and_language(
+            pool,
+            &video.id,
+            &output.language,
+        )
+        .await;
+
+        let payload = match processed {
+            Ok(storage) => PayloadType::BatukaVideoProcessedUpload(VideoPayload {
+                video_uri: storage.video_path,
+                video_id: video.id,
+                language: Some(output.language),
+            }),
+            Err(sqlx::Error::RowNotFound) => PayloadType::BatukaSrtTranslationUpload(SrtPayload {
+                video_id: video.id,
+                srt_uri,
+                language: Some(output.language),
+            }),
+            Err(e) => return Err(e.into()),
+        };
+
+        payloads.push(payload);
+    }
+
+    if payloads.is_empty() {
+        return Err(AppError::bad_request(
+            "Video has no outputs left to resume".to_string(),
+        ));
+    }
+
+    return Ok(payloads);
+}
+
+async fn find_review(
+    pool: &PgPool,
+    video_id: &Uuid,
+    language: &str,
+) -> Result<VideoReview, AppError> {
+    let review = queries::review::find_by_video_id_and_language(pool, video_id, language).await?;
+
+    return match review {
+        Some(review) => Ok(review),
+        None => Err(AppError::not_found(format!(
+            "Video has no subtitles to review in {}",
+            language
+        ))),
+    };
+}
+
+/// Edits one line of the review, lines are numbered from 1 like in the srt file
+pub async fn update_review_line(
+    pool: &PgPool,
+    video_id: &Uuid,
+    language: &str,
+    index: usize,
+    body: &UpdateReviewLine,
+) -> Result<VideoReview, AppError> {
+    let review = find_review(pool, video_id, language).await?;
+
+    if review.approved_at.is_some() {
+        return Err(AppError::bad_request(
+            "Approved subtitles can't be edited".to_string(),
+        ));
+    }
+
+    let mut sentences = srt::parse(&review.srt).map_err(|e| {
+        tracing::error!("Review {} has an invalid srt: {}", review.id, e);
+        AppError::internal_server_error()
+    })?;
+
+    let sentence = match index.checked_sub(1).and_then(|i| sentences.get_mut(i)) {
+        Some(sentence) => sentence,
+        None => return Err(AppError::not_found(format!("Line {} not found", index))),
+    };
+    body.apply(sentence)?;
+
+    queries::review::update_srt(pool, review.id, &srt::write(sentences)).await?;
+
+    return find_review(pool, video_id, language).await;
+}
+
+/// Approves the review and enqueues the translated srt again,
+/// this time the queue subtitles the output with the reviewed lines
+pub async fn approve_review(
+    pool: &PgPool,
+    video_id: &Uuid,
+    language: &str,
+) -> Result<VideoReview, AppError> {
+    let review = find_review(pool, video_id, language).await?;
+    let srt_uri = find_translation_uri(pool, video_id, language).await?;
+
+    let payload = PayloadType::BatukaSrtTranslationUpload(SrtPayload {
+        video_id: *video_id,
+        srt_uri,
+        language: Some(language.to_string()),
+    });
+
+    // the approval is claimed along with the message, two approvals never enqueue twice
+    let mut trx = pool.begin().await?;
+    if !queries::review::approve(&mut *trx, review.id).await? {
+        return Err(AppError::conflict(
+            "Subtitles are already approved".to_string(),
+        ));
+    }
+    queries::outbox::enqueue(&mut *trx, &payload).await?;
+    trx.commit().await?;
+
+    return find_review(pool, video_id, language).await;
+}
+
+async fn find_translation_uri(
+    pool: &PgPool,
+    video_id: &Uuid,
+    language: &str,
+) -> Result<String, AppError> {
+    let translations = queries::translation::find_by_video_id(pool, video_id).await?;
+    let srt_uri = translations
+        .into_iter()
+        .find(|translation| translation.language == language)
+        .and_then(|translation| translation.path);
+
+    return match srt_uri {
+        Some(srt_uri) => Ok(srt_uri),
+        None => Err(AppError::not_found(format!(
+            "Video has no translation in {}",
+            language
+        ))),
+    };
+}
+
+/// Where the srt of a revision is stored, revision 0 is the translated srt
+async fn find_revision_uri(
+    pool: &PgPool,
+    video_id: &Uuid,
+    language: &str,
+    revision: i32,
+) -> Result<String, AppError> {
+    if revision == 0 {
+        return find_translation_uri(pool, video_id, language).await;
+    }
+
+    let revision =
+        queries::subtitles_revision::find_by_revision(pool, video_id, language, revision).await?;
+
+    return Ok(revision.path);
+}
+
+/// Srt when no format is given
+pub fn subtitles_format(format: Option<&str>) -> Result<Format, AppError> {
+    match format {
+        Some(format) => Format::from_str(format).map_err(AppError::bad_request),
+        None => Ok(Format::Srt),
+    }
+}
+
+/// Reads the cues of a revision, the latest one when none is given
+pub async fn find_subtitles<BC: BucketClient>(
+    pool: &PgPool,
+    bucket_client: &BC,
+    video_id: &Uuid,
+    language: &str,
+    revision: Option<i32>,
+) -> Result<(i32, Document), AppError> {
+    let revision = match revision {
+        Some(revision) => revision,
+        None => queries::subtitles_revision::next_revision(pool, video_id, language).await? - 1,
+    };
+
+    let srt_uri = find_revision_uri(pool, video_id, language, revision).await?;
+    let srt = bucket_client.download_file(&srt_uri).await?;
+    let srt = String::from_utf8(srt).map_err(|e| AppError::bad_request(e.to_string()))?;
+    let document = Document::parse(&srt, Format::Srt)?;
+
+    return Ok((revision, document));
+}
+
+/// Stores the subtitles as a new srt revision, returning its number
+pub async fn save_subtitles<BC: BucketClient>(
+    pool: &PgPool,
+    bucket_client: &BC,
+    video_id: &Uuid,
+    language: &str,
+    document: &Document,
+    user_id: i32,
+) -> Result<i32, AppError> {
+    document.validate()?;
+
+    // only translated languages have subtitles to edit
+    find_translation_uri(pool, video_id, language).await?;
+
+    let path = queries::subtitles_revision::path(video_id, language);
+    bucket_client
+        .upload_file(&path, document.write(Format::Srt).into_bytes())
+        .await?;
+
+    let revision = queries::subtitles_revision::next_revision(pool, video_id, language).await?;
+
+    let dto = CreateRevisionDto {
+        video_id,
+        language,
+        revision,
+        storage_id: bucket_client.id(),
+        path: &path,
+        user_id,
+    };
+    queries::subtitles_revision::create(pool, dto)
+        .await
+        .map_err(|e| match &e {
+            sqlx::Error::Database(db) if db.is_unique_violation() => AppError::conflict(
+                "The subtitles were saved by someone else meanwhile, reload them".to_string(),
+            ),
+            _ => e.into(),
+        })?;
+
+    return Ok(revision);
+}
+
+/// Subtitles the output again with the srt of the revision.
+/// A published output is final, the queue would subtitle it again but never upload it
+pub async fn render_subtitles(
+    pool: &PgPool,
+    video_id: &Uuid,
+    language: &str,
+    revision: i32,
+) -> Result<(), AppError> {
+    let output = queries::output::find_by_video_id_and_language(pool, video_id, language).await?;
+    if output.stage == VideoStage::Done || output.url.is_some() {
+        return Err(AppError::conflict(format!(
+            "The video in {} is already published",
+            language
+        )));
+    }
+
+    let srt_uri = find_revision_uri(pool, video_id, language, revision).await?;
+
+    let payload = PayloadType::BatukaSrtTranslationUpload(SrtPayload {
+        video_id: *video_id,
+        srt_uri,
+        language: Some(language.to_string()),
+    });
+
+    let mut trx = pool.begin().await?;
+    // the output was already subtitled, the handler must not skip it
+    queries::checkpoint::delete(&mut *trx, video_id, VideoStage::Subtitling, Some(language))
+        .await?;
+    queries::outbox::enqueue(&mut *trx, &payload).await?;
+    trx.commit().await?;
+
+    return Ok(());
+}
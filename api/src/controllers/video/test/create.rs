@@ -1,7 +1,10 @@
 use std::sync::Arc;
 
 use actix_http::StatusCode;
-use marco_polo_rs_core::database::{models::video::Video, queries};
+use marco_polo_rs_core::database::{
+    models::video::{speaker_labels::SpeakerLabels, subtitles_mode::SubtitlesMode, Video},
+    queries,
+};
 use sqlx::PgPool;
 
 use actix_web::{
@@ -13,15 +16,15 @@ use actix_web::{
 
 use crate::{
     controllers::{
-        test::mock::{cloud_service::CloudServiceMock, video_platform::youtube::YoutubeClientMock},
+        test::mock::video_platform::youtube::YoutubeClientMock,
         video::dtos::{
-            create::{Create, Cut},
+            create::{Create, Cut, CutLanguage},
             VideoDTO,
         },
     },
     models::error::AppErrorResponse,
     utils::test::get_token,
-    AppCloudService, AppPool, AppYoutubeClient,
+    AppPool, AppYoutubeClient,
 };
 
 use super::{super::create_video, innit_test_app};
@@ -67,6 +70,10 @@ async fn test_create_video_ok(pool: PgPool) {
     assert_eq!(video.title, dto.cuts[0].title);
     assert_eq!(video.description, dto.cuts[0].description);
     assert_eq!(video.channel_id, dto.cuts[0].channel_id);
+
+    let pending = queries::outbox::find_unsent(pool.as_ref()).await.unwrap();
+    assert_eq!(pending.len(), 1);
+    assert!(pending[0].payload.contains("BatukaDownloadVideo"));
 }
 
 #[sqlx::test(
@@ -243,20 +250,13 @@ async fn test_create_video_channel_error_when_getting_info(pool: PgPool) {
     let pool = AppPool { pool };
 
     let web_data = web::Data::new(pool);
-    let app_cloud_service = web::Data::new(AppCloudService {
-        client: Arc::new(CloudServiceMock::new()),
-    });
     let app_youtube_client = web::Data::new(AppYoutubeClient {
         client: Arc::new(YoutubeClientMock::with_error()),
     });
     let app = App::new()
         .app_data(web_data)
-        .app_data(app_cloud_service)
         .app_data(app_youtube_client)
-        .route(
-            "/video",
-            post().to(create_video::<CloudServiceMock, YoutubeClientMock>),
-        );
+        .route("/video", post().to(create_video::<YoutubeClientMock>));
 
     let test_app = test::init_service(app).await;
 
@@ -379,6 +379,287 @@ async fn test_create_video_bad_request_start_time_end_time(pool: PgPool) {
     }
 }
 
+#[sqlx::test(
+    migrations = "../migrations",
+    fixtures("../../../test/fixtures/channels")
+)]
+async fn test_create_video_with_languages(pool: PgPool) {
+    let jwt = get_token!(&pool, 1);
+    let pool = Arc::new(pool);
+    let app = innit_test_app(pool.clone()).await;
+
+    let cut = Cut {
+        channel_id: 1,
+        description: "This is a test video about Elon Musk".to_string(),
+        title: "Elon Musk Test".to_string(),
+        ..Default::default()
+    };
+
+    let dto = Create {
+        video_url: "https://www.youtube.com/watch?v=1".to_string(),
+        language: Some("English".to_string()),
+        target_language: Some("es".to_string()),
+        cuts: vec![cut],
+        ..Default::default()
+    };
+
+    let request = test::TestRequest::post()
+        .uri("/video")
+        .insert_header(("Authorization", jwt))
+        .insert_header(ContentType::json())
+        .set_json(&dto)
+        .to_request();
+
+    let response = test::call_service(&app, request).await;
+
+    assert_eq!(response.status().as_u16(), StatusCode::CREATED);
+
+    let video: Video = sqlx::query_as("SELECT * FROM videos WHERE channel_id = 1")
+        .fetch_one(pool.as_ref())
+        .await
+        .unwrap();
+
+    assert_eq!(video.language, "en");
+    assert_eq!(video.target_language, "es");
+}
+
+#[sqlx::test(
+    migrations = "../migrations",
+    fixtures("../../../test/fixtures/channels")
+)]
+async fn test_create_video_with_soft_subtitles(pool: PgPool) {
+    let jwt = get_token!(&pool, 1);
+    let pool = Arc::new(pool);
+    let app = innit_test_app(pool.clone()).await;
+
+    let cut = Cut {
+        channel_id: 1,
+        description: "This is a test video about Elon Musk".to_string(),
+        title: "Elon Musk Test".to_string(),
+        ..Default::default()
+    };
+
+    let dto = Create {
+        video_url: "https://www.youtube.com/watch?v=1".to_string(),
+        subtitles: Some(SubtitlesMode::Both),
+        speaker_labels: Some(SpeakerLabels::Colors),
+        cuts: vec![cut],
+        ..Default::default()
+    };
+
+    let request = test::TestRequest::post()
+        .uri("/video")
+        .insert_header(("Authorization", jwt))
+        .insert_header(ContentType::json())
+        .set_json(&dto)
+        .to_request();
+
+    let response = test::call_service(&app, request).await;
+
+    assert_eq!(response.status().as_u16(), StatusCode::CREATED);
+
+    let video: Video = sqlx::query_as("SELECT * FROM videos WHERE channel_id = 1")
+        .fetch_one(pool.as_ref())
+        .await
+        .unwrap();
+
+    let mode = queries::video::find_subtitles_mode(pool.as_ref(), &video.id)
+        .await
+        .unwrap();
+    assert_eq!(mode, SubtitlesMode::Both);
+
+    let speaker_labels = queries::video::find_speaker_labels(pool.as_ref(), &video.id)
+        .await
+        .unwrap();
+    assert_eq!(speaker_labels, SpeakerLabels::Colors);
+}
+
+#[sqlx::test(
+    migrations = "../migrations",
+    fixtures("../../../test/fixtures/channels")
+)]
+async fn test_create_video_not_found_when_style_does_not_exist(pool: PgPool) {
+    let jwt = get_token!(&pool, 1);
+    let pool = Arc::new(pool);
+    let app = innit_test_app(pool.clone()).await;
+
+    let cut = Cut {
+        channel_id: 1,
+        description: "This is a test video about Elon Musk".to_string(),
+        title: "Elon Musk Test".to_string(),
+        ..Default::default()
+    };
+
+    let dto = Create {
+        video_url: "https://www.youtube.com/watch?v=1".to_string(),
+        subtitles_style_id: Some(1),
+        cuts: vec![cut],
+        ..Default::default()
+    };
+
+    let request = test::TestRequest::post()
+        .uri("/video")
+        .insert_header(("Authorization", jwt))
+        .insert_header(ContentType::json())
+        .set_json(&dto)
+        .to_request();
+
+    let response = test::call_service(&app, request).await;
+
+    assert_eq!(response.status().as_u16(), StatusCode::NOT_FOUND);
+}
+
+#[sqlx::test(
+    migrations = "../migrations",
+    fixtures("../../../test/fixtures/channels")
+)]
+async fn test_create_video_bad_request_unsupported_language(pool: PgPool) {
+    let jwt = get_token!(&pool, 1);
+    let pool = Arc::new(pool);
+    let app = innit_test_app(pool.clone()).await;
+
+    let cut = Cut {
+        channel_id: 1,
+        description: "This is a test video about Elon Musk".to_string(),
+        title: "Elon Musk Test".to_string(),
+        ..Default::default()
+    };
+
+    let dto = Create {
+        video_url: "https://www.youtube.com/watch?v=1".to_string(),
+        target_language: Some("klingon".to_string()),
+        cuts: vec![cut],
+        ..Default::default()
+    };
+
+    let request = test::TestRequest::post()
+        .uri("/video")
+        .insert_header(("Authorization", jwt))
+        .insert_header(ContentType::json())
+        .set_json(&dto)
+        .to_request();
+
+    let response = test::call_service(&app, request).await;
+
+    assert_eq!(response.status().as_u16(), StatusCode::BAD_REQUEST);
+
+    let body: AppErrorResponse = test::read_body_json(response).await;
+
+    let error = body.errors[0]
+        .split(": ")
+        .collect::<Vec<&str>>()
+        .pop()
+        .unwrap();
+    assert_eq!(error, "Unsupported language".to_string());
+}
+
+#[sqlx::test(
+    migrations = "../migrations",
+    fixtures("../../../test/fixtures/channels")
+)]
+async fn test_create_video_with_multiple_outputs(pool: PgPool) {
+    let jwt = get_token!(&pool, 1);
+    let pool = Arc::new(pool);
+    let app = innit_test_app(pool.clone()).await;
+
+    let cut = Cut {
+        channel_id: 1,
+        description: "This is a test video about Elon Musk".to_string(),
+        title: "Elon Musk Test".to_string(),
+        languages: Some(vec![
+            CutLanguage {
+                language: "es".to_string(),
+                channel_id: None,
+            },
+            CutLanguage {
+                language: "pt-br".to_string(),
+                channel_id: None,
+            },
+        ]),
+        ..Default::default()
+    };
+
+    let dto = Create {
+        video_url: "https://www.youtube.com/watch?v=1".to_string(),
+        cuts: vec![cut],
+        ..Default::default()
+    };
+
+    let request = test::TestRequest::post()
+        .uri("/video")
+        .insert_header(("Authorization", jwt))
+        .insert_header(ContentType::json())
+        .set_json(&dto)
+        .to_request();
+
+    let response = test::call_service(&app, request).await;
+
+    assert_eq!(response.status().as_u16(), StatusCode::CREATED);
+
+    let video: Video = sqlx::query_as("SELECT * FROM videos WHERE channel_id = 1")
+        .fetch_one(pool.as_ref())
+        .await
+        .unwrap();
+
+    assert_eq!(video.target_language, "es");
+
+    let outputs = queries::output::find_by_video_id(pool.as_ref(), &video.id)
+        .await
+        .unwrap();
+
+    assert_eq!(outputs.len(), 2);
+    assert_eq!(outputs[0].language, "es");
+    assert_eq!(outputs[1].language, "pt-br");
+    assert_eq!(outputs[1].channel_id, 1);
+}
+
+#[sqlx::test(
+    migrations = "../migrations",
+    fixtures("../../../test/fixtures/channels")
+)]
+async fn test_create_video_bad_request_duplicated_language(pool: PgPool) {
+    let jwt = get_token!(&pool, 1);
+    let pool = Arc::new(pool);
+    let app = innit_test_app(pool.clone()).await;
+
+    let language = CutLanguage {
+        language: "es".to_string(),
+        channel_id: None,
+    };
+
+    let cut = Cut {
+        channel_id: 1,
+        description: "This is a test video about Elon Musk".to_string(),
+        title: "Elon Musk Test".to_string(),
+        languages: Some(vec![language.clone(), language]),
+        ..Default::default()
+    };
+
+    let dto = Create {
+        video_url: "https://www.youtube.com/watch?v=1".to_string(),
+        cuts: vec![cut],
+        ..Default::default()
+    };
+
+    let request = test::TestRequest::post()
+        .uri("/video")
+        .insert_header(("Authorization", jwt))
+        .insert_header(ContentType::json())
+        .set_json(&dto)
+        .to_request();
+
+    let response = test::call_service(&app, request).await;
+
+    assert_eq!(response.status().as_u16(), StatusCode::BAD_REQUEST);
+
+    let count: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM videos")
+        .fetch_one(pool.as_ref())
+        .await
+        .unwrap();
+
+    assert_eq!(count.0, 0);
+}
+
 #[sqlx::test(
     migrations = "../migrations",
     fixtures("../../../test/fixtures/channels")
@@ -0,0 +1,159 @@
+use std::{pin::Pin, str::FromStr, sync::Arc, time::Duration};
+
+use actix_http::Request;
+use actix_web::{
+    body::{BoxBody, MessageBody},
+    dev::ServiceResponse,
+    test,
+    web::{self},
+};
+use marco_polo_rs_core::database::models::{
+    video::stage::VideoStage,
+    video_event::{ProgressStep, VideoEvent, VideoEventKind},
+};
+use reqwest::StatusCode;
+use sqlx::PgPool;
+use tokio::sync::broadcast;
+use uuid::Uuid;
+
+use crate::{
+    controllers::{
+        test::{
+            create_test_app,
+            mock::{cloud_service::CloudServiceMock, video_platform::youtube::YoutubeClientMock},
+        },
+        video::create_scope,
+    },
+    events,
+    utils::test::get_token,
+    AppPool, AppVideoEvents,
+};
+
+const VIDEO_ID: &str = "2c20e6d2-7bce-47b7-b02d-7f45fb106df5";
+const OTHER_VIDEO_ID: &str = "806b57d2-f221-11ed-a05b-0242ac120003";
+
+async fn innit_events_test_app(
+    pool: Arc<PgPool>,
+    sender: broadcast::Sender<VideoEvent>,
+) -> impl actix_web::dev::Service<Request, Response = ServiceResponse, Error = actix_web::Error> {
+    let app = create_test_app()
+        .app_data(web::Data::new(AppPool { pool }))
+        .app_data(web::Data::new(AppVideoEvents { sender }))
+        .service(create_scope::<YoutubeClientMock, CloudServiceMock>());
+
+    return test::init_service(app).await;
+}
+
+async fn next_chunk(body: &mut BoxBody) -> String {
+    let chunk = tokio::time::timeout(
+        Duration::from_secs(5),
+        futures::future::poll_fn(|cx| Pin::new(&mut *body).poll_next(cx)),
+    )
+    .await
+    .expect("The stream sent nothing")
+    .unwrap()
+    .unwrap();
+
+    return String::from_utf8(chunk.to_vec()).unwrap();
+}
+
+fn progress_event(video_id: &str, user_id: i32) -> VideoEvent {
+    VideoEvent {
+        video_id: Uuid::from_str(video_id).unwrap(),
+        user_id,
+        kind: VideoEventKind::Progress {
+            step: ProgressStep::Download,
+            percentage: 50.0,
+        },
+    }
+}
+
+#[sqlx::test(
+    migrations = "../migrations",
+    fixtures("../../../test/fixtures/videos")
+)]
+async fn test_video_events_start_with_the_current_stage(pool: PgPool) {
+    let pool = Arc::new(pool);
+    let token = get_token!(pool.as_ref(), 789);
+    let sender = events::channel();
+
+    let test_app = innit_events_test_app(pool.clone(), sender.clone()).await;
+
+    let request = test::TestRequest::get()
+        .uri(&format!("/video/{}/events", VIDEO_ID))
+        .insert_header(("Authorization", token))
+        .to_request();
+
+    let response = test::call_service(&test_app, request).await;
+    assert_eq!(response.status().as_u16(), StatusCode::OK);
+    assert_eq!(
+        response.headers().get("Content-Type").unwrap(),
+        "text/event-stream"
+    );
+
+    let mut body = response.into_body();
+
+    let current = next_chunk(&mut body).await;
+    assert!(current.starts_with("event: stage\ndata: "));
+    let expected_stage = serde_json::to_string(&VideoStage::Downloading).unwrap();
+    assert!(current.contains(&expected_stage));
+
+    sender.send(progress_event(OTHER_VIDEO_ID, 456)).unwrap();
+    sender.send(progress_event(VIDEO_ID, 789)).unwrap();
+
+    let progress = next_chunk(&mut body).await;
+    let data = progress
+        .strip_prefix("event: progress\ndata: ")
+        .unwrap()
+        .trim_end();
+    let event: VideoEvent = serde_json::from_str(data).unwrap();
+    assert_eq!(event, progress_event(VIDEO_ID, 789));
+}
+
+#[sqlx::test(
+    migrations = "../migrations",
+    fixtures("../../../test/fixtures/videos")
+)]
+async fn test_video_events_of_another_user_not_found(pool: PgPool) {
+    let pool = Arc::new(pool);
+    let token = get_token!(pool.as_ref(), 789);
+
+    let test_app = innit_events_test_app(pool.clone(), events::channel()).await;
+
+    let request = test::TestRequest::get()
+        .uri(&format!("/video/{}/events", OTHER_VIDEO_ID))
+        .insert_header(("Authorization", token))
+        .to_request();
+
+    let response = test::call_service(&test_app, request).await;
+    assert_eq!(response.status().as_u16(), StatusCode::NOT_FOUND);
+}
+
+#[sqlx::test(
+    migrations = "../migrations",
+    fixtures("../../../test/fixtures/videos")
+)]
+async fn test_user_events_only_stream_own_videos(pool: PgPool) {
+    let pool = Arc::new(pool);
+    let token = get_token!(pool.as_ref(), 789);
+    let sender = events::channel();
+
+    let test_app = innit_events_test_app(pool.clone(), sender.clone()).await;
+
+    let request = test::TestRequest::get()
+        .uri("/video/events")
+        .insert_header(("Authorization", token))
+        .to_request();
+
+    let response = test::call_service(&test_app, request).await;
+    assert_eq!(response.status().as_u16(), StatusCode::OK);
+
+    sender.send(progress_event(OTHER_VIDEO_ID, 456)).unwrap();
+    sender.send(progress_event(VIDEO_ID, 789)).unwrap();
+
+    let mut body = response.into_body();
+    let chunk = next_chunk(&mut body).await;
+
+    assert!(chunk.contains(VIDEO_ID));
+    assert!(!chunk.contains(OTHER_VIDEO_ID));
+}
@@ -0,0 +1,28 @@
+UPDATE videos
+SET error = true,
+  stage = 'TRANSCRIBING'
+WHERE id = '806b57d2-f221-11ed-a05b-0242ac120003';
+INSERT INTO videos_outputs (video_id, channel_id, language, stage)
+VALUES (
+    '806b57d2-f221-11ed-a05b-0242ac120003',
+    666,
+    'pt-br',
+    'TRANSLATING'
+  );
+INSERT INTO videos_storages (video_id, storage_id, video_path, format, stage, size)
+VALUES (
+    '806b57d2-f221-11ed-a05b-0242ac120003',
+    2,
+    'videos/raw/806b57d2-f221-11ed-a05b-0242ac120003.mkv',
+    'MKV',
+    'RAW',
+    1000
+  );
+INSERT INTO videos_transcriptions (video_id, transcriber_id, transcription_id, storage_id, path)
+VALUES (
+    '806b57d2-f221-11ed-a05b-0242ac120003',
+    3,
+    'transcription_id',
+    2,
+    'srt_transcriptions/806b57d2-f221-11ed-a05b-0242ac120003.srt'
+  );
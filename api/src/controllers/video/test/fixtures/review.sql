@@ -0,0 +1,25 @@
+UPDATE videos
+SET review = true,
+  stage = 'REVIEWING'
+WHERE id = '806b57d2-f221-11ed-a05b-0242ac120003';
+INSERT INTO videos_outputs (video_id, channel_id, language, stage)
+VALUES (
+    '806b57d2-f221-11ed-a05b-0242ac120003',
+    666,
+    'pt-br',
+    'REVIEWING'
+  );
+INSERT INTO videos_translations (video_id, translator_id, storage_id, path, language)
+VALUES (
+    '806b57d2-f221-11ed-a05b-0242ac120003',
+    4,
+    2,
+    'srt_translations/806b57d2-f221-11ed-a05b-0242ac120003.pt-br.srt',
+    'pt-br'
+  );
+INSERT INTO videos_reviews (video_id, language, srt)
+VALUES (
+    '806b57d2-f221-11ed-a05b-0242ac120003',
+    'pt-br',
+    E'1\n00:00:01,000 --> 00:00:02,500\nOlá mundo\n\n2\n00:00:03,000 --> 00:00:04,000\nTchau\n\n'
+  );
@@ -0,0 +1,15 @@
+INSERT INTO videos_outputs (video_id, channel_id, language, stage)
+VALUES (
+    '806b57d2-f221-11ed-a05b-0242ac120003',
+    666,
+    'pt-br',
+    'REVIEWING'
+  );
+INSERT INTO videos_translations (video_id, translator_id, storage_id, path, language)
+VALUES (
+    '806b57d2-f221-11ed-a05b-0242ac120003',
+    4,
+    1,
+    'srt_translations/806b57d2-f221-11ed-a05b-0242ac120003.pt-br.srt',
+    'pt-br'
+  );
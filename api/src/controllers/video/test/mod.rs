@@ -23,7 +23,7 @@ use crate::{
         },
         video::dtos::VideoErrorDTO,
     },
-    AppCloudService, AppYoutubeClient,
+    AppYoutubeClient,
 };
 
 use crate::controllers::video::dtos::VideoDTO;
@@ -35,6 +35,14 @@ use super::create_scope;
 
 #[cfg(test)]
 mod create;
+#[cfg(test)]
+mod events;
+#[cfg(test)]
+mod retry;
+#[cfg(test)]
+mod review;
+#[cfg(test)]
+mod subtitles;
 
 #[sqlx::test(
     migrations = "../migrations",
@@ -59,6 +67,7 @@ async fn test_find_by_id_get_ok(pool: PgPool) {
         channel_id: 666,
         url: Some("https://video.com".to_string()),
         language: "English".to_string(),
+        target_language: "pt-br".to_string(),
         created_at: date,
         updated_at: date,
         tags: Some(vec!["elon-musk".to_string(), "test".to_string()]),
@@ -422,19 +431,15 @@ async fn innit_test_app(
 ) -> impl actix_web::dev::Service<Request, Response = ServiceResponse, Error = actix_web::Error> {
     let pool = AppPool { pool };
     let web_data = web::Data::new(pool);
-    let app_cloud_service = web::Data::new(AppCloudService {
-        client: Arc::new(CloudServiceMock::new()),
-    });
     let app_youtube_client = web::Data::new(AppYoutubeClient {
         client: Arc::new(YoutubeClientMock::new()),
     });
 
     let app = create_test_app();
-    let scope = create_scope::<CloudServiceMock, YoutubeClientMock>();
+    let scope = create_scope::<YoutubeClientMock, CloudServiceMock>();
 
     let app = app
         .app_data(web_data)
-        .app_data(app_cloud_service)
         .app_data(app_youtube_client)
         .service(scope);
 
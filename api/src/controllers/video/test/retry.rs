@@ -0,0 +1,203 @@
+use std::{str::FromStr, sync::Arc};
+
+use actix_web::{http::header::ContentType, test};
+use marco_polo_rs_core::{database::queries, internals::cloud::models::payload::PayloadType};
+use reqwest::StatusCode;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    controllers::video::service::{find_resume_payloads, retry_video},
+    models::error::AppErrorType,
+    utils::test::get_token,
+};
+
+use super::innit_test_app;
+
+const VIDEO_ID: &str = "806b57d2-f221-11ed-a05b-0242ac120003";
+
+#[sqlx::test(
+    migrations = "../migrations",
+    fixtures("../../../test/fixtures/videos", "retry")
+)]
+async fn test_resume_from_transcription(pool: PgPool) {
+    let id = Uuid::from_str(VIDEO_ID).unwrap();
+    let video = queries::video::find_by_id(&pool, &id).await.unwrap();
+
+    let payloads = find_resume_payloads(&pool, &video).await.unwrap();
+
+    assert_eq!(payloads.len(), 1);
+    match &payloads[0] {
+        PayloadType::BatukaSrtTranscriptionUpload(payload) => {
+            assert_eq!(
+                payload.srt_uri,
+                "srt_transcriptions/806b57d2-f221-11ed-a05b-0242ac120003.srt"
+            );
+        }
+        payload => panic!("unexpected payload {:?}", payload),
+    }
+}
+
+#[sqlx::test(
+    migrations = "../migrations",
+    fixtures("../../../test/fixtures/videos", "retry")
+)]
+async fn test_resume_outputs_from_translation(pool: PgPool) {
+    let id = Uuid::from_str(VIDEO_ID).unwrap();
+
+    sqlx::query!(
+        r#"
+        UPDATE videos SET stage = 'SUBTITLING' WHERE id = $1;
+        "#,
+        id
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    sqlx::query!(
+        r#"
+        INSERT INTO videos_translations (video_id, translator_id, storage_id, path, language)
+        VALUES ($1, 4, 2, 'srt_translations/806b57d2-f221-11ed-a05b-0242ac120003.pt-br.srt', 'pt-br');
+        "#,
+        id
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let video = queries::video::find_by_id(&pool, &id).await.unwrap();
+    let payloads = find_resume_payloads(&pool, &video).await.unwrap();
+
+    assert_eq!(payloads.len(), 1);
+    match &payloads[0] {
+        PayloadType::BatukaSrtTranslationUpload(payload) => {
+            assert_eq!(payload.language, Some("pt-br".to_string()));
+        }
+        payload => panic!("unexpected payload {:?}", payload),
+    }
+}
+
+#[sqlx::test(
+    migrations = "../migrations",
+    fixtures("../../../test/fixtures/videos", "retry")
+)]
+async fn test_resume_from_download_without_storage(pool: PgPool) {
+    let id = Uuid::from_str(VIDEO_ID).unwrap();
+
+    sqlx::query!(
+        r#"
+        DELETE FROM videos_transcriptions WHERE video_id = $1;
+        "#,
+        id
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    sqlx::query!(
+        r#"
+        DELETE FROM videos_storages WHERE video_id = $1;
+        "#,
+        id
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let video = queries::video::find_by_id(&pool, &id).await.unwrap();
+    let payloads = find_resume_payloads(&pool, &video).await.unwrap();
+
+    match &payloads[0] {
+        PayloadType::BatukaDownloadVideo(payload) => {
+            assert_eq!(payload.video_ids, vec![id]);
+        }
+        payload => panic!("unexpected payload {:?}", payload),
+    }
+}
+
+#[sqlx::test(
+    migrations = "../migrations",
+    fixtures("../../../test/fixtures/videos", "retry")
+)]
+async fn test_retry_video_ok(pool: PgPool) {
+    let pool = Arc::new(pool);
+    let token = get_token!(pool.as_ref(), 456);
+
+    let test_app = innit_test_app(pool.clone()).await;
+
+    let request = test::TestRequest::post()
+        .uri(&format!("/video/{}/retry", VIDEO_ID))
+        .insert_header(ContentType::json())
+        .insert_header(("Authorization", token))
+        .to_request();
+
+    let response = test::call_service(&test_app, request).await;
+    assert_eq!(response.status().as_u16(), StatusCode::OK);
+
+    let id = Uuid::from_str(VIDEO_ID).unwrap();
+    let video = queries::video::find_by_id(pool.as_ref(), &id)
+        .await
+        .unwrap();
+    assert!(!video.error);
+
+    let pending = queries::outbox::find_unsent(pool.as_ref()).await.unwrap();
+    assert_eq!(pending.len(), 1);
+}
+
+#[sqlx::test(
+    migrations = "../migrations",
+    fixtures("../../../test/fixtures/videos", "retry")
+)]
+async fn test_retry_video_claimed_once(pool: PgPool) {
+    let id = Uuid::from_str(VIDEO_ID).unwrap();
+    let video = queries::video::find_by_id(&pool, &id).await.unwrap();
+
+    retry_video(&pool, &video).await.unwrap();
+    // a retry sent along with the first one still sees the video failed
+    let error = retry_video(&pool, &video).await.unwrap_err();
+
+    assert_eq!(error.error_type, AppErrorType::Conflict);
+    let pending = queries::outbox::find_unsent(&pool).await.unwrap();
+    assert_eq!(pending.len(), 1);
+}
+
+#[sqlx::test(
+    migrations = "../migrations",
+    fixtures("../../../test/fixtures/videos", "retry")
+)]
+async fn test_retry_video_not_failed(pool: PgPool) {
+    let pool = Arc::new(pool);
+    let token = get_token!(pool.as_ref(), 456);
+
+    let test_app = innit_test_app(pool.clone()).await;
+
+    let request = test::TestRequest::post()
+        .uri("/video/b7a720e3-010e-4d88-919b-7aee4d7a3144/retry")
+        .insert_header(ContentType::json())
+        .insert_header(("Authorization", token))
+        .to_request();
+
+    let response = test::call_service(&test_app, request).await;
+    assert_eq!(response.status().as_u16(), StatusCode::BAD_REQUEST);
+}
+
+#[sqlx::test(
+    migrations = "../migrations",
+    fixtures("../../../test/fixtures/videos", "retry")
+)]
+async fn test_retry_video_of_other_user(pool: PgPool) {
+    let pool = Arc::new(pool);
+    let token = get_token!(pool.as_ref(), 789);
+
+    let test_app = innit_test_app(pool.clone()).await;
+
+    let request = test::TestRequest::post()
+        .uri(&format!("/video/{}/retry", VIDEO_ID))
+        .insert_header(ContentType::json())
+        .insert_header(("Authorization", token))
+        .to_request();
+
+    let response = test::call_service(&test_app, request).await;
+    assert_eq!(response.status().as_u16(), StatusCode::NOT_FOUND);
+}
@@ -0,0 +1,171 @@
+use std::{str::FromStr, sync::Arc};
+
+use actix_web::{http::header::ContentType, test};
+use marco_polo_rs_core::{database::queries, internals::cloud::models::payload::PayloadType};
+use reqwest::StatusCode;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    controllers::video::dtos::review::{ReviewDTO, UpdateReviewLine},
+    utils::test::get_token,
+};
+
+use super::innit_test_app;
+
+const VIDEO_ID: &str = "806b57d2-f221-11ed-a05b-0242ac120003";
+
+#[sqlx::test(
+    migrations = "../migrations",
+    fixtures("../../../test/fixtures/videos", "review")
+)]
+async fn test_find_video_reviews(pool: PgPool) {
+    let pool = Arc::new(pool);
+    let token = get_token!(pool.as_ref(), 456);
+    let test_app = innit_test_app(pool.clone()).await;
+
+    let request = test::TestRequest::get()
+        .uri(&format!("/video/{}/reviews", VIDEO_ID))
+        .insert_header(("Authorization", token))
+        .to_request();
+
+    let response = test::call_service(&test_app, request).await;
+    assert_eq!(response.status().as_u16(), StatusCode::OK);
+
+    let reviews: Vec<ReviewDTO> = test::read_body_json(response).await;
+    assert_eq!(reviews.len(), 1);
+    assert!(!reviews[0].approved);
+    assert_eq!(reviews[0].lines.len(), 2);
+    assert_eq!(reviews[0].lines[0].start_time, "00:00:01,000");
+    assert_eq!(reviews[0].lines[0].end_time, "00:00:02,500");
+    assert_eq!(reviews[0].lines[1].text, "Tchau");
+}
+
+#[sqlx::test(
+    migrations = "../migrations",
+    fixtures("../../../test/fixtures/videos", "review")
+)]
+async fn test_update_review_line(pool: PgPool) {
+    let pool = Arc::new(pool);
+    let token = get_token!(pool.as_ref(), 456);
+    let test_app = innit_test_app(pool.clone()).await;
+
+    let body = UpdateReviewLine {
+        text: Some("Até logo".to_string()),
+        end_time: Some("00:00:04,200".to_string()),
+        ..Default::default()
+    };
+
+    let request = test::TestRequest::patch()
+        .uri(&format!("/video/{}/reviews/pt-br/lines/2", VIDEO_ID))
+        .insert_header(ContentType::json())
+        .insert_header(("Authorization", token.clone()))
+        .set_json(&body)
+        .to_request();
+
+    let response = test::call_service(&test_app, request).await;
+    assert_eq!(response.status().as_u16(), StatusCode::OK);
+
+    let review: ReviewDTO = test::read_body_json(response).await;
+    assert_eq!(review.lines[1].text, "Até logo");
+    assert_eq!(review.lines[1].start_time, "00:00:03,000");
+    assert_eq!(review.lines[1].end_time, "00:00:04,200");
+    assert_eq!(review.lines[0].text, "Olá mundo");
+
+    // ending before it starts
+    let body = UpdateReviewLine {
+        end_time: Some("00:00:00,500".to_string()),
+        ..Default::default()
+    };
+
+    let request = test::TestRequest::patch()
+        .uri(&format!("/video/{}/reviews/pt-br/lines/1", VIDEO_ID))
+        .insert_header(ContentType::json())
+        .insert_header(("Authorization", token.clone()))
+        .set_json(&body)
+        .to_request();
+
+    let response = test::call_service(&test_app, request).await;
+    assert_eq!(response.status().as_u16(), StatusCode::BAD_REQUEST);
+
+    let request = test::TestRequest::patch()
+        .uri(&format!("/video/{}/reviews/pt-br/lines/3", VIDEO_ID))
+        .insert_header(ContentType::json())
+        .insert_header(("Authorization", token))
+        .set_json(&UpdateReviewLine::default())
+        .to_request();
+
+    let response = test::call_service(&test_app, request).await;
+    assert_eq!(response.status().as_u16(), StatusCode::NOT_FOUND);
+}
+
+#[sqlx::test(
+    migrations = "../migrations",
+    fixtures("../../../test/fixtures/videos", "review")
+)]
+async fn test_approve_review(pool: PgPool) {
+    let pool = Arc::new(pool);
+    let token = get_token!(pool.as_ref(), 456);
+    let test_app = innit_test_app(pool.clone()).await;
+
+    let request = test::TestRequest::post()
+        .uri(&format!("/video/{}/reviews/pt-br/approve", VIDEO_ID))
+        .insert_header(("Authorization", token.clone()))
+        .to_request();
+
+    let response = test::call_service(&test_app, request).await;
+    assert_eq!(response.status().as_u16(), StatusCode::OK);
+
+    let review: ReviewDTO = test::read_body_json(response).await;
+    assert!(review.approved);
+
+    let messages = queries::outbox::find_unsent(pool.as_ref()).await.unwrap();
+    assert_eq!(messages.len(), 1);
+    match PayloadType::from_json(&messages[0].payload).unwrap() {
+        PayloadType::BatukaSrtTranslationUpload(payload) => {
+            assert_eq!(payload.video_id, Uuid::from_str(VIDEO_ID).unwrap());
+            assert_eq!(payload.language, Some("pt-br".to_string()));
+        }
+        payload => panic!("unexpected payload {:?}", payload),
+    }
+
+    let request = test::TestRequest::post()
+        .uri(&format!("/video/{}/reviews/pt-br/approve", VIDEO_ID))
+        .insert_header(("Authorization", token.clone()))
+        .to_request();
+
+    let response = test::call_service(&test_app, request).await;
+    assert_eq!(response.status().as_u16(), StatusCode::CONFLICT);
+
+    let messages = queries::outbox::find_unsent(pool.as_ref()).await.unwrap();
+    assert_eq!(messages.len(), 1);
+
+    // approved subtitles are final
+    let request = test::TestRequest::patch()
+        .uri(&format!("/video/{}/reviews/pt-br/lines/1", VIDEO_ID))
+        .insert_header(ContentType::json())
+        .insert_header(("Authorization", token))
+        .set_json(&UpdateReviewLine::default())
+        .to_request();
+
+    let response = test::call_service(&test_app, request).await;
+    assert_eq!(response.status().as_u16(), StatusCode::BAD_REQUEST);
+}
+
+#[sqlx::test(
+    migrations = "../migrations",
+    fixtures("../../../test/fixtures/videos", "review")
+)]
+async fn test_review_of_other_user(pool: PgPool) {
+    let pool = Arc::new(pool);
+    let token = get_token!(pool.as_ref(), 789);
+    let test_app = innit_test_app(pool.clone()).await;
+
+    let request = test::TestRequest::post()
+        .uri(&format!("/video/{}/reviews/pt-br/approve", VIDEO_ID))
+        .insert_header(("Authorization", token))
+        .to_request();
+
+    let response = test::call_service(&test_app, request).await;
+    assert_eq!(response.status().as_u16(), StatusCode::NOT_FOUND);
+}
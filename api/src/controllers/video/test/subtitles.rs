@@ -0,0 +1,338 @@
+use std::sync::Arc;
+
+use actix_http::Request;
+use actix_web::{dev::ServiceResponse, http::header::ContentType, test, web};
+use marco_polo_rs_core::{
+    database::queries,
+    internals::cloud::{
+        local::{bucket::LocalBucketClient, queue::LocalQueueClient, LocalCloudService},
+        models::payload::PayloadType,
+        traits::BucketClient,
+    },
+};
+use reqwest::StatusCode;
+use sqlx::PgPool;
+
+use crate::{
+    controllers::{
+        test::{create_test_app, mock::video_platform::youtube::YoutubeClientMock},
+        video::{
+            create_scope,
+            dtos::subtitles::{CueDTO, SubtitlesDTO, SubtitlesRevisionDTO, UpdateSubtitles},
+        },
+    },
+    models::error::AppErrorResponse,
+    utils::test::get_token,
+    AppCloudService, AppPool,
+};
+
+const VIDEO_ID: &str = "806b57d2-f221-11ed-a05b-0242ac120003";
+const TRANSLATED_SRT: &str =
+    "1\n00:00:01,000 --> 00:00:02,500\nOlá mundo\n\n2\n00:00:03,000 --> 00:00:04,000\nTchau\n\n";
+
+/// The subtitles are read from and written to a bucket on a temporary folder
+async fn innit_subtitles_test_app(
+    pool: Arc<PgPool>,
+) -> impl actix_web::dev::Service<Request, Response = ServiceResponse, Error = actix_web::Error> {
+    let root = std::env::temp_dir().join(uuid::Uuid::new_v4().to_string());
+    let bucket_client = LocalBucketClient::new(
+        root,
+        "http://localhost:8080/storage/local".to_string(),
+        "secret".to_string(),
+    );
+
+    let translation_uri = format!("srt_translations/{}.pt-br.srt", VIDEO_ID);
+    bucket_client
+        .upload_file(&translation_uri, TRANSLATED_SRT.as_bytes().to_vec())
+        .await
+        .unwrap();
+
+    let cloud_service = LocalCloudService {
+        bucket_client,
+        queue_client: LocalQueueClient::new(pool.as_ref().clone()),
+    };
+
+    let app = create_test_app()
+        .app_data(web::Data::new(AppPool { pool }))
+        .app_data(web::Data::new(AppCloudService {
+            client: Arc::new(cloud_service),
+        }))
+        .service(create_scope::<YoutubeClientMock, LocalCloudService>());
+
+    return test::init_service(app).await;
+}
+
+fn cue(start_time: &str, end_time: &str, text: &str) -> CueDTO {
+    CueDTO {
+        start_time: start_time.to_string(),
+        end_time: end_time.to_string(),
+        text: text.to_string(),
+    }
+}
+
+#[sqlx::test(
+    migrations = "../migrations",
+    fixtures("../../../test/fixtures/videos", "subtitles")
+)]
+async fn test_edit_subtitles(pool: PgPool) {
+    let pool = Arc::new(pool);
+    let token = get_token!(pool.as_ref(), 456);
+    let test_app = innit_subtitles_test_app(pool.clone()).await;
+
+    let request = test::TestRequest::get()
+        .uri(&format!("/video/{}/subtitles", VIDEO_ID))
+        .insert_header(("Authorization", token.clone()))
+        .to_request();
+
+    let response = test::call_service(&test_app, request).await;
+    assert_eq!(response.status().as_u16(), StatusCode::OK);
+
+    let subtitles: SubtitlesDTO = test::read_body_json(response).await;
+    assert_eq!(subtitles.revision, 0);
+    assert_eq!(subtitles.language, "pt-br");
+    assert_eq!(subtitles.cues.len(), 2);
+
+    let body = UpdateSubtitles {
+        language: Some("pt-br".to_string()),
+        cues: vec![
+            cue("00:00:01,000", "00:00:02,500", "Olá, mundo!"),
+            cue("00:00:02,500", "00:00:04,000", "Tchau"),
+        ],
+    };
+
+    let request = test::TestRequest::put()
+        .uri(&format!("/video/{}/subtitles", VIDEO_ID))
+        .insert_header(ContentType::json())
+        .insert_header(("Authorization", token.clone()))
+        .set_json(&body)
+        .to_request();
+
+    let response = test::call_service(&test_app, request).await;
+    assert_eq!(response.status().as_u16(), StatusCode::CREATED);
+
+    let subtitles: SubtitlesDTO = test::read_body_json(response).await;
+    assert_eq!(subtitles.revision, 1);
+
+    let request = test::TestRequest::get()
+        .uri(&format!("/video/{}/subtitles?language=pt-br", VIDEO_ID))
+        .insert_header(("Authorization", token.clone()))
+        .to_request();
+
+    let response = test::call_service(&test_app, request).await;
+    let subtitles: SubtitlesDTO = test::read_body_json(response).await;
+    assert_eq!(subtitles.revision, 1);
+    assert_eq!(subtitles.cues, body.cues);
+
+    let request = test::TestRequest::get()
+        .uri(&format!("/video/{}/subtitles?revision=0", VIDEO_ID))
+        .insert_header(("Authorization", token.clone()))
+        .to_request();
+
+    let response = test::call_service(&test_app, request).await;
+    let subtitles: SubtitlesDTO = test::read_body_json(response).await;
+    assert_eq!(subtitles.cues[0].text, "Olá mundo");
+
+    let request = test::TestRequest::get()
+        .uri(&format!("/video/{}/subtitles/revisions", VIDEO_ID))
+        .insert_header(("Authorization", token))
+        .to_request();
+
+    let response = test::call_service(&test_app, request).await;
+    let revisions: Vec<SubtitlesRevisionDTO> = test::read_body_json(response).await;
+    assert_eq!(revisions.len(), 1);
+    assert_eq!(revisions[0].user_id, 456);
+}
+
+#[sqlx::test(
+    migrations = "../migrations",
+    fixtures("../../../test/fixtures/videos", "subtitles")
+)]
+async fn test_overlapping_cues(pool: PgPool) {
+    let pool = Arc::new(pool);
+    let token = get_token!(pool.as_ref(), 456);
+    let test_app = innit_subtitles_test_app(pool.clone()).await;
+
+    let body = UpdateSubtitles {
+        language: None,
+        cues: vec![
+            cue("00:00:01,000", "00:00:03,000", "Olá"),
+            cue("00:00:02,000", "00:00:04,000", "Tchau"),
+        ],
+    };
+
+    let request = test::TestRequest::put()
+        .uri(&format!("/video/{}/subtitles", VIDEO_ID))
+        .insert_header(ContentType::json())
+        .insert_header(("Authorization", token))
+        .set_json(&body)
+        .to_request();
+
+    let response = test::call_service(&test_app, request).await;
+    assert_eq!(response.status().as_u16(), StatusCode::BAD_REQUEST);
+
+    let id = uuid::Uuid::parse_str(VIDEO_ID).unwrap();
+    let revisions =
+        queries::subtitles_revision::find_by_video_id_and_language(pool.as_ref(), &id, "pt-br")
+            .await
+            .unwrap();
+    assert!(revisions.is_empty());
+}
+
+#[sqlx::test(
+    migrations = "../migrations",
+    fixtures("../../../test/fixtures/videos", "subtitles")
+)]
+async fn test_render_revision(pool: PgPool) {
+    let pool = Arc::new(pool);
+    let token = get_token!(pool.as_ref(), 456);
+    let test_app = innit_subtitles_test_app(pool.clone()).await;
+
+    let request = test::TestRequest::post()
+        .uri(&format!("/video/{}/subtitles/revisions/1/render", VIDEO_ID))
+        .insert_header(("Authorization", token.clone()))
+        .to_request();
+
+    let response = test::call_service(&test_app, request).await;
+    assert_eq!(response.status().as_u16(), StatusCode::NOT_FOUND);
+
+    let body = UpdateSubtitles {
+        language: None,
+        cues: vec![cue("00:00:01,000", "00:00:02,000", "Olá")],
+    };
+
+    let request = test::TestRequest::put()
+        .uri(&format!("/video/{}/subtitles", VIDEO_ID))
+        .insert_header(ContentType::json())
+        .insert_header(("Authorization", token.clone()))
+        .set_json(&body)
+        .to_request();
+
+    let response = test::call_service(&test_app, request).await;
+    assert_eq!(response.status().as_u16(), StatusCode::CREATED);
+
+    let request = test::TestRequest::post()
+        .uri(&format!("/video/{}/subtitles/revisions/1/render", VIDEO_ID))
+        .insert_header(("Authorization", token))
+        .to_request();
+
+    let response = test::call_service(&test_app, request).await;
+    assert_eq!(response.status().as_u16(), StatusCode::ACCEPTED);
+
+    let id = uuid::Uuid::parse_str(VIDEO_ID).unwrap();
+    let messages = queries::outbox::find_unsent(pool.as_ref()).await.unwrap();
+    assert_eq!(messages.len(), 1);
+    match PayloadType::from_json(&messages[0].payload).unwrap() {
+        PayloadType::BatukaSrtTranslationUpload(payload) => {
+            let revision =
+                queries::subtitles_revision::find_by_revision(pool.as_ref(), &id, "pt-br", 1)
+                    .await
+                    .unwrap();
+            assert_eq!(payload.srt_uri, revision.path);
+            assert_eq!(payload.language, Some("pt-br".to_string()));
+        }
+        payload => panic!("unexpected payload {:?}", payload),
+    }
+}
+
+#[sqlx::test(
+    migrations = "../migrations",
+    fixtures("../../../test/fixtures/videos", "subtitles")
+)]
+async fn test_render_published_output(pool: PgPool) {
+    let pool = Arc::new(pool);
+    let token = get_token!(pool.as_ref(), 456);
+    let test_app = innit_subtitles_test_app(pool.clone()).await;
+
+    let id = uuid::Uuid::parse_str(VIDEO_ID).unwrap();
+    let output = queries::output::find_by_video_id_and_language(pool.as_ref(), &id, "pt-br")
+        .await
+        .unwrap();
+    let mut conn = pool.acquire().await.unwrap();
+    queries::output::set_url(&mut conn, output.id, "https://youtu.be/1")
+        .await
+        .unwrap();
+    drop(conn);
+
+    let request = test::TestRequest::post()
+        .uri(&format!("/video/{}/subtitles/revisions/0/render", VIDEO_ID))
+        .insert_header(("Authorization", token))
+        .to_request();
+
+    let response = test::call_service(&test_app, request).await;
+    assert_eq!(response.status().as_u16(), StatusCode::CONFLICT);
+
+    let messages = queries::outbox::find_unsent(pool.as_ref()).await.unwrap();
+    assert!(messages.is_empty());
+}
+
+#[sqlx::test(
+    migrations = "../migrations",
+    fixtures("../../../test/fixtures/videos", "subtitles")
+)]
+async fn test_subtitles_files(pool: PgPool) {
+    let pool = Arc::new(pool);
+    let token = get_token!(pool.as_ref(), 456);
+    let test_app = innit_subtitles_test_app(pool.clone()).await;
+
+    let request = test::TestRequest::get()
+        .uri(&format!("/video/{}/subtitles/file?format=vtt", VIDEO_ID))
+        .insert_header(("Authorization", token.clone()))
+        .to_request();
+
+    let response = test::call_service(&test_app, request).await;
+    assert_eq!(response.status().as_u16(), StatusCode::OK);
+    assert_eq!(response.headers().get("Content-Type").unwrap(), "text/vtt");
+
+    let body = test::read_body(response).await;
+    assert_eq!(
+        body,
+        "WEBVTT\n\n00:00:01.000 --> 00:00:02.500\nOlá mundo\n\n00:00:03.000 --> 00:00:04.000\nTchau\n\n"
+    );
+
+    let ass = "[Script Info]\nScriptType: v4.00+\n\n[Events]\nFormat: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text\nDialogue: 0,0:00:01.00,0:00:02.00,Default,,0,0,0,,{\\i1}Olá{\\i0}\\Nmundo\n";
+
+    let request = test::TestRequest::put()
+        .uri(&format!("/video/{}/subtitles/file?format=ass", VIDEO_ID))
+        .insert_header(("Authorization", token.clone()))
+        .set_payload(ass)
+        .to_request();
+
+    let response = test::call_service(&test_app, request).await;
+    assert_eq!(response.status().as_u16(), StatusCode::CREATED);
+
+    let subtitles: SubtitlesDTO = test::read_body_json(response).await;
+    assert_eq!(subtitles.revision, 1);
+    assert_eq!(
+        subtitles.cues,
+        vec![cue("00:00:01,000", "00:00:02,000", "<i>Olá</i>\nmundo")]
+    );
+
+    let request = test::TestRequest::get()
+        .uri(&format!("/video/{}/subtitles/file", VIDEO_ID))
+        .insert_header(("Authorization", token.clone()))
+        .to_request();
+
+    let response = test::call_service(&test_app, request).await;
+    assert_eq!(
+        response.headers().get("Content-Type").unwrap(),
+        "application/x-subrip"
+    );
+
+    let body = test::read_body(response).await;
+    assert_eq!(
+        body,
+        "1\n00:00:01,000 --> 00:00:02,000\n<i>Olá</i>\nmundo\n\n"
+    );
+
+    let request = test::TestRequest::put()
+        .uri(&format!("/video/{}/subtitles/file?format=ass", VIDEO_ID))
+        .insert_header(("Authorization", token))
+        .set_payload(ass.replace("0:00:02.00", "0:00:2"))
+        .to_request();
+
+    let response = test::call_service(&test_app, request).await;
+    assert_eq!(response.status().as_u16(), StatusCode::BAD_REQUEST);
+
+    let errors: AppErrorResponse = test::read_body_json(response).await;
+    assert_eq!(errors.errors, vec!["Line 6: Invalid time: 0:00:2"]);
+}
@@ -0,0 +1,86 @@
+use chrono::NaiveDateTime;
+use marco_polo_rs_core::database::models::webhook::{Webhook, WebhookDelivery, WebhookEvent};
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+const MAX_DELIVERIES_LIMIT: i64 = 100;
+
+#[derive(Debug, Validate, Deserialize, Serialize)]
+pub struct CreateWebhook {
+    #[validate(url(message = "Invalid Url"))]
+    pub url: String,
+    #[validate(length(min = 16, max = 255, message = "Secret must have 16 to 255 characters"))]
+    pub secret: String,
+    #[validate(length(min = 1, message = "Subscribe to at least one event"))]
+    pub events: Vec<WebhookEvent>,
+}
+
+/// The secret is never sent back, only the user that registered it knows it
+#[derive(Serialize, Debug, PartialEq, Deserialize)]
+pub struct WebhookDTO {
+    pub id: i32,
+    pub url: String,
+    pub events: Vec<WebhookEvent>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+impl From<Webhook> for WebhookDTO {
+    fn from(value: Webhook) -> Self {
+        return Self {
+            id: value.id,
+            url: value.url,
+            events: value.events,
+            created_at: value.created_at,
+            updated_at: value.updated_at,
+        };
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct DeliveriesQuery {
+    pub offset: Option<i64>,
+    pub limit: Option<i64>,
+}
+
+impl DeliveriesQuery {
+    pub fn to_tuple(&self) -> (i64, i64) {
+        let offset = self.offset.unwrap_or(0).max(0);
+        let limit = self.limit.unwrap_or(10).clamp(1, MAX_DELIVERIES_LIMIT);
+        (offset, limit)
+    }
+}
+
+#[derive(Serialize, Debug, PartialEq, Deserialize)]
+pub struct WebhookDeliveryDTO {
+    pub id: i64,
+    pub event: WebhookEvent,
+    pub payload: serde_json::Value,
+    pub attempts: i32,
+    pub response_status: Option<i32>,
+    pub last_error: Option<String>,
+    pub next_attempt_at: NaiveDateTime,
+    pub delivered_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+}
+
+impl From<WebhookDelivery> for WebhookDeliveryDTO {
+    fn from(value: WebhookDelivery) -> Self {
+        let payload = match serde_json::from_str(&value.payload) {
+            Ok(payload) => payload,
+            Err(_) => serde_json::Value::String(value.payload),
+        };
+
+        return Self {
+            id: value.id,
+            event: value.event,
+            payload,
+            attempts: value.attempts,
+            response_status: value.response_status,
+            last_error: value.last_error,
+            next_attempt_at: value.next_attempt_at,
+            delivered_at: value.delivered_at,
+            created_at: value.created_at,
+        };
+    }
+}
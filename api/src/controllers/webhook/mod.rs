@@ -0,0 +1,112 @@
+use actix_web::{
+    delete, get, post,
+    web::{self, Json},
+    HttpResponse, Responder, Scope,
+};
+use marco_polo_rs_core::database::queries::{self, webhook::CreateWebhookDto};
+use validator::Validate;
+
+use crate::{middleware::jwt_token::TokenClaims, models::error::AppError, AppPool, AppUrlPolicy};
+
+use self::dtos::{CreateWebhook, DeliveriesQuery, WebhookDTO, WebhookDeliveryDTO};
+
+mod dtos;
+#[cfg(test)]
+mod test;
+
+#[post("")]
+async fn create_webhook(
+    pool: web::Data<AppPool>,
+    url_policy: web::Data<AppUrlPolicy>,
+    body: Json<CreateWebhook>,
+    jwt: TokenClaims,
+) -> Result<impl Responder, AppError> {
+    body.validate()?;
+    let pool = &pool.pool;
+
+    if let Err(e) = url_policy.policy.check(&body.url).await {
+        return Err(AppError::bad_request(e.to_string()));
+    }
+
+    let dto = CreateWebhookDto {
+        user_id: jwt.id,
+        url: &body.url,
+        secret: &body.secret,
+        events: &body.events,
+    };
+    let webhook = queries::webhook::create(pool, dto).await?;
+    let dto: WebhookDTO = webhook.into();
+
+    return Ok(HttpResponse::Created().json(dto));
+}
+
+#[get("")]
+async fn find_all(pool: web::Data<AppPool>, jwt: TokenClaims) -> Result<impl Responder, AppError> {
+    let pool = &pool.pool;
+
+    let webhooks = queries::webhook::find_all_by_user(pool, jwt.id).await?;
+    let dto: Vec<WebhookDTO> = webhooks.into_iter().map(|w| w.into()).collect();
+
+    return Ok(Json(dto));
+}
+
+#[get("/{id}")]
+async fn find_by_id(
+    id: web::Path<i32>,
+    pool: web::Data<AppPool>,
+    jwt: TokenClaims,
+) -> Result<impl Responder, AppError> {
+    let pool = &pool.pool;
+
+    let webhook = queries::webhook::find_by_id_and_user(pool, id.into_inner(), jwt.id).await?;
+    let dto: WebhookDTO = webhook.into();
+
+    return Ok(Json(dto));
+}
+
+#[delete("/{id}")]
+async fn delete_by_id(
+    id: web::Path<i32>,
+    pool: web::Data<AppPool>,
+    jwt: TokenClaims,
+) -> Result<impl Responder, AppError> {
+    let pool = &pool.pool;
+
+    let webhook = queries::webhook::find_by_id_and_user(pool, id.into_inner(), jwt.id).await?;
+    queries::webhook::delete(pool, webhook.id).await?;
+
+    return Ok(HttpResponse::Ok().finish());
+}
+
+#[get("/{id}/deliveries")]
+async fn find_deliveries(
+    id: web::Path<i32>,
+    pool: web::Data<AppPool>,
+    query: web::Query<DeliveriesQuery>,
+    jwt: TokenClaims,
+) -> Result<impl Responder, AppError> {
+    let pool = &pool.pool;
+    let (offset, limit) = query.to_tuple();
+
+    let webhook = queries::webhook::find_by_id_and_user(pool, id.into_inner(), jwt.id).await?;
+    let deliveries = queries::webhook::find_deliveries(pool, webhook.id, offset, limit).await?;
+    let dto: Vec<WebhookDeliveryDTO> = deliveries.into_iter().map(|d| d.into()).collect();
+
+    return Ok(Json(dto));
+}
+
+fn create_scope() -> Scope {
+    let scope = web::scope("/webhook")
+        .service(create_webhook)
+        .service(find_all)
+        .service(find_by_id)
+        .service(delete_by_id)
+        .service(find_deliveries);
+
+    return scope;
+}
+
+pub fn init_routes(config: &mut web::ServiceConfig) {
+    let scope = create_scope();
+    config.service(scope);
+}
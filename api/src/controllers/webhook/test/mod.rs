@@ -0,0 +1,271 @@
+use std::{str::FromStr, sync::Arc};
+
+use actix_http::Request;
+use actix_web::{
+    dev::ServiceResponse,
+    http::header::ContentType,
+    test,
+    web::{self},
+};
+use marco_polo_rs_core::{
+    database::{
+        models::{video::stage::VideoStage, webhook::WebhookEvent},
+        queries::{self, webhook::CreateWebhookDto},
+    },
+    util::security::url_policy::UrlPolicy,
+};
+use reqwest::StatusCode;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{controllers::test::create_test_app, utils::test::get_token, AppPool, AppUrlPolicy};
+
+use super::{
+    create_scope,
+    dtos::{CreateWebhook, WebhookDTO, WebhookDeliveryDTO},
+};
+
+async fn innit_test_app(
+    pool: Arc<PgPool>,
+) -> impl actix_web::dev::Service<Request, Response = ServiceResponse, Error = actix_web::Error> {
+    let pool = AppPool { pool };
+    let web_data = web::Data::new(pool);
+
+    // example.com is not resolved, the tests run offline
+    let url_policy = AppUrlPolicy {
+        policy: UrlPolicy {
+            allowed_hosts: vec!["example.com".to_string()],
+        },
+    };
+    let url_policy = web::Data::new(url_policy);
+
+    let app = create_test_app();
+    let scope = create_scope();
+
+    let app = app.app_data(web_data).app_data(url_policy).service(scope);
+
+    let test_app = test::init_service(app).await;
+
+    return test_app;
+}
+
+async fn create_webhook(pool: &PgPool, user_id: i32) -> i32 {
+    let dto = CreateWebhookDto {
+        user_id,
+        url: "https://example.com/hook",
+        secret: "a-very-long-secret",
+        events: &[WebhookEvent::Stage],
+    };
+
+    queries::webhook::create(pool, dto).await.unwrap().id
+}
+
+#[sqlx::test(
+    migrations = "../migrations",
+    fixtures("../../../test/fixtures/videos")
+)]
+async fn test_create_webhook_ok(pool: PgPool) {
+    let pool = Arc::new(pool);
+    let token = get_token!(pool.as_ref(), 456);
+
+    let test_app = innit_test_app(pool.clone()).await;
+
+    let body = CreateWebhook {
+        url: "https://example.com/hook".to_string(),
+        secret: "a-very-long-secret".to_string(),
+        events: vec![WebhookEvent::Stage, WebhookEvent::Published],
+    };
+
+    let request = test::TestRequest::post()
+        .uri("/webhook")
+        .insert_header(ContentType::json())
+        .insert_header(("Authorization", token))
+        .set_json(body)
+        .to_request();
+
+    let response = test::call_service(&test_app, request).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let body: serde_json::Value = test::read_body_json(response).await;
+    assert!(body.get("secret").is_none());
+
+    let dto: WebhookDTO = serde_json::from_value(body).unwrap();
+    assert_eq!(
+        dto.events,
+        vec![WebhookEvent::Stage, WebhookEvent::Published]
+    );
+
+    let webhooks = queries::webhook::find_all_by_user(pool.as_ref(), 456)
+        .await
+        .unwrap();
+    assert_eq!(webhooks.len(), 1);
+    assert_eq!(webhooks[0].secret, "a-very-long-secret");
+}
+
+#[sqlx::test(
+    migrations = "../migrations",
+    fixtures("../../../test/fixtures/videos")
+)]
+async fn test_create_webhook_without_events(pool: PgPool) {
+    let pool = Arc::new(pool);
+    let token = get_token!(pool.as_ref(), 456);
+
+    let test_app = innit_test_app(pool.clone()).await;
+
+    let body = CreateWebhook {
+        url: "https://example.com/hook".to_string(),
+        secret: "a-very-long-secret".to_string(),
+        events: vec![],
+    };
+
+    let request = test::TestRequest::post()
+        .uri("/webhook")
+        .insert_header(ContentType::json())
+        .insert_header(("Authorization", token))
+        .set_json(body)
+        .to_request();
+
+    let response = test::call_service(&test_app, request).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[sqlx::test(
+    migrations = "../migrations",
+    fixtures("../../../test/fixtures/videos")
+)]
+async fn test_create_webhook_to_private_address(pool: PgPool) {
+    let pool = Arc::new(pool);
+    let token = get_token!(pool.as_ref(), 456);
+
+    let test_app = innit_test_app(pool.clone()).await;
+
+    let urls = [
+        "https://127.0.0.1/hook",
+        "https://169.254.169.254/latest/meta-data",
+        "https://[::1]/hook",
+        "http://93.184.216.34/hook",
+    ];
+
+    for url in urls {
+        let body = CreateWebhook {
+            url: url.to_string(),
+            secret: "a-very-long-secret".to_string(),
+            events: vec![WebhookEvent::Stage],
+        };
+
+        let request = test::TestRequest::post()
+            .uri("/webhook")
+            .insert_header(ContentType::json())
+            .insert_header(("Authorization", token.clone()))
+            .set_json(body)
+            .to_request();
+
+        let response = test::call_service(&test_app, request).await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST, "{}", url);
+    }
+
+    let webhooks = queries::webhook::find_all_by_user(pool.as_ref(), 456)
+        .await
+        .unwrap();
+    assert!(webhooks.is_empty());
+}
+
+#[sqlx::test(
+    migrations = "../migrations",
+    fixtures("../../../test/fixtures/videos")
+)]
+async fn test_find_all_only_own_webhooks(pool: PgPool) {
+    let pool = Arc::new(pool);
+    let token = get_token!(pool.as_ref(), 456);
+    let own = create_webhook(pool.as_ref(), 456).await;
+    create_webhook(pool.as_ref(), 789).await;
+
+    let test_app = innit_test_app(pool.clone()).await;
+
+    let request = test::TestRequest::get()
+        .uri("/webhook")
+        .insert_header(("Authorization", token))
+        .to_request();
+
+    let response = test::call_service(&test_app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let webhooks: Vec<WebhookDTO> = test::read_body_json(response).await;
+    assert_eq!(webhooks.len(), 1);
+    assert_eq!(webhooks[0].id, own);
+}
+
+#[sqlx::test(
+    migrations = "../migrations",
+    fixtures("../../../test/fixtures/videos")
+)]
+async fn test_find_deliveries_ok(pool: PgPool) {
+    let pool = Arc::new(pool);
+    let token = get_token!(pool.as_ref(), 456);
+    let webhook_id = create_webhook(pool.as_ref(), 456).await;
+
+    let video_id = Uuid::from_str("806b57d2-f221-11ed-a05b-0242ac120003").unwrap();
+    queries::video::change_stage(pool.as_ref(), &video_id, VideoStage::Cutting)
+        .await
+        .unwrap();
+
+    let test_app = innit_test_app(pool.clone()).await;
+
+    let request = test::TestRequest::get()
+        .uri(&format!("/webhook/{}/deliveries", webhook_id))
+        .insert_header(("Authorization", token))
+        .to_request();
+
+    let response = test::call_service(&test_app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let deliveries: Vec<WebhookDeliveryDTO> = test::read_body_json(response).await;
+    assert_eq!(deliveries.len(), 1);
+    assert_eq!(deliveries[0].event, WebhookEvent::Stage);
+    assert_eq!(deliveries[0].payload["stage"], "Cutting");
+}
+
+#[sqlx::test(
+    migrations = "../migrations",
+    fixtures("../../../test/fixtures/videos")
+)]
+async fn test_find_deliveries_of_another_user_not_found(pool: PgPool) {
+    let pool = Arc::new(pool);
+    let token = get_token!(pool.as_ref(), 456);
+    let webhook_id = create_webhook(pool.as_ref(), 789).await;
+
+    let test_app = innit_test_app(pool.clone()).await;
+
+    let request = test::TestRequest::get()
+        .uri(&format!("/webhook/{}/deliveries", webhook_id))
+        .insert_header(("Authorization", token))
+        .to_request();
+
+    let response = test::call_service(&test_app, request).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[sqlx::test(
+    migrations = "../migrations",
+    fixtures("../../../test/fixtures/videos")
+)]
+async fn test_delete_webhook_ok(pool: PgPool) {
+    let pool = Arc::new(pool);
+    let token = get_token!(pool.as_ref(), 456);
+    let webhook_id = create_webhook(pool.as_ref(), 456).await;
+
+    let test_app = innit_test_app(pool.clone()).await;
+
+    let request = test::TestRequest::delete()
+        .uri(&format!("/webhook/{}", webhook_id))
+        .insert_header(("Authorization", token))
+        .to_request();
+
+    let response = test::call_service(&test_app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let webhooks = queries::webhook::find_all_by_user(pool.as_ref(), 456)
+        .await
+        .unwrap();
+    assert!(webhooks.is_empty());
+}
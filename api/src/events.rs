@@ -0,0 +1,94 @@
+use std::{convert::Infallible, time::Duration};
+
+use actix_web::web::Bytes;
+use futures::{stream, Stream, StreamExt};
+use marco_polo_rs_core::database::models::video_event::{VideoEvent, VIDEO_EVENTS_CHANNEL};
+use sqlx::{postgres::PgListener, PgPool};
+use tokio::{
+    sync::broadcast::{self, error::RecvError},
+    time::{interval_at, Instant, Interval},
+};
+
+/// Events a slow stream can fall behind before it skips some
+const CHANNEL_CAPACITY: usize = 1024;
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+/// Proxies close connections that stay quiet for too long
+const KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(15);
+
+pub fn channel() -> broadcast::Sender<VideoEvent> {
+    let (sender, _) = broadcast::channel(CHANNEL_CAPACITY);
+    sender
+}
+
+/// Forwards the notified video events to every open stream, listening again when the connection drops
+pub async fn listen(pool: PgPool, sender: broadcast::Sender<VideoEvent>) {
+    loop {
+        if let Err(e) = forward(&pool, &sender).await {
+            tracing::error!("Video events listener failed: {}", e);
+        }
+        tokio::time::sleep(RECONNECT_DELAY).await;
+    }
+}
+
+async fn forward(pool: &PgPool, sender: &broadcast::Sender<VideoEvent>) -> Result<(), sqlx::Error> {
+    let mut listener = PgListener::connect_with(pool).await?;
+    listener.listen(VIDEO_EVENTS_CHANNEL).await?;
+    tracing::info!("Listening to video events...");
+
+    loop {
+        let notification = listener.recv().await?;
+        match serde_json::from_str::<VideoEvent>(notification.payload()) {
+            // fails only when no stream is open
+            Ok(event) => {
+                let _ = sender.send(event);
+            }
+            Err(e) => tracing::warn!("Invalid video event: {}", e),
+        }
+    }
+}
+
+/// Server-sent events starting with `initial`, followed by the events `filter` accepts
+pub fn stream(
+    initial: Vec<VideoEvent>,
+    receiver: broadcast::Receiver<VideoEvent>,
+    filter: impl Fn(&VideoEvent) -> bool + 'static,
+) -> impl Stream<Item = Result<Bytes, Infallible>> {
+    let initial = stream::iter(initial).map(|event| Ok(format_event(&event)));
+
+    let keep_alive = interval_at(Instant::now() + KEEP_ALIVE_INTERVAL, KEEP_ALIVE_INTERVAL);
+    let live = stream::unfold(
+        (receiver, keep_alive, filter),
+        |(mut receiver, mut keep_alive, filter)| async move {
+            let chunk = next_chunk(&mut receiver, &mut keep_alive, &filter).await?;
+            Some((Ok(chunk), (receiver, keep_alive, filter)))
+        },
+    );
+
+    initial.chain(live)
+}
+
+async fn next_chunk(
+    receiver: &mut broadcast::Receiver<VideoEvent>,
+    keep_alive: &mut Interval,
+    filter: &impl Fn(&VideoEvent) -> bool,
+) -> Option<Bytes> {
+    loop {
+        tokio::select! {
+            _ = keep_alive.tick() => return Some(Bytes::from_static(b": keep-alive\n\n")),
+            result = receiver.recv() => match result {
+                Ok(event) if filter(&event) => return Some(format_event(&event)),
+                Ok(_) => continue,
+                Err(RecvError::Lagged(skipped)) => {
+                    tracing::warn!("Video events stream skipped {} events", skipped);
+                    continue;
+                }
+                Err(RecvError::Closed) => return None,
+            },
+        }
+    }
+}
+
+fn format_event(event: &VideoEvent) -> Bytes {
+    let data = serde_json::to_string(event).expect("A video event is always serializable");
+    Bytes::from(format!("event: {}\ndata: {}\n\n", event.kind.name(), data))
+}
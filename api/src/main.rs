@@ -1,30 +1,37 @@
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc, time::Instant};
 
 use actix_cors::Cors;
 use actix_web::{
+    dev::Service,
     get,
     middleware::NormalizePath,
     web::{self, Json, JsonConfig, QueryConfig},
     App, HttpServer, Responder,
 };
-use mail::{engine::MailEngine, sender::MailSender, Mailer};
 use marco_polo_rs_core::{
-    database::create_pool,
+    database::{create_pool, models::video_event::VideoEvent},
     env,
     internals::{
         cloud::{default_cloud_service, traits::CloudService},
+        transcriber::{assembly_ai::AssemblyAiClient, traits::TranscriberClient},
         video_platform::youtube::{client, traits::YoutubeClient},
     },
+    mail::{self, engine::MailEngine, sender::MailSender, Mailer},
+    util::{logging, metrics, security::url_policy::UrlPolicy},
 };
 use models::{error::AppError, result::AppResult};
+use tokio::sync::broadcast;
+use tracing_actix_web::TracingLogger;
 
 mod auth;
 mod controllers;
-mod mail;
+mod events;
 mod middleware;
 mod models;
 mod utils;
 
+const DEFAULT_METRICS_PORT: u16 = 9001;
+
 struct AppPool {
     pool: Arc<sqlx::PgPool>,
 }
@@ -37,36 +44,93 @@ struct AppCloudService<CS: CloudService> {
     client: Arc<CS>,
 }
 
+/// The transcribers by the name on their webhook url
+struct AppTranscriberClients {
+    clients: HashMap<&'static str, Arc<dyn TranscriberClient + Send + Sync>>,
+}
+
 struct AppMailer<ME: MailEngine, MS: MailSender> {
     mailer: Arc<Mailer<ME, MS>>,
 }
 
+struct AppVideoEvents {
+    sender: broadcast::Sender<VideoEvent>,
+}
+
+/// Where the webhooks of the users may point to
+struct AppUrlPolicy {
+    policy: UrlPolicy,
+}
+
 #[get("/")]
 async fn hello() -> impl Responder {
     let result: AppResult<String> = AppResult::new("hello word 2".to_string());
     return Json(result);
 }
 
+/// Serves the metrics on `API_METRICS_PORT`, apart from the public listener
+fn install_metrics() {
+    let port = match std::env::var("API_METRICS_PORT") {
+        Ok(port) => port.parse().expect("API_METRICS_PORT must be a port"),
+        Err(_) => DEFAULT_METRICS_PORT,
+    };
+
+    metrics::builder()
+        .with_http_listener(([0, 0, 0, 0], port))
+        .install()
+        .expect("Failed to install the metrics exporter");
+
+    tracing::info!("Serving metrics on port {}", port);
+}
+
 #[tokio::main]
 async fn main() -> std::io::Result<()> {
-    println!("Starting server...");
+    dotenv::dotenv().ok();
+    logging::init();
+    tracing::info!("Starting server...");
 
     let youtube_client = client::YoutubeClient::new();
     let youtube_client = Arc::new(youtube_client);
-    dotenv::dotenv().ok();
     env::check_envs();
+
+    install_metrics();
     let pool = create_pool().await;
     let pool = Arc::new(pool);
 
-    let cloud_service = default_cloud_service();
+    let cloud_service = default_cloud_service(&pool);
     let cloud_service = Arc::new(cloud_service);
 
     let app_mailer = Arc::new(mail::Mailer::default());
 
+    let mut transcriber_clients: HashMap<&'static str, Arc<dyn TranscriberClient + Send + Sync>> =
+        HashMap::new();
+    transcriber_clients.insert("assemblyai", Arc::new(AssemblyAiClient::new()));
+
+    let video_events = events::channel();
+    tokio::spawn(events::listen(pool.as_ref().clone(), video_events.clone()));
+
+    let url_policy = UrlPolicy::from_env();
+
     HttpServer::new(move || {
         App::new()
             .wrap(NormalizePath::trim())
             .wrap(Cors::permissive())
+            .wrap_fn(|req, srv| {
+                let start = Instant::now();
+                let method = req.method().to_string();
+                let response = srv.call(req);
+                async move {
+                    let response = response.await?;
+                    let route = match response.request().match_pattern() {
+                        Some(pattern) => pattern,
+                        None => String::from("unmatched"),
+                    };
+                    let status = response.status().as_u16();
+                    metrics::record_http_request(method, route, status, start.elapsed());
+                    Ok(response)
+                }
+            })
+            .wrap(TracingLogger::default())
             .app_data(JsonConfig::default().error_handler(|err, _req| {
                 let error = AppError::from(err);
                 return error.into();
@@ -82,9 +146,18 @@ async fn main() -> std::io::Result<()> {
             .app_data(web::Data::new(AppYoutubeClient {
                 client: youtube_client.clone(),
             }))
+            .app_data(web::Data::new(AppTranscriberClients {
+                clients: transcriber_clients.clone(),
+            }))
             .app_data(web::Data::new(AppMailer {
                 mailer: app_mailer.clone(),
             }))
+            .app_data(web::Data::new(AppVideoEvents {
+                sender: video_events.clone(),
+            }))
+            .app_data(web::Data::new(AppUrlPolicy {
+                policy: url_policy.clone(),
+            }))
             .service(hello)
             .configure(controllers::init_routes)
     })
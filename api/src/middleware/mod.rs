@@ -1,2 +1 @@
-pub mod api_token;
 pub mod jwt_token;
@@ -6,11 +6,12 @@ use actix_web::{
     HttpResponse,
 };
 
-use marco_polo_rs_core::internals::video_platform::errors::HeathCheckError;
+use marco_polo_rs_core::{
+    internals::video_platform::errors::HeathCheckError, mail::MailError,
+    util::subtitles::SubtitleError,
+};
 use serde::{Deserialize, Serialize};
 
-use crate::mail::MailError;
-
 #[derive(Serialize, Deserialize, Debug)]
 pub struct AppErrorResponse {
     pub errors: Vec<String>,
@@ -22,6 +23,8 @@ pub enum AppErrorType {
     InternalServerError,
     NotFound,
     Unauthorized,
+    Forbidden,
+    Conflict,
 }
 
 impl fmt::Display for AppErrorType {
@@ -68,6 +71,14 @@ impl AppError {
         return Self::new(AppErrorType::Unauthorized, message);
     }
 
+    pub fn forbidden(message: String) -> Self {
+        return Self::new(AppErrorType::Forbidden, message);
+    }
+
+    pub fn conflict(message: String) -> Self {
+        return Self::new(AppErrorType::Conflict, message);
+    }
+
     fn message(&self) -> String {
         self.message.clone()
     }
@@ -86,6 +97,8 @@ impl ResponseError for AppError {
             AppErrorType::InternalServerError => actix_web::http::StatusCode::INTERNAL_SERVER_ERROR,
             AppErrorType::NotFound => actix_web::http::StatusCode::NOT_FOUND,
             AppErrorType::Unauthorized => actix_web::http::StatusCode::UNAUTHORIZED,
+            AppErrorType::Forbidden => actix_web::http::StatusCode::FORBIDDEN,
+            AppErrorType::Conflict => actix_web::http::StatusCode::CONFLICT,
         }
     }
 
@@ -142,7 +155,7 @@ impl From<sqlx::Error> for AppError {
                 return Self::new(AppErrorType::NotFound, value.to_string());
             }
             _ => {
-                println!("{:?}", value);
+                tracing::error!("{:?}", value);
                 return Self::new(AppErrorType::InternalServerError, value.to_string());
             }
         }
@@ -185,6 +198,12 @@ impl From<MailError> for AppError {
     }
 }
 
+impl From<SubtitleError> for AppError {
+    fn from(value: SubtitleError) -> Self {
+        return Self::new(AppErrorType::BadRequest, value.to_string());
+    }
+}
+
 impl From<HeathCheckError<'_>> for AppError {
     fn from(value: HeathCheckError) -> Self {
         match value {
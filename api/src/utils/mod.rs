@@ -1,2 +1,3 @@
 #[cfg(test)]
-pub mod test;
\ No newline at end of file
+pub mod test;
+pub mod validation;
\ No newline at end of file
@@ -0,0 +1,14 @@
+use validator::ValidationError;
+
+pub fn validate_time(time: &str) -> Result<(), ValidationError> {
+    let times = time.split(":").collect::<Vec<&str>>();
+    if times.len() != 3 {
+        return Err(ValidationError::new("Invalid Time Format"));
+    }
+
+    for time in times {
+        time.parse::<i32>()
+            .map_err(|_| ValidationError::new("Invalid Time Format"))?;
+    }
+    return Ok(());
+}
@@ -8,8 +8,8 @@ pub struct Args {
     #[arg(short, long, default_value = "./input.mp4")]
     pub input: String,
 
-    /// Path to the output video file or srt file if srt_only is on.
-    /// Use the same extension as the input file
+    /// Path to the output video file or subtitles file if srt_only is on.
+    /// Use the same extension as the input file, subtitles can be srt, vtt or ass
     #[arg(short, long, default_value = "./output.mp4")]
     pub output: String,
 
@@ -21,7 +21,38 @@ pub struct Args {
     #[arg(long, default_value = "false")]
     pub srt_only: bool,
 
+    /// Mux the subtitles as a track the player can turn off, instead of burning them.
+    /// The output must be an mp4, mov, mkv or webm file
+    #[arg(long, default_value = "false")]
+    pub soft_subtitles: bool,
+
+    /// Define which transcription service to use (assembly_ai or whisper).
+    /// whisper runs on this machine, with the whisper.cpp binary and a ggml model
+    #[arg(long, default_value = "assembly_ai")]
+    pub transcriber: String,
+
+    /// Path to the ggml model whisper.cpp transcribes with
+    #[arg(long)]
+    pub whisper_model: Option<String>,
+
+    /// The whisper.cpp binary, looked up on the PATH unless a path is given
+    #[arg(long, default_value = "whisper-cli")]
+    pub whisper_binary: String,
+
+    /// Mark who says each subtitle (none, dashes or colors).
+    /// Only assembly_ai tells the speakers apart
+    #[arg(long, default_value = "none")]
+    pub speaker_labels: String,
+
     /// Define which translation service to use (google or deepl)
     #[arg(short, long, default_value = "google")]
     pub translation_service: String,
+
+    /// Language spoken on the input video
+    #[arg(long, default_value = "en")]
+    pub source_language: String,
+
+    /// Language the subtitles will be translated to
+    #[arg(long, default_value = "pt-br")]
+    pub target_language: String,
 }
@@ -6,7 +6,7 @@ use serde::Deserialize;
 #[serde(rename_all = "camelCase")]
 pub struct Keys {
     pub deepl: Option<String>,
-    pub assembly_ai: String,
+    pub assembly_ai: Option<String>,
     pub google: Option<String>,
     pub email: String,
     pub password: String,
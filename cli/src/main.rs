@@ -3,16 +3,20 @@ use args::Args;
 use clap::Parser;
 use keys::Keys;
 use marco_polo_rs_core::{
-    internals::transcriber::{
-        assembly_ai::AssemblyAiClient,
-        traits::{Sentence, TranscriberClient},
+    internals::{
+        transcriber::{
+            assembly_ai::AssemblyAiClient,
+            traits::{Sentence, TranscribeOptions, TranscriberClient},
+            whisper::WhisperClient,
+        },
+        translator::language::Language,
     },
-    util::ffmpeg,
+    util::{ffmpeg, progress, subtitles::Format},
     SyncError,
 };
-use srt::{get_srt_string, write_srt_file};
+use srt::{convert_srt, get_srt_string, parse_speaker_labels, write_srt_file};
 
-use std::env;
+use std::{env, str::FromStr};
 mod api;
 mod args;
 mod keys;
@@ -56,10 +60,15 @@ On Windows, you can install FFmpeg by downloading a build from the official webs
         .await
         .expect("Failed to login");
 
-    let assembly_ai_client =
-        marco_polo_rs_core::internals::transcriber::assembly_ai::AssemblyAiClient::new();
+    let sentences = match args.transcriber.as_str() {
+        "whisper" => match WhisperClient::new() {
+            Ok(client) => get_sentences(client, &args).await,
+            Err(e) => Err(e),
+        },
+        _ => get_sentences(AssemblyAiClient::new(), &args).await,
+    };
 
-    let sentences = match get_sentences(assembly_ai_client, &args).await {
+    let sentences = match sentences {
         Ok(sentences) => sentences,
         Err(e) => {
             eprintln!("{}", e);
@@ -74,13 +83,20 @@ On Windows, you can install FFmpeg by downloading a build from the official webs
         }
     };
 
-    let srt_path_string = match args.srt_only {
-        false => "./output.srt".to_string(),
+    let (srt_path_string, srt_file_string) = match args.srt_only {
+        false => ("./output.srt".to_string(), srt_file_string),
         true => {
+            let format = Format::from_path(&args.output).unwrap_or(Format::Srt);
             let path = std::path::PathBuf::from(&args.output);
             let file_stem = path.file_stem().unwrap().to_str().unwrap();
-            let path_str = format!("{}.srt", file_stem);
-            path_str
+            let path_str = format!("{}.{}", file_stem, format.extension());
+
+            match convert_srt(srt_file_string, format) {
+                Ok(subtitles) => (path_str, subtitles),
+                Err(_) => {
+                    std::process::exit(1);
+                }
+            }
         }
     };
 
@@ -92,7 +108,7 @@ On Windows, you can install FFmpeg by downloading a build from the official webs
     }
 
     if args.srt_only {
-        println!("Srt file written to {}", srt_path_string);
+        println!("Subtitles written to {}", srt_path_string);
         std::process::exit(0);
     } else {
         match write_subtitles_to_video(&args).await {
@@ -116,27 +132,54 @@ async fn write_subtitles_to_video(args: &Args) -> Result<(), SyncError> {
     println!("Writing subtitles to video...");
     println!("This may take a while...");
 
-    ffmpeg::subtitle_video_to_file(&input_path, &srt_path, &output_path)?;
+    if args.soft_subtitles {
+        let language = Language::from_str(&args.target_language)
+            .map(|language| language.iso_639_2())
+            .unwrap_or("und");
+        let streams = [ffmpeg::SubtitleStream {
+            path: "./output.srt",
+            language,
+        }];
+
+        ffmpeg::mux_subtitles_to_file(
+            &input_path,
+            &streams,
+            None,
+            &output_path,
+            &progress::ignore,
+        )?;
+    } else {
+        ffmpeg::subtitle_video_to_file(&input_path, &srt_path, &output_path, &progress::ignore)?;
+    }
 
     std::fs::remove_file("./output.srt")?;
     Ok(())
 }
 
 async fn get_sentences(
-    assembly_ai_client: AssemblyAiClient,
+    transcriber_client: impl TranscriberClient,
     args: &Args,
 ) -> Result<Vec<Sentence>, SyncError> {
     println!("Extracting audio from video...");
-    println!("Sending audio to AssemblyAI...");
-    let transcription_id = assembly_ai_client.transcribe_from_file(&args.input).await?;
+    match args.transcriber.as_str() {
+        "whisper" => println!("Transcribing audio with whisper..."),
+        _ => println!("Sending audio to AssemblyAI..."),
+    }
+    let options = TranscribeOptions {
+        speaker_labels: parse_speaker_labels(args).is_enabled(),
+        ..Default::default()
+    };
+    let transcription_id = transcriber_client
+        .transcribe_from_file(&args.input, &options)
+        .await?;
 
     println!("Waiting for transcription to complete...");
     println!("This may take a while...");
-    assembly_ai_client.pool(&transcription_id).await?;
+    transcriber_client.pool(&transcription_id).await?;
 
     println!("Transcription complete!");
 
-    let sentences = assembly_ai_client
+    let sentences = transcriber_client
         .get_transcription_sentences(&transcription_id)
         .await?;
     Ok(sentences)
@@ -169,9 +212,33 @@ fn setup_env(args: &Args, keys: &Keys) {
         std::process::exit(1);
     }
 
-    env::set_var("ASSEMBLY_AI_WEBHOOK_ENDPOINT", "");
-    env::set_var("ASSEMBLY_AI_WEBHOOK_TOKEN", "");
-    env::set_var("ASSEMBLY_AI_BASE_URL", ASSEMBLY_AI_BASE_URL);
-    env::set_var("ASSEMBLY_AI_API_KEY", "test");
-    env::set_var("ASSEMBLY_AI_API_KEY", keys.assembly_ai.to_string());
+    if args.transcriber == "whisper" {
+        env::set_var("WHISPER_BINARY", &args.whisper_binary);
+        env::set_var(
+            "WHISPER_MODEL",
+            args.whisper_model
+                .as_ref()
+                .expect("--whisper-model to be set when 'whisper' is set as the transcriber"),
+        );
+        // whisper knows the languages by their two letter codes
+        let language = args.source_language.split('-').next().unwrap_or("auto");
+        env::set_var("WHISPER_LANGUAGE", language);
+        env::set_var(
+            "WHISPER_OUTPUT_PATH",
+            env::temp_dir().join("marco-polo-whisper"),
+        );
+    } else if args.transcriber == "assembly_ai" {
+        env::set_var("ASSEMBLY_AI_WEBHOOK_ENDPOINT", "");
+        env::set_var("ASSEMBLY_AI_WEBHOOK_TOKEN", "");
+        env::set_var("ASSEMBLY_AI_BASE_URL", ASSEMBLY_AI_BASE_URL);
+        env::set_var(
+            "ASSEMBLY_AI_API_KEY",
+            keys.assembly_ai.as_ref().expect(
+                "assemblyAi to be set on the 'keys' file when 'assembly_ai' is set as the transcriber",
+            ),
+        );
+    } else {
+        eprintln!("Transcriber '{}' not supported", args.transcriber);
+        std::process::exit(1);
+    }
 }
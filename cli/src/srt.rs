@@ -1,12 +1,15 @@
-use std::{fs::File, io::Write};
+use std::{fs::File, io::Write, str::FromStr};
 
 use marco_polo_rs_core::{
+    database::models::video::speaker_labels::SpeakerLabels,
     internals::{
         transcriber::traits::Sentence,
         translator::{
-            deepl::DeeplClient, google_v2::GoogleTranslateV2Client, traits::TranslatorClient,
+            deepl::DeeplClient, google_v2::GoogleTranslateV2Client, language::Language,
+            traits::TranslatorClient,
         },
     },
+    util::subtitles::{Document, Format},
     SyncError,
 };
 
@@ -14,25 +17,35 @@ use crate::args::Args;
 
 pub async fn get_srt_string(sentences: Vec<Sentence>, args: &Args) -> Result<String, ()> {
     let srt_file_string: String;
+    let languages = match parse_languages(args) {
+        Ok(languages) => languages,
+        Err(e) => {
+            eprintln!("{}", e);
+            return Err(());
+        }
+    };
+    let speaker_labels = parse_speaker_labels(args);
 
     if args.translation_service == "deepl" {
         let client = DeeplClient::new();
-        srt_file_string = match get_srt_file_string(sentences, client).await {
-            Ok(srt_file_string) => srt_file_string,
-            Err(e) => {
-                eprintln!("{}", e);
-                return Err(());
-            }
-        };
+        srt_file_string =
+            match get_srt_file_string(sentences, client, languages, speaker_labels).await {
+                Ok(srt_file_string) => srt_file_string,
+                Err(e) => {
+                    eprintln!("{}", e);
+                    return Err(());
+                }
+            };
     } else if args.translation_service == "google" {
         let client = GoogleTranslateV2Client::new();
-        srt_file_string = match get_srt_file_string(sentences, client).await {
-            Ok(srt_file_string) => srt_file_string,
-            Err(e) => {
-                eprintln!("{}", e);
-                return Err(());
-            }
-        };
+        srt_file_string =
+            match get_srt_file_string(sentences, client, languages, speaker_labels).await {
+                Ok(srt_file_string) => srt_file_string,
+                Err(e) => {
+                    eprintln!("{}", e);
+                    return Err(());
+                }
+            };
     } else {
         eprintln!(
             "Translation service '{}' not supported",
@@ -44,6 +57,20 @@ pub async fn get_srt_string(sentences: Vec<Sentence>, args: &Args) -> Result<Str
     return Ok(srt_file_string);
 }
 
+pub fn parse_speaker_labels(args: &Args) -> SpeakerLabels {
+    match args.speaker_labels.as_str() {
+        "dashes" => SpeakerLabels::Dashes,
+        "colors" => SpeakerLabels::Colors,
+        _ => SpeakerLabels::None,
+    }
+}
+
+fn parse_languages(args: &Args) -> Result<(Language, Language), String> {
+    let source_language = Language::from_str(&args.source_language)?;
+    let target_language = Language::from_str(&args.target_language)?;
+    Ok((source_language, target_language))
+}
+
 pub fn write_srt_file(srt_path: &str, srt: String) -> Result<(), ()> {
     match File::create(&srt_path) {
         Ok(mut file) => match file.write_all(srt.as_bytes()) {
@@ -63,17 +90,35 @@ pub fn write_srt_file(srt_path: &str, srt: String) -> Result<(), ()> {
     }
 }
 
+/// The subtitles are created as srt, other formats are converted from it
+pub fn convert_srt(srt: String, format: Format) -> Result<String, ()> {
+    if format == Format::Srt {
+        return Ok(srt);
+    }
+
+    match Document::convert(&srt, Format::Srt, format) {
+        Ok(subtitles) => Ok(subtitles),
+        Err(e) => {
+            eprintln!("Unable to convert the subtitles to {}: {}", format, e);
+            Err(())
+        }
+    }
+}
+
 async fn get_srt_file_string(
     mut sentences: Vec<Sentence>,
     translator_client: impl TranslatorClient,
+    languages: (Language, Language),
+    speaker_labels: SpeakerLabels,
 ) -> Result<String, SyncError> {
+    let (source_language, target_language) = languages;
     let string_sentences = sentences
         .iter()
         .map(|s| s.text.as_str())
         .collect::<Vec<&str>>();
 
     let translated_sentences = translator_client
-        .translate_sentences(string_sentences)
+        .translate_sentences(string_sentences, source_language, target_language)
         .await?;
 
     for (i, translation) in translated_sentences.into_iter().enumerate() {
@@ -81,6 +126,7 @@ async fn get_srt_file_string(
     }
 
     println!("Creating new srt file...");
-    let new_srt_buffer = marco_polo_rs_core::util::srt::create_based_on_sentences(sentences);
+    let new_srt_buffer =
+        marco_polo_rs_core::util::srt::create_based_on_sentences(sentences, speaker_labels);
     Ok(new_srt_buffer)
 }
@@ -4,7 +4,7 @@ pub mod models;
 pub mod queries;
 
 pub async fn create_pool() -> PgPool {
-    println!("Creating database pool...");
+    tracing::info!("Creating database pool...");
     let database_url = std::env::var("DATABASE_URL").unwrap();
     let pool = PgPool::connect(&database_url).await.unwrap();
 
@@ -32,7 +32,7 @@ impl<'de> Deserialize<'de> for AuthType {
         let data = match value.get_mut("data") {
             Some(data) => data.take(),
             None => {
-                eprintln!("Missing data field");
+                tracing::error!("Missing data field");
                 return Ok(AuthType::Invalid);
             }
         };
@@ -40,7 +40,7 @@ impl<'de> Deserialize<'de> for AuthType {
         let type_ = match value.get("type") {
             Some(type_) => type_,
             None => {
-                eprintln!("Missing type field");
+                tracing::error!("Missing type field");
                 return Ok(AuthType::Invalid);
             }
         };
@@ -50,14 +50,14 @@ impl<'de> Deserialize<'de> for AuthType {
                 let data: Oath2Data = match serde_json::from_value(data) {
                     Ok(data) => data,
                     Err(e) => {
-                        eprintln!("Error deserializing OAUTH2 data: {}", e);
+                        tracing::error!("Error deserializing OAUTH2 data: {}", e);
                         return Ok(AuthType::Invalid);
                     }
                 };
                 Ok(AuthType::Oauth2(data))
             }
             _ => {
-                eprintln!("Invalid auth type {:?}", type_);
+                tracing::error!("Invalid auth type {:?}", type_);
                 Ok(AuthType::Invalid)
             }
         }
@@ -0,0 +1,19 @@
+use chrono::NaiveDateTime;
+use uuid::Uuid;
+
+use super::video::stage::VideoStage;
+
+/// A pipeline message that was given up on, kept so admins can replay it.
+/// `payload` is the body the queue received
+#[derive(Debug, Clone, PartialEq, sqlx::FromRow)]
+pub struct FailedMessage {
+    pub id: i32,
+    pub payload: String,
+    pub video_ids: Vec<Uuid>,
+    pub error: String,
+    pub stage: Option<VideoStage>,
+    pub attempts: i32,
+    pub replayed_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
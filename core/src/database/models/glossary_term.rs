@@ -0,0 +1,16 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+/// A name or jargon of a channel. Every term is boosted on the transcription, terms without
+/// a translation are kept as they are and the others are always translated the same way
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, sqlx::FromRow)]
+pub struct GlossaryTerm {
+    pub id: i32,
+    pub channel_id: i32,
+    pub term: String,
+    /// The language the translation is in, set together with it
+    pub language: Option<String>,
+    pub translation: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
@@ -1,11 +1,22 @@
 pub mod channel;
+pub mod failed_message;
+pub mod glossary_term;
+pub mod notification;
 pub mod original_video;
+pub mod outbox_message;
+pub mod queue_message;
 pub mod service_provider;
+pub mod subtitles_style;
 pub mod traits;
 pub mod user;
 pub mod video;
 pub mod video_error;
+pub mod video_event;
+pub mod video_output;
+pub mod video_review;
 pub mod video_storage;
+pub mod video_subtitles_revision;
 pub mod video_subtitling;
 pub mod video_transcription;
 pub mod video_translation;
+pub mod webhook;
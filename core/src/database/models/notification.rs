@@ -0,0 +1,74 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+use super::video::stage::VideoStage;
+
+/// Which emails a user wants, users without a row receive all of them
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, sqlx::FromRow)]
+pub struct NotificationPreferences {
+    pub user_id: i32,
+    pub videos_finished: bool,
+    pub video_failed: bool,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "notification_kinds", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum NotificationKind {
+    /// Every cut of an original video was published
+    VideosFinished,
+    /// The queue gave up on a message of the videos
+    VideoFailed,
+}
+
+impl NotificationKind {
+    pub fn template(&self) -> &'static str {
+        match self {
+            NotificationKind::VideosFinished => "videos-finished",
+            NotificationKind::VideoFailed => "video-failed",
+        }
+    }
+
+    pub fn subject(&self) -> &'static str {
+        match self {
+            NotificationKind::VideosFinished => "Your videos are ready",
+            NotificationKind::VideoFailed => "Your videos failed",
+        }
+    }
+}
+
+/// An email waiting to be sent by the queue, `params` are the json of the template
+#[derive(Debug, Clone, PartialEq, sqlx::FromRow)]
+pub struct EmailNotification {
+    pub id: i64,
+    pub user_id: i32,
+    pub kind: NotificationKind,
+    pub reference: String,
+    pub params: String,
+    pub attempts: i32,
+    pub last_error: Option<String>,
+    pub sent_at: Option<NaiveDateTime>,
+    pub locked_until: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NotifiedVideo {
+    pub title: String,
+    pub url: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VideosFinishedParams {
+    pub original_url: String,
+    pub videos: Vec<NotifiedVideo>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VideoFailedParams {
+    pub stage: Option<VideoStage>,
+    pub error: String,
+    pub videos: Vec<NotifiedVideo>,
+}
@@ -0,0 +1,15 @@
+use chrono::NaiveDateTime;
+
+/// A pipeline message written in the same transaction as the rows it refers to.
+/// The relay of the queue claims it until `locked_until`, publishes it and sets `sent_at`
+#[derive(Debug, Clone, PartialEq, sqlx::FromRow)]
+pub struct OutboxMessage {
+    pub id: i64,
+    pub payload: String,
+    pub attempts: i32,
+    pub last_error: Option<String>,
+    pub sent_at: Option<NaiveDateTime>,
+    pub locked_until: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
@@ -0,0 +1,17 @@
+use chrono::NaiveDateTime;
+use uuid::Uuid;
+
+/// A message of the postgres backed queue.
+/// The receipt handle changes on every receive, like SQS does,
+/// and messages received too many times are moved to the dead letters
+#[derive(Debug, Clone, PartialEq, sqlx::FromRow)]
+pub struct QueuedMessage {
+    pub id: i64,
+    pub body: String,
+    pub receipt_handle: Option<Uuid>,
+    pub receive_count: i32,
+    pub visible_at: NaiveDateTime,
+    pub dead_lettered_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
@@ -0,0 +1,116 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+use crate::util::subtitles::Style;
+
+/// A named look of the burned subtitles, picked per video or per channel.
+/// Sizes are in pixels of the video, colors are `#RRGGBB` or `#RRGGBBAA`
+#[derive(Debug, Clone, PartialEq, sqlx::FromRow)]
+pub struct SubtitlesStyle {
+    pub id: i32,
+    pub user_id: i32,
+    pub name: String,
+    pub font_name: String,
+    pub font_size: i32,
+    pub primary_color: String,
+    pub outline_color: String,
+    pub outline: i32,
+    /// Draws a box of this color behind the text, instead of the outline
+    pub background_color: Option<String>,
+    pub position: SubtitlesPosition,
+    /// Distance to the edge of the video the subtitles are close to
+    pub margin: i32,
+    /// Characters per line, the lines are broken again when set
+    pub max_line_width: Option<i32>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "subtitles_positions", rename_all = "SCREAMING_SNAKE_CASE")]
+#[serde(rename_all = "snake_case")]
+pub enum SubtitlesPosition {
+    Top,
+    Middle,
+    #[default]
+    Bottom,
+}
+
+impl SubtitlesPosition {
+    /// The centered alignments of the numpad notation of ASS
+    pub fn alignment(&self) -> u8 {
+        match self {
+            SubtitlesPosition::Top => 8,
+            SubtitlesPosition::Middle => 5,
+            SubtitlesPosition::Bottom => 2,
+        }
+    }
+}
+
+/// `#RRGGBB` or `#RRGGBBAA` to the `&HAABBGGRR` of ASS, where the alpha is the transparency
+pub fn ass_color(color: &str) -> Option<String> {
+    let hex = color.strip_prefix('#')?;
+    if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+
+    let (red, green, blue, alpha) = match hex.len() {
+        6 => (&hex[0..2], &hex[2..4], &hex[4..6], "FF"),
+        8 => (&hex[0..2], &hex[2..4], &hex[4..6], &hex[6..8]),
+        _ => return None,
+    };
+
+    let transparency = 255 - u8::from_str_radix(alpha, 16).ok()?;
+
+    return Some(format!(
+        "&H{:02X}{}{}{}",
+        transparency,
+        blue.to_uppercase(),
+        green.to_uppercase(),
+        red.to_uppercase()
+    ));
+}
+
+impl From<&SubtitlesStyle> for Style {
+    /// Colors were validated when the style was saved, invalid ones keep the default
+    fn from(value: &SubtitlesStyle) -> Self {
+        let default = Style::default();
+        let color = |color: &str, default: String| ass_color(color).unwrap_or(default);
+
+        let mut style = Style {
+            font_name: value.font_name.clone(),
+            font_size: value.font_size.max(1) as u32,
+            primary_color: color(&value.primary_color, default.primary_color.clone()),
+            outline_color: color(&value.outline_color, default.outline_color.clone()),
+            outline: value.outline.max(0) as f32,
+            alignment: value.position.alignment(),
+            margin_v: value.margin.max(0) as u32,
+            ..default.clone()
+        };
+
+        // libass fills the box with the outline color
+        if let Some(background_color) = &value.background_color {
+            let background_color = color(background_color, default.back_color);
+            style.opaque_box = true;
+            style.outline_color = background_color.clone();
+            style.back_color = background_color;
+        }
+
+        return style;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ass_color() {
+        assert_eq!(ass_color("#FFCC00").as_deref(), Some("&H0000CCFF"));
+        assert_eq!(ass_color("#000000ff").as_deref(), Some("&H00000000"));
+        assert_eq!(ass_color("#00000080").as_deref(), Some("&H7F000000"));
+        assert_eq!(ass_color("FFCC00"), None);
+        assert_eq!(ass_color("#FFCC0"), None);
+        assert_eq!(ass_color("#GGCC00"), None);
+    }
+}
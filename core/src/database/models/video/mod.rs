@@ -8,7 +8,9 @@ use self::stage::VideoStage;
 
 use super::traits::FromRowAlias;
 
+pub mod speaker_labels;
 pub mod stage;
+pub mod subtitles_mode;
 
 pub mod with;
 
@@ -22,6 +24,7 @@ pub struct Video {
     pub channel_id: i32,
     pub url: Option<String>,
     pub language: String,
+    pub target_language: String,
     pub stage: VideoStage,
     pub error: bool,
     pub original_video_id: i32,
@@ -45,6 +48,7 @@ impl FromRowAlias for Video {
             channel_id: row.try_get(format!("{}channel_id", alias).as_str())?,
             url: row.try_get(format!("{}url", alias).as_str())?,
             language: row.try_get(format!("{}language", alias).as_str())?,
+            target_language: row.try_get(format!("{}target_language", alias).as_str())?,
             stage: row.try_get(format!("{}stage", alias).as_str())?,
             error: row.try_get(format!("{}error", alias).as_str())?,
             original_video_id: row.try_get(format!("{}original_video_id", alias).as_str())?,
@@ -0,0 +1,25 @@
+use serde::{Deserialize, Serialize};
+
+/// How the subtitles tell who is speaking
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(
+    type_name = "videos_speaker_labels",
+    rename_all = "SCREAMING_SNAKE_CASE"
+)]
+#[serde(rename_all = "snake_case")]
+pub enum SpeakerLabels {
+    /// The speakers are not told apart
+    #[default]
+    None,
+    /// A cue where the speaker changes starts with `- `
+    Dashes,
+    /// Each speaker gets a color, the first one keeps the color of the style
+    Colors,
+}
+
+impl SpeakerLabels {
+    /// Whether the transcription must tell the speakers apart
+    pub fn is_enabled(&self) -> bool {
+        return *self != SpeakerLabels::None;
+    }
+}
@@ -9,6 +9,8 @@ pub enum VideoStage {
     RawUploading,
     Transcribing,
     Translating,
+    /// Waiting for the user to approve the translated subtitles
+    Reviewing,
     Subtitling,
     Uploading,
     Done,
@@ -22,6 +24,7 @@ impl Display for VideoStage {
             VideoStage::RawUploading => write!(f, "RawUploading"),
             VideoStage::Transcribing => write!(f, "Transcribing"),
             VideoStage::Translating => write!(f, "Translating"),
+            VideoStage::Reviewing => write!(f, "Reviewing"),
             VideoStage::Subtitling => write!(f, "Subtitling"),
             VideoStage::Uploading => write!(f, "Uploading"),
             VideoStage::Done => write!(f, "Done"),
@@ -39,11 +42,12 @@ impl FromStr for VideoStage {
             "RawUploading" => Ok(VideoStage::RawUploading),
             "Transcribing" => Ok(VideoStage::Transcribing),
             "Translating" => Ok(VideoStage::Translating),
+            "Reviewing" => Ok(VideoStage::Reviewing),
             "Subtitling" => Ok(VideoStage::Subtitling),
             "Uploading" => Ok(VideoStage::Uploading),
             "Done" => Ok(VideoStage::Done),
             _ => Err(format!(
-                "{} is not a valid video stage. expected ('Downloading', 'Transcribing', 'Translating', 'Subtitling', 'Uploading', 'Done')",
+                "{} is not a valid video stage. expected ('Downloading', 'Transcribing', 'Translating', 'Reviewing', 'Subtitling', 'Uploading', 'Done')",
                 s
             )),
         }
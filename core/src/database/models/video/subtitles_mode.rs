@@ -0,0 +1,24 @@
+use serde::{Deserialize, Serialize};
+
+/// How the subtitles end up in the processed video
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(
+    type_name = "videos_subtitles_modes",
+    rename_all = "SCREAMING_SNAKE_CASE"
+)]
+#[serde(rename_all = "snake_case")]
+pub enum SubtitlesMode {
+    /// Drawn into the frames, the video is encoded again
+    #[default]
+    Burned,
+    /// Muxed as tracks the player can turn off, and uploaded as captions where the platform has them
+    Soft,
+    /// Burned, with the soft tracks as well
+    Both,
+}
+
+impl SubtitlesMode {
+    pub fn has_soft_tracks(&self) -> bool {
+        return *self != SubtitlesMode::Burned;
+    }
+}
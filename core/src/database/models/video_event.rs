@@ -0,0 +1,115 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::video::stage::VideoStage;
+
+/// Postgres channel the video events are notified on
+pub const VIDEO_EVENTS_CHANNEL: &str = "video_events";
+
+/// Notify payloads are limited to 8000 bytes
+const MAX_ERROR_LENGTH: usize = 1000;
+
+/// Something that happened to a video while it goes through the pipeline
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VideoEvent {
+    pub video_id: Uuid,
+    pub user_id: i32,
+    #[serde(flatten)]
+    pub kind: VideoEventKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum VideoEventKind {
+    Stage {
+        stage: VideoStage,
+    },
+    Error {
+        stage: VideoStage,
+        error: String,
+    },
+    Progress {
+        step: ProgressStep,
+        percentage: f32,
+    },
+    /// One per output, in its language
+    Published {
+        url: String,
+        language: String,
+    },
+}
+
+/// Steps that take long enough to report how far they are
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProgressStep {
+    Download,
+    Cut,
+    Subtitle,
+    Upload,
+}
+
+impl VideoEventKind {
+    pub fn error(stage: VideoStage, error: &str) -> Self {
+        let error = error.chars().take(MAX_ERROR_LENGTH).collect();
+        VideoEventKind::Error { stage, error }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            VideoEventKind::Stage { .. } => "stage",
+            VideoEventKind::Error { .. } => "error",
+            VideoEventKind::Progress { .. } => "progress",
+            VideoEventKind::Published { .. } => "published",
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use uuid::Uuid;
+
+    use crate::database::models::video::stage::VideoStage;
+
+    use super::{ProgressStep, VideoEvent, VideoEventKind};
+
+    #[test]
+    fn test_serialize_flattens_the_kind() {
+        let event = VideoEvent {
+            video_id: Uuid::nil(),
+            user_id: 1,
+            kind: VideoEventKind::Progress {
+                step: ProgressStep::Download,
+                percentage: 50.0,
+            },
+        };
+
+        let json = serde_json::to_value(&event).unwrap();
+
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "video_id": Uuid::nil(),
+                "user_id": 1,
+                "type": "progress",
+                "step": "download",
+                "percentage": 50.0
+            })
+        );
+
+        let parsed: VideoEvent = serde_json::from_value(json).unwrap();
+        assert_eq!(parsed, event);
+    }
+
+    #[test]
+    fn test_error_is_truncated() {
+        let error = "a".repeat(2000);
+
+        let kind = VideoEventKind::error(VideoStage::Cutting, &error);
+
+        match kind {
+            VideoEventKind::Error { error, .. } => assert_eq!(error.len(), 1000),
+            _ => panic!("Expected an error event"),
+        }
+    }
+}
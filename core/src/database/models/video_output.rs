@@ -0,0 +1,21 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use super::video::stage::VideoStage;
+
+/// A translated rendition of a video. Each one is translated,
+/// subtitled and uploaded to its own channel
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, FromRow)]
+pub struct VideoOutput {
+    pub id: i32,
+    pub video_id: Uuid,
+    pub channel_id: i32,
+    pub language: String,
+    pub stage: VideoStage,
+    pub url: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+    pub uploaded_at: Option<NaiveDateTime>,
+}
@@ -0,0 +1,17 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// The translated srt of an output, held until the user approves it.
+/// Only videos created with `review` pause for it before being subtitled
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, FromRow)]
+pub struct VideoReview {
+    pub id: i32,
+    pub video_id: Uuid,
+    pub language: String,
+    pub srt: String,
+    pub approved_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
@@ -62,6 +62,7 @@ pub struct VideosStorage {
     pub format: VideoFormat,
     pub video_path: String,
     pub size: i64,
+    pub language: Option<String>,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
     pub deleted_at: Option<NaiveDateTime>,
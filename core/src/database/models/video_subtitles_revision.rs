@@ -0,0 +1,18 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// An edited srt of an output, stored as a new object on every edit.
+/// Revision 0 is the translated srt, it lives on `videos_translations`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, FromRow)]
+pub struct VideoSubtitlesRevision {
+    pub id: i32,
+    pub video_id: Uuid,
+    pub language: String,
+    pub revision: i32,
+    pub storage_id: i32,
+    pub path: String,
+    pub user_id: i32,
+    pub created_at: NaiveDateTime,
+}
@@ -0,0 +1,70 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use sqlx::postgres::{PgHasArrayType, PgTypeInfo};
+
+use super::video_event::VideoEventKind;
+
+/// An endpoint of a user that receives the events of its videos
+#[derive(Debug, Clone, PartialEq, sqlx::FromRow)]
+pub struct Webhook {
+    pub id: i32,
+    pub user_id: i32,
+    pub url: String,
+    /// Key of the HMAC signature sent with every delivery
+    pub secret: String,
+    pub events: Vec<WebhookEvent>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+    pub deleted_at: Option<NaiveDateTime>,
+}
+
+/// The video events a webhook can subscribe to, progress is too frequent to be delivered
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "webhook_events", rename_all = "SCREAMING_SNAKE_CASE")]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookEvent {
+    Stage,
+    Error,
+    Published,
+}
+
+impl PgHasArrayType for WebhookEvent {
+    fn array_type_info() -> PgTypeInfo {
+        PgTypeInfo::with_name("_webhook_events")
+    }
+}
+
+impl WebhookEvent {
+    pub fn from_kind(kind: &VideoEventKind) -> Option<Self> {
+        match kind {
+            VideoEventKind::Stage { .. } => Some(WebhookEvent::Stage),
+            VideoEventKind::Error { .. } => Some(WebhookEvent::Error),
+            VideoEventKind::Published { .. } => Some(WebhookEvent::Published),
+            VideoEventKind::Progress { .. } => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            WebhookEvent::Stage => "stage",
+            WebhookEvent::Error => "error",
+            WebhookEvent::Published => "published",
+        }
+    }
+}
+
+/// One event sent to one webhook, retried with a backoff until the endpoint accepts it
+#[derive(Debug, Clone, PartialEq, sqlx::FromRow)]
+pub struct WebhookDelivery {
+    pub id: i64,
+    pub webhook_id: i32,
+    pub event: WebhookEvent,
+    pub payload: String,
+    pub attempts: i32,
+    pub response_status: Option<i32>,
+    pub last_error: Option<String>,
+    pub next_attempt_at: NaiveDateTime,
+    pub delivered_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
@@ -0,0 +1,97 @@
+use sqlx::{PgExecutor, PgPool};
+use uuid::Uuid;
+
+use crate::database::models::video::stage::VideoStage;
+
+/// Stages done once per video are stored without a language
+fn language_key(language: Option<&str>) -> &str {
+    language.unwrap_or("")
+}
+
+pub async fn exists(
+    pool: &PgPool,
+    video_id: &Uuid,
+    stage: VideoStage,
+    language: Option<&str>,
+) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query!(
+        r#"
+        SELECT EXISTS(
+            SELECT 1 FROM videos_checkpoints
+            WHERE video_id = $1 AND stage = $2 AND language = $3
+        ) as "exists!"
+        "#,
+        video_id,
+        stage as VideoStage,
+        language_key(language),
+    )
+    .fetch_one(pool)
+    .await?;
+
+    Ok(result.exists)
+}
+
+pub async fn create(
+    pool: impl PgExecutor<'_>,
+    video_id: &Uuid,
+    stage: VideoStage,
+    language: Option<&str>,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        r#"
+        INSERT INTO videos_checkpoints (video_id, stage, language)
+        VALUES ($1, $2, $3)
+        ON CONFLICT DO NOTHING
+        "#,
+        video_id,
+        stage as VideoStage,
+        language_key(language),
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn delete(
+    pool: impl PgExecutor<'_>,
+    video_id: &Uuid,
+    stage: VideoStage,
+    language: Option<&str>,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        r#"
+        DELETE FROM videos_checkpoints
+        WHERE video_id = $1 AND stage = $2 AND language = $3
+        "#,
+        video_id,
+        stage as VideoStage,
+        language_key(language),
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Keeps the checkpoints of the outputs already done, their stages must not run again
+pub async fn delete_unfinished_by_video_id(
+    pool: impl PgExecutor<'_>,
+    video_id: &Uuid,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        r#"
+        DELETE FROM videos_checkpoints c
+        WHERE c.video_id = $1
+        AND NOT EXISTS(
+            SELECT 1 FROM videos_outputs o
+            WHERE o.video_id = c.video_id AND o.language = c.language AND o.stage = 'DONE'
+        )
+        "#,
+        video_id,
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
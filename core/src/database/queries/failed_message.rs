@@ -0,0 +1,109 @@
+use chrono::NaiveDateTime;
+use sqlx::{PgExecutor, PgPool};
+use uuid::Uuid;
+
+use crate::database::models::{failed_message::FailedMessage, video::stage::VideoStage};
+
+pub struct CreateFailedMessageDto<'a> {
+    pub payload: &'a str,
+    pub video_ids: &'a [Uuid],
+    pub error: &'a str,
+    pub stage: Option<VideoStage>,
+    pub attempts: i32,
+}
+
+pub async fn create(
+    pool: impl PgExecutor<'_>,
+    dto: CreateFailedMessageDto<'_>,
+) -> Result<i32, sqlx::Error> {
+    let row = sqlx::query!(
+        r#"
+        INSERT INTO failed_messages (payload, video_ids, error, stage, attempts)
+        VALUES ($1, $2, $3, $4, $5)
+        RETURNING id
+        "#,
+        dto.payload,
+        dto.video_ids,
+        dto.error,
+        dto.stage as Option<VideoStage>,
+        dto.attempts,
+    )
+    .fetch_one(pool)
+    .await?;
+
+    Ok(row.id)
+}
+
+pub async fn find_all(pool: &PgPool) -> Result<Vec<FailedMessage>, sqlx::Error> {
+    let messages = sqlx::query_as!(
+        FailedMessage,
+        r#"
+        SELECT
+            id,
+            payload,
+            video_ids as "video_ids: Vec<Uuid>",
+            error,
+            stage as "stage: VideoStage",
+            attempts,
+            replayed_at as "replayed_at: NaiveDateTime",
+            created_at as "created_at: NaiveDateTime",
+            updated_at as "updated_at: NaiveDateTime"
+        FROM failed_messages
+        ORDER BY id DESC
+        "#
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(messages)
+}
+
+pub async fn find_by_id(pool: &PgPool, id: i32) -> Result<FailedMessage, sqlx::Error> {
+    let message = sqlx::query_as!(
+        FailedMessage,
+        r#"
+        SELECT
+            id,
+            payload,
+            video_ids as "video_ids: Vec<Uuid>",
+            error,
+            stage as "stage: VideoStage",
+            attempts,
+            replayed_at as "replayed_at: NaiveDateTime",
+            created_at as "created_at: NaiveDateTime",
+            updated_at as "updated_at: NaiveDateTime"
+        FROM failed_messages
+        WHERE id = $1
+        "#,
+        id
+    )
+    .fetch_one(pool)
+    .await?;
+
+    Ok(message)
+}
+
+/// Claims the replay of a message, storing the payload that is sent again since it may have
+/// been edited. False when the message was already replayed, so it is never sent twice
+pub async fn set_replayed(
+    pool: impl PgExecutor<'_>,
+    id: i32,
+    payload: &str,
+) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query!(
+        r#"
+        UPDATE failed_messages
+        SET
+            payload = $1,
+            replayed_at = NOW(),
+            updated_at = NOW()
+        WHERE id = $2 AND replayed_at IS NULL
+        "#,
+        payload,
+        id
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
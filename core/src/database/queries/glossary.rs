@@ -0,0 +1,114 @@
+use chrono::NaiveDateTime;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::database::models::glossary_term::GlossaryTerm;
+
+pub struct CreateGlossaryTermDto<'a> {
+    pub channel_id: i32,
+    pub term: &'a str,
+    pub language: Option<&'a str>,
+    pub translation: Option<&'a str>,
+}
+
+pub async fn create(
+    pool: &PgPool,
+    dto: CreateGlossaryTermDto<'_>,
+) -> Result<GlossaryTerm, sqlx::Error> {
+    let term = sqlx::query_as!(
+        GlossaryTerm,
+        r#"
+        INSERT INTO glossary_terms (channel_id, term, language, translation)
+        VALUES ($1, $2, $3, $4)
+        RETURNING
+            id,
+            channel_id,
+            term,
+            language,
+            translation,
+            created_at as "created_at: NaiveDateTime",
+            updated_at as "updated_at: NaiveDateTime"
+        "#,
+        dto.channel_id,
+        dto.term,
+        dto.language,
+        dto.translation,
+    )
+    .fetch_one(pool)
+    .await?;
+
+    Ok(term)
+}
+
+pub async fn find_all_by_channel(
+    pool: &PgPool,
+    channel_id: i32,
+) -> Result<Vec<GlossaryTerm>, sqlx::Error> {
+    let terms = sqlx::query_as!(
+        GlossaryTerm,
+        r#"
+        SELECT
+            id,
+            channel_id,
+            term,
+            language,
+            translation,
+            created_at as "created_at: NaiveDateTime",
+            updated_at as "updated_at: NaiveDateTime"
+        FROM glossary_terms
+        WHERE channel_id = $1
+        ORDER BY id
+        "#,
+        channel_id,
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(terms)
+}
+
+/// The glossary of the channel the video belongs to
+pub async fn find_all_by_video(
+    pool: &PgPool,
+    video_id: &Uuid,
+) -> Result<Vec<GlossaryTerm>, sqlx::Error> {
+    let terms = sqlx::query_as!(
+        GlossaryTerm,
+        r#"
+        SELECT
+            g.id,
+            g.channel_id,
+            g.term,
+            g.language,
+            g.translation,
+            g.created_at as "created_at: NaiveDateTime",
+            g.updated_at as "updated_at: NaiveDateTime"
+        FROM glossary_terms g
+        INNER JOIN videos v ON v.channel_id = g.channel_id
+        WHERE v.id = $1
+        ORDER BY g.id
+        "#,
+        video_id,
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(terms)
+}
+
+/// Fails with `RowNotFound` when the term is not on the glossary of the channel
+pub async fn delete(pool: &PgPool, channel_id: i32, id: i32) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        r#"
+        DELETE FROM glossary_terms
+        WHERE id = $1 AND channel_id = $2
+        RETURNING id
+        "#,
+        id,
+        channel_id,
+    )
+    .fetch_one(pool)
+    .await?;
+
+    Ok(())
+}
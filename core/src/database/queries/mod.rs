@@ -1,15 +1,27 @@
 pub mod channel;
+pub mod checkpoint;
+pub mod failed_message;
 pub mod filter;
+pub mod glossary;
 mod macros;
+pub mod notification;
 pub mod original_video;
+pub mod outbox;
+pub mod output;
 pub mod pagination;
+pub mod queue;
+pub mod review;
 pub mod storage;
+pub mod subtitles_revision;
+pub mod subtitles_style;
 pub mod subtitling;
 pub mod transcription;
 pub mod translation;
 pub mod user;
 pub mod video;
 pub mod video_error;
+pub mod video_event;
+pub mod webhook;
 
 #[cfg(test)]
 mod test;
@@ -0,0 +1,238 @@
+use chrono::NaiveDateTime;
+use sqlx::{PgExecutor, PgPool};
+
+use crate::database::models::notification::{
+    EmailNotification, NotificationKind, NotificationPreferences,
+};
+
+pub async fn find_preferences(
+    pool: &PgPool,
+    user_id: i32,
+) -> Result<Option<NotificationPreferences>, sqlx::Error> {
+    let preferences = sqlx::query_as!(
+        NotificationPreferences,
+        r#"
+        SELECT
+            user_id,
+            videos_finished,
+            video_failed,
+            created_at as "created_at: NaiveDateTime",
+            updated_at as "updated_at: NaiveDateTime"
+        FROM notification_preferences
+        WHERE user_id = $1
+        "#,
+        user_id,
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(preferences)
+}
+
+pub struct UpdatePreferencesDto {
+    pub user_id: i32,
+    pub videos_finished: bool,
+    pub video_failed: bool,
+}
+
+pub async fn upsert_preferences(
+    pool: &PgPool,
+    dto: UpdatePreferencesDto,
+) -> Result<NotificationPreferences, sqlx::Error> {
+    let preferences = sqlx::query_as!(
+        NotificationPreferences,
+        r#"
+        INSERT INTO notification_preferences (user_id, videos_finished, video_failed)
+        VALUES ($1, $2, $3)
+        ON CONFLICT (user_id) DO UPDATE
+        SET videos_finished = $2, video_failed = $3, updated_at = NOW()
+        RETURNING
+            user_id,
+            videos_finished,
+            video_failed,
+            created_at as "created_at: NaiveDateTime",
+            updated_at as "updated_at: NaiveDateTime"
+        "#,
+        dto.user_id,
+        dto.videos_finished,
+        dto.video_failed,
+    )
+    .fetch_one(pool)
+    .await?;
+
+    Ok(preferences)
+}
+
+/// Whether the user wants this kind of email, everything is sent until they opt out
+pub async fn wants(
+    pool: &PgPool,
+    user_id: i32,
+    kind: NotificationKind,
+) -> Result<bool, sqlx::Error> {
+    let preferences = match find_preferences(pool, user_id).await? {
+        Some(preferences) => preferences,
+        None => return Ok(true),
+    };
+
+    let wants = match kind {
+        NotificationKind::VideosFinished => preferences.videos_finished,
+        NotificationKind::VideoFailed => preferences.video_failed,
+    };
+
+    Ok(wants)
+}
+
+pub struct CreateNotificationDto<'a> {
+    pub user_id: i32,
+    pub kind: NotificationKind,
+    pub reference: &'a str,
+    pub params: &'a str,
+}
+
+/// Returns false when the reference was already notified
+pub async fn enqueue(
+    pool: impl PgExecutor<'_>,
+    dto: CreateNotificationDto<'_>,
+) -> Result<bool, sqlx::Error> {
+    let row = sqlx::query!(
+        r#"
+        INSERT INTO email_notifications (user_id, kind, reference, params)
+        VALUES ($1, $2, $3, $4)
+        ON CONFLICT (kind, reference) DO NOTHING
+        RETURNING id
+        "#,
+        dto.user_id,
+        dto.kind as NotificationKind,
+        dto.reference,
+        dto.params,
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row.is_some())
+}
+
+/// A notification along with who receives it
+#[derive(Debug, Clone, PartialEq)]
+pub struct PendingNotification {
+    pub id: i64,
+    pub kind: NotificationKind,
+    pub params: String,
+    pub attempts: i32,
+    pub email: String,
+    pub name: String,
+}
+
+/// Takes the next emails out of reach of the other queues for `lease_seconds`.
+/// An email whose queue died before `mark_sent` goes out again once the lease is over,
+/// the user may get it twice but never misses it
+pub async fn claim_pending(
+    pool: impl PgExecutor<'_>,
+    limit: i64,
+    max_attempts: i32,
+    lease_seconds: i32,
+) -> Result<Vec<PendingNotification>, sqlx::Error> {
+    let notifications = sqlx::query_as!(
+        PendingNotification,
+        r#"
+        WITH claimed AS (
+            UPDATE email_notifications
+            SET
+                attempts = attempts + 1,
+                locked_until = NOW() + $3 * INTERVAL '1 second',
+                updated_at = NOW()
+            WHERE id IN (
+                SELECT id FROM email_notifications
+                WHERE
+                    sent_at IS NULL
+                    AND attempts < $2
+                    AND (locked_until IS NULL OR locked_until <= NOW())
+                ORDER BY id
+                LIMIT $1
+                FOR UPDATE SKIP LOCKED
+            )
+            RETURNING id, user_id, kind, params, attempts
+        )
+        SELECT
+            c.id as "id!",
+            c.kind as "kind!: NotificationKind",
+            c.params as "params!",
+            c.attempts as "attempts!",
+            u.email,
+            u.name
+        FROM claimed c
+        INNER JOIN users u ON u.id = c.user_id
+        ORDER BY c.id
+        "#,
+        limit,
+        max_attempts,
+        lease_seconds as f64,
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(notifications)
+}
+
+/// The unsent notifications, claimed or not
+pub async fn find_unsent(pool: impl PgExecutor<'_>) -> Result<Vec<EmailNotification>, sqlx::Error> {
+    let notifications = sqlx::query_as!(
+        EmailNotification,
+        r#"
+        SELECT
+            id,
+            user_id,
+            kind as "kind: NotificationKind",
+            reference,
+            params,
+            attempts,
+            last_error,
+            sent_at as "sent_at: NaiveDateTime",
+            locked_until as "locked_until: NaiveDateTime",
+            created_at as "created_at: NaiveDateTime",
+            updated_at as "updated_at: NaiveDateTime"
+        FROM email_notifications
+        WHERE sent_at IS NULL
+        ORDER BY id
+        "#,
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(notifications)
+}
+
+pub async fn mark_sent(pool: impl PgExecutor<'_>, id: i64) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        r#"
+        UPDATE email_notifications
+        SET sent_at = NOW(), locked_until = NULL, updated_at = NOW()
+        WHERE id = $1
+        "#,
+        id,
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn mark_failed(
+    pool: impl PgExecutor<'_>,
+    id: i64,
+    error: &str,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        r#"
+        UPDATE email_notifications
+        SET last_error = $2, locked_until = NULL, updated_at = NOW()
+        WHERE id = $1
+        "#,
+        id,
+        error,
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
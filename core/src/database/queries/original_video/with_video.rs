@@ -12,6 +12,7 @@ SELECT
     v.channel_id AS "v.channel_id", 
     v.url AS "v.url", 
     v.language AS "v.language", 
+    v.target_language AS "v.target_language", 
     v.stage AS "v.stage", 
     v.error AS "v.error", 
     v.original_video_id AS "v.original_video_id", 
@@ -0,0 +1,182 @@
+use chrono::NaiveDateTime;
+use sqlx::PgExecutor;
+
+use crate::{
+    database::models::outbox_message::OutboxMessage,
+    internals::cloud::{models::payload::PayloadType, traits::BucketClient},
+};
+
+pub async fn enqueue(pool: impl PgExecutor<'_>, payload: &PayloadType) -> Result<i64, sqlx::Error> {
+    let row = sqlx::query!(
+        r#"
+        INSERT INTO outbox_messages (payload)
+        VALUES ($1)
+        RETURNING id
+        "#,
+        payload.to_json(),
+    )
+    .fetch_one(pool)
+    .await?;
+
+    Ok(row.id)
+}
+
+/// Enqueues the event of an upload when the bucket doesn't notify it, on the transaction
+/// that records the upload, so the event is never published for a rolled back one
+pub async fn enqueue_upload_event(
+    pool: impl PgExecutor<'_>,
+    bucket_client: &impl BucketClient,
+    file_uri: &str,
+) -> Result<(), sqlx::Error> {
+    if let Some(event) = bucket_client.upload_event(file_uri) {
+        enqueue(pool, &event).await?;
+    }
+
+    Ok(())
+}
+
+/// Claims the oldest unsent messages for `lease_seconds`, counting the attempt up front.
+/// The claim is committed before anything is published, a relay that stops midway
+/// leaves its messages to the next one once the lease ends
+pub async fn claim_pending(
+    pool: impl PgExecutor<'_>,
+    limit: i64,
+    max_attempts: i32,
+    lease_seconds: i32,
+) -> Result<Vec<OutboxMessage>, sqlx::Error> {
+    let mut messages = sqlx::query_as!(
+        OutboxMessage,
+        r#"
+        UPDATE outbox_messages
+        SET
+            attempts = attempts + 1,
+            locked_until = NOW() + $3 * INTERVAL '1 second',
+            updated_at = NOW()
+        WHERE id IN (
+            SELECT id FROM outbox_messages
+            WHERE
+                sent_at IS NULL
+                AND attempts < $2
+                AND (locked_until IS NULL OR locked_until <= NOW())
+            ORDER BY id
+            LIMIT $1
+            FOR UPDATE SKIP LOCKED
+        )
+        RETURNING
+            id,
+            payload,
+            attempts,
+            last_error,
+            sent_at as "sent_at: NaiveDateTime",
+            locked_until as "locked_until: NaiveDateTime",
+            created_at as "created_at: NaiveDateTime",
+            updated_at as "updated_at: NaiveDateTime"
+        "#,
+        limit,
+        max_attempts,
+        lease_seconds as f64,
+    )
+    .fetch_all(pool)
+    .await?;
+
+    messages.sort_by_key(|message| message.id);
+    Ok(messages)
+}
+
+/// Removes the unsent messages that used their `max_attempts`, once their last claim ended.
+/// The relay keeps them as failed messages, which can be replayed
+pub async fn take_exhausted(
+    pool: impl PgExecutor<'_>,
+    max_attempts: i32,
+) -> Result<Vec<OutboxMessage>, sqlx::Error> {
+    let mut messages = sqlx::query_as!(
+        OutboxMessage,
+        r#"
+        DELETE FROM outbox_messages
+        WHERE id IN (
+            SELECT id FROM outbox_messages
+            WHERE
+                sent_at IS NULL
+                AND attempts >= $1
+                AND (locked_until IS NULL OR locked_until <= NOW())
+            FOR UPDATE SKIP LOCKED
+        )
+        RETURNING
+            id,
+            payload,
+            attempts,
+            last_error,
+            sent_at as "sent_at: NaiveDateTime",
+            locked_until as "locked_until: NaiveDateTime",
+            created_at as "created_at: NaiveDateTime",
+            updated_at as "updated_at: NaiveDateTime"
+        "#,
+        max_attempts,
+    )
+    .fetch_all(pool)
+    .await?;
+
+    messages.sort_by_key(|message| message.id);
+    Ok(messages)
+}
+
+/// The messages still to be published, oldest first
+pub async fn find_unsent(pool: impl PgExecutor<'_>) -> Result<Vec<OutboxMessage>, sqlx::Error> {
+    let messages = sqlx::query_as!(
+        OutboxMessage,
+        r#"
+        SELECT
+            id,
+            payload,
+            attempts,
+            last_error,
+            sent_at as "sent_at: NaiveDateTime",
+            locked_until as "locked_until: NaiveDateTime",
+            created_at as "created_at: NaiveDateTime",
+            updated_at as "updated_at: NaiveDateTime"
+        FROM outbox_messages
+        WHERE sent_at IS NULL
+        ORDER BY id
+        "#
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(messages)
+}
+
+pub async fn mark_sent(pool: impl PgExecutor<'_>, id: i64) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        r#"
+        UPDATE outbox_messages
+        SET sent_at = NOW(), locked_until = NULL, updated_at = NOW()
+        WHERE id = $1
+        "#,
+        id,
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Gives the claim back, the message is retried on the next batch
+pub async fn mark_failed(
+    pool: impl PgExecutor<'_>,
+    id: i64,
+    error: &str,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        r#"
+        UPDATE outbox_messages
+        SET last_error = $2, locked_until = NULL, updated_at = NOW()
+        WHERE id = $1
+        "#,
+        id,
+        error,
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
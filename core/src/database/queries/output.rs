@@ -0,0 +1,198 @@
+use chrono::NaiveDateTime;
+use sqlx::{PgConnection, PgExecutor, PgPool, QueryBuilder};
+use uuid::Uuid;
+
+use crate::database::{
+    models::{
+        video::stage::VideoStage,
+        video_event::{VideoEvent, VideoEventKind},
+        video_output::VideoOutput,
+    },
+    queries::video_event,
+};
+
+pub struct CreateOutputDto<'a> {
+    pub video_id: Uuid,
+    pub channel_id: i32,
+    pub language: &'a str,
+}
+
+pub async fn create_many(
+    pool: impl PgExecutor<'_>,
+    dtos: Vec<CreateOutputDto<'_>>,
+) -> Result<(), sqlx::Error> {
+    let mut query_builder =
+        QueryBuilder::new("INSERT INTO videos_outputs (video_id, channel_id, language) ");
+
+    query_builder.push_values(&dtos, |mut builder, dto| {
+        builder
+            .push_bind(dto.video_id)
+            .push_bind(dto.channel_id)
+            .push_bind(dto.language);
+    });
+
+    let insert_query = query_builder.build();
+    insert_query.execute(pool).await?;
+
+    Ok(())
+}
+
+pub async fn find_by_video_id(
+    pool: &PgPool,
+    video_id: &Uuid,
+) -> Result<Vec<VideoOutput>, sqlx::Error> {
+    let outputs = sqlx::query_as!(
+        VideoOutput,
+        r#"
+        SELECT
+            id,
+            video_id as "video_id: Uuid",
+            channel_id,
+            language,
+            stage as "stage: VideoStage",
+            url,
+            created_at as "created_at: NaiveDateTime",
+            updated_at as "updated_at: NaiveDateTime",
+            uploaded_at as "uploaded_at: NaiveDateTime"
+        FROM videos_outputs
+        WHERE video_id = $1
+        ORDER BY id
+        "#,
+        video_id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(outputs)
+}
+
+pub async fn find_by_video_id_and_owner(
+    pool: &PgPool,
+    video_id: &Uuid,
+    user_id: i32,
+) -> Result<Vec<VideoOutput>, sqlx::Error> {
+    let outputs = sqlx::query_as!(
+        VideoOutput,
+        r#"
+        SELECT
+            vo.id,
+            vo.video_id as "video_id: Uuid",
+            vo.channel_id,
+            vo.language,
+            vo.stage as "stage: VideoStage",
+            vo.url,
+            vo.created_at as "created_at: NaiveDateTime",
+            vo.updated_at as "updated_at: NaiveDateTime",
+            vo.uploaded_at as "uploaded_at: NaiveDateTime"
+        FROM videos_outputs vo
+        JOIN videos v ON v.id = vo.video_id
+        WHERE vo.video_id = $1 AND v.user_id = $2
+        ORDER BY vo.id
+        "#,
+        video_id,
+        user_id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(outputs)
+}
+
+pub async fn find_by_video_id_and_language(
+    pool: &PgPool,
+    video_id: &Uuid,
+    language: &str,
+) -> Result<VideoOutput, sqlx::Error> {
+    let output = sqlx::query_as!(
+        VideoOutput,
+        r#"
+        SELECT
+            id,
+            video_id as "video_id: Uuid",
+            channel_id,
+            language,
+            stage as "stage: VideoStage",
+            url,
+            created_at as "created_at: NaiveDateTime",
+            updated_at as "updated_at: NaiveDateTime",
+            uploaded_at as "uploaded_at: NaiveDateTime"
+        FROM videos_outputs
+        WHERE video_id = $1 AND language = $2
+        "#,
+        video_id,
+        language
+    )
+    .fetch_one(pool)
+    .await?;
+
+    Ok(output)
+}
+
+pub async fn change_stage(
+    pool: impl PgExecutor<'_>,
+    id: i32,
+    stage: VideoStage,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        r#"
+        UPDATE videos_outputs
+        SET
+        stage = $1,
+        updated_at = NOW()
+        WHERE id = $2
+        "#,
+        stage as VideoStage,
+        id,
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Also publishes the url, the event is sent when the transaction of the caller commits
+pub async fn set_url(conn: &mut PgConnection, id: i32, url: &str) -> Result<(), sqlx::Error> {
+    let result = sqlx::query!(
+        r#"
+        UPDATE videos_outputs o
+        SET url = $1, stage = 'DONE', uploaded_at = NOW(), updated_at = NOW()
+        FROM videos v
+        WHERE o.id = $2 AND v.id = o.video_id
+        RETURNING o.video_id as "video_id: Uuid", o.language, v.user_id
+        "#,
+        url,
+        id,
+    )
+    .fetch_optional(&mut *conn)
+    .await?;
+
+    if let Some(result) = result {
+        let event = VideoEvent {
+            video_id: result.video_id,
+            user_id: result.user_id,
+            kind: VideoEventKind::Published {
+                url: url.to_string(),
+                language: result.language,
+            },
+        };
+        video_event::publish(conn, &event).await?;
+    }
+
+    Ok(())
+}
+
+/// Counts the outputs of a video that were not published yet
+pub async fn count_unfinished(pool: &PgPool, video_id: &Uuid) -> Result<i64, sqlx::Error> {
+    let row = sqlx::query!(
+        r#"
+        SELECT COUNT(*) as "count!: i64"
+        FROM videos_outputs
+        WHERE video_id = $1 AND stage != 'DONE'
+        "#,
+        video_id
+    )
+    .fetch_one(pool)
+    .await?;
+
+    Ok(row.count)
+}
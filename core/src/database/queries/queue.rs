@@ -0,0 +1,159 @@
+use chrono::NaiveDateTime;
+use sqlx::PgExecutor;
+use uuid::Uuid;
+
+use crate::database::models::queue_message::QueuedMessage;
+
+pub async fn send(
+    pool: impl PgExecutor<'_>,
+    body: &str,
+    delay_seconds: i32,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        r#"
+        INSERT INTO queue_messages (body, visible_at)
+        VALUES ($1, NOW() + $2 * INTERVAL '1 second')
+        "#,
+        body,
+        delay_seconds as f64,
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Locks up to `max_messages` visible messages, hiding them for `visibility_timeout` seconds.
+/// Dead letters and messages already received `max_receive_count` times are skipped
+pub async fn receive(
+    pool: impl PgExecutor<'_>,
+    max_messages: i64,
+    visibility_timeout: i32,
+    max_receive_count: i32,
+) -> Result<Vec<QueuedMessage>, sqlx::Error> {
+    let messages = sqlx::query_as!(
+        QueuedMessage,
+        r#"
+        UPDATE queue_messages
+        SET
+            receipt_handle = gen_random_uuid(),
+            receive_count = receive_count + 1,
+            visible_at = NOW() + $2 * INTERVAL '1 second',
+            updated_at = NOW()
+        WHERE id IN (
+            SELECT id FROM queue_messages
+            WHERE
+                visible_at <= NOW()
+                AND dead_lettered_at IS NULL
+                AND receive_count < $3
+            ORDER BY id
+            LIMIT $1
+            FOR UPDATE SKIP LOCKED
+        )
+        RETURNING
+            id,
+            body,
+            receipt_handle as "receipt_handle: Uuid",
+            receive_count,
+            visible_at as "visible_at: NaiveDateTime",
+            dead_lettered_at as "dead_lettered_at: NaiveDateTime",
+            created_at as "created_at: NaiveDateTime",
+            updated_at as "updated_at: NaiveDateTime"
+        "#,
+        max_messages,
+        visibility_timeout as f64,
+        max_receive_count,
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(messages)
+}
+
+pub async fn delete(pool: impl PgExecutor<'_>, receipt_handle: &Uuid) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        r#"
+        DELETE FROM queue_messages
+        WHERE receipt_handle = $1
+        "#,
+        receipt_handle
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn change_visibility(
+    pool: impl PgExecutor<'_>,
+    receipt_handle: &Uuid,
+    visibility_timeout: i32,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        r#"
+        UPDATE queue_messages
+        SET
+            visible_at = NOW() + $2 * INTERVAL '1 second',
+            updated_at = NOW()
+        WHERE receipt_handle = $1
+        "#,
+        receipt_handle,
+        visibility_timeout as f64,
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Moves the visible messages that were already received `max_receive_count` times to the dead letters,
+/// returns how many were moved
+pub async fn dead_letter_exhausted(
+    pool: impl PgExecutor<'_>,
+    max_receive_count: i32,
+) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query!(
+        r#"
+        UPDATE queue_messages
+        SET
+            dead_lettered_at = NOW(),
+            receipt_handle = NULL,
+            updated_at = NOW()
+        WHERE
+            dead_lettered_at IS NULL
+            AND visible_at <= NOW()
+            AND receive_count >= $1
+        "#,
+        max_receive_count
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+pub async fn find_dead_letters(
+    pool: impl PgExecutor<'_>,
+) -> Result<Vec<QueuedMessage>, sqlx::Error> {
+    let messages = sqlx::query_as!(
+        QueuedMessage,
+        r#"
+        SELECT
+            id,
+            body,
+            receipt_handle as "receipt_handle: Uuid",
+            receive_count,
+            visible_at as "visible_at: NaiveDateTime",
+            dead_lettered_at as "dead_lettered_at: NaiveDateTime",
+            created_at as "created_at: NaiveDateTime",
+            updated_at as "updated_at: NaiveDateTime"
+        FROM queue_messages
+        WHERE dead_lettered_at IS NOT NULL
+        ORDER BY dead_lettered_at
+        "#
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(messages)
+}
@@ -0,0 +1,119 @@
+use chrono::NaiveDateTime;
+use sqlx::{PgExecutor, PgPool};
+use uuid::Uuid;
+
+use crate::database::models::video_review::VideoReview;
+
+pub struct CreateReviewDto<'a> {
+    pub video_id: &'a Uuid,
+    pub language: &'a str,
+    pub srt: &'a str,
+}
+
+/// A redelivered translation keeps the review the user may already be editing
+pub async fn create(
+    pool: impl PgExecutor<'_>,
+    dto: CreateReviewDto<'_>,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        r#"
+        INSERT INTO videos_reviews (video_id, language, srt)
+        VALUES ($1, $2, $3)
+        ON CONFLICT (video_id, language) DO NOTHING
+        "#,
+        dto.video_id,
+        dto.language,
+        dto.srt,
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn find_by_video_id(
+    pool: &PgPool,
+    video_id: &Uuid,
+) -> Result<Vec<VideoReview>, sqlx::Error> {
+    let reviews = sqlx::query_as!(
+        VideoReview,
+        r#"
+        SELECT
+            id,
+            video_id as "video_id: Uuid",
+            language,
+            srt,
+            approved_at as "approved_at: NaiveDateTime",
+            created_at as "created_at: NaiveDateTime",
+            updated_at as "updated_at: NaiveDateTime"
+        FROM videos_reviews
+        WHERE video_id = $1
+        ORDER BY id
+        "#,
+        video_id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(reviews)
+}
+
+pub async fn find_by_video_id_and_language(
+    pool: impl PgExecutor<'_>,
+    video_id: &Uuid,
+    language: &str,
+) -> Result<Option<VideoReview>, sqlx::Error> {
+    let review = sqlx::query_as!(
+        VideoReview,
+        r#"
+        SELECT
+            id,
+            video_id as "video_id: Uuid",
+            language,
+            srt,
+            approved_at as "approved_at: NaiveDateTime",
+            created_at as "created_at: NaiveDateTime",
+            updated_at as "updated_at: NaiveDateTime"
+        FROM videos_reviews
+        WHERE video_id = $1 AND language = $2
+        "#,
+        video_id,
+        language,
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(review)
+}
+
+pub async fn update_srt(pool: impl PgExecutor<'_>, id: i32, srt: &str) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        r#"
+        UPDATE videos_reviews
+        SET srt = $2, updated_at = NOW()
+        WHERE id = $1
+        "#,
+        id,
+        srt,
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// False when the review was already approved, so the approved srt is only enqueued once
+pub async fn approve(pool: impl PgExecutor<'_>, id: i32) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query!(
+        r#"
+        UPDATE videos_reviews
+        SET approved_at = NOW(), updated_at = NOW()
+        WHERE id = $1 AND approved_at IS NULL
+        "#,
+        id,
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
@@ -11,6 +11,7 @@ pub struct CreateStorageDto<'a> {
     pub format: VideoFormat,
     pub stage: StorageVideoStage,
     pub size: i64,
+    pub language: Option<&'a str>,
 }
 
 pub async fn create(
@@ -19,8 +20,8 @@ pub async fn create(
 ) -> Result<(), sqlx::Error> {
     sqlx::query!(
         r#"
-        INSERT INTO videos_storages (video_id, storage_id, video_path, format, stage,size)
-        VALUES ($1, $2, $3, $4, $5, $6);
+        INSERT INTO videos_storages (video_id, storage_id, video_path, format, stage,size, language)
+        VALUES ($1, $2, $3, $4, $5, $6, $7);
         "#,
         dto.video_id,
         dto.storage_id,
@@ -28,6 +29,7 @@ pub async fn create(
         dto.format as VideoFormat,
         dto.stage as StorageVideoStage,
         dto.size,
+        dto.language,
     )
     .execute(pool)
     .await?;
@@ -49,6 +51,7 @@ pub async fn find_by_video_id_and_stage(
             storage_id,
             video_path,
             size,
+            language,
             format as "format: VideoFormat",
             stage as "stage: StorageVideoStage",
             created_at as "created_at: NaiveDateTime",
@@ -65,3 +68,39 @@ pub async fn find_by_video_id_and_stage(
 
     Ok(result)
 }
+
+/// Finds the subtitled video of a given language.
+/// Videos processed before the multi-language outputs have no language and are used as fallback
+pub async fn find_processed_by_video_id_and_language(
+    pool: &PgPool,
+    video_id: &Uuid,
+    language: &str,
+) -> Result<VideosStorage, sqlx::Error> {
+    let result = sqlx::query_as!(
+        VideosStorage,
+        r#"
+        SELECT 
+            id,
+            video_id as "video_id: Uuid",
+            storage_id,
+            video_path,
+            size,
+            language,
+            format as "format: VideoFormat",
+            stage as "stage: StorageVideoStage",
+            created_at as "created_at: NaiveDateTime",
+            updated_at as "updated_at: NaiveDateTime",
+            deleted_at as "deleted_at: NaiveDateTime"
+        FROM videos_storages
+            WHERE video_id = $1 AND stage = 'PROCESSED' AND (language = $2 OR language IS NULL)
+        ORDER BY language NULLS LAST
+        LIMIT 1
+        "#,
+        video_id,
+        language,
+    )
+    .fetch_one(pool)
+    .await?;
+
+    Ok(result)
+}
@@ -0,0 +1,138 @@
+use chrono::NaiveDateTime;
+use sqlx::{PgExecutor, PgPool};
+use uuid::Uuid;
+
+use crate::database::models::video_subtitles_revision::VideoSubtitlesRevision;
+
+const REVISIONS_FOLDER: &str = "srt_revisions/";
+
+/// Object key of a revision, it keeps the `{video_id}.{language}` name the queue parses.
+/// The folder is unique per save, two saves racing for the same revision number never
+/// write over each other, the one whose row isn't created just leaves an unused object
+pub fn path(video_id: &Uuid, language: &str) -> String {
+    format!(
+        "{}{}/{}.{}.srt",
+        REVISIONS_FOLDER,
+        Uuid::new_v4(),
+        video_id,
+        language
+    )
+}
+
+pub fn is_revision_path(path: &str) -> bool {
+    path.starts_with(REVISIONS_FOLDER)
+}
+
+pub async fn next_revision(
+    pool: &PgPool,
+    video_id: &Uuid,
+    language: &str,
+) -> Result<i32, sqlx::Error> {
+    let result = sqlx::query!(
+        r#"
+        SELECT COALESCE(MAX(revision), 0) + 1 as "revision!"
+        FROM videos_subtitles_revisions
+        WHERE video_id = $1 AND language = $2
+        "#,
+        video_id,
+        language,
+    )
+    .fetch_one(pool)
+    .await?;
+
+    Ok(result.revision)
+}
+
+pub struct CreateRevisionDto<'a> {
+    pub video_id: &'a Uuid,
+    pub language: &'a str,
+    pub revision: i32,
+    pub storage_id: i32,
+    pub path: &'a str,
+    pub user_id: i32,
+}
+
+/// Fails when the same revision was saved concurrently
+pub async fn create(
+    pool: impl PgExecutor<'_>,
+    dto: CreateRevisionDto<'_>,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        r#"
+        INSERT INTO videos_subtitles_revisions (video_id, language, revision, storage_id, path, user_id)
+        VALUES ($1, $2, $3, $4, $5, $6)
+        "#,
+        dto.video_id,
+        dto.language,
+        dto.revision,
+        dto.storage_id,
+        dto.path,
+        dto.user_id,
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// The history of the subtitles, newest revision first
+pub async fn find_by_video_id_and_language(
+    pool: &PgPool,
+    video_id: &Uuid,
+    language: &str,
+) -> Result<Vec<VideoSubtitlesRevision>, sqlx::Error> {
+    let revisions = sqlx::query_as!(
+        VideoSubtitlesRevision,
+        r#"
+        SELECT
+            id,
+            video_id as "video_id: Uuid",
+            language,
+            revision,
+            storage_id,
+            path,
+            user_id,
+            created_at as "created_at: NaiveDateTime"
+        FROM videos_subtitles_revisions
+        WHERE video_id = $1 AND language = $2
+        ORDER BY revision DESC
+        "#,
+        video_id,
+        language,
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(revisions)
+}
+
+pub async fn find_by_revision(
+    pool: &PgPool,
+    video_id: &Uuid,
+    language: &str,
+    revision: i32,
+) -> Result<VideoSubtitlesRevision, sqlx::Error> {
+    let revision = sqlx::query_as!(
+        VideoSubtitlesRevision,
+        r#"
+        SELECT
+            id,
+            video_id as "video_id: Uuid",
+            language,
+            revision,
+            storage_id,
+            path,
+            user_id,
+            created_at as "created_at: NaiveDateTime"
+        FROM videos_subtitles_revisions
+        WHERE video_id = $1 AND language = $2 AND revision = $3
+        "#,
+        video_id,
+        language,
+        revision,
+    )
+    .fetch_one(pool)
+    .await?;
+
+    Ok(revision)
+}
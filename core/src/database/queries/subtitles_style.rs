@@ -0,0 +1,253 @@
+use chrono::NaiveDateTime;
+use sqlx::{PgExecutor, PgPool};
+use uuid::Uuid;
+
+use crate::database::models::subtitles_style::{SubtitlesPosition, SubtitlesStyle};
+
+pub struct CreateSubtitlesStyleDto<'a> {
+    pub user_id: i32,
+    pub name: &'a str,
+    pub font_name: &'a str,
+    pub font_size: i32,
+    pub primary_color: &'a str,
+    pub outline_color: &'a str,
+    pub outline: i32,
+    pub background_color: Option<&'a str>,
+    pub position: SubtitlesPosition,
+    pub margin: i32,
+    pub max_line_width: Option<i32>,
+}
+
+pub async fn create(
+    pool: &PgPool,
+    dto: CreateSubtitlesStyleDto<'_>,
+) -> Result<SubtitlesStyle, sqlx::Error> {
+    let style = sqlx::query_as!(
+        SubtitlesStyle,
+        r#"
+        INSERT INTO subtitles_styles (user_id, name, font_name, font_size, primary_color, outline_color, outline, background_color, position, margin, max_line_width)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+        RETURNING
+            id,
+            user_id,
+            name,
+            font_name,
+            font_size,
+            primary_color,
+            outline_color,
+            outline,
+            background_color,
+            position as "position: SubtitlesPosition",
+            margin,
+            max_line_width,
+            created_at as "created_at: NaiveDateTime",
+            updated_at as "updated_at: NaiveDateTime"
+        "#,
+        dto.user_id,
+        dto.name,
+        dto.font_name,
+        dto.font_size,
+        dto.primary_color,
+        dto.outline_color,
+        dto.outline,
+        dto.background_color,
+        dto.position as SubtitlesPosition,
+        dto.margin,
+        dto.max_line_width,
+    )
+    .fetch_one(pool)
+    .await?;
+
+    Ok(style)
+}
+
+/// Every field is replaced, the owner of the style stays the same
+pub async fn update(
+    pool: &PgPool,
+    id: i32,
+    dto: CreateSubtitlesStyleDto<'_>,
+) -> Result<SubtitlesStyle, sqlx::Error> {
+    let style = sqlx::query_as!(
+        SubtitlesStyle,
+        r#"
+        UPDATE subtitles_styles
+        SET name = $2, font_name = $3, font_size = $4, primary_color = $5, outline_color = $6, outline = $7,
+            background_color = $8, position = $9, margin = $10, max_line_width = $11, updated_at = NOW()
+        WHERE id = $1
+        RETURNING
+            id,
+            user_id,
+            name,
+            font_name,
+            font_size,
+            primary_color,
+            outline_color,
+            outline,
+            background_color,
+            position as "position: SubtitlesPosition",
+            margin,
+            max_line_width,
+            created_at as "created_at: NaiveDateTime",
+            updated_at as "updated_at: NaiveDateTime"
+        "#,
+        id,
+        dto.name,
+        dto.font_name,
+        dto.font_size,
+        dto.primary_color,
+        dto.outline_color,
+        dto.outline,
+        dto.background_color,
+        dto.position as SubtitlesPosition,
+        dto.margin,
+        dto.max_line_width,
+    )
+    .fetch_one(pool)
+    .await?;
+
+    Ok(style)
+}
+
+pub async fn find_all_by_user(
+    pool: &PgPool,
+    user_id: i32,
+) -> Result<Vec<SubtitlesStyle>, sqlx::Error> {
+    let styles = sqlx::query_as!(
+        SubtitlesStyle,
+        r#"
+        SELECT
+            id,
+            user_id,
+            name,
+            font_name,
+            font_size,
+            primary_color,
+            outline_color,
+            outline,
+            background_color,
+            position as "position: SubtitlesPosition",
+            margin,
+            max_line_width,
+            created_at as "created_at: NaiveDateTime",
+            updated_at as "updated_at: NaiveDateTime"
+        FROM subtitles_styles
+        WHERE user_id = $1
+        ORDER BY id
+        "#,
+        user_id,
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(styles)
+}
+
+pub async fn find_by_id_and_user(
+    pool: &PgPool,
+    id: i32,
+    user_id: i32,
+) -> Result<SubtitlesStyle, sqlx::Error> {
+    let style = sqlx::query_as!(
+        SubtitlesStyle,
+        r#"
+        SELECT
+            id,
+            user_id,
+            name,
+            font_name,
+            font_size,
+            primary_color,
+            outline_color,
+            outline,
+            background_color,
+            position as "position: SubtitlesPosition",
+            margin,
+            max_line_width,
+            created_at as "created_at: NaiveDateTime",
+            updated_at as "updated_at: NaiveDateTime"
+        FROM subtitles_styles
+        WHERE id = $1 AND user_id = $2
+        "#,
+        id,
+        user_id,
+    )
+    .fetch_one(pool)
+    .await?;
+
+    Ok(style)
+}
+
+/// Videos and channels that used the style go back to the default look
+pub async fn delete(pool: &PgPool, id: i32) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        r#"
+        DELETE FROM subtitles_styles
+        WHERE id = $1
+        "#,
+        id,
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// The style the videos of the channel get when they don't pick one
+pub async fn set_channel_style(
+    pool: impl PgExecutor<'_>,
+    channel_id: i32,
+    style_id: Option<i32>,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        r#"
+        UPDATE channels
+        SET subtitles_style_id = $2, updated_at = NOW()
+        WHERE id = $1
+        "#,
+        channel_id,
+        style_id,
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// The style of the video, or else the one of the channel the output is published on
+pub async fn find_by_output(
+    pool: &PgPool,
+    video_id: &Uuid,
+    channel_id: i32,
+) -> Result<Option<SubtitlesStyle>, sqlx::Error> {
+    let style = sqlx::query_as!(
+        SubtitlesStyle,
+        r#"
+        SELECT
+            s.id,
+            s.user_id,
+            s.name,
+            s.font_name,
+            s.font_size,
+            s.primary_color,
+            s.outline_color,
+            s.outline,
+            s.background_color,
+            s.position as "position: SubtitlesPosition",
+            s.margin,
+            s.max_line_width,
+            s.created_at as "created_at: NaiveDateTime",
+            s.updated_at as "updated_at: NaiveDateTime"
+        FROM subtitles_styles s
+        WHERE s.id = COALESCE(
+            (SELECT subtitles_style_id FROM videos WHERE id = $1),
+            (SELECT subtitles_style_id FROM channels WHERE id = $2)
+        )
+        "#,
+        video_id,
+        channel_id,
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(style)
+}
@@ -0,0 +1,115 @@
+use std::str::FromStr;
+
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::database::{
+    models::video::stage::VideoStage,
+    queries::{
+        checkpoint::{create, delete, delete_unfinished_by_video_id, exists},
+        output::{self, CreateOutputDto},
+    },
+};
+
+#[sqlx::test(migrations = "../migrations", fixtures("videos"))]
+async fn test_create_checkpoint(pool: PgPool) {
+    let id = Uuid::from_str("806b5a48-f221-11ed-a05b-0242ac120096").unwrap();
+
+    assert!(!exists(&pool, &id, VideoStage::Cutting, None).await.unwrap());
+
+    create(&pool, &id, VideoStage::Cutting, None).await.unwrap();
+    // a redelivered message records the same checkpoint again
+    create(&pool, &id, VideoStage::Cutting, None).await.unwrap();
+
+    assert!(exists(&pool, &id, VideoStage::Cutting, None).await.unwrap());
+    assert!(!exists(&pool, &id, VideoStage::Transcribing, None)
+        .await
+        .unwrap());
+}
+
+#[sqlx::test(migrations = "../migrations", fixtures("videos"))]
+async fn test_checkpoint_per_language(pool: PgPool) {
+    let id = Uuid::from_str("806b5a48-f221-11ed-a05b-0242ac120096").unwrap();
+
+    create(&pool, &id, VideoStage::Uploading, Some("pt-br"))
+        .await
+        .unwrap();
+
+    assert!(exists(&pool, &id, VideoStage::Uploading, Some("pt-br"))
+        .await
+        .unwrap());
+    assert!(!exists(&pool, &id, VideoStage::Uploading, Some("es"))
+        .await
+        .unwrap());
+    assert!(!exists(&pool, &id, VideoStage::Uploading, None)
+        .await
+        .unwrap());
+
+    delete_unfinished_by_video_id(&pool, &id).await.unwrap();
+
+    assert!(!exists(&pool, &id, VideoStage::Uploading, Some("pt-br"))
+        .await
+        .unwrap());
+}
+
+#[sqlx::test(migrations = "../migrations", fixtures("videos"))]
+async fn test_delete_checkpoint(pool: PgPool) {
+    let id = Uuid::from_str("806b5a48-f221-11ed-a05b-0242ac120096").unwrap();
+
+    create(&pool, &id, VideoStage::Downloading, None)
+        .await
+        .unwrap();
+    create(&pool, &id, VideoStage::Cutting, None).await.unwrap();
+
+    delete(&pool, &id, VideoStage::Downloading, None)
+        .await
+        .unwrap();
+
+    assert!(!exists(&pool, &id, VideoStage::Downloading, None)
+        .await
+        .unwrap());
+    assert!(exists(&pool, &id, VideoStage::Cutting, None).await.unwrap());
+}
+
+#[sqlx::test(migrations = "../migrations", fixtures("videos"))]
+async fn test_delete_unfinished_keeps_done_outputs(pool: PgPool) {
+    let id = Uuid::from_str("806b5a48-f221-11ed-a05b-0242ac120096").unwrap();
+
+    let dtos = ["pt-br", "es"]
+        .into_iter()
+        .map(|language| CreateOutputDto {
+            video_id: id,
+            channel_id: 666,
+            language,
+        })
+        .collect();
+    output::create_many(&pool, dtos).await.unwrap();
+    let done = output::find_by_video_id_and_language(&pool, &id, "pt-br")
+        .await
+        .unwrap();
+    output::change_stage(&pool, done.id, VideoStage::Done)
+        .await
+        .unwrap();
+
+    create(&pool, &id, VideoStage::Transcribing, None)
+        .await
+        .unwrap();
+    create(&pool, &id, VideoStage::Uploading, Some("pt-br"))
+        .await
+        .unwrap();
+    create(&pool, &id, VideoStage::Subtitling, Some("es"))
+        .await
+        .unwrap();
+
+    delete_unfinished_by_video_id(&pool, &id).await.unwrap();
+
+    assert!(!exists(&pool, &id, VideoStage::Transcribing, None)
+        .await
+        .unwrap());
+    assert!(exists(&pool, &id, VideoStage::Uploading, Some("pt-br"))
+        .await
+        .unwrap());
+    assert!(!exists(&pool, &id, VideoStage::Subtitling, Some("es"))
+        .await
+        .unwrap());
+}
@@ -0,0 +1,58 @@
+use std::str::FromStr;
+
+use sqlx::PgPool;
+
+use crate::database::{
+    models::video::stage::VideoStage,
+    queries::failed_message::{create, find_all, find_by_id, set_replayed, CreateFailedMessageDto},
+};
+
+#[sqlx::test(migrations = "../migrations", fixtures("videos"))]
+async fn test_create_failed_message(pool: PgPool) {
+    let id = uuid::Uuid::from_str("806b5a48-f221-11ed-a05b-0242ac120096").unwrap();
+    let video_ids = vec![id];
+
+    let dto = CreateFailedMessageDto {
+        payload: r#"{"type": "BatukaCutVideo"}"#,
+        video_ids: &video_ids,
+        error: "Video has no end time",
+        stage: Some(VideoStage::Cutting),
+        attempts: 3,
+    };
+
+    let message_id = create(&pool, dto).await.unwrap();
+
+    let message = find_by_id(&pool, message_id).await.unwrap();
+
+    assert_eq!(message.video_ids, video_ids);
+    assert_eq!(message.stage, Some(VideoStage::Cutting));
+    assert_eq!(message.attempts, 3);
+    assert!(message.replayed_at.is_none());
+
+    let messages = find_all(&pool).await.unwrap();
+    assert_eq!(messages.len(), 1);
+}
+
+#[sqlx::test(migrations = "../migrations")]
+async fn test_set_replayed(pool: PgPool) {
+    let dto = CreateFailedMessageDto {
+        payload: "old",
+        video_ids: &[],
+        error: "error",
+        stage: None,
+        attempts: 1,
+    };
+
+    let message_id = create(&pool, dto).await.unwrap();
+    assert!(set_replayed(&pool, message_id, "new").await.unwrap());
+
+    let message = find_by_id(&pool, message_id).await.unwrap();
+
+    assert_eq!(message.payload, "new");
+    assert!(message.replayed_at.is_some());
+
+    // a replay is only claimed once
+    assert!(!set_replayed(&pool, message_id, "newer").await.unwrap());
+    let message = find_by_id(&pool, message_id).await.unwrap();
+    assert_eq!(message.payload, "new");
+}
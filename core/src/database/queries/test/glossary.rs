@@ -0,0 +1,67 @@
+use std::str::FromStr;
+
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::database::queries::glossary::{
+    create, delete, find_all_by_channel, find_all_by_video, CreateGlossaryTermDto,
+};
+
+const VIDEO_ID: &str = "806b5a48-f221-11ed-a05b-0242ac120096";
+
+#[sqlx::test(migrations = "../migrations", fixtures("videos"))]
+async fn test_create_find_and_delete(pool: PgPool) {
+    let kept = create(
+        &pool,
+        CreateGlossaryTermDto {
+            channel_id: 666,
+            term: "FFmpeg",
+            language: None,
+            translation: None,
+        },
+    )
+    .await
+    .unwrap();
+
+    let translated = create(
+        &pool,
+        CreateGlossaryTermDto {
+            channel_id: 666,
+            term: "pull request",
+            language: Some("pt-br"),
+            translation: Some("PR"),
+        },
+    )
+    .await
+    .unwrap();
+
+    let terms = find_all_by_channel(&pool, 666).await.unwrap();
+    assert_eq!(terms, vec![kept.clone(), translated.clone()]);
+
+    let video_id = Uuid::from_str(VIDEO_ID).unwrap();
+    let terms = find_all_by_video(&pool, &video_id).await.unwrap();
+    assert_eq!(terms.len(), 2);
+
+    // only on the channel it belongs to
+    assert!(delete(&pool, 1, kept.id).await.is_err());
+
+    delete(&pool, 666, kept.id).await.unwrap();
+    let terms = find_all_by_channel(&pool, 666).await.unwrap();
+    assert_eq!(terms, vec![translated]);
+}
+
+#[sqlx::test(migrations = "../migrations", fixtures("videos"))]
+async fn test_translation_needs_language(pool: PgPool) {
+    let result = create(
+        &pool,
+        CreateGlossaryTermDto {
+            channel_id: 666,
+            term: "pull request",
+            language: None,
+            translation: Some("PR"),
+        },
+    )
+    .await;
+
+    assert!(result.is_err());
+}
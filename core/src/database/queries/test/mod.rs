@@ -1,11 +1,23 @@
 mod channel;
+mod checkpoint;
+mod failed_message;
+mod glossary;
+mod notification;
+mod outbox;
+mod output;
+mod queue;
+mod review;
 mod storage;
+mod subtitles_revision;
+mod subtitles_style;
 mod subtitling;
 mod transcription;
 mod translation;
 mod user;
 mod video;
 mod video_error;
+mod video_event;
+mod webhook;
 
 mod original_video;
 
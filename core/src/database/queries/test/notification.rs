@@ -0,0 +1,105 @@
+use sqlx::PgPool;
+
+use crate::database::{
+    models::notification::NotificationKind,
+    queries::notification::{
+        claim_pending, enqueue, find_preferences, find_unsent, mark_failed, mark_sent,
+        upsert_preferences, wants, CreateNotificationDto, UpdatePreferencesDto,
+    },
+};
+
+fn finished_dto(reference: &str) -> CreateNotificationDto<'_> {
+    CreateNotificationDto {
+        user_id: 666,
+        kind: NotificationKind::VideosFinished,
+        reference,
+        params: "{}",
+    }
+}
+
+#[sqlx::test(migrations = "../migrations", fixtures("user"))]
+async fn test_wants_everything_by_default(pool: PgPool) {
+    assert!(find_preferences(&pool, 666).await.unwrap().is_none());
+
+    assert!(wants(&pool, 666, NotificationKind::VideosFinished)
+        .await
+        .unwrap());
+    assert!(wants(&pool, 666, NotificationKind::VideoFailed)
+        .await
+        .unwrap());
+}
+
+#[sqlx::test(migrations = "../migrations", fixtures("user"))]
+async fn test_upsert_preferences(pool: PgPool) {
+    let dto = UpdatePreferencesDto {
+        user_id: 666,
+        videos_finished: true,
+        video_failed: true,
+    };
+    upsert_preferences(&pool, dto).await.unwrap();
+
+    let dto = UpdatePreferencesDto {
+        user_id: 666,
+        videos_finished: false,
+        video_failed: true,
+    };
+    let preferences = upsert_preferences(&pool, dto).await.unwrap();
+    assert!(!preferences.videos_finished);
+
+    assert!(!wants(&pool, 666, NotificationKind::VideosFinished)
+        .await
+        .unwrap());
+    assert!(wants(&pool, 666, NotificationKind::VideoFailed)
+        .await
+        .unwrap());
+}
+
+#[sqlx::test(migrations = "../migrations", fixtures("user"))]
+async fn test_enqueue_once_per_reference(pool: PgPool) {
+    assert!(enqueue(&pool, finished_dto("1")).await.unwrap());
+    assert!(!enqueue(&pool, finished_dto("1")).await.unwrap());
+    assert!(enqueue(&pool, finished_dto("2")).await.unwrap());
+
+    let pending = claim_pending(&pool, 10, 3, 60).await.unwrap();
+    assert_eq!(pending.len(), 2);
+    assert_eq!(pending[0].kind, NotificationKind::VideosFinished);
+}
+
+#[sqlx::test(migrations = "../migrations", fixtures("user"))]
+async fn test_sent_and_failed_notifications(pool: PgPool) {
+    enqueue(&pool, finished_dto("1")).await.unwrap();
+    enqueue(&pool, finished_dto("2")).await.unwrap();
+
+    let pending = claim_pending(&pool, 10, 1, 60).await.unwrap();
+    mark_sent(&pool, pending[0].id).await.unwrap();
+    mark_failed(&pool, pending[1].id, "Connection refused")
+        .await
+        .unwrap();
+
+    let pending = claim_pending(&pool, 10, 1, 60).await.unwrap();
+    assert!(pending.is_empty());
+
+    let pending = claim_pending(&pool, 10, 2, 60).await.unwrap();
+    assert_eq!(pending.len(), 1);
+    assert_eq!(pending[0].attempts, 2);
+}
+
+#[sqlx::test(migrations = "../migrations", fixtures("user"))]
+async fn test_claimed_notification_is_skipped_until_the_lease_ends(pool: PgPool) {
+    enqueue(&pool, finished_dto("1")).await.unwrap();
+
+    let claimed = claim_pending(&pool, 10, 3, 60).await.unwrap();
+    assert_eq!(claimed.len(), 1);
+    assert!(claim_pending(&pool, 10, 3, 60).await.unwrap().is_empty());
+
+    let unsent = find_unsent(&pool).await.unwrap();
+    assert!(unsent[0].locked_until.is_some());
+
+    mark_failed(&pool, claimed[0].id, "Connection refused")
+        .await
+        .unwrap();
+
+    let claimed = claim_pending(&pool, 10, 3, 60).await.unwrap();
+    assert_eq!(claimed.len(), 1);
+    assert_eq!(claimed[0].attempts, 2);
+}
@@ -0,0 +1,90 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    database::queries::outbox::{
+        claim_pending, enqueue, find_unsent, mark_failed, mark_sent, take_exhausted,
+    },
+    internals::cloud::models::payload::{PayloadType, VideoDownloadPayload},
+};
+
+fn download_payload() -> PayloadType {
+    PayloadType::BatukaDownloadVideo(VideoDownloadPayload {
+        original_video_id: 1,
+        video_ids: vec![Uuid::new_v4()],
+    })
+}
+
+#[sqlx::test(migrations = "../migrations")]
+async fn test_enqueue_and_claim_pending(pool: PgPool) {
+    let payload = download_payload();
+    enqueue(&pool, &payload).await.unwrap();
+
+    let messages = claim_pending(&pool, 10, 3, 60).await.unwrap();
+
+    assert_eq!(messages.len(), 1);
+    assert_eq!(messages[0].payload, payload.to_json());
+    assert_eq!(messages[0].attempts, 1);
+    assert!(messages[0].sent_at.is_none());
+    assert!(messages[0].locked_until.is_some());
+}
+
+#[sqlx::test(migrations = "../migrations")]
+async fn test_sent_message_is_not_pending(pool: PgPool) {
+    let id = enqueue(&pool, &download_payload()).await.unwrap();
+
+    mark_sent(&pool, id).await.unwrap();
+
+    let messages = claim_pending(&pool, 10, 3, 60).await.unwrap();
+    assert!(messages.is_empty());
+    assert!(find_unsent(&pool).await.unwrap().is_empty());
+}
+
+#[sqlx::test(migrations = "../migrations")]
+async fn test_claimed_message_is_skipped_until_released(pool: PgPool) {
+    let id = enqueue(&pool, &download_payload()).await.unwrap();
+
+    let claimed = claim_pending(&pool, 10, 3, 60).await.unwrap();
+    assert_eq!(claimed.len(), 1);
+
+    let messages = claim_pending(&pool, 10, 3, 60).await.unwrap();
+    assert!(messages.is_empty());
+
+    mark_failed(&pool, id, "queue unavailable").await.unwrap();
+
+    let messages = claim_pending(&pool, 10, 3, 60).await.unwrap();
+    assert_eq!(messages[0].attempts, 2);
+    assert_eq!(
+        messages[0].last_error,
+        Some("queue unavailable".to_string())
+    );
+}
+
+#[sqlx::test(migrations = "../migrations")]
+async fn test_expired_claim_is_claimed_again(pool: PgPool) {
+    enqueue(&pool, &download_payload()).await.unwrap();
+
+    claim_pending(&pool, 10, 3, 0).await.unwrap();
+
+    let messages = claim_pending(&pool, 10, 3, 60).await.unwrap();
+    assert_eq!(messages.len(), 1);
+}
+
+#[sqlx::test(migrations = "../migrations")]
+async fn test_exhausted_message_is_taken(pool: PgPool) {
+    let id = enqueue(&pool, &download_payload()).await.unwrap();
+
+    for _ in 0..3 {
+        claim_pending(&pool, 10, 3, 60).await.unwrap();
+        mark_failed(&pool, id, "queue unavailable").await.unwrap();
+    }
+
+    let messages = claim_pending(&pool, 10, 3, 60).await.unwrap();
+    assert!(messages.is_empty());
+
+    let exhausted = take_exhausted(&pool, 3).await.unwrap();
+    assert_eq!(exhausted.len(), 1);
+    assert_eq!(exhausted[0].attempts, 3);
+
+    assert!(find_unsent(&pool).await.unwrap().is_empty());
+}
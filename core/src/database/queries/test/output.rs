@@ -0,0 +1,78 @@
+use std::str::FromStr;
+
+use sqlx::PgPool;
+
+use crate::database::{
+    models::video::stage::VideoStage,
+    queries::output::{
+        change_stage, count_unfinished, create_many, find_by_video_id,
+        find_by_video_id_and_language, set_url, CreateOutputDto,
+    },
+};
+
+fn create_dtos(id: uuid::Uuid) -> Vec<CreateOutputDto<'static>> {
+    vec![
+        CreateOutputDto {
+            video_id: id,
+            channel_id: 666,
+            language: "pt-br",
+        },
+        CreateOutputDto {
+            video_id: id,
+            channel_id: 666,
+            language: "es",
+        },
+    ]
+}
+
+#[sqlx::test(migrations = "../migrations", fixtures("videos"))]
+async fn test_create_many_outputs(pool: PgPool) {
+    let id = uuid::Uuid::from_str("806b5a48-f221-11ed-a05b-0242ac120096").unwrap();
+
+    create_many(&pool, create_dtos(id)).await.unwrap();
+
+    let outputs = find_by_video_id(&pool, &id).await.unwrap();
+
+    assert_eq!(outputs.len(), 2);
+    assert_eq!(outputs[0].language, "pt-br");
+    assert_eq!(outputs[1].language, "es");
+    assert_eq!(outputs[0].stage, VideoStage::Downloading);
+}
+
+#[sqlx::test(migrations = "../migrations", fixtures("videos"))]
+async fn test_create_duplicated_language(pool: PgPool) {
+    let id = uuid::Uuid::from_str("806b5a48-f221-11ed-a05b-0242ac120096").unwrap();
+
+    let mut dtos = create_dtos(id);
+    dtos[1].language = "pt-br";
+
+    let result = create_many(&pool, dtos).await;
+    assert!(result.is_err());
+}
+
+#[sqlx::test(migrations = "../migrations", fixtures("videos"))]
+async fn test_count_unfinished(pool: PgPool) {
+    let id = uuid::Uuid::from_str("806b5a48-f221-11ed-a05b-0242ac120096").unwrap();
+
+    create_many(&pool, create_dtos(id)).await.unwrap();
+
+    let output = find_by_video_id_and_language(&pool, &id, "es")
+        .await
+        .unwrap();
+    let mut conn = pool.acquire().await.unwrap();
+    set_url(&mut conn, output.id, "https://www.youtube.com/watch?v=1")
+        .await
+        .unwrap();
+    drop(conn);
+
+    assert_eq!(count_unfinished(&pool, &id).await.unwrap(), 1);
+
+    let output = find_by_video_id_and_language(&pool, &id, "pt-br")
+        .await
+        .unwrap();
+    change_stage(&pool, output.id, VideoStage::Done)
+        .await
+        .unwrap();
+
+    assert_eq!(count_unfinished(&pool, &id).await.unwrap(), 0);
+}
@@ -0,0 +1,98 @@
+use sqlx::PgPool;
+
+use crate::database::queries::queue::{
+    change_visibility, dead_letter_exhausted, delete, find_dead_letters, receive, send,
+};
+
+#[sqlx::test(migrations = "../migrations")]
+async fn test_send_and_receive(pool: PgPool) {
+    send(&pool, "first", 0).await.unwrap();
+    send(&pool, "second", 0).await.unwrap();
+
+    let messages = receive(&pool, 10, 30, 3).await.unwrap();
+
+    assert_eq!(messages.len(), 2);
+    assert_eq!(messages[0].body, "first");
+    assert!(messages[0].receipt_handle.is_some());
+}
+
+#[sqlx::test(migrations = "../migrations")]
+async fn test_delayed_message_is_not_received(pool: PgPool) {
+    send(&pool, "delayed", 60).await.unwrap();
+
+    let messages = receive(&pool, 10, 30, 3).await.unwrap();
+
+    assert!(messages.is_empty());
+}
+
+#[sqlx::test(migrations = "../migrations")]
+async fn test_received_message_is_hidden(pool: PgPool) {
+    send(&pool, "hidden", 0).await.unwrap();
+
+    let messages = receive(&pool, 10, 30, 3).await.unwrap();
+    assert_eq!(messages.len(), 1);
+
+    let messages = receive(&pool, 10, 30, 3).await.unwrap();
+    assert!(messages.is_empty());
+}
+
+#[sqlx::test(migrations = "../migrations")]
+async fn test_change_visibility(pool: PgPool) {
+    send(&pool, "visible again", 0).await.unwrap();
+
+    let messages = receive(&pool, 10, 30, 3).await.unwrap();
+    let first_handle = messages[0].receipt_handle.unwrap();
+
+    change_visibility(&pool, &first_handle, 0).await.unwrap();
+
+    let messages = receive(&pool, 10, 30, 3).await.unwrap();
+    assert_eq!(messages.len(), 1);
+    assert_ne!(messages[0].receipt_handle.unwrap(), first_handle);
+}
+
+#[sqlx::test(migrations = "../migrations")]
+async fn test_delete(pool: PgPool) {
+    send(&pool, "deleted", 0).await.unwrap();
+
+    let messages = receive(&pool, 10, 0, 3).await.unwrap();
+    delete(&pool, &messages[0].receipt_handle.unwrap())
+        .await
+        .unwrap();
+
+    let messages = receive(&pool, 10, 0, 3).await.unwrap();
+    assert!(messages.is_empty());
+}
+
+#[sqlx::test(migrations = "../migrations")]
+async fn test_receive_count(pool: PgPool) {
+    send(&pool, "counted", 0).await.unwrap();
+
+    let messages = receive(&pool, 10, 0, 3).await.unwrap();
+    assert_eq!(messages[0].receive_count, 1);
+
+    let messages = receive(&pool, 10, 0, 3).await.unwrap();
+    assert_eq!(messages[0].receive_count, 2);
+}
+
+#[sqlx::test(migrations = "../migrations")]
+async fn test_dead_letter_exhausted(pool: PgPool) {
+    send(&pool, "exhausted", 0).await.unwrap();
+    send(&pool, "fresh", 0).await.unwrap();
+
+    let messages = receive(&pool, 1, 0, 2).await.unwrap();
+    assert_eq!(messages[0].body, "exhausted");
+    let messages = receive(&pool, 1, 0, 2).await.unwrap();
+    assert_eq!(messages[0].body, "exhausted");
+
+    let moved = dead_letter_exhausted(&pool, 2).await.unwrap();
+    assert_eq!(moved, 1);
+
+    let dead_letters = find_dead_letters(&pool).await.unwrap();
+    assert_eq!(dead_letters.len(), 1);
+    assert_eq!(dead_letters[0].body, "exhausted");
+    assert!(dead_letters[0].receipt_handle.is_none());
+
+    let messages = receive(&pool, 10, 0, 2).await.unwrap();
+    assert_eq!(messages.len(), 1);
+    assert_eq!(messages[0].body, "fresh");
+}
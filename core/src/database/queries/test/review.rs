@@ -0,0 +1,83 @@
+use std::str::FromStr;
+
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::database::queries::{
+    review::{
+        approve, create, find_by_video_id, find_by_video_id_and_language, update_srt,
+        CreateReviewDto,
+    },
+    video::requires_review,
+};
+
+const SRT: &str = "1\n00:00:01,000 --> 00:00:02,000\nOlá\n\n";
+
+fn video_id() -> Uuid {
+    Uuid::from_str("806b5a48-f221-11ed-a05b-0242ac120096").unwrap()
+}
+
+fn dto<'a>(video_id: &'a Uuid, srt: &'a str) -> CreateReviewDto<'a> {
+    CreateReviewDto {
+        video_id,
+        language: "pt-br",
+        srt,
+    }
+}
+
+#[sqlx::test(migrations = "../migrations", fixtures("videos"))]
+async fn test_videos_are_not_reviewed_by_default(pool: PgPool) {
+    assert!(!requires_review(&pool, &video_id()).await.unwrap());
+
+    sqlx::query!("UPDATE videos SET review = true WHERE id = $1", video_id())
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    assert!(requires_review(&pool, &video_id()).await.unwrap());
+}
+
+#[sqlx::test(migrations = "../migrations", fixtures("videos"))]
+async fn test_create_keeps_the_edited_review(pool: PgPool) {
+    let id = video_id();
+    create(&pool, dto(&id, SRT)).await.unwrap();
+
+    let review = find_by_video_id_and_language(&pool, &id, "pt-br")
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(review.srt, SRT);
+    assert!(review.approved_at.is_none());
+
+    update_srt(&pool, review.id, "edited").await.unwrap();
+    // a redelivered translation
+    create(&pool, dto(&id, SRT)).await.unwrap();
+
+    let reviews = find_by_video_id(&pool, &id).await.unwrap();
+    assert_eq!(reviews.len(), 1);
+    assert_eq!(reviews[0].srt, "edited");
+
+    assert!(find_by_video_id_and_language(&pool, &id, "es")
+        .await
+        .unwrap()
+        .is_none());
+}
+
+#[sqlx::test(migrations = "../migrations", fixtures("videos"))]
+async fn test_approve_review(pool: PgPool) {
+    let id = video_id();
+    create(&pool, dto(&id, SRT)).await.unwrap();
+
+    let review = find_by_video_id_and_language(&pool, &id, "pt-br")
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(approve(&pool, review.id).await.unwrap());
+    assert!(!approve(&pool, review.id).await.unwrap());
+
+    let review = find_by_video_id_and_language(&pool, &id, "pt-br")
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(review.approved_at.is_some());
+}
@@ -18,6 +18,7 @@ async fn test_create_storage(pool: PgPool) {
         format: VideoFormat::Mp4,
         stage: StorageVideoStage::Raw,
         size: 1234,
+        language: None,
     };
 
     let result = create(&pool, dto).await;
@@ -45,6 +46,7 @@ async fn test_create_storage_if_foreign_key(pool: PgPool) {
         format: VideoFormat::Mp4,
         stage: StorageVideoStage::Raw,
         size: 1234,
+        language: None,
     };
 
     let result = create(&pool, dto).await;
@@ -0,0 +1,67 @@
+use std::str::FromStr;
+
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::database::queries::subtitles_revision::{
+    create, find_by_revision, find_by_video_id_and_language, next_revision, path, CreateRevisionDto,
+};
+
+fn video_id() -> Uuid {
+    Uuid::from_str("806b5a48-f221-11ed-a05b-0242ac120096").unwrap()
+}
+
+async fn save(pool: &PgPool, video_id: &Uuid, language: &str) -> i32 {
+    let revision = next_revision(pool, video_id, language).await.unwrap();
+    let dto = CreateRevisionDto {
+        video_id,
+        language,
+        revision,
+        storage_id: 1,
+        path: &path(video_id, language),
+        user_id: 666,
+    };
+    create(pool, dto).await.unwrap();
+    revision
+}
+
+#[sqlx::test(migrations = "../migrations", fixtures("videos"))]
+async fn test_revisions_are_numbered_per_language(pool: PgPool) {
+    let id = video_id();
+
+    assert_eq!(save(&pool, &id, "pt-br").await, 1);
+    assert_eq!(save(&pool, &id, "pt-br").await, 2);
+    assert_eq!(save(&pool, &id, "es").await, 1);
+
+    let revisions = find_by_video_id_and_language(&pool, &id, "pt-br")
+        .await
+        .unwrap();
+    let numbers: Vec<i32> = revisions.iter().map(|r| r.revision).collect();
+    assert_eq!(numbers, vec![2, 1]);
+
+    let revision = find_by_revision(&pool, &id, "pt-br", 1).await.unwrap();
+    assert!(revision.path.starts_with("srt_revisions/"));
+    assert!(revision
+        .path
+        .ends_with(&format!("/{}.pt-br.srt", video_id())));
+    assert_ne!(revision.path, revisions[0].path);
+}
+
+#[sqlx::test(migrations = "../migrations", fixtures("videos"))]
+async fn test_same_revision_twice(pool: PgPool) {
+    let id = video_id();
+    save(&pool, &id, "pt-br").await;
+
+    let dto = CreateRevisionDto {
+        video_id: &id,
+        language: "pt-br",
+        revision: 1,
+        storage_id: 1,
+        path: &path(&id, "pt-br"),
+        user_id: 666,
+    };
+    assert!(create(&pool, dto).await.is_err());
+
+    let result = find_by_revision(&pool, &id, "pt-br", 2).await;
+    assert!(matches!(result, Err(sqlx::Error::RowNotFound)));
+}
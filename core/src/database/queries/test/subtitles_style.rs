@@ -0,0 +1,89 @@
+use std::str::FromStr;
+
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::database::{
+    models::subtitles_style::SubtitlesPosition,
+    queries::subtitles_style::{
+        create, delete, find_all_by_user, find_by_id_and_user, find_by_output, set_channel_style,
+        update, CreateSubtitlesStyleDto,
+    },
+};
+
+const VIDEO_ID: &str = "806b5a48-f221-11ed-a05b-0242ac120096";
+
+fn style_dto(name: &str) -> CreateSubtitlesStyleDto<'_> {
+    CreateSubtitlesStyleDto {
+        user_id: 666,
+        name,
+        font_name: "Roboto",
+        font_size: 64,
+        primary_color: "#FFFFFF",
+        outline_color: "#000000",
+        outline: 3,
+        background_color: None,
+        position: SubtitlesPosition::Bottom,
+        margin: 120,
+        max_line_width: Some(32),
+    }
+}
+
+#[sqlx::test(migrations = "../migrations", fixtures("videos"))]
+async fn test_create_update_and_delete(pool: PgPool) {
+    let style = create(&pool, style_dto("Shorts")).await.unwrap();
+    assert_eq!(style.font_size, 64);
+    assert_eq!(style.position, SubtitlesPosition::Bottom);
+
+    let mut dto = style_dto("Shorts");
+    dto.background_color = Some("#00000080");
+    dto.position = SubtitlesPosition::Middle;
+    let updated = update(&pool, style.id, dto).await.unwrap();
+    assert_eq!(updated.background_color.as_deref(), Some("#00000080"));
+    assert_eq!(updated.position, SubtitlesPosition::Middle);
+
+    let found = find_by_id_and_user(&pool, style.id, 666).await.unwrap();
+    assert_eq!(found, updated);
+    assert!(find_by_id_and_user(&pool, style.id, 1).await.is_err());
+
+    // names are unique per user
+    assert!(create(&pool, style_dto("Shorts")).await.is_err());
+
+    delete(&pool, style.id).await.unwrap();
+    assert!(find_all_by_user(&pool, 666).await.unwrap().is_empty());
+}
+
+#[sqlx::test(migrations = "../migrations", fixtures("videos"))]
+async fn test_find_by_output(pool: PgPool) {
+    let video_id = Uuid::from_str(VIDEO_ID).unwrap();
+    assert!(find_by_output(&pool, &video_id, 666)
+        .await
+        .unwrap()
+        .is_none());
+
+    let channel_style = create(&pool, style_dto("Landscape")).await.unwrap();
+    set_channel_style(&pool, 666, Some(channel_style.id))
+        .await
+        .unwrap();
+
+    let style = find_by_output(&pool, &video_id, 666).await.unwrap();
+    assert_eq!(style.map(|s| s.id), Some(channel_style.id));
+
+    let video_style = create(&pool, style_dto("Shorts")).await.unwrap();
+    sqlx::query!(
+        "UPDATE videos SET subtitles_style_id = $1 WHERE id = $2",
+        video_style.id,
+        video_id
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let style = find_by_output(&pool, &video_id, 666).await.unwrap();
+    assert_eq!(style.map(|s| s.id), Some(video_style.id));
+
+    // deleting the style of the video falls back to the one of the channel
+    delete(&pool, video_style.id).await.unwrap();
+    let style = find_by_output(&pool, &video_id, 666).await.unwrap();
+    assert_eq!(style.map(|s| s.id), Some(channel_style.id));
+}
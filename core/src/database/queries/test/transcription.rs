@@ -8,7 +8,8 @@ use sqlx::PgPool;
 use crate::database::{
     models::video_transcription::VideosTranscription,
     queries::transcription::{
-        create, find_by_video_id, update, CreateTranscriptionDto, UpdateVideoTranscriptionDto,
+        claim_pending, create, find_by_video_id, mark_failed, update, CreateTranscriptionDto,
+        UpdateVideoTranscriptionDto,
     },
 };
 
@@ -131,3 +132,36 @@ async fn test_update_transcription(pool: PgPool) {
     assert_eq!(updated_path, new_path.to_string());
     assert_eq!(updated_storage_id, new_storage_id);
 }
+
+#[sqlx::test(migrations = "../migrations", fixtures("videos"))]
+async fn test_claim_pending(pool: PgPool) {
+    let id = uuid::Uuid::from_str("806b5a48-f221-11ed-a05b-0242ac120096").unwrap();
+    let dto = || CreateTranscriptionDto {
+        video_id: id,
+        transcription_id: "Transcription_Test_Pending".to_string(),
+        transcriber_id: 3,
+    };
+    create(&pool, dto()).await.unwrap();
+
+    let pending = claim_pending(&pool, 3, 10, 0).await.unwrap();
+    assert_eq!(pending.len(), 1);
+    assert_eq!(pending[0].transcription_id, "Transcription_Test_Pending");
+    // just checked
+    assert!(claim_pending(&pool, 3, 10, 60).await.unwrap().is_empty());
+    assert!(claim_pending(&pool, 1, 10, 0).await.unwrap().is_empty());
+
+    mark_failed(&pool, &id).await.unwrap();
+    assert!(claim_pending(&pool, 3, 10, 0).await.unwrap().is_empty());
+
+    // requesting it again brings it back, unchecked
+    create(&pool, dto()).await.unwrap();
+    assert_eq!(claim_pending(&pool, 3, 10, 60).await.unwrap().len(), 1);
+
+    let dto = UpdateVideoTranscriptionDto {
+        video_id: id,
+        storage_id: 1,
+        path: "srt_transcriptions/test.srt".to_string(),
+    };
+    update(&pool, dto).await.unwrap();
+    assert!(claim_pending(&pool, 3, 10, 0).await.unwrap().is_empty());
+}
@@ -3,7 +3,7 @@ use std::str::FromStr;
 use sqlx::PgPool;
 use uuid::Uuid;
 
-use crate::database::queries::translation::{create, CreateTranslationDto};
+use crate::database::queries::translation::{create, find_by_video_id, CreateTranslationDto};
 
 #[sqlx::test(migrations = "../migrations", fixtures("videos", "service_providers"))]
 async fn test_create_translation(pool: PgPool) {
@@ -15,6 +15,7 @@ async fn test_create_translation(pool: PgPool) {
         translation_id: Some(String::from("id_translation")),
         storage_id: 5678,
         path: "../translation",
+        language: "pt-br",
     };
 
     let test = create(&pool, dto).await;
@@ -41,8 +42,30 @@ async fn test_create_translation_if_foreign_key(pool: PgPool) {
         translation_id: Some(String::from("id_translation")),
         storage_id: 5678,
         path: "../translation",
+        language: "pt-br",
     };
 
     let test = create(&pool, dto).await;
     assert!(test.is_err());
 }
+
+#[sqlx::test(migrations = "../migrations", fixtures("videos", "service_providers"))]
+async fn test_find_translations_by_video_id(pool: PgPool) {
+    let id = Uuid::from_str("806b5a48-f221-11ed-a05b-0242ac120096").unwrap();
+
+    for language in ["pt-br", "es"] {
+        let dto = CreateTranslationDto {
+            video_id: &id,
+            translator_id: 1234,
+            translation_id: None,
+            storage_id: 5678,
+            path: "../translation",
+            language,
+        };
+        create(&pool, dto).await.unwrap();
+    }
+
+    let translations = find_by_video_id(&pool, &id).await.unwrap();
+
+    assert_eq!(translations.len(), 2);
+}
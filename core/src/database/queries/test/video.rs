@@ -7,7 +7,9 @@ use uuid::Uuid;
 use crate::database::{
     models::{
         original_video::OriginalVideo,
-        video::{Video, VideoOrderFields},
+        video::{
+            speaker_labels::SpeakerLabels, subtitles_mode::SubtitlesMode, Video, VideoOrderFields,
+        },
         video_storage::StorageVideoStage,
     },
     queries::{
@@ -15,7 +17,7 @@ use crate::database::{
         pagination::Pagination,
         video::{
             create, create_errors, create_many, find_all, find_by_id, find_by_id_with_storage,
-            find_by_transcription_id,
+            find_by_transcription_id, find_speaker_labels, find_subtitles_mode,
             with_original::{
                 find_all_with_original, find_by_user_id_with_original, find_with_original,
             },
@@ -214,10 +216,15 @@ async fn test_create_video(pool: PgPool) {
         user_id: 666,
         channel_id: 666,
         language: "en",
+        target_language: "pt-br",
         end_time: None,
         original_id: 666,
         start_time: "00:00:00",
         tags: None,
+        review: false,
+        subtitles_mode: SubtitlesMode::Burned,
+        subtitles_style_id: None,
+        speaker_labels: SpeakerLabels::None,
     };
 
     create(&pool, dto).await.unwrap();
@@ -246,9 +253,14 @@ async fn test_create_video_with_tags(pool: PgPool) {
         end_time: None,
         channel_id: 666,
         language: "en",
+        target_language: "pt-br",
         original_id: 666,
         start_time: "00:00:00",
         tags: Some("test;test".into()),
+        review: false,
+        subtitles_mode: SubtitlesMode::Soft,
+        subtitles_style_id: None,
+        speaker_labels: SpeakerLabels::Dashes,
     };
 
     create(&pool, dto).await.unwrap();
@@ -260,6 +272,11 @@ async fn test_create_video_with_tags(pool: PgPool) {
 
     assert!(count.count.is_some());
     assert_eq!(count.count.unwrap(), 1);
+
+    let subtitles_mode = find_subtitles_mode(&pool, &id).await.unwrap();
+    assert_eq!(subtitles_mode, SubtitlesMode::Soft);
+    let speaker_labels = find_speaker_labels(&pool, &id).await.unwrap();
+    assert_eq!(speaker_labels, SpeakerLabels::Dashes);
 }
 
 #[sqlx::test(migrations = "../migrations")]
@@ -274,9 +291,14 @@ async fn test_create_fail_if_foreign_key(pool: PgPool) {
         end_time: None,
         channel_id: 666,
         language: "en",
+        target_language: "pt-br",
         original_id: 666,
         start_time: "00:00:00",
         tags: None,
+        review: false,
+        subtitles_mode: SubtitlesMode::Burned,
+        subtitles_style_id: None,
+        speaker_labels: SpeakerLabels::None,
     };
 
     let result = create(&pool, dto).await;
@@ -468,10 +490,15 @@ async fn test_create_many(pool: PgPool) {
             user_id: 666,
             channel_id: 666,
             language: "en",
+            target_language: "pt-br",
             end_time: None,
             original_id: 666,
             start_time: "00:00:00",
             tags: None,
+            review: false,
+            subtitles_mode: SubtitlesMode::Burned,
+            subtitles_style_id: None,
+            speaker_labels: SpeakerLabels::None,
         };
 
         dtos.push(dto);
@@ -0,0 +1,94 @@
+use std::str::FromStr;
+
+use sqlx::{postgres::PgListener, PgPool};
+use uuid::Uuid;
+
+use crate::database::{
+    models::{
+        video::stage::VideoStage,
+        video_event::{ProgressStep, VideoEvent, VideoEventKind, VIDEO_EVENTS_CHANNEL},
+    },
+    queries::{
+        video::{change_stage_with, create_errors, CreateErrorsDto},
+        video_event::notify_progress,
+    },
+};
+
+async fn listen(pool: &PgPool) -> PgListener {
+    let mut listener = PgListener::connect_with(pool).await.unwrap();
+    listener.listen(VIDEO_EVENTS_CHANNEL).await.unwrap();
+    listener
+}
+
+async fn next_event(listener: &mut PgListener) -> VideoEvent {
+    let notification = listener.recv().await.unwrap();
+    serde_json::from_str(notification.payload()).unwrap()
+}
+
+#[sqlx::test(migrations = "../migrations", fixtures("videos"))]
+async fn test_change_stage_notifies(pool: PgPool) {
+    let id = Uuid::from_str("806b5a48-f221-11ed-a05b-0242ac120096").unwrap();
+    let mut listener = listen(&pool).await;
+
+    let mut trx = pool.begin().await.unwrap();
+    change_stage_with(&mut *trx, &id, VideoStage::Cutting)
+        .await
+        .unwrap();
+    trx.commit().await.unwrap();
+
+    let event = next_event(&mut listener).await;
+
+    assert_eq!(
+        event,
+        VideoEvent {
+            video_id: id,
+            user_id: 666,
+            kind: VideoEventKind::Stage {
+                stage: VideoStage::Cutting
+            },
+        }
+    );
+}
+
+#[sqlx::test(migrations = "../migrations", fixtures("videos"))]
+async fn test_create_errors_notifies(pool: PgPool) {
+    let id = Uuid::from_str("806b5a48-f221-11ed-a05b-0242ac120096").unwrap();
+    let mut listener = listen(&pool).await;
+
+    let dto = CreateErrorsDto {
+        video_ids: vec![id],
+        error: "Test Error",
+    };
+    create_errors(&pool, dto).await.unwrap();
+
+    let event = next_event(&mut listener).await;
+
+    assert_eq!(
+        event.kind,
+        VideoEventKind::Error {
+            stage: VideoStage::Downloading,
+            error: String::from("Test Error"),
+        }
+    );
+}
+
+#[sqlx::test(migrations = "../migrations", fixtures("videos"))]
+async fn test_notify_progress(pool: PgPool) {
+    let id = Uuid::from_str("806b5a48-f221-11ed-a05b-0242ac120096").unwrap();
+    let mut listener = listen(&pool).await;
+
+    notify_progress(&pool, &id, ProgressStep::Download, 25.0)
+        .await
+        .unwrap();
+
+    let event = next_event(&mut listener).await;
+
+    assert_eq!(event.video_id, id);
+    assert_eq!(
+        event.kind,
+        VideoEventKind::Progress {
+            step: ProgressStep::Download,
+            percentage: 25.0,
+        }
+    );
+}
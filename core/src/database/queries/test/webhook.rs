@@ -0,0 +1,180 @@
+use std::str::FromStr;
+
+use chrono::{Duration, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::database::{
+    models::{video::stage::VideoStage, webhook::WebhookEvent},
+    queries::{
+        output::{self, create_many, find_by_video_id_and_language, CreateOutputDto},
+        video::{change_stage, finish, set_url},
+        webhook::{
+            claim_due_deliveries, create, delete, find_all_by_user, find_deliveries,
+            mark_delivered, mark_failed, CreateWebhookDto, FailedDeliveryDto,
+        },
+    },
+};
+
+async fn create_webhook(pool: &PgPool, events: &[WebhookEvent]) -> i32 {
+    let dto = CreateWebhookDto {
+        user_id: 666,
+        url: "https://example.com/hook",
+        secret: "secret",
+        events,
+    };
+
+    create(pool, dto).await.unwrap().id
+}
+
+#[sqlx::test(migrations = "../migrations", fixtures("videos"))]
+async fn test_create_and_find_all_by_user(pool: PgPool) {
+    create_webhook(&pool, &[WebhookEvent::Stage, WebhookEvent::Error]).await;
+
+    let webhooks = find_all_by_user(&pool, 666).await.unwrap();
+
+    assert_eq!(webhooks.len(), 1);
+    assert_eq!(
+        webhooks[0].events,
+        vec![WebhookEvent::Stage, WebhookEvent::Error]
+    );
+}
+
+#[sqlx::test(migrations = "../migrations", fixtures("videos"))]
+async fn test_only_subscribed_events_are_delivered(pool: PgPool) {
+    let id = Uuid::from_str("806b5a48-f221-11ed-a05b-0242ac120096").unwrap();
+    let stage_webhook = create_webhook(&pool, &[WebhookEvent::Stage]).await;
+    let published_webhook = create_webhook(&pool, &[WebhookEvent::Published]).await;
+
+    change_stage(&pool, &id, VideoStage::Cutting).await.unwrap();
+
+    let deliveries = claim_due_deliveries(&pool, 10, 5, 60).await.unwrap();
+    assert_eq!(deliveries.len(), 1);
+    assert_eq!(deliveries[0].event, WebhookEvent::Stage);
+    assert!(deliveries[0].payload.contains("\"stage\":\"Cutting\""));
+
+    let dto = CreateOutputDto {
+        video_id: id,
+        channel_id: 666,
+        language: "es",
+    };
+    create_many(&pool, vec![dto]).await.unwrap();
+    let output = find_by_video_id_and_language(&pool, &id, "es")
+        .await
+        .unwrap();
+
+    let mut conn = pool.acquire().await.unwrap();
+    output::set_url(&mut conn, output.id, "https://www.youtube.com/watch?v=1")
+        .await
+        .unwrap();
+    drop(conn);
+
+    let published_deliveries = find_deliveries(&pool, published_webhook, 0, 10)
+        .await
+        .unwrap();
+    assert_eq!(published_deliveries.len(), 1);
+    assert!(published_deliveries[0]
+        .payload
+        .contains("https://www.youtube.com/watch?v=1"));
+    assert!(published_deliveries[0]
+        .payload
+        .contains("\"language\":\"es\""));
+
+    // the outputs published their urls already, the video only tells it is done
+    let finished = set_url(&pool, id, "https://www.youtube.com/watch?v=1")
+        .await
+        .unwrap();
+    assert!(finished);
+
+    let stage_deliveries = find_deliveries(&pool, stage_webhook, 0, 10).await.unwrap();
+    assert_eq!(stage_deliveries.len(), 2);
+
+    let published_deliveries = find_deliveries(&pool, published_webhook, 0, 10)
+        .await
+        .unwrap();
+    assert_eq!(published_deliveries.len(), 1);
+}
+
+#[sqlx::test(migrations = "../migrations", fixtures("videos"))]
+async fn test_video_is_finished_once(pool: PgPool) {
+    let id = Uuid::from_str("806b5a48-f221-11ed-a05b-0242ac120096").unwrap();
+    let stage_webhook = create_webhook(&pool, &[WebhookEvent::Stage]).await;
+
+    let finished = set_url(&pool, id, "https://www.youtube.com/watch?v=1")
+        .await
+        .unwrap();
+    assert!(finished);
+
+    assert!(!finish(&pool, id).await.unwrap());
+    let finished = set_url(&pool, id, "https://www.youtube.com/watch?v=1")
+        .await
+        .unwrap();
+    assert!(!finished);
+
+    let stage_deliveries = find_deliveries(&pool, stage_webhook, 0, 10).await.unwrap();
+    assert_eq!(stage_deliveries.len(), 1);
+}
+
+#[sqlx::test(migrations = "../migrations", fixtures("videos"))]
+async fn test_failed_delivery_waits_for_next_attempt(pool: PgPool) {
+    let id = Uuid::from_str("806b5a48-f221-11ed-a05b-0242ac120096").unwrap();
+    let webhook_id = create_webhook(&pool, &[WebhookEvent::Stage]).await;
+    change_stage(&pool, &id, VideoStage::Cutting).await.unwrap();
+
+    let delivery = claim_due_deliveries(&pool, 10, 5, 60)
+        .await
+        .unwrap()
+        .remove(0);
+
+    let dto = FailedDeliveryDto {
+        id: delivery.id,
+        response_status: Some(500),
+        error: "Internal Server Error",
+        next_attempt_at: Utc::now().naive_utc() + Duration::minutes(1),
+    };
+    mark_failed(&pool, dto).await.unwrap();
+
+    let due = claim_due_deliveries(&pool, 10, 5, 60).await.unwrap();
+    assert!(due.is_empty());
+
+    mark_delivered(&pool, delivery.id, 200).await.unwrap();
+
+    let deliveries = find_deliveries(&pool, webhook_id, 0, 10).await.unwrap();
+    assert_eq!(deliveries[0].attempts, 1);
+    assert_eq!(deliveries[0].response_status, Some(200));
+    assert!(deliveries[0].delivered_at.is_some());
+}
+
+#[sqlx::test(migrations = "../migrations", fixtures("videos"))]
+async fn test_claimed_delivery_is_skipped_until_the_lease_ends(pool: PgPool) {
+    let id = Uuid::from_str("806b5a48-f221-11ed-a05b-0242ac120096").unwrap();
+    create_webhook(&pool, &[WebhookEvent::Stage]).await;
+    change_stage(&pool, &id, VideoStage::Cutting).await.unwrap();
+
+    let claimed = claim_due_deliveries(&pool, 10, 5, 0).await.unwrap();
+    assert_eq!(claimed.len(), 1);
+    assert_eq!(claimed[0].attempts, 1);
+
+    // the queue that claimed it died before marking it, so the expired lease is claimed again
+    let claimed = claim_due_deliveries(&pool, 10, 5, 60).await.unwrap();
+    assert_eq!(claimed.len(), 1);
+    assert_eq!(claimed[0].attempts, 2);
+
+    let due = claim_due_deliveries(&pool, 10, 5, 60).await.unwrap();
+    assert!(due.is_empty());
+}
+
+#[sqlx::test(migrations = "../migrations", fixtures("videos"))]
+async fn test_delete_drops_pending_deliveries(pool: PgPool) {
+    let id = Uuid::from_str("806b5a48-f221-11ed-a05b-0242ac120096").unwrap();
+    let webhook_id = create_webhook(&pool, &[WebhookEvent::Stage]).await;
+    change_stage(&pool, &id, VideoStage::Cutting).await.unwrap();
+
+    delete(&pool, webhook_id).await.unwrap();
+
+    let webhooks = find_all_by_user(&pool, 666).await.unwrap();
+    assert!(webhooks.is_empty());
+
+    let deliveries = claim_due_deliveries(&pool, 10, 5, 60).await.unwrap();
+    assert!(deliveries.is_empty());
+}
@@ -1,5 +1,5 @@
 use chrono::{DateTime, Utc};
-use sqlx::PgPool;
+use sqlx::{PgExecutor, PgPool};
 use uuid::Uuid;
 
 use crate::database::models::video_transcription::VideosTranscription;
@@ -47,7 +47,9 @@ pub async fn create(pool: &PgPool, dto: CreateTranscriptionDto) -> Result<(), sq
     sqlx::query!(
         r#"
         INSERT INTO videos_transcriptions (video_id, transcription_id, transcriber_id)
-        VALUES ($1, $2, $3);
+        VALUES ($1, $2, $3)
+        ON CONFLICT (video_id) DO UPDATE
+        SET transcription_id = $2, transcriber_id = $3, storage_id = NULL, path = NULL, updated_at = NOW(), deleted_at = NULL, checked_at = NULL;
         "#,
         dto.video_id,
         dto.transcription_id,
@@ -59,7 +61,10 @@ pub async fn create(pool: &PgPool, dto: CreateTranscriptionDto) -> Result<(), sq
     Ok(())
 }
 
-pub async fn update(pool: &PgPool, dto: UpdateVideoTranscriptionDto) -> Result<(), sqlx::Error> {
+pub async fn update(
+    pool: impl PgExecutor<'_>,
+    dto: UpdateVideoTranscriptionDto,
+) -> Result<(), sqlx::Error> {
     sqlx::query!(
         r#"
             UPDATE videos_transcriptions SET storage_id = $1, path = $2, updated_at = NOW()
@@ -74,3 +79,64 @@ pub async fn update(pool: &PgPool, dto: UpdateVideoTranscriptionDto) -> Result<(
 
     Ok(())
 }
+
+/// Transcriptions of the transcriber still waiting for their srt that weren't checked in the
+/// last `interval_seconds`, the longest unchecked first. Setting `checked_at` is the claim,
+/// other queues skip them until the interval passes, and the ones that stay processing for
+/// long go to the back instead of taking every batch
+pub async fn claim_pending(
+    pool: impl PgExecutor<'_>,
+    transcriber_id: i32,
+    limit: i64,
+    interval_seconds: i32,
+) -> Result<Vec<VideosTranscription>, sqlx::Error> {
+    let transcriptions = sqlx::query_as!(
+        VideosTranscription,
+        r#"
+        UPDATE videos_transcriptions
+        SET checked_at = NOW()
+        WHERE video_id IN (
+            SELECT video_id FROM videos_transcriptions
+            WHERE
+                transcriber_id = $1
+                AND path IS NULL
+                AND deleted_at IS NULL
+                AND (checked_at IS NULL OR checked_at <= NOW() - $3 * INTERVAL '1 second')
+            ORDER BY checked_at NULLS FIRST
+            LIMIT $2
+            FOR UPDATE SKIP LOCKED
+        )
+        RETURNING
+          video_id as "video_id: Uuid",
+          transcriber_id,
+          transcription_id,
+          storage_id,
+          path,
+          created_at as "created_at: DateTime<Utc>",
+          updated_at as "updated_at: DateTime<Utc>",
+          deleted_at as "deleted_at: DateTime<Utc>"
+        "#,
+        transcriber_id,
+        limit,
+        interval_seconds as f64,
+    )
+    .fetch_all(pool)
+    .await?;
+
+    return Ok(transcriptions);
+}
+
+/// A failed transcription is not checked again, until the video is retried
+pub async fn mark_failed(pool: impl PgExecutor<'_>, video_id: &Uuid) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        r#"
+        UPDATE videos_transcriptions SET deleted_at = NOW(), updated_at = NOW()
+        WHERE video_id = $1
+        "#,
+        video_id,
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
@@ -1,30 +1,66 @@
+use chrono::{DateTime, Utc};
+use sqlx::PgExecutor;
 use uuid::Uuid;
 
+use crate::database::models::video_translation::VideosTranslation;
+
 pub struct CreateTranslationDto<'a> {
     pub video_id: &'a Uuid,
     pub translator_id: i32,
     pub translation_id: Option<String>,
     pub storage_id: i32,
     pub path: &'a str,
+    pub language: &'a str,
 }
 
 pub async fn create<'a>(
-    pool: &sqlx::PgPool,
+    pool: impl PgExecutor<'_>,
     dto: CreateTranslationDto<'a>,
 ) -> Result<(), sqlx::Error> {
     sqlx::query!(
         r#"
-        INSERT INTO videos_translations (video_id, translator_id, translation_id, storage_id, path)
-        VALUES ($1, $2, $3, $4, $5);
+        INSERT INTO videos_translations (video_id, translator_id, translation_id, storage_id, path, language)
+        VALUES ($1, $2, $3, $4, $5, $6)
+        ON CONFLICT (video_id, language) DO UPDATE
+        SET translator_id = $2, translation_id = $3, storage_id = $4, path = $5, updated_at = NOW();
         "#,
         dto.video_id,
         dto.translator_id,
         dto.translation_id,
         dto.storage_id,
-        dto.path
+        dto.path,
+        dto.language
     )
     .execute(pool)
     .await?;
 
     Ok(())
 }
+
+pub async fn find_by_video_id(
+    pool: &sqlx::PgPool,
+    video_id: &Uuid,
+) -> Result<Vec<VideosTranslation>, sqlx::Error> {
+    let translations = sqlx::query_as!(
+        VideosTranslation,
+        r#"
+        SELECT
+          video_id as "video_id: Uuid",
+          translator_id,
+          translation_id,
+          storage_id,
+          path,
+          language,
+          created_at as "created_at: DateTime<Utc>",
+          updated_at as "updated_at: DateTime<Utc>",
+          deleted_at as "deleted_at: DateTime<Utc>"
+        FROM videos_translations
+        WHERE video_id = $1
+        "#,
+        video_id
+    )
+    .fetch_all(pool)
+    .await?;
+
+    return Ok(translations);
+}
@@ -1,18 +1,21 @@
 use chrono::NaiveDateTime;
 
-use sqlx::{PgExecutor, PgPool, QueryBuilder};
+use sqlx::{Acquire, PgConnection, PgExecutor, PgPool, Postgres, QueryBuilder};
 use uuid::Uuid;
 
 use crate::database::models::{
     video::{
+        speaker_labels::SpeakerLabels,
         stage::VideoStage,
+        subtitles_mode::SubtitlesMode,
         with::{VideoWithStorage, VideoWithStorageAndChannel},
         Video,
     },
+    video_event::{VideoEvent, VideoEventKind},
     video_storage::StorageVideoStage,
 };
 
-use super::{filter::Filter, macros::find_all, pagination::Pagination, storage};
+use super::{filter::Filter, macros::find_all, pagination::Pagination, storage, video_event};
 
 pub mod with_original;
 
@@ -23,10 +26,17 @@ pub struct CreateVideoDto<'a> {
     pub user_id: i32,
     pub channel_id: i32,
     pub language: &'a str,
+    pub target_language: &'a str,
     pub tags: Option<String>,
     pub start_time: &'a str,
     pub end_time: Option<&'a str>,
     pub original_id: i32,
+    /// Pauses the video on `Reviewing` until its subtitles are approved
+    pub review: bool,
+    pub subtitles_mode: SubtitlesMode,
+    /// Overrides the style of the channel
+    pub subtitles_style_id: Option<i32>,
+    pub speaker_labels: SpeakerLabels,
 }
 
 pub struct CreateErrorsDto<'a> {
@@ -37,8 +47,8 @@ pub struct CreateErrorsDto<'a> {
 pub async fn create(pool: impl PgExecutor<'_>, dto: CreateVideoDto<'_>) -> Result<(), sqlx::Error> {
     sqlx::query!(
         r#"
-        INSERT INTO videos (id, title, description, user_id, channel_id, language, start_time, original_video_id, tags,end_time)
-        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9,$10);
+        INSERT INTO videos (id, title, description, user_id, channel_id, language, start_time, original_video_id, tags,end_time, target_language, review, subtitles_mode, subtitles_style_id, speaker_labels)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9,$10, $11, $12, $13, $14, $15);
         "#,
         dto.id,
         dto.title,
@@ -50,6 +60,11 @@ pub async fn create(pool: impl PgExecutor<'_>, dto: CreateVideoDto<'_>) -> Resul
         dto.original_id,
         dto.tags,
         dto.end_time,
+        dto.target_language,
+        dto.review,
+        dto.subtitles_mode as SubtitlesMode,
+        dto.subtitles_style_id,
+        dto.speaker_labels as SpeakerLabels,
     )
     .execute(pool)
     .await?;
@@ -62,7 +77,7 @@ pub async fn create_many(
     dtos: Vec<CreateVideoDto<'_>>,
 ) -> Result<(), sqlx::Error> {
     let mut query_builder = QueryBuilder::new(
-        "INSERT INTO videos (id, title, description, user_id, channel_id, language, start_time, original_video_id, tags,end_time) ",
+        "INSERT INTO videos (id, title, description, user_id, channel_id, language, start_time, original_video_id, tags,end_time, target_language, review, subtitles_mode, subtitles_style_id, speaker_labels) ",
     );
 
     query_builder.push_values(&dtos, |mut builder, dto| {
@@ -76,7 +91,12 @@ pub async fn create_many(
             .push_bind(dto.start_time)
             .push_bind(dto.original_id)
             .push_bind(&dto.tags)
-            .push_bind(dto.end_time);
+            .push_bind(dto.end_time)
+            .push_bind(dto.target_language)
+            .push_bind(dto.review)
+            .push_bind(dto.subtitles_mode)
+            .push_bind(dto.subtitles_style_id)
+            .push_bind(dto.speaker_labels);
     });
 
     let insert_query = query_builder.build();
@@ -85,12 +105,71 @@ pub async fn create_many(
     Ok(())
 }
 
+/// Whether the video waits for its subtitles to be approved before being subtitled
+pub async fn requires_review(pool: &PgPool, video_id: &Uuid) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query!(
+        r#"
+        SELECT review FROM videos
+        WHERE id = $1
+        "#,
+        video_id,
+    )
+    .fetch_one(pool)
+    .await?;
+
+    Ok(result.review)
+}
+
+pub async fn find_subtitles_mode(
+    pool: &PgPool,
+    video_id: &Uuid,
+) -> Result<SubtitlesMode, sqlx::Error> {
+    let result = sqlx::query!(
+        r#"
+        SELECT subtitles_mode as "subtitles_mode: SubtitlesMode" FROM videos
+        WHERE id = $1
+        "#,
+        video_id,
+    )
+    .fetch_one(pool)
+    .await?;
+
+    Ok(result.subtitles_mode)
+}
+
+pub async fn find_speaker_labels(
+    pool: &PgPool,
+    video_id: &Uuid,
+) -> Result<SpeakerLabels, sqlx::Error> {
+    let result = sqlx::query!(
+        r#"
+        SELECT speaker_labels as "speaker_labels: SpeakerLabels" FROM videos
+        WHERE id = $1
+        "#,
+        video_id,
+    )
+    .fetch_one(pool)
+    .await?;
+
+    Ok(result.speaker_labels)
+}
+
 pub async fn change_stage(
-    pool: impl PgExecutor<'_>,
+    pool: &PgPool,
     video_id: &Uuid,
     stage: VideoStage,
 ) -> Result<(), sqlx::Error> {
-    sqlx::query!(
+    let mut conn = pool.acquire().await?;
+    return change_stage_with(&mut conn, video_id, stage).await;
+}
+
+/// Also notifies the video events, on a transaction they are sent on commit
+pub async fn change_stage_with(
+    conn: &mut PgConnection,
+    video_id: &Uuid,
+    stage: VideoStage,
+) -> Result<(), sqlx::Error> {
+    let result = sqlx::query!(
         r#"
         UPDATE videos
         SET 
@@ -98,13 +177,23 @@ pub async fn change_stage(
         updated_at = NOW(),
         error = false
         WHERE id = $2
+        RETURNING user_id
         "#,
-        stage as VideoStage,
+        stage.clone() as VideoStage,
         video_id,
     )
-    .execute(pool)
+    .fetch_optional(&mut *conn)
     .await?;
 
+    if let Some(result) = result {
+        let event = VideoEvent {
+            video_id: *video_id,
+            user_id: result.user_id,
+            kind: VideoEventKind::Stage { stage },
+        };
+        video_event::publish(&mut *conn, &event).await?;
+    }
+
     Ok(())
 }
 
@@ -131,12 +220,16 @@ pub async fn change_error_state(
 }
 
 //This method is assuming that every video has the same stage. maybe we should change this later
-pub async fn create_errors(pool: &PgPool, dto: CreateErrorsDto<'_>) -> Result<i64, sqlx::Error> {
+/// With a transaction the errors are written in a savepoint, kept only if the caller commits
+pub async fn create_errors(
+    pool: impl Acquire<'_, Database = Postgres>,
+    dto: CreateErrorsDto<'_>,
+) -> Result<i64, sqlx::Error> {
     let mut trx = pool.begin().await?;
     let video_ids = dto.video_ids;
     let video_id = video_ids.first().ok_or(sqlx::Error::RowNotFound)?;
 
-    println!("{:?}", video_ids);
+    tracing::debug!("{:?}", video_ids);
 
     //Postgres hack see:https://github.com/launchbadge/sqlx/blob/main/FAQ.md
     let result = sqlx::query!(
@@ -146,14 +239,26 @@ pub async fn create_errors(pool: &PgPool, dto: CreateErrorsDto<'_>) -> Result<i6
         error = true,
         updated_at = NOW()
         WHERE id = ANY($1)
-        RETURNING stage as "stage: VideoStage"
+        RETURNING id, user_id, stage as "stage: VideoStage"
     "#,
         &video_ids[..],
     )
-    .fetch_one(&mut *trx)
+    .fetch_all(&mut *trx)
     .await?;
 
-    let stage = result.stage;
+    let stage = match result.first() {
+        Some(row) => row.stage.clone(),
+        None => return Err(sqlx::Error::RowNotFound),
+    };
+
+    for row in &result {
+        let event = VideoEvent {
+            video_id: row.id,
+            user_id: row.user_id,
+            kind: VideoEventKind::error(row.stage.clone(), dto.error),
+        };
+        video_event::publish(&mut *trx, &event).await?;
+    }
 
     let mut query_builder =
         QueryBuilder::new("INSERT INTO videos_errors (video_id, error, stage) ");
@@ -186,20 +291,73 @@ pub async fn create_errors(pool: &PgPool, dto: CreateErrorsDto<'_>) -> Result<i6
     Ok(count)
 }
 
-pub async fn set_url(pool: &PgPool, video_id: Uuid, url: &str) -> Result<(), sqlx::Error> {
-    sqlx::query!(
+/// Marks the video as done with the url of its primary output.
+/// False when it was done already, so only one of the outputs finishing together finishes it
+pub async fn set_url(pool: &PgPool, video_id: Uuid, url: &str) -> Result<bool, sqlx::Error> {
+    let mut trx = pool.begin().await?;
+
+    let result = sqlx::query!(
         r#"
         UPDATE videos
         SET url = $1, stage = 'DONE', uploaded_at = NOW(), error = false
-        WHERE id = $2
+        WHERE id = $2 AND stage <> 'DONE'
+        RETURNING user_id
         "#,
         url,
         video_id,
     )
-    .execute(pool)
+    .fetch_optional(&mut *trx)
     .await?;
 
-    Ok(())
+    let finished = publish_done(&mut *trx, video_id, result.map(|row| row.user_id)).await?;
+    trx.commit().await?;
+
+    Ok(finished)
+}
+
+/// Marks the video as done when its primary output has no url, false when it was done already
+pub async fn finish(pool: &PgPool, video_id: Uuid) -> Result<bool, sqlx::Error> {
+    let mut trx = pool.begin().await?;
+
+    let result = sqlx::query!(
+        r#"
+        UPDATE videos
+        SET stage = 'DONE', updated_at = NOW(), error = false
+        WHERE id = $1 AND stage <> 'DONE'
+        RETURNING user_id
+        "#,
+        video_id,
+    )
+    .fetch_optional(&mut *trx)
+    .await?;
+
+    let finished = publish_done(&mut *trx, video_id, result.map(|row| row.user_id)).await?;
+    trx.commit().await?;
+
+    Ok(finished)
+}
+
+/// The outputs publish their own urls, the video only tells it is done
+async fn publish_done(
+    conn: &mut PgConnection,
+    video_id: Uuid,
+    user_id: Option<i32>,
+) -> Result<bool, sqlx::Error> {
+    let user_id = match user_id {
+        Some(user_id) => user_id,
+        None => return Ok(false),
+    };
+
+    let event = VideoEvent {
+        video_id,
+        user_id,
+        kind: VideoEventKind::Stage {
+            stage: VideoStage::Done,
+        },
+    };
+    video_event::publish(conn, &event).await?;
+
+    Ok(true)
 }
 
 find_all!(Video, "videos");
@@ -217,6 +375,7 @@ pub async fn find_by_transcription_id(
             v.description,
             v.url,
             v.language,
+            v.target_language,
             v.user_id,
             v.channel_id,
             v.error,
@@ -254,6 +413,7 @@ pub async fn find_by_id(pool: &PgPool, id: &Uuid) -> Result<Video, sqlx::Error>
             v.description,
             v.url,
             v.language,
+            v.target_language,
             v.user_id,
             v.channel_id,
             v.error,
@@ -371,3 +531,66 @@ pub async fn bulk_update_end_time(
 
     Ok(())
 }
+
+/// Replaces the cut times of the videos, `None` keeps the current value
+pub async fn update_times(
+    pool: impl PgExecutor<'_>,
+    ids: &[Uuid],
+    start_time: Option<&str>,
+    end_time: Option<&str>,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        r#"
+        UPDATE videos
+        SET
+        start_time = COALESCE($1, start_time),
+        end_time = COALESCE($2, end_time),
+        updated_at = NOW()
+        WHERE id = ANY($3)
+        "#,
+        start_time,
+        end_time,
+        ids,
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Clears the error of a failed video. False when it wasn't failed,
+/// so only one of the retries sent together resumes it
+pub async fn claim_retry(pool: impl PgExecutor<'_>, id: &Uuid) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query!(
+        r#"
+        UPDATE videos
+        SET
+        error = false,
+        updated_at = NOW()
+        WHERE id = $1 AND error = true
+        RETURNING id
+        "#,
+        id,
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(result.is_some())
+}
+
+pub async fn clear_errors(pool: impl PgExecutor<'_>, ids: &[Uuid]) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        r#"
+        UPDATE videos
+        SET
+        error = false,
+        updated_at = NOW()
+        WHERE id = ANY($1)
+        "#,
+        ids,
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
@@ -15,6 +15,7 @@ v.title,
 v.description,
 v.url,
 v.language,
+v.target_language,
 v.user_id,
 v.channel_id,
 v.error,
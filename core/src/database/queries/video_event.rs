@@ -0,0 +1,53 @@
+use sqlx::{PgConnection, PgExecutor, PgPool};
+use uuid::Uuid;
+
+use crate::database::{
+    models::video_event::{ProgressStep, VideoEvent, VideoEventKind, VIDEO_EVENTS_CHANNEL},
+    queries::webhook,
+};
+
+/// Listeners only receive the event once the transaction of the executor commits
+pub async fn notify(pool: impl PgExecutor<'_>, event: &VideoEvent) -> Result<(), sqlx::Error> {
+    let payload = serde_json::to_string(event).expect("A video event is always serializable");
+
+    // pg_notify returns void, which the query macros can't describe
+    sqlx::query("SELECT pg_notify($1, $2)")
+        .bind(VIDEO_EVENTS_CHANNEL)
+        .bind(payload)
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+/// Notifies the event and queues it for the webhooks subscribed to it
+pub async fn publish(conn: &mut PgConnection, event: &VideoEvent) -> Result<(), sqlx::Error> {
+    notify(&mut *conn, event).await?;
+    webhook::enqueue_deliveries(&mut *conn, event).await?;
+
+    Ok(())
+}
+
+pub async fn notify_progress(
+    pool: &PgPool,
+    video_id: &Uuid,
+    step: ProgressStep,
+    percentage: f32,
+) -> Result<(), sqlx::Error> {
+    let video = sqlx::query!(
+        r#"
+        SELECT user_id FROM videos WHERE id = $1
+        "#,
+        video_id,
+    )
+    .fetch_one(pool)
+    .await?;
+
+    let event = VideoEvent {
+        video_id: *video_id,
+        user_id: video.user_id,
+        kind: VideoEventKind::Progress { step, percentage },
+    };
+
+    notify(pool, &event).await
+}
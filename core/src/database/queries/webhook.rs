@@ -0,0 +1,311 @@
+use chrono::NaiveDateTime;
+use sqlx::{PgExecutor, PgPool};
+
+use crate::database::models::{
+    video_event::VideoEvent,
+    webhook::{Webhook, WebhookDelivery, WebhookEvent},
+};
+
+pub struct CreateWebhookDto<'a> {
+    pub user_id: i32,
+    pub url: &'a str,
+    pub secret: &'a str,
+    pub events: &'a [WebhookEvent],
+}
+
+pub async fn create(pool: &PgPool, dto: CreateWebhookDto<'_>) -> Result<Webhook, sqlx::Error> {
+    let webhook = sqlx::query_as!(
+        Webhook,
+        r#"
+        INSERT INTO webhooks (user_id, url, secret, events)
+        VALUES ($1, $2, $3, $4)
+        RETURNING
+            id,
+            user_id,
+            url,
+            secret,
+            events as "events: Vec<WebhookEvent>",
+            created_at as "created_at: NaiveDateTime",
+            updated_at as "updated_at: NaiveDateTime",
+            deleted_at as "deleted_at: NaiveDateTime"
+        "#,
+        dto.user_id,
+        dto.url,
+        dto.secret,
+        dto.events as &[WebhookEvent],
+    )
+    .fetch_one(pool)
+    .await?;
+
+    Ok(webhook)
+}
+
+pub async fn find_all_by_user(pool: &PgPool, user_id: i32) -> Result<Vec<Webhook>, sqlx::Error> {
+    let webhooks = sqlx::query_as!(
+        Webhook,
+        r#"
+        SELECT
+            id,
+            user_id,
+            url,
+            secret,
+            events as "events: Vec<WebhookEvent>",
+            created_at as "created_at: NaiveDateTime",
+            updated_at as "updated_at: NaiveDateTime",
+            deleted_at as "deleted_at: NaiveDateTime"
+        FROM webhooks
+        WHERE user_id = $1 AND deleted_at IS NULL
+        ORDER BY id
+        "#,
+        user_id,
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(webhooks)
+}
+
+pub async fn find_by_id_and_user(
+    pool: &PgPool,
+    id: i32,
+    user_id: i32,
+) -> Result<Webhook, sqlx::Error> {
+    let webhook = sqlx::query_as!(
+        Webhook,
+        r#"
+        SELECT
+            id,
+            user_id,
+            url,
+            secret,
+            events as "events: Vec<WebhookEvent>",
+            created_at as "created_at: NaiveDateTime",
+            updated_at as "updated_at: NaiveDateTime",
+            deleted_at as "deleted_at: NaiveDateTime"
+        FROM webhooks
+        WHERE id = $1 AND user_id = $2 AND deleted_at IS NULL
+        "#,
+        id,
+        user_id,
+    )
+    .fetch_one(pool)
+    .await?;
+
+    Ok(webhook)
+}
+
+/// The pending deliveries of the webhook are dropped along with it
+pub async fn delete(pool: &PgPool, id: i32) -> Result<(), sqlx::Error> {
+    let mut trx = pool.begin().await?;
+
+    sqlx::query!(
+        r#"
+        UPDATE webhooks
+        SET deleted_at = NOW(), updated_at = NOW()
+        WHERE id = $1
+        "#,
+        id,
+    )
+    .execute(&mut *trx)
+    .await?;
+
+    sqlx::query!(
+        r#"
+        DELETE FROM webhook_deliveries
+        WHERE webhook_id = $1 AND delivered_at IS NULL
+        "#,
+        id,
+    )
+    .execute(&mut *trx)
+    .await?;
+
+    trx.commit().await?;
+
+    Ok(())
+}
+
+/// Queues the event for every webhook of the owner of the video subscribed to it.
+/// Written with the executor of the change, so nothing is delivered for a rolled back one
+pub async fn enqueue_deliveries(
+    pool: impl PgExecutor<'_>,
+    event: &VideoEvent,
+) -> Result<(), sqlx::Error> {
+    let webhook_event = match WebhookEvent::from_kind(&event.kind) {
+        Some(webhook_event) => webhook_event,
+        None => return Ok(()),
+    };
+
+    let payload = serde_json::to_string(event).expect("A video event is always serializable");
+
+    sqlx::query!(
+        r#"
+        INSERT INTO webhook_deliveries (webhook_id, event, payload)
+        SELECT id, $2, $3
+        FROM webhooks
+        WHERE user_id = $1 AND $2 = ANY(events) AND deleted_at IS NULL
+        "#,
+        event.user_id,
+        webhook_event as WebhookEvent,
+        payload,
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// A delivery along with where and how to send it
+#[derive(Debug, Clone, PartialEq)]
+pub struct PendingDelivery {
+    pub id: i64,
+    pub event: WebhookEvent,
+    pub payload: String,
+    pub attempts: i32,
+    pub url: String,
+    pub secret: String,
+}
+
+/// Claims the due deliveries by counting the attempt and pushing `next_attempt_at` out
+/// by `lease_seconds`, so they can be sent outside of any transaction and another queue
+/// only retries them once the lease is over.
+/// Deliveries that failed `max_attempts` times are left on the log
+pub async fn claim_due_deliveries(
+    pool: impl PgExecutor<'_>,
+    limit: i64,
+    max_attempts: i32,
+    lease_seconds: i32,
+) -> Result<Vec<PendingDelivery>, sqlx::Error> {
+    let deliveries = sqlx::query_as!(
+        PendingDelivery,
+        r#"
+        WITH claimed AS (
+            UPDATE webhook_deliveries
+            SET
+                attempts = attempts + 1,
+                next_attempt_at = NOW() + $3 * INTERVAL '1 second',
+                updated_at = NOW()
+            WHERE id IN (
+                SELECT id FROM webhook_deliveries
+                WHERE
+                    delivered_at IS NULL
+                    AND attempts < $2
+                    AND next_attempt_at <= NOW()
+                ORDER BY id
+                LIMIT $1
+                FOR UPDATE SKIP LOCKED
+            )
+            RETURNING id, webhook_id, event, payload, attempts
+        )
+        SELECT
+            c.id as "id!",
+            c.event as "event!: WebhookEvent",
+            c.payload as "payload!",
+            c.attempts as "attempts!",
+            w.url,
+            w.secret
+        FROM claimed c
+        INNER JOIN webhooks w ON w.id = c.webhook_id
+        ORDER BY c.id
+        "#,
+        limit,
+        max_attempts,
+        lease_seconds as f64,
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(deliveries)
+}
+
+pub async fn mark_delivered(
+    pool: impl PgExecutor<'_>,
+    id: i64,
+    response_status: i32,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        r#"
+        UPDATE webhook_deliveries
+        SET
+            delivered_at = NOW(),
+            response_status = $2,
+            last_error = NULL,
+            updated_at = NOW()
+        WHERE id = $1
+        "#,
+        id,
+        response_status,
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+pub struct FailedDeliveryDto<'a> {
+    pub id: i64,
+    pub response_status: Option<i32>,
+    pub error: &'a str,
+    pub next_attempt_at: NaiveDateTime,
+}
+
+pub async fn mark_failed(
+    pool: impl PgExecutor<'_>,
+    dto: FailedDeliveryDto<'_>,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        r#"
+        UPDATE webhook_deliveries
+        SET
+            response_status = $2,
+            last_error = $3,
+            next_attempt_at = $4,
+            updated_at = NOW()
+        WHERE id = $1
+        "#,
+        dto.id,
+        dto.response_status,
+        dto.error,
+        dto.next_attempt_at,
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn find_deliveries(
+    pool: &PgPool,
+    webhook_id: i32,
+    offset: i64,
+    limit: i64,
+) -> Result<Vec<WebhookDelivery>, sqlx::Error> {
+    let deliveries = sqlx::query_as!(
+        WebhookDelivery,
+        r#"
+        SELECT
+            id,
+            webhook_id,
+            event as "event: WebhookEvent",
+            payload,
+            attempts,
+            response_status,
+            last_error,
+            next_attempt_at as "next_attempt_at: NaiveDateTime",
+            delivered_at as "delivered_at: NaiveDateTime",
+            created_at as "created_at: NaiveDateTime",
+            updated_at as "updated_at: NaiveDateTime"
+        FROM webhook_deliveries
+        WHERE webhook_id = $1
+        ORDER BY id DESC
+        OFFSET $2
+        LIMIT $3
+        "#,
+        webhook_id,
+        offset,
+        limit,
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(deliveries)
+}
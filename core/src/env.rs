@@ -8,16 +8,30 @@ pub fn check_envs() {
     std::env::var("API_JSON_WEB_TOKEN_SECRET").expect("API_JSON_WEB_TOKEN_SECRET not found");
 
     //AWS
-    std::env::var("AWS_BUCKET_NAME").expect("AWS_BUCKET_NAME not found");
-    std::env::var("AWS_ACCESS_KEY_ID").expect("AWS_ACCESS_KEY_ID not found");
-    std::env::var("AWS_SECRET_ACCESS_KEY").expect("AWS_SECRET_ACCESS_KEY not found");
-    std::env::var("AWS_QUEUE_URL").expect("AWS_QUEUE_URL not found");
+    #[cfg(not(feature = "local"))]
+    {
+        std::env::var("AWS_BUCKET_NAME").expect("AWS_BUCKET_NAME not found");
+        std::env::var("AWS_ACCESS_KEY_ID").expect("AWS_ACCESS_KEY_ID not found");
+        std::env::var("AWS_SECRET_ACCESS_KEY").expect("AWS_SECRET_ACCESS_KEY not found");
+        std::env::var("AWS_QUEUE_URL").expect("AWS_QUEUE_URL not found");
+    }
+
+    //LOCAL STORAGE
+    #[cfg(feature = "local")]
+    {
+        std::env::var("LOCAL_STORAGE_PATH").expect("LOCAL_STORAGE_PATH not found");
+        std::env::var("LOCAL_STORAGE_URL").expect("LOCAL_STORAGE_URL not found");
+        std::env::var("LOCAL_STORAGE_SECRET").expect("LOCAL_STORAGE_SECRET not found");
+    }
 
     //ASSEMBLY_AI
     std::env::var("ASSEMBLY_AI_API_KEY").expect("ASSEMBLY_AI_API_KEY not found");
     std::env::var("ASSEMBLY_AI_BASE_URL").expect("ASSEMBLY_AI_BASE_URL not found");
-    std::env::var("ASSEMBLY_AI_WEBHOOK_ENDPOINT").expect("ASSEMBLY_AI_WEBHOOK_ENDPOINT not found");
-    std::env::var("ASSEMBLY_AI_WEBHOOK_TOKEN").expect("ASSEMBLY_AI_WEBHOOK_TOKEN not found");
+    if !crate::internals::transcriber::polling_enabled() {
+        std::env::var("ASSEMBLY_AI_WEBHOOK_ENDPOINT")
+            .expect("ASSEMBLY_AI_WEBHOOK_ENDPOINT not found");
+        std::env::var("ASSEMBLY_AI_WEBHOOK_TOKEN").expect("ASSEMBLY_AI_WEBHOOK_TOKEN not found");
+    }
 
     //DEEPL
     std::env::var("DEEPL_BASE_URL").expect("DEEPL_BASE_URL not set");
@@ -4,7 +4,7 @@ use self::s3::S3Client;
 
 use super::traits::CloudService;
 
-mod payload;
+pub(crate) mod payload;
 pub mod s3;
 pub mod sqs;
 
@@ -1,5 +1,11 @@
-use crate::internals::cloud::models::payload::{SrtPayload, VideoPayload};
+use crate::{
+    internals::cloud::models::payload::{
+        PayloadType, SrtPayload, VideoCutPayload, VideoDownloadPayload, VideoPayload,
+    },
+    SyncError,
+};
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -15,43 +21,91 @@ pub struct S3SrtPayload {
     pub s3srt_uri: String,
 }
 
-impl Into<VideoPayload> for S3UploadPayload {
-    fn into(self) -> VideoPayload {
-        let video_id = self
-            .s3video_uri
-            .split('/')
-            .last()
-            .unwrap()
-            .split('.')
-            .next()
-            .unwrap();
+/// Object keys follow the `{video_id}.{extension}` or `{video_id}.{language}.{extension}` pattern,
+/// the ones that don't are refused, like an edited payload being replayed
+fn parse_uri(uri: &str) -> Result<(uuid::Uuid, Option<String>), SyncError> {
+    let file_name = uri.split('/').last().unwrap_or(uri);
+    let parts: Vec<&str> = file_name.split('.').collect();
 
-        let video_id = uuid::Uuid::parse_str(video_id).unwrap();
+    let video_id = match uuid::Uuid::parse_str(parts[0]) {
+        Ok(video_id) => video_id,
+        Err(_) => return Err(format!("No video id in the key {}", uri).into()),
+    };
 
-        VideoPayload {
-            video_id,
-            video_uri: self.s3video_uri,
+    let language = match parts.len() {
+        3 => Some(parts[1].to_string()),
+        _ => None,
+    };
+
+    Ok((video_id, language))
+}
+
+/// Parses the `{"type": ..., "payload": ...}` body shared by every queue message
+pub(crate) fn parse_message(body: &str) -> Result<PayloadType, SyncError> {
+    let v: Value = serde_json::from_str(body)?;
+    let type_field = match v["type"].as_str() {
+        Some(type_field) => type_field,
+        None => return Err("No type field".into()),
+    };
+    if v["payload"].is_null() {
+        return Err("No payload field".into());
+    }
+    let payload = v["payload"].to_string();
+    match type_field {
+        "BatukaVideoRawUpload" => {
+            let payload: S3UploadPayload = serde_json::from_str(&payload)?;
+            return Ok(PayloadType::BatukaVideoRawUpload(payload.try_into()?));
+        }
+        "BatukaSrtTranscriptionUpload" => {
+            let payload: S3SrtPayload = serde_json::from_str(&payload)?;
+            return Ok(PayloadType::BatukaSrtTranscriptionUpload(payload.try_into()?));
+        }
+        "BatukaSrtTranslationUpload" => {
+            let payload: S3SrtPayload = serde_json::from_str(&payload)?;
+            return Ok(PayloadType::BatukaSrtTranslationUpload(payload.try_into()?));
         }
+        "BatukaVideoProcessedUpload" => {
+            let payload: S3UploadPayload = serde_json::from_str(&payload)?;
+            return Ok(PayloadType::BatukaVideoProcessedUpload(payload.try_into()?));
+        }
+        "BatukaDownloadVideo" => {
+            let payload: VideoDownloadPayload = serde_json::from_str(&payload)?;
+            return Ok(PayloadType::BatukaDownloadVideo(payload));
+        }
+        "BatukaCutVideo" => {
+            let payload: VideoCutPayload = serde_json::from_str(&payload)?;
+            return Ok(PayloadType::BatukaCutVideo(payload));
+        }
+
+        _ => Err("Invalid type field".into()),
     }
 }
 
-impl Into<SrtPayload> for S3SrtPayload {
-    fn into(self) -> SrtPayload {
-        let video_id = self
-            .s3srt_uri
-            .split('/')
-            .last()
-            .unwrap()
-            .split('.')
-            .next()
-            .unwrap();
+impl TryFrom<S3UploadPayload> for VideoPayload {
+    type Error = SyncError;
 
-        let video_id = uuid::Uuid::parse_str(video_id).unwrap();
+    fn try_from(payload: S3UploadPayload) -> Result<Self, Self::Error> {
+        let (video_id, language) = parse_uri(&payload.s3video_uri)?;
 
-        SrtPayload {
+        Ok(VideoPayload {
             video_id,
-            srt_uri: self.s3srt_uri,
-        }
+            video_uri: payload.s3video_uri,
+            language,
+        })
+    }
+}
+
+impl TryFrom<S3SrtPayload> for SrtPayload {
+    type Error = SyncError;
+
+    fn try_from(payload: S3SrtPayload) -> Result<Self, Self::Error> {
+        let (video_id, language) = parse_uri(&payload.s3srt_uri)?;
+
+        Ok(SrtPayload {
+            video_id,
+            srt_uri: payload.s3srt_uri,
+            language,
+        })
     }
 }
 
@@ -66,9 +120,74 @@ mod test {
             s3video_uri: uri.clone(),
         };
 
-        let upload_payload: super::VideoPayload = s3_upload_payload.into();
+        let upload_payload: super::VideoPayload = s3_upload_payload.try_into().unwrap();
+
+        assert_eq!(upload_payload.video_uri, uri);
+        assert_eq!(upload_payload.video_id, uuid);
+        assert_eq!(upload_payload.language, None);
+    }
+
+    #[test]
+    fn test_into_upload_payload_with_language() {
+        let uuid = uuid::Uuid::new_v4();
+        let uri = format!("videos/processed/{}.es.mkv", uuid);
+        let s3_upload_payload = super::S3UploadPayload {
+            s3video_uri: uri.clone(),
+        };
+
+        let upload_payload: super::VideoPayload = s3_upload_payload.try_into().unwrap();
 
         assert_eq!(upload_payload.video_uri, uri);
         assert_eq!(upload_payload.video_id, uuid);
+        assert_eq!(upload_payload.language, Some("es".to_string()));
+    }
+
+    #[test]
+    fn test_into_srt_payload_with_language() {
+        let uuid = uuid::Uuid::new_v4();
+        let uri = format!("srt_translations/{}.pt-br.srt", uuid);
+        let s3_srt_payload = super::S3SrtPayload {
+            s3srt_uri: uri.clone(),
+        };
+
+        let srt_payload: super::SrtPayload = s3_srt_payload.try_into().unwrap();
+
+        assert_eq!(srt_payload.srt_uri, uri);
+        assert_eq!(srt_payload.video_id, uuid);
+        assert_eq!(srt_payload.language, Some("pt-br".to_string()));
+    }
+
+    #[test]
+    fn test_payload_round_trip() {
+        use crate::internals::cloud::models::payload::PayloadType;
+
+        let uuid = uuid::Uuid::new_v4();
+        let payload: super::VideoPayload = super::S3UploadPayload {
+            s3video_uri: format!("videos/processed/{}.es.mkv", uuid),
+        }
+        .try_into()
+        .unwrap();
+
+        let json = PayloadType::BatukaVideoProcessedUpload(payload).to_json();
+
+        match PayloadType::from_json(&json).unwrap() {
+            PayloadType::BatukaVideoProcessedUpload(payload) => {
+                assert_eq!(payload.video_id, uuid);
+                assert_eq!(payload.language, Some("es".to_string()));
+            }
+            _ => panic!("Unexpected payload"),
+        }
+    }
+
+    #[test]
+    fn test_parse_message_without_video_id() {
+        let bodies = [
+            r#"{"type": "BatukaVideoRawUpload", "payload": {"s3VideoURI": "videos/raw/video.mkv"}}"#,
+            r#"{"type": "BatukaSrtTranslationUpload", "payload": {"s3SrtURI": "no-slash"}}"#,
+        ];
+
+        for body in bodies {
+            assert!(super::parse_message(body).is_err(), "{}", body);
+        }
     }
 }
@@ -25,7 +25,7 @@ pub struct S3Client {
 
 impl S3Client {
     pub fn new() -> Result<Self, SyncError> {
-        println!("Creating S3 client...");
+        tracing::info!("Creating S3 client...");
         let region = rusoto_core::Region::SaEast1;
         let bucket_name = std::env::var("AWS_BUCKET_NAME")?;
 
@@ -133,7 +133,7 @@ impl BucketClient for S3Client {
             .complete_multipart_upload(complete_request)
             .await?;
 
-        println!("File uploaded successfully");
+        tracing::info!("File uploaded successfully");
 
         Ok(())
     }
@@ -1,18 +1,21 @@
 use crate::{
     internals::cloud::{
-        models::payload::{PayloadType, VideoCutPayload, VideoDownloadPayload},
+        models::payload::PayloadType,
         traits::{QueueClient, QueueMessage},
     },
     SyncError,
 };
 use async_trait::async_trait;
+use chrono::NaiveDateTime;
 use rusoto_sqs::{
     ChangeMessageVisibilityRequest, DeleteMessageRequest, Message, ReceiveMessageRequest,
     SendMessageRequest, Sqs,
 };
-use serde_json::Value;
 
-use super::payload::{S3SrtPayload, S3UploadPayload};
+use super::payload::parse_message;
+
+const RECEIVE_COUNT_ATTRIBUTE: &str = "ApproximateReceiveCount";
+const SENT_TIMESTAMP_ATTRIBUTE: &str = "SentTimestamp";
 
 #[derive(Clone)]
 pub struct SQSClient {
@@ -22,7 +25,7 @@ pub struct SQSClient {
 
 impl SQSClient {
     pub fn new(queue_url: String) -> Self {
-        println!("Creating SQS client...");
+        tracing::info!("Creating SQS client...");
         let region = rusoto_core::Region::SaEast1;
         let client = rusoto_sqs::SqsClient::new(region);
         SQSClient { client, queue_url }
@@ -51,43 +54,19 @@ impl QueueMessage for Message {
             Some(body) => body,
             None => return Err("No body found".into()),
         };
-        let v: Value = serde_json::from_str(body)?;
-        let type_field = match v["type"].as_str() {
-            Some(type_field) => type_field,
-            None => return Err("No type field".into()),
-        };
-        if v["payload"].is_null() {
-            return Err("No payload field".into());
-        }
-        let payload = v["payload"].to_string();
-        match type_field {
-            "BatukaVideoRawUpload" => {
-                let payload: S3UploadPayload = serde_json::from_str(&payload)?;
-                return Ok(PayloadType::BatukaVideoRawUpload(payload.into()));
-            }
-            "BatukaSrtTranscriptionUpload" => {
-                let payload: S3SrtPayload = serde_json::from_str(&payload)?;
-                return Ok(PayloadType::BatukaSrtTranscriptionUpload(payload.into()));
-            }
-            "BatukaSrtTranslationUpload" => {
-                let payload: S3SrtPayload = serde_json::from_str(&payload)?;
-                return Ok(PayloadType::BatukaSrtTranslationUpload(payload.into()));
-            }
-            "BatukaVideoProcessedUpload" => {
-                let payload: S3UploadPayload = serde_json::from_str(&payload)?;
-                return Ok(PayloadType::BatukaVideoProcessedUpload(payload.into()));
-            }
-            "BatukaDownloadVideo" => {
-                let payload: VideoDownloadPayload = serde_json::from_str(&payload)?;
-                return Ok(PayloadType::BatukaDownloadVideo(payload));
-            }
-            "BatukaCutVideo" => {
-                let payload: VideoCutPayload = serde_json::from_str(&payload)?;
-                return Ok(PayloadType::BatukaCutVideo(payload));
-            }
+        return parse_message(body);
+    }
 
-            _ => Err("Invalid type field".into()),
-        }
+    fn receive_count(&self) -> Option<i64> {
+        let attributes = self.attributes.as_ref()?;
+        let count = attributes.get(RECEIVE_COUNT_ATTRIBUTE)?;
+        count.parse().ok()
+    }
+
+    fn sent_at(&self) -> Option<NaiveDateTime> {
+        let attributes = self.attributes.as_ref()?;
+        let timestamp = attributes.get(SENT_TIMESTAMP_ATTRIBUTE)?;
+        NaiveDateTime::from_timestamp_millis(timestamp.parse().ok()?)
     }
 }
 
@@ -100,6 +79,10 @@ impl QueueClient for SQSClient {
             max_number_of_messages: Some(10),
             wait_time_seconds: Some(20),
             visibility_timeout: Some(100),
+            attribute_names: Some(vec![
+                RECEIVE_COUNT_ATTRIBUTE.to_string(),
+                SENT_TIMESTAMP_ATTRIBUTE.to_string(),
+            ]),
             ..Default::default()
         };
 
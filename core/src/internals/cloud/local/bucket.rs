@@ -0,0 +1,285 @@
+use std::path::{Component, Path, PathBuf};
+
+use async_trait::async_trait;
+use serde_json::json;
+
+use crate::{
+    internals::{
+        cloud::{
+            aws::payload::{parse_message, S3SrtPayload, S3UploadPayload},
+            models::payload::PayloadType,
+            traits::BucketClient,
+        },
+        ServiceProvider,
+    },
+    SyncError,
+};
+
+use super::signature;
+
+const DEFAULT_DOWNLOAD_EXPIRATION: u64 = 60 * 60 * 24 * 7;
+
+/// Stores objects under a directory, using the object key as the relative path.
+/// Uploads have the same events the S3 bucket notifications send, which the
+/// uploaders write to the outbox themselves
+#[derive(Clone)]
+pub struct LocalBucketClient {
+    root: PathBuf,
+    base_url: String,
+    secret: String,
+}
+
+impl LocalBucketClient {
+    pub fn new(root: PathBuf, base_url: String, secret: String) -> Self {
+        tracing::info!("Creating local bucket client at {}...", root.display());
+        Self {
+            root,
+            base_url,
+            secret,
+        }
+    }
+
+    pub fn from_env() -> Result<Self, SyncError> {
+        let root = std::env::var("LOCAL_STORAGE_PATH")?;
+        let base_url = std::env::var("LOCAL_STORAGE_URL")?;
+        let secret = std::env::var("LOCAL_STORAGE_SECRET")?;
+
+        return Ok(Self::new(PathBuf::from(root), base_url, secret));
+    }
+
+    /// Resolves the path of an object, rejecting keys that would escape the storage directory
+    pub fn object_path(&self, file_uri: &str) -> Result<PathBuf, SyncError> {
+        let key = Path::new(file_uri);
+        let is_valid = key.components().count() > 0
+            && key.components().all(|c| matches!(c, Component::Normal(_)));
+
+        if !is_valid {
+            return Err(format!("Invalid object key: {}", file_uri).into());
+        }
+
+        return Ok(self.root.join(key));
+    }
+
+    pub fn signed_url(&self, method: &str, file_uri: &str, expires_in: u64) -> String {
+        let expires = chrono::Utc::now().timestamp() + expires_in as i64;
+        let signature = signature::sign(&self.secret, method, file_uri, expires);
+
+        format!(
+            "{}/{}?expires={}&signature={}",
+            self.base_url.trim_end_matches('/'),
+            file_uri,
+            expires,
+            signature
+        )
+    }
+
+    pub fn verify(&self, method: &str, file_uri: &str, expires: i64, signature: &str) -> bool {
+        let now = chrono::Utc::now().timestamp();
+        signature::verify(&self.secret, method, file_uri, expires, signature, now)
+    }
+
+    async fn prepare_path(&self, file_uri: &str) -> Result<PathBuf, SyncError> {
+        let path = self.object_path(file_uri)?;
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+
+        Ok(path)
+    }
+}
+
+/// Mirrors the messages the S3 notifications send for each folder of the bucket
+pub fn upload_event(file_uri: &str) -> Option<String> {
+    let video_payload = || {
+        json!(S3UploadPayload {
+            s3video_uri: file_uri.to_string(),
+        })
+    };
+    let srt_payload = || {
+        json!(S3SrtPayload {
+            s3srt_uri: file_uri.to_string(),
+        })
+    };
+
+    let (type_field, payload) = if file_uri.starts_with("videos/raw/") {
+        ("BatukaVideoRawUpload", video_payload())
+    } else if file_uri.starts_with("videos/processed/") {
+        ("BatukaVideoProcessedUpload", video_payload())
+    } else if file_uri.starts_with("srt_transcriptions/") {
+        ("BatukaSrtTranscriptionUpload", srt_payload())
+    } else if file_uri.starts_with("srt_translations/") {
+        ("BatukaSrtTranslationUpload", srt_payload())
+    } else {
+        return None;
+    };
+
+    let body = json!({"type": type_field, "payload": payload});
+    Some(body.to_string())
+}
+
+impl ServiceProvider for LocalBucketClient {
+    fn id(&self) -> i32 {
+        1
+    }
+}
+
+#[async_trait]
+impl BucketClient for LocalBucketClient {
+    async fn create_signed_upload_url(&self, expires_in: u16) -> Result<String, SyncError> {
+        let uuid = uuid::Uuid::new_v4().to_string();
+        let file_name = format!("videos/raw/{}.mkv", uuid);
+        return self
+            .create_signed_upload_url_with_uri(&file_name, expires_in)
+            .await;
+    }
+
+    async fn create_signed_upload_url_with_uri(
+        &self,
+        file_uri: &str,
+        expires_in: u16,
+    ) -> Result<String, SyncError> {
+        self.object_path(file_uri)?;
+        return Ok(self.signed_url("PUT", file_uri, expires_in as u64));
+    }
+
+    async fn create_signed_download_url(
+        &self,
+        file_uri: &str,
+        expires_in: Option<u16>,
+    ) -> Result<String, SyncError> {
+        self.object_path(file_uri)?;
+        let expires_in = match expires_in {
+            Some(expires_in) => expires_in as u64,
+            None => DEFAULT_DOWNLOAD_EXPIRATION,
+        };
+        return Ok(self.signed_url("GET", file_uri, expires_in));
+    }
+
+    fn upload_event(&self, file_uri: &str) -> Option<PayloadType> {
+        let body = upload_event(file_uri)?;
+        return parse_message(&body).ok();
+    }
+
+    async fn upload_file(&self, file_uri: &str, file: Vec<u8>) -> Result<(), SyncError> {
+        let path = self.prepare_path(file_uri).await?;
+        tokio::fs::write(path, file).await?;
+        Ok(())
+    }
+
+    async fn upload_file_from_path(
+        &self,
+        file_uri: &str,
+        file_path: &str,
+    ) -> Result<(), SyncError> {
+        let path = self.prepare_path(file_uri).await?;
+        tokio::fs::copy(file_path, path).await?;
+        Ok(())
+    }
+
+    async fn download_file(&self, file_uri: &str) -> Result<Vec<u8>, SyncError> {
+        let path = self.object_path(file_uri)?;
+        let file = tokio::fs::read(path).await?;
+        Ok(file)
+    }
+
+    async fn download_file_to_path(
+        &self,
+        file_uri: &str,
+        destination_path: &str,
+    ) -> Result<(), SyncError> {
+        let path = self.object_path(file_uri)?;
+        tokio::fs::copy(path, destination_path).await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::PathBuf;
+
+    use crate::internals::cloud::{aws::payload::parse_message, models::payload::PayloadType};
+
+    use super::{upload_event, BucketClient, LocalBucketClient};
+
+    fn client() -> (LocalBucketClient, PathBuf) {
+        let root = std::env::temp_dir().join(uuid::Uuid::new_v4().to_string());
+        let client = LocalBucketClient::new(
+            root.clone(),
+            "http://localhost:8080/storage/local".to_string(),
+            "secret".to_string(),
+        );
+        (client, root)
+    }
+
+    #[test]
+    fn test_upload_event_is_parseable() {
+        let id = uuid::Uuid::new_v4();
+        let body = upload_event(&format!("srt_translations/{}.es.srt", id)).unwrap();
+
+        match parse_message(&body).unwrap() {
+            PayloadType::BatukaSrtTranslationUpload(payload) => {
+                assert_eq!(payload.video_id, id);
+                assert_eq!(payload.language, Some("es".to_string()));
+            }
+            _ => panic!("Unexpected payload"),
+        }
+
+        assert!(upload_event("other/file.txt").is_none());
+    }
+
+    #[test]
+    fn test_object_path_rejects_escaping_keys() {
+        let (client, root) = client();
+
+        assert_eq!(
+            client.object_path("videos/raw/1.mkv").unwrap(),
+            root.join("videos/raw/1.mkv")
+        );
+        assert!(client.object_path("../etc/passwd").is_err());
+        assert!(client.object_path("/etc/passwd").is_err());
+        assert!(client.object_path("videos/../../passwd").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_upload_and_download() {
+        let (client, root) = client();
+        let id = uuid::Uuid::new_v4();
+        let file_uri = format!("videos/processed/{}.mkv", id);
+
+        client
+            .upload_file(&file_uri, b"video".to_vec())
+            .await
+            .unwrap();
+
+        let file = client.download_file(&file_uri).await.unwrap();
+        assert_eq!(file, b"video".to_vec());
+
+        match client.upload_event(&file_uri) {
+            Some(PayloadType::BatukaVideoProcessedUpload(payload)) => {
+                assert_eq!(payload.video_id, id);
+            }
+            _ => panic!("Unexpected event"),
+        }
+
+        std::fs::remove_dir_all(root).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_signed_url() {
+        let (client, _) = client();
+
+        let url = client
+            .create_signed_download_url("videos/raw/1.mkv", Some(60))
+            .await
+            .unwrap();
+
+        let query = url.split('?').last().unwrap();
+        let params: Vec<&str> = query.split('&').collect();
+        let expires: i64 = params[0].trim_start_matches("expires=").parse().unwrap();
+        let signature = params[1].trim_start_matches("signature=");
+
+        assert!(url.starts_with("http://localhost:8080/storage/local/videos/raw/1.mkv?"));
+        assert!(client.verify("GET", "videos/raw/1.mkv", expires, signature));
+        assert!(!client.verify("PUT", "videos/raw/1.mkv", expires, signature));
+    }
+}
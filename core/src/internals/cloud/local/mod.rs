@@ -0,0 +1,50 @@
+use sqlx::PgPool;
+
+use crate::{internals::ServiceProvider, SyncError};
+
+use self::{bucket::LocalBucketClient, queue::LocalQueueClient};
+
+use super::traits::CloudService;
+
+pub mod bucket;
+pub mod queue;
+pub mod signature;
+
+/// Runs the pipeline without AWS: objects live on the filesystem and
+/// messages on the database
+#[derive(Clone)]
+pub struct LocalCloudService {
+    pub bucket_client: LocalBucketClient,
+    pub queue_client: LocalQueueClient,
+}
+
+impl LocalCloudService {
+    pub fn new(pool: PgPool) -> Result<Self, SyncError> {
+        let bucket_client = LocalBucketClient::from_env()?;
+        let queue_client = LocalQueueClient::new(pool);
+
+        return Ok(Self {
+            bucket_client,
+            queue_client,
+        });
+    }
+}
+
+impl ServiceProvider for LocalCloudService {
+    fn id(&self) -> i32 {
+        return 1;
+    }
+}
+
+impl CloudService for LocalCloudService {
+    type BC = LocalBucketClient;
+    type QC = LocalQueueClient;
+
+    fn bucket_client(&self) -> &Self::BC {
+        &self.bucket_client
+    }
+
+    fn queue_client(&self) -> &Self::QC {
+        &self.queue_client
+    }
+}
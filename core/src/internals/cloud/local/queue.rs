@@ -0,0 +1,208 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    database::{models::queue_message::QueuedMessage, queries},
+    internals::cloud::{
+        aws::payload::parse_message,
+        models::payload::PayloadType,
+        traits::{QueueClient, QueueMessage},
+        MAX_DELIVERIES,
+    },
+    SyncError,
+};
+
+const MAX_NUMBER_OF_MESSAGES: i64 = 10;
+const VISIBILITY_TIMEOUT: i32 = 100;
+const SEND_DELAY: i32 = 2; // delay to give db trx time to commit
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+const DEFAULT_MAX_RECEIVE_COUNT: i32 = MAX_DELIVERIES as i32;
+
+/// Queue backed by the `queue_messages` table, following the SQS semantics:
+/// received messages stay hidden until their visibility timeout ends or they are deleted,
+/// and are moved to the dead letters after `max_receive_count` receives
+#[derive(Clone)]
+pub struct LocalQueueClient {
+    pool: PgPool,
+    max_receive_count: i32,
+}
+
+impl LocalQueueClient {
+    pub fn new(pool: PgPool) -> Self {
+        tracing::info!("Creating local queue client...");
+        let max_receive_count = match std::env::var("QUEUE_MAX_RECEIVE_COUNT") {
+            Ok(count) => count.parse().unwrap_or(DEFAULT_MAX_RECEIVE_COUNT),
+            Err(_) => DEFAULT_MAX_RECEIVE_COUNT,
+        };
+
+        Self::with_max_receive_count(pool, max_receive_count)
+    }
+
+    pub fn with_max_receive_count(pool: PgPool, max_receive_count: i32) -> Self {
+        Self {
+            pool,
+            max_receive_count,
+        }
+    }
+}
+
+impl QueueMessage for QueuedMessage {
+    fn get_handle(&self) -> String {
+        match &self.receipt_handle {
+            Some(handle) => handle.to_string(),
+            None => String::new(),
+        }
+    }
+
+    fn get_message(&self) -> String {
+        self.body.clone()
+    }
+
+    fn to_payload(&self) -> Result<PayloadType, SyncError> {
+        parse_message(&self.body)
+    }
+
+    fn receive_count(&self) -> Option<i64> {
+        Some(self.receive_count as i64)
+    }
+
+    fn sent_at(&self) -> Option<NaiveDateTime> {
+        Some(self.created_at)
+    }
+}
+
+fn receipt_handle(message: &QueuedMessage) -> Result<Uuid, SyncError> {
+    match message.receipt_handle {
+        Some(receipt_handle) => Ok(receipt_handle),
+        None => Err("No receipt handle found".into()),
+    }
+}
+
+#[async_trait]
+impl QueueClient for LocalQueueClient {
+    type M = QueuedMessage;
+
+    async fn receive_message(&self) -> Result<Option<Vec<Self::M>>, SyncError> {
+        let dead_lettered =
+            queries::queue::dead_letter_exhausted(&self.pool, self.max_receive_count).await?;
+        if dead_lettered > 0 {
+            tracing::info!("Moved {} messages to the dead letters", dead_lettered);
+        }
+
+        let messages = queries::queue::receive(
+            &self.pool,
+            MAX_NUMBER_OF_MESSAGES,
+            VISIBILITY_TIMEOUT,
+            self.max_receive_count,
+        )
+        .await?;
+
+        if messages.is_empty() {
+            tokio::time::sleep(POLL_INTERVAL).await;
+            return Ok(None);
+        }
+
+        return Ok(Some(messages));
+    }
+
+    async fn send_message(&self, payload: PayloadType) -> Result<(), SyncError> {
+        queries::queue::send(&self.pool, &payload.to_json(), SEND_DELAY).await?;
+        return Ok(());
+    }
+
+    async fn delete_message(&self, message: Self::M) -> Result<(), SyncError> {
+        let receipt_handle = receipt_handle(&message)?;
+        queries::queue::delete(&self.pool, &receipt_handle).await?;
+        return Ok(());
+    }
+
+    async fn change_message_visibility(
+        &self,
+        message: &Self::M,
+        visibility_timeout: usize,
+    ) -> Result<(), SyncError> {
+        let receipt_handle = receipt_handle(message)?;
+        queries::queue::change_visibility(&self.pool, &receipt_handle, visibility_timeout as i32)
+            .await?;
+        return Ok(());
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use sqlx::PgPool;
+
+    use crate::{
+        database::queries,
+        internals::cloud::{
+            models::payload::{PayloadType, VideoDownloadPayload},
+            traits::{QueueClient, QueueMessage},
+        },
+    };
+
+    use super::LocalQueueClient;
+
+    async fn send_visible(pool: &PgPool) {
+        let payload = PayloadType::BatukaDownloadVideo(VideoDownloadPayload {
+            original_video_id: 1,
+            video_ids: vec![uuid::Uuid::new_v4()],
+        });
+        queries::queue::send(pool, &payload.to_json(), 0)
+            .await
+            .unwrap();
+    }
+
+    #[sqlx::test(migrations = "../migrations")]
+    async fn test_receive_and_parse(pool: PgPool) {
+        let client = LocalQueueClient::with_max_receive_count(pool.clone(), 2);
+        send_visible(&pool).await;
+
+        let messages = client.receive_message().await.unwrap().unwrap();
+
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].receive_count(), Some(1));
+        assert!(matches!(
+            messages[0].to_payload().unwrap(),
+            PayloadType::BatukaDownloadVideo(_)
+        ));
+    }
+
+    #[sqlx::test(migrations = "../migrations")]
+    async fn test_dead_letter_after_max_receives(pool: PgPool) {
+        let client = LocalQueueClient::with_max_receive_count(pool.clone(), 2);
+        send_visible(&pool).await;
+
+        for _ in 0..2 {
+            let messages = client.receive_message().await.unwrap().unwrap();
+            client
+                .change_message_visibility(&messages[0], 0)
+                .await
+                .unwrap();
+        }
+
+        let messages = client.receive_message().await.unwrap();
+        assert!(messages.is_none());
+
+        let dead_letters = queries::queue::find_dead_letters(&pool).await.unwrap();
+        assert_eq!(dead_letters.len(), 1);
+        assert_eq!(dead_letters[0].receive_count, 2);
+    }
+
+    #[sqlx::test(migrations = "../migrations")]
+    async fn test_delete_message(pool: PgPool) {
+        let client = LocalQueueClient::with_max_receive_count(pool.clone(), 2);
+        send_visible(&pool).await;
+
+        let mut messages = client.receive_message().await.unwrap().unwrap();
+        let message = messages.pop().unwrap();
+        client.change_message_visibility(&message, 0).await.unwrap();
+        client.delete_message(message).await.unwrap();
+
+        let messages = client.receive_message().await.unwrap();
+        assert!(messages.is_none());
+    }
+}
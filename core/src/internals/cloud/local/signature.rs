@@ -0,0 +1,114 @@
+use ring::hmac;
+
+fn message(method: &str, file_uri: &str, expires: i64) -> String {
+    format!("{}\n{}\n{}", method.to_uppercase(), file_uri, expires)
+}
+
+/// Signs the method, object key and expiration (unix seconds) of a storage url
+pub fn sign(secret: &str, method: &str, file_uri: &str, expires: i64) -> String {
+    let key = hmac::Key::new(hmac::HMAC_SHA256, secret.as_bytes());
+    let tag = hmac::sign(&key, message(method, file_uri, expires).as_bytes());
+
+    tag.as_ref()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect::<String>()
+}
+
+pub fn verify(
+    secret: &str,
+    method: &str,
+    file_uri: &str,
+    expires: i64,
+    signature: &str,
+    now: i64,
+) -> bool {
+    if expires < now {
+        return false;
+    }
+
+    let tag = match decode_hex(signature) {
+        Some(tag) => tag,
+        None => return false,
+    };
+
+    let key = hmac::Key::new(hmac::HMAC_SHA256, secret.as_bytes());
+    hmac::verify(&key, message(method, file_uri, expires).as_bytes(), &tag).is_ok()
+}
+
+fn decode_hex(value: &str) -> Option<Vec<u8>> {
+    if value.len() % 2 != 0 {
+        return None;
+    }
+
+    (0..value.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(value.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::{sign, verify};
+
+    #[test]
+    fn test_verify_signed_url() {
+        let signature = sign("secret", "GET", "videos/raw/1.mkv", 100);
+        assert!(verify(
+            "secret",
+            "GET",
+            "videos/raw/1.mkv",
+            100,
+            &signature,
+            50
+        ));
+    }
+
+    #[test]
+    fn test_verify_expired() {
+        let signature = sign("secret", "GET", "videos/raw/1.mkv", 100);
+        assert!(!verify(
+            "secret",
+            "GET",
+            "videos/raw/1.mkv",
+            100,
+            &signature,
+            101
+        ));
+    }
+
+    #[test]
+    fn test_verify_other_method_or_key() {
+        let signature = sign("secret", "GET", "videos/raw/1.mkv", 100);
+        assert!(!verify(
+            "secret",
+            "PUT",
+            "videos/raw/1.mkv",
+            100,
+            &signature,
+            50
+        ));
+        assert!(!verify(
+            "secret",
+            "GET",
+            "videos/raw/2.mkv",
+            100,
+            &signature,
+            50
+        ));
+    }
+
+    #[test]
+    fn test_verify_wrong_secret() {
+        let signature = sign("other", "GET", "videos/raw/1.mkv", 100);
+        assert!(!verify(
+            "secret",
+            "GET",
+            "videos/raw/1.mkv",
+            100,
+            &signature,
+            50
+        ));
+        assert!(!verify("secret", "GET", "videos/raw/1.mkv", 100, "zz", 50));
+    }
+}
@@ -1,11 +1,29 @@
-use self::aws::AwsCloudService;
+use sqlx::PgPool;
 
 pub mod aws;
+pub mod local;
 pub mod models;
 pub mod traits;
 
-pub fn default_cloud_service() -> AwsCloudService {
+/// Deliveries of a message before it is given up on: the workers save it as failed
+/// and the local queue moves it to the dead letters
+pub const MAX_DELIVERIES: i64 = 3;
+
+/// The cloud service used by the binaries, the `local` feature swaps AWS for the filesystem and database
+#[cfg(not(feature = "local"))]
+pub type DefaultCloudService = aws::AwsCloudService;
+#[cfg(feature = "local")]
+pub type DefaultCloudService = local::LocalCloudService;
+
+#[cfg(not(feature = "local"))]
+pub fn default_cloud_service(_pool: &PgPool) -> DefaultCloudService {
     let queue_url = std::env::var("AWS_QUEUE_URL").unwrap();
     let cloud_service = aws::AwsCloudService::new(queue_url).unwrap();
     return cloud_service;
 }
+
+#[cfg(feature = "local")]
+pub fn default_cloud_service(pool: &PgPool) -> DefaultCloudService {
+    let cloud_service = local::LocalCloudService::new(pool.clone()).unwrap();
+    return cloud_service;
+}
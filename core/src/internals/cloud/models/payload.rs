@@ -2,12 +2,16 @@ use serde::{Deserialize, Serialize};
 use serde_json::json;
 use uuid::Uuid;
 
-use crate::database::models::video_storage::VideoFormat;
+use crate::{
+    database::models::video_storage::VideoFormat, internals::cloud::aws::payload::parse_message,
+    SyncError,
+};
 
 #[derive(Debug, Serialize)]
 pub struct VideoPayload {
     pub video_uri: String,
     pub video_id: Uuid,
+    pub language: Option<String>,
 }
 
 impl VideoPayload {
@@ -20,6 +24,7 @@ impl VideoPayload {
 pub struct SrtPayload {
     pub video_id: Uuid,
     pub srt_uri: String,
+    pub language: Option<String>,
 }
 
 impl SrtPayload {
@@ -58,21 +63,43 @@ pub enum PayloadType {
 }
 
 impl PayloadType {
+    pub const VIDEO_RAW_UPLOAD: &'static str = "BatukaVideoRawUpload";
+    pub const VIDEO_PROCESSED_UPLOAD: &'static str = "BatukaVideoProcessedUpload";
+    pub const SRT_TRANSCRIPTION_UPLOAD: &'static str = "BatukaSrtTranscriptionUpload";
+    pub const SRT_TRANSLATION_UPLOAD: &'static str = "BatukaSrtTranslationUpload";
+    pub const DOWNLOAD_VIDEO: &'static str = "BatukaDownloadVideo";
+    pub const CUT_VIDEO: &'static str = "BatukaCutVideo";
+
+    /// The name of every payload, as given by `name`
+    pub const NAMES: [&'static str; 6] = [
+        Self::VIDEO_RAW_UPLOAD,
+        Self::VIDEO_PROCESSED_UPLOAD,
+        Self::SRT_TRANSCRIPTION_UPLOAD,
+        Self::SRT_TRANSLATION_UPLOAD,
+        Self::DOWNLOAD_VIDEO,
+        Self::CUT_VIDEO,
+    ];
+
+    /// Upload payloads are written like the bucket notifications, so they can be parsed back
     pub fn to_json(&self) -> String {
         match self {
             PayloadType::BatukaVideoRawUpload(payload) => {
+                let payload = json!({"s3VideoURI": payload.video_uri});
                 let json = json!({"type": "BatukaVideoRawUpload", "payload": payload});
                 return json.to_string();
             }
             PayloadType::BatukaVideoProcessedUpload(payload) => {
+                let payload = json!({"s3VideoURI": payload.video_uri});
                 let json = json!({"type": "BatukaVideoProcessedUpload", "payload": payload});
                 return json.to_string();
             }
             PayloadType::BatukaSrtTranscriptionUpload(payload) => {
+                let payload = json!({"s3SrtURI": payload.srt_uri});
                 let json = json!({"type": "BatukaSrtTranscriptionUpload", "payload": payload});
                 return json.to_string();
             }
             PayloadType::BatukaSrtTranslationUpload(payload) => {
+                let payload = json!({"s3SrtURI": payload.srt_uri});
                 let json = json!({"type": "BatukaSrtTranslationUpload", "payload": payload});
                 return json.to_string();
             }
@@ -87,6 +114,22 @@ impl PayloadType {
         }
     }
 
+    /// The `type` field of the message body
+    pub fn name(&self) -> &'static str {
+        match self {
+            PayloadType::BatukaVideoRawUpload(_) => Self::VIDEO_RAW_UPLOAD,
+            PayloadType::BatukaVideoProcessedUpload(_) => Self::VIDEO_PROCESSED_UPLOAD,
+            PayloadType::BatukaSrtTranscriptionUpload(_) => Self::SRT_TRANSCRIPTION_UPLOAD,
+            PayloadType::BatukaSrtTranslationUpload(_) => Self::SRT_TRANSLATION_UPLOAD,
+            PayloadType::BatukaDownloadVideo(_) => Self::DOWNLOAD_VIDEO,
+            PayloadType::BatukaCutVideo(_) => Self::CUT_VIDEO,
+        }
+    }
+
+    pub fn from_json(body: &str) -> Result<Self, SyncError> {
+        parse_message(body)
+    }
+
     pub fn video_ids(&self) -> Vec<Uuid> {
         match self {
             PayloadType::BatukaVideoRawUpload(payload) => vec![payload.video_id],
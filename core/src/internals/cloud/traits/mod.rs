@@ -1,6 +1,7 @@
 use std::fmt::Debug;
 
 use async_trait::async_trait;
+use chrono::NaiveDateTime;
 
 use crate::{internals::ServiceProvider, SyncError};
 
@@ -34,6 +35,11 @@ pub trait BucketClient: ServiceProvider + Sync {
         file_uri: &str,
         destination_path: &str,
     ) -> Result<(), SyncError>;
+
+    /// The event of an upload, for buckets that don't send their own notifications
+    fn upload_event(&self, _file_uri: &str) -> Option<PayloadType> {
+        None
+    }
 }
 
 #[async_trait]
@@ -53,6 +59,14 @@ pub trait QueueMessage {
     fn get_message(&self) -> String;
     fn get_handle(&self) -> String;
     fn to_payload(&self) -> Result<PayloadType, SyncError>;
+    /// How many times the message was delivered, including this one, when the queue tracks it
+    fn receive_count(&self) -> Option<i64> {
+        None
+    }
+    /// When the message was sent, in UTC, when the queue tracks it
+    fn sent_at(&self) -> Option<NaiveDateTime> {
+        None
+    }
 }
 
 pub trait CloudService: ServiceProvider {
@@ -1,20 +1,23 @@
 use crate::{
-    database::models::video::with::VideoWithStorage,
+    database::models::{
+        subtitles_style::SubtitlesStyle,
+        video::{subtitles_mode::SubtitlesMode, with::VideoWithStorage},
+    },
     internals::{cloud::traits::BucketClient, ServiceProvider},
-    util::fs::create_temp_dir,
+    util::{fs::create_temp_dir, progress::OnProgress},
 };
 
-use super::traits::SubtitlerClient;
+use super::traits::{SubtitleTrack, SubtitlerClient};
 use async_trait::async_trait;
 mod util;
-use crate::util::ffmpeg::subtitle_video_to_file;
+use crate::util::ffmpeg::{mux_subtitles_to_file, subtitle_video_to_file, SubtitleStream};
 
 #[derive(Clone)]
 pub struct LocalClient;
 
 impl LocalClient {
     pub fn new() -> Self {
-        println!("Creating LocalClient...");
+        tracing::info!("Creating LocalClient...");
         Self {}
     }
 }
@@ -34,26 +37,84 @@ impl<BC: BucketClient> SubtitlerClient<BC> for LocalClient {
     async fn subtitle(
         &self,
         video: &VideoWithStorage,
+        tracks: &[SubtitleTrack],
+        mode: SubtitlesMode,
+        style: Option<&SubtitlesStyle>,
         bucket_client: &BC,
+        on_progress: OnProgress<'_>,
     ) -> Result<String, Box<dyn std::error::Error + Sync + Send>> {
         let video_id = video.video.id.to_string();
         let temp_dir = create_temp_dir()?;
         let temp_file_paths =
-            util::write_to_temp_files(bucket_client, &temp_dir, &video_id).await?;
+            util::write_to_temp_files(bucket_client, &temp_dir, &video_id, tracks).await?;
 
-        match subtitle_video_to_file(
-            &temp_file_paths[0],
-            &temp_file_paths[1],
-            &temp_file_paths[2],
-        ) {
+        // the output path is only written by ffmpeg
+        let mut inputs = temp_file_paths.clone();
+        let output_path = inputs.remove(1);
+        let subtitle_paths = inputs[1..].to_vec();
+
+        for subtitle_path in &subtitle_paths {
+            match util::check_subtitles(subtitle_path) {
+                Ok(_) => {}
+                Err(e) => {
+                    util::delete_temp_files(inputs)?;
+                    return Err(e);
+                }
+            };
+        }
+
+        // the soft tracks keep the look the player gives them
+        let burned_path = match style {
+            Some(style) if mode != SubtitlesMode::Soft => {
+                match util::write_styled_subtitles(&subtitle_paths[0], &inputs[0], style) {
+                    Ok(path) => {
+                        inputs.push(path.clone());
+                        path
+                    }
+                    Err(e) => {
+                        util::delete_temp_files(inputs)?;
+                        return Err(e);
+                    }
+                }
+            }
+            _ => subtitle_paths[0].clone(),
+        };
+
+        let result = match mode {
+            SubtitlesMode::Burned => {
+                subtitle_video_to_file(&inputs[0], &burned_path, &output_path, on_progress)
+            }
+            _ => {
+                let paths: Vec<&str> = subtitle_paths
+                    .iter()
+                    .map(|path| path.to_str().unwrap())
+                    .collect();
+                let streams: Vec<SubtitleStream> = tracks
+                    .iter()
+                    .zip(paths)
+                    .map(|(track, path)| SubtitleStream {
+                        path,
+                        language: track.iso_639_2(),
+                    })
+                    .collect();
+                let burned = match mode {
+                    SubtitlesMode::Both => Some(&burned_path),
+                    _ => None,
+                };
+
+                mux_subtitles_to_file(&inputs[0], &streams, burned, &output_path, on_progress)
+            }
+        };
+
+        match result {
             Ok(_) => {}
             Err(e) => {
-                util::delete_temp_files(temp_file_paths)?;
+                inputs.push(output_path);
+                util::delete_temp_files(inputs)?;
                 return Err(e);
             }
         };
-        let to_delete = temp_file_paths[0..2].to_vec();
-        util::delete_temp_files(to_delete)?;
-        Ok(temp_file_paths[2].to_str().unwrap().to_string())
+        util::delete_temp_files(inputs)?;
+        Ok(output_path.to_str().unwrap().to_string())
     }
 }
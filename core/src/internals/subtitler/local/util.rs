@@ -1,42 +1,110 @@
 use std::path::PathBuf;
 
-use crate::internals::cloud::traits::BucketClient;
+use crate::{
+    database::models::subtitles_style::SubtitlesStyle,
+    internals::{cloud::traits::BucketClient, subtitler::traits::SubtitleTrack},
+    util::{
+        ffmpeg::ffprobe,
+        subtitles::{Document, Format, Style},
+    },
+};
 
+/// Returns the paths of the video, the output and then of each track, in order
 pub async fn write_to_temp_files<BC: BucketClient + Sync>(
     bucket_client: &BC,
     temp_dir: &PathBuf,
     id: &str,
+    tracks: &[SubtitleTrack],
 ) -> Result<Vec<PathBuf>, Box<dyn std::error::Error + Sync + Send>> {
-    let video_path = temp_dir.join(format!("input_{}.{}", id, "mkv"));
-    let srt_path = temp_dir.join(format!("{}.{}", id, "srt"));
-    let output_path = temp_dir.join(format!("output_{}.{}", id, "mkv"));
+    let srt_uri = match tracks.first() {
+        Some(track) => track.uri.as_str(),
+        None => return Err("No subtitles to burn".into()),
+    };
 
-    let video_uri = format!("videos/raw/{}.{}", id, "mkv"); // for now, we only support mkv,refactor later
-    let srt_uri = format!("srt_translations/{}.srt", id);
+    // the srt name carries the language, so outputs of the same video don't collide
+    let srt_name = srt_uri.split('/').last().unwrap_or(id);
+    let srt_name = match srt_name.rsplit_once('.') {
+        Some((name, _)) => name,
+        None => srt_name,
+    };
+
+    let video_path = temp_dir.join(format!("input_{}.{}", srt_name, "mkv"));
+    let output_path = temp_dir.join(format!("output_{}.{}", srt_name, "mkv"));
 
-    let mut temp_file_paths = Vec::new();
+    let video_uri = format!("videos/raw/{}.{}", id, "mkv"); // for now, we only support mkv,refactor later
 
     bucket_client
         .download_file_to_path(&video_uri, video_path.to_str().unwrap())
         .await?;
-    let result = bucket_client
-        .download_file_to_path(&srt_uri, srt_path.to_str().unwrap())
-        .await;
 
-    match result {
-        Ok(_) => {}
+    let mut temp_file_paths = vec![video_path, output_path];
+
+    for (index, track) in tracks.iter().enumerate() {
+        // ffmpeg reads the subtitles by their extension
+        let format = Format::from_path(&track.uri).unwrap_or(Format::Srt);
+        let track_path = match index {
+            0 => temp_dir.join(format!("{}.{}", srt_name, format.extension())),
+            _ => temp_dir.join(format!("{}.{}.{}", srt_name, index, format.extension())),
+        };
+
+        let result = bucket_client
+            .download_file_to_path(&track.uri, track_path.to_str().unwrap())
+            .await;
+
+        match result {
+            Ok(_) => temp_file_paths.push(track_path),
+            Err(e) => {
+                // the output was not written yet
+                temp_file_paths.remove(1);
+                delete_temp_files(temp_file_paths)?;
+                return Err(e);
+            }
+        }
+    }
+
+    Ok(temp_file_paths)
+}
+
+/// Fails with the line of the error, instead of leaving the subtitles to ffmpeg
+pub fn check_subtitles(srt_path: &PathBuf) -> Result<(), Box<dyn std::error::Error + Sync + Send>> {
+    let format = Format::from_path(srt_path.to_str().unwrap()).unwrap_or(Format::Srt);
+    let subtitles = std::fs::read_to_string(srt_path)?;
+    Document::parse(&subtitles, format)?;
+    Ok(())
+}
+
+/// Writes the subtitles as an ASS file with the style, sized for the resolution of the video
+pub fn write_styled_subtitles(
+    subtitles_path: &PathBuf,
+    video_path: &PathBuf,
+    style: &SubtitlesStyle,
+) -> Result<PathBuf, Box<dyn std::error::Error + Sync + Send>> {
+    let format = Format::from_path(subtitles_path.to_str().unwrap()).unwrap_or(Format::Srt);
+    let subtitles = std::fs::read_to_string(subtitles_path)?;
+    let mut document = Document::parse(&subtitles, format)?;
+
+    document.styles = vec![Style::from(style)];
+    for cue in document.cues.iter_mut() {
+        cue.style = None;
+    }
+
+    // without it, libass scales the sizes from its 384x288 default
+    document.resolution = match ffprobe::get_video_resolution(video_path.to_str().unwrap()) {
+        Ok(resolution) => Some(resolution),
         Err(e) => {
-            temp_file_paths.push(video_path);
-            delete_temp_files(temp_file_paths)?;
-            return Err(e);
+            tracing::warn!("Failed to get the resolution of the video: {}", e);
+            None
         }
+    };
+
+    if let Some(max_line_width) = style.max_line_width {
+        document.wrap_lines(max_line_width.max(1) as usize);
     }
 
-    temp_file_paths.push(video_path);
-    temp_file_paths.push(srt_path);
-    temp_file_paths.push(output_path);
+    let styled_path = subtitles_path.with_extension("styled.ass");
+    std::fs::write(&styled_path, document.write(Format::Ass))?;
 
-    Ok(temp_file_paths)
+    Ok(styled_path)
 }
 
 pub fn _read_output_file(
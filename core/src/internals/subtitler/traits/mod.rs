@@ -1,16 +1,46 @@
+use std::str::FromStr;
+
 use crate::{
-    database::models::video::with::VideoWithStorage,
-    internals::{cloud::traits::BucketClient, ServiceProvider},
+    database::models::{
+        subtitles_style::SubtitlesStyle,
+        video::{subtitles_mode::SubtitlesMode, with::VideoWithStorage},
+    },
+    internals::{cloud::traits::BucketClient, translator::language::Language, ServiceProvider},
+    util::progress::OnProgress,
 };
 use async_trait::async_trait;
 
+/// A subtitle file of the bucket and the language of its text
+#[derive(Debug, Clone, PartialEq)]
+pub struct SubtitleTrack {
+    pub uri: String,
+    pub language: String,
+}
+
+impl SubtitleTrack {
+    /// `und`, undetermined, for languages the pipeline doesn't know
+    pub fn iso_639_2(&self) -> &'static str {
+        return Language::from_str(&self.language)
+            .map(|language| language.iso_639_2())
+            .unwrap_or("und");
+    }
+}
+
 #[async_trait]
 pub trait SubtitlerClient<BC: BucketClient>: ServiceProvider {
     /// returns the estimated time in seconds
     fn estimate_time(&self, payload: &VideoWithStorage, bucket_client: &BC) -> u32;
+    /// The first of the `tracks` is the translated subtitle of the output, the one burned into
+    /// the video and the default soft track; the other tracks are only muxed by the soft modes.
+    /// The `style` is the look of the burned subtitles, clients that can't style them ignore it.
+    /// Clients that can't tell how far the work is never call `on_progress`
     async fn subtitle(
         &self,
         payload: &VideoWithStorage,
+        tracks: &[SubtitleTrack],
+        mode: SubtitlesMode,
+        style: Option<&SubtitlesStyle>,
         bucket_client: &BC,
+        on_progress: OnProgress<'_>,
     ) -> Result<String, Box<dyn std::error::Error + Sync + Send>>;
 }
@@ -1,14 +1,19 @@
-use std::println;
-
 use async_trait::async_trait;
 use serde_json::json;
 
 use crate::{
-    database::models::video::with::VideoWithStorage,
+    database::models::{
+        subtitles_style::SubtitlesStyle,
+        video::{subtitles_mode::SubtitlesMode, with::VideoWithStorage},
+    },
     internals::{cloud::aws::s3::S3Client, ServiceProvider},
+    util::{
+        ffmpeg::{mux_arguments, SubtitleStream},
+        progress::OnProgress,
+    },
 };
 
-use super::traits::SubtitlerClient;
+use super::traits::{SubtitleTrack, SubtitlerClient};
 
 use crate::internals::cloud::traits::BucketClient;
 
@@ -48,26 +53,69 @@ impl SubtitlerClient<S3Client> for VideoBoxClient {
     async fn subtitle(
         &self,
         video: &VideoWithStorage,
+        tracks: &[SubtitleTrack],
+        mode: SubtitlesMode,
+        _style: Option<&SubtitlesStyle>,
         bucket_client: &S3Client,
+        _on_progress: OnProgress<'_>,
     ) -> Result<String, Box<dyn std::error::Error + Sync + Send>> {
-        let file_name = format!("{}.{}", video.video.id, video.storage.format.to_string());
+        let srt_uri = match tracks.first() {
+            Some(track) => &track.uri,
+            None => return Err("No subtitles to burn".into()),
+        };
+        let format = video.storage.format.to_string();
+        let video_uri = format!("videos/raw/{}.{}", video.video.id, format);
 
-        let video_uri = format!("videos/raw/{}", file_name);
-        let translated_srt_uri = format!("translations/{}.srt", video.video.id);
+        // keeps the language segment of the srt, e.g. {id}.{lang}.srt -> {id}.{lang}.{format}
+        let srt_name = srt_uri.split('/').last().unwrap().trim_end_matches(".srt");
+        let file_name = format!("{}.{}", srt_name, format);
 
         let presigned_video_url = bucket_client
             .create_signed_download_url(&video_uri, None)
             .await?;
 
         let presigned_srt_url = bucket_client
-            .create_signed_download_url(&translated_srt_uri, None)
+            .create_signed_download_url(srt_uri, None)
             .await?;
 
         let api_url = format!("{}/tasks", self.base_url);
-        let command = format!(
-            "ffmpeg -i \"{}\" -vf subtitles=\"{}\" {}",
-            presigned_video_url, presigned_srt_url, file_name
-        );
+        let command = match mode {
+            SubtitlesMode::Burned => format!(
+                "ffmpeg -i \"{}\" -vf subtitles=\"{}\" {}",
+                presigned_video_url, presigned_srt_url, file_name
+            ),
+            _ => {
+                let mut track_urls = vec![];
+                for track in tracks {
+                    let url = bucket_client
+                        .create_signed_download_url(&track.uri, None)
+                        .await?;
+                    track_urls.push(url);
+                }
+
+                let streams: Vec<SubtitleStream> = tracks
+                    .iter()
+                    .zip(&track_urls)
+                    .map(|(track, url)| SubtitleStream {
+                        path: url,
+                        language: track.iso_639_2(),
+                    })
+                    .collect();
+
+                let burned = match mode {
+                    SubtitlesMode::Both => Some(presigned_srt_url.as_str()),
+                    _ => None,
+                };
+
+                let arguments = mux_arguments(&presigned_video_url, &streams, burned, &file_name)?;
+                let arguments: Vec<String> = arguments
+                    .into_iter()
+                    .map(|argument| format!("\"{}\"", argument))
+                    .collect();
+
+                format!("ffmpeg {}", arguments.join(" "))
+            }
+        };
 
         let storage_credentials = json!({
           "access_key": std::env::var("AWS_ACCESS_KEY_ID").unwrap(),
@@ -80,7 +128,7 @@ impl SubtitlerClient<S3Client> for VideoBoxClient {
            "command": command,
            "storage_credentials": storage_credentials
         });
-        println!("{:?}", body);
+        tracing::debug!("{:?}", body);
 
         let response = self
             .client
@@ -92,7 +140,7 @@ impl SubtitlerClient<S3Client> for VideoBoxClient {
 
         if response.status().is_success() {
             let response_text = response.text().await?;
-            println!("{:?}", response_text);
+            tracing::debug!("{:?}", response_text);
 
             let response_body = serde_json::from_str::<serde_json::Value>(&response_text)?;
 
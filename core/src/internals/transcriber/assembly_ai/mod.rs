@@ -1,5 +1,4 @@
 use std::path::PathBuf;
-use std::thread;
 use std::time::Duration;
 
 use reqwest::header::{HeaderMap, HeaderValue};
@@ -11,42 +10,55 @@ use serde_json::{json, Value};
 use crate::{internals::ServiceProvider, SyncError};
 
 use self::payload::{
-    request::TranscribeRequestBody, response::TranscribeSentencesResponse, response::UploadResponse,
+    request::TranscribeRequestBody,
+    response::{TranscribeSentencesResponse, UploadResponse, WebhookBody},
 };
 
-use super::traits::{Sentence, TranscriberClient};
+use super::traits::{Sentence, TranscribeOptions, TranscriberClient, TranscriptionStatus};
 
-mod payload;
+pub(crate) mod payload;
 
 use crate::util;
 
+#[derive(Debug, Clone)]
+struct Webhook {
+    url: String,
+    token: String,
+}
+
 #[derive(Debug, Clone)]
 pub struct AssemblyAiClient {
     api_key: String,
     api_url: String,
-    webhook_url: String,
-    webhook_token: String,
+    /// Not sent when the queue polls the transcriptions
+    webhook: Option<Webhook>,
     client: Client,
 }
 
 impl AssemblyAiClient {
     pub fn new() -> Self {
-        println!("Creating AssemblyAI client...");
+        tracing::info!("Creating AssemblyAI client...");
         let api_key = std::env::var("ASSEMBLY_AI_API_KEY").unwrap();
         let api_url = std::env::var("ASSEMBLY_AI_BASE_URL").unwrap();
 
-        let our_base_url = std::env::var("API_URL").unwrap();
-        let endpoint_url = std::env::var("ASSEMBLY_AI_WEBHOOK_ENDPOINT").unwrap();
+        let webhook = match super::polling_enabled() {
+            true => None,
+            false => {
+                let our_base_url = std::env::var("API_URL").unwrap();
+                let endpoint_url = std::env::var("ASSEMBLY_AI_WEBHOOK_ENDPOINT").unwrap();
 
-        let webhook_url = format!("{}/{}", our_base_url, endpoint_url);
-        let webhook_token = std::env::var("ASSEMBLY_AI_WEBHOOK_TOKEN").unwrap();
+                Some(Webhook {
+                    url: format!("{}/{}", our_base_url, endpoint_url),
+                    token: std::env::var("ASSEMBLY_AI_WEBHOOK_TOKEN").unwrap(),
+                })
+            }
+        };
 
         let client = Client::new();
         Self {
             api_key,
             api_url,
-            webhook_url,
-            webhook_token,
+            webhook,
             client,
         }
     }
@@ -86,16 +98,25 @@ impl TranscriberClient for AssemblyAiClient {
         return Ok(sentences);
     }
 
-    async fn transcribe(&self, media_url: &str) -> Result<String, SyncError> {
+    async fn transcribe(
+        &self,
+        media_url: &str,
+        options: &TranscribeOptions,
+    ) -> Result<String, SyncError> {
         let url = format!("{}/transcript", self.api_url);
 
         let client = Client::new();
 
         let req_body = TranscribeRequestBody {
             audio_url: media_url.to_string(),
-            webhook_url: self.webhook_url.to_string(),
-            webhook_auth_header_name: "Authorization".to_string(),
-            webhook_auth_header_value: self.webhook_token.to_string(),
+            webhook_url: self.webhook.as_ref().map(|webhook| webhook.url.to_string()),
+            webhook_auth_header_name: self.webhook.as_ref().map(|_| "Authorization".to_string()),
+            webhook_auth_header_value: self
+                .webhook
+                .as_ref()
+                .map(|webhook| webhook.token.to_string()),
+            speaker_labels: options.speaker_labels,
+            word_boost: options.word_boost.clone(),
         };
 
         let parsed_body = serde_json::to_string(&req_body)?;
@@ -121,7 +142,11 @@ impl TranscriberClient for AssemblyAiClient {
         return transcript_id;
     }
 
-    async fn transcribe_from_file(&self, file_path: &str) -> Result<String, SyncError> {
+    async fn transcribe_from_file(
+        &self,
+        file_path: &str,
+        options: &TranscribeOptions,
+    ) -> Result<String, SyncError> {
         let path = PathBuf::from(file_path);
         let audio_buff = util::ffmpeg::extract_audio_from_video_to_buff(&path)?;
 
@@ -146,9 +171,13 @@ impl TranscriberClient for AssemblyAiClient {
 
         let upload: UploadResponse = response.json().await?;
 
-        let req_body = json!({
+        let mut req_body = json!({
             "audio_url": upload.upload_url,
+            "speaker_labels": options.speaker_labels,
         });
+        if !options.word_boost.is_empty() {
+            req_body["word_boost"] = json!(options.word_boost);
+        }
 
         let parsed_body = serde_json::to_string(&req_body)?;
 
@@ -173,35 +202,60 @@ impl TranscriberClient for AssemblyAiClient {
         return transcript_id;
     }
 
+    async fn status(&self, transcription_id: &str) -> Result<TranscriptionStatus, SyncError> {
+        let url = format!("{}/transcript/{}", self.api_url, transcription_id);
+
+        let resp = self
+            .client
+            .get(&url)
+            .header("Authorization", &self.api_key)
+            .send()
+            .await?;
+
+        let resp_body = resp.text().await?;
+
+        let resp_body: Value = serde_json::from_str(&resp_body)?;
+
+        let status = match resp_body["status"].as_str() {
+            Some(status) => status,
+            None => return Err("Could not get transcript status".into()),
+        };
+
+        let status = match status {
+            "completed" => TranscriptionStatus::Completed,
+            "error" => {
+                let error = resp_body["error"].as_str().unwrap_or("Unknown error");
+                TranscriptionStatus::Failed(error.to_string())
+            }
+            _ => TranscriptionStatus::Processing,
+        };
+
+        return Ok(status);
+    }
+
     async fn pool(&self, transcription_id: &str) -> Result<(), SyncError> {
-        let client = reqwest::Client::new();
-        let pooling_url = format!("{}/transcript/{}", self.api_url, transcription_id);
         loop {
-            let pooling_resp = client
-                .get(&pooling_url)
-                .header("Authorization", &self.api_key)
-                .send()
-                .await?;
-
-            let pooling_resp_body = pooling_resp.text().await?;
-
-            let pooling_resp_body: Value = serde_json::from_str(&pooling_resp_body)?;
-
-            let status = match pooling_resp_body["status"].as_str() {
-                Some(status) => status,
-                None => return Err("Could not get transcript status".into()),
-            };
-
-            if status == "completed" {
-                return Ok(());
-            } else if status == "error" {
-                eprintln!(
-                    "Transcription failed with error: {}",
-                    pooling_resp_body["error"]
-                );
-                return Err("Transcription failed".into());
+            match self.status(transcription_id).await? {
+                TranscriptionStatus::Completed => return Ok(()),
+                TranscriptionStatus::Failed(error) => {
+                    tracing::error!("Transcription failed with error: {}", error);
+                    return Err("Transcription failed".into());
+                }
+                TranscriptionStatus::Processing => {}
             }
-            thread::sleep(Duration::from_secs(3));
+            tokio::time::sleep(Duration::from_secs(3)).await;
         }
     }
+
+    fn authorize_webhook(&self, authorization: &str) -> bool {
+        return match &self.webhook {
+            Some(webhook) => webhook.token == authorization,
+            None => false,
+        };
+    }
+
+    fn webhook_transcription_id(&self, body: &[u8]) -> Result<String, SyncError> {
+        let body: WebhookBody = serde_json::from_slice(body)?;
+        return Ok(body.transcript_id);
+    }
 }
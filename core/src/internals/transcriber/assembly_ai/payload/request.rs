@@ -6,10 +6,20 @@ use serde::{Deserialize, Serialize};
 pub struct TranscribeRequestBody {
     #[serde(rename = "audio_url")]
     pub audio_url: String,
-    #[serde(rename = "webhook_url")]
-    pub webhook_url: String,
-    #[serde(rename = "webhook_auth_header_name")]
-    pub webhook_auth_header_name: String,
-    #[serde(rename = "webhook_auth_header_value")]
-    pub webhook_auth_header_value: String,
+    #[serde(rename = "webhook_url", skip_serializing_if = "Option::is_none")]
+    pub webhook_url: Option<String>,
+    #[serde(
+        rename = "webhook_auth_header_name",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub webhook_auth_header_name: Option<String>,
+    #[serde(
+        rename = "webhook_auth_header_value",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub webhook_auth_header_value: Option<String>,
+    #[serde(rename = "speaker_labels")]
+    pub speaker_labels: bool,
+    #[serde(rename = "word_boost", skip_serializing_if = "Vec::is_empty")]
+    pub word_boost: Vec<String>,
 }
@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 
-use crate::internals::transcriber::traits::Sentence;
+use crate::internals::transcriber::traits::{Sentence, Word as TimedWord};
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 
@@ -8,6 +8,13 @@ pub struct UploadResponse {
     pub upload_url: String,
 }
 
+/// Sent to the webhook when a transcription is completed or failed
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WebhookBody {
+    pub status: String,
+    pub transcript_id: String,
+}
+
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct TranscribeSentencesResponse {
@@ -26,6 +33,9 @@ pub struct AssemblyAiSentence {
     pub end: i32,
     pub confidence: f64,
     pub words: Vec<Word>,
+    /// Only sent when the speaker labels were requested
+    #[serde(default)]
+    pub speaker: Option<String>,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -39,10 +49,22 @@ pub struct Word {
 
 impl Into<Sentence> for AssemblyAiSentence {
     fn into(self) -> Sentence {
+        let words = self
+            .words
+            .into_iter()
+            .map(|word| TimedWord {
+                text: word.text,
+                start_time: word.start,
+                end_time: word.end,
+            })
+            .collect();
+
         Sentence {
             text: self.text,
             start_time: self.start,
             end_time: self.end,
+            words: Some(words),
+            speaker: self.speaker,
         }
     }
 }
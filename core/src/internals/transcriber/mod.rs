@@ -1,2 +1,11 @@
 pub mod assembly_ai;
 pub mod traits;
+pub mod whisper;
+
+/// With `TRANSCRIPTION_POLLING=true` the queue asks the transcriber when the transcriptions
+/// are done, instead of waiting for the webhook of the provider to reach the api
+pub fn polling_enabled() -> bool {
+    return std::env::var("TRANSCRIPTION_POLLING")
+        .map(|polling| polling == "true")
+        .unwrap_or(false);
+}
@@ -3,20 +3,65 @@ use serde::{Deserialize, Serialize};
 
 use crate::{internals::ServiceProvider, SyncError};
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Sentence {
     pub start_time: i32,
     pub end_time: i32,
     pub text: String,
+    /// Timings of the spoken words, when the transcriber has them.
+    /// They are kept after the translation, which only replaces the text
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub words: Option<Vec<Word>>,
+    /// Who said it, when the transcription was diarized. Ids are only unique within a video
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub speaker: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Word {
+    pub start_time: i32,
+    pub end_time: i32,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TranscribeOptions {
+    /// Tells the speakers apart, transcribers that can't do it ignore it
+    pub speaker_labels: bool,
+    /// Names and jargon the transcriber should expect, from the glossary of the channel
+    pub word_boost: Vec<String>,
+}
+
+/// Where a requested transcription is, without waiting for it
+#[derive(Debug, Clone, PartialEq)]
+pub enum TranscriptionStatus {
+    Processing,
+    Completed,
+    Failed(String),
 }
 
 #[async_trait]
 pub trait TranscriberClient: ServiceProvider {
-    async fn transcribe(&self, media_url: &str) -> Result<String, SyncError>;
-    async fn transcribe_from_file(&self, file_path: &str) -> Result<String, SyncError>;
+    async fn transcribe(
+        &self,
+        media_url: &str,
+        options: &TranscribeOptions,
+    ) -> Result<String, SyncError>;
+    async fn transcribe_from_file(
+        &self,
+        file_path: &str,
+        options: &TranscribeOptions,
+    ) -> Result<String, SyncError>;
     async fn get_transcription_sentences(
         &self,
         transcription_id: &str,
     ) -> Result<Vec<Sentence>, SyncError>;
+    async fn status(&self, transcription_id: &str) -> Result<TranscriptionStatus, SyncError>;
+    /// Waits until the transcription is completed
     async fn pool(&self, transcription_id: &str) -> Result<(), SyncError>;
+    /// Whether a webhook was sent by the provider, given its `Authorization` header.
+    /// Providers that send no webhooks refuse all of them
+    fn authorize_webhook(&self, authorization: &str) -> bool;
+    /// The id of the transcription the body of a webhook is about
+    fn webhook_transcription_id(&self, body: &[u8]) -> Result<String, SyncError>;
 }
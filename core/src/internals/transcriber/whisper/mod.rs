@@ -0,0 +1,361 @@
+use std::path::PathBuf;
+use std::process::Command;
+
+use async_trait::async_trait;
+use uuid::Uuid;
+
+use crate::{
+    internals::ServiceProvider,
+    util::{ffmpeg, fs::create_temp_dir, metrics},
+    SyncError,
+};
+
+use self::payload::WhisperOutput;
+
+use super::traits::{Sentence, TranscribeOptions, TranscriberClient, TranscriptionStatus};
+
+mod payload;
+
+const DEFAULT_BINARY: &str = "whisper-cli";
+const DEFAULT_LANGUAGE: &str = "auto";
+const DEFAULT_OUTPUT_PATH: &str = "whisper_transcriptions";
+
+/// Transcribes on this machine with a whisper.cpp binary, on the CPU and without network.
+/// The transcription is done when `transcribe` returns, its id names the file with the result
+#[derive(Debug, Clone)]
+pub struct WhisperClient {
+    binary: String,
+    model: String,
+    language: String,
+    threads: Option<String>,
+    output_path: PathBuf,
+}
+
+impl WhisperClient {
+    /// Fails without `WHISPER_MODEL`, the only setting with no default
+    pub fn new() -> Result<Self, SyncError> {
+        tracing::info!("Creating Whisper client...");
+        let binary = std::env::var("WHISPER_BINARY").unwrap_or(DEFAULT_BINARY.to_string());
+        let model = std::env::var("WHISPER_MODEL")
+            .map_err(|_| "WHISPER_MODEL must be the path of a whisper.cpp model")?;
+        let language = std::env::var("WHISPER_LANGUAGE").unwrap_or(DEFAULT_LANGUAGE.to_string());
+        let threads = std::env::var("WHISPER_THREADS").ok();
+        let output_path =
+            std::env::var("WHISPER_OUTPUT_PATH").unwrap_or(DEFAULT_OUTPUT_PATH.to_string());
+
+        Ok(Self {
+            binary,
+            model,
+            language,
+            threads,
+            output_path: PathBuf::from(output_path),
+        })
+    }
+
+    fn result_path(&self, transcription_id: &str) -> PathBuf {
+        self.output_path.join(format!("{}.json", transcription_id))
+    }
+
+    /// ffmpeg and whisper keep a core busy for minutes, so they run off the async workers
+    async fn transcribe_media(
+        &self,
+        media_path: &str,
+        options: &TranscribeOptions,
+    ) -> Result<String, SyncError> {
+        let client = self.clone();
+        let media_path = media_path.to_string();
+        let options = options.clone();
+
+        let result = tokio::task::spawn_blocking(move || {
+            client.transcribe_media_blocking(&media_path, &options)
+        })
+        .await?;
+
+        return result;
+    }
+
+    /// The audio goes through ffmpeg first, whisper only reads a few audio formats.
+    /// It is extracted to a folder of its own, removed whether whisper succeeds or not
+    fn transcribe_media_blocking(
+        &self,
+        media_path: &str,
+        options: &TranscribeOptions,
+    ) -> Result<String, SyncError> {
+        if options.speaker_labels {
+            tracing::warn!("Whisper can't tell the speakers apart, transcribing without them");
+        }
+
+        let temp_dir = create_temp_dir()?.join(format!("whisper-{}", Uuid::new_v4()));
+        std::fs::create_dir_all(&temp_dir)?;
+        let audio_path = temp_dir.join("audio.mp3");
+
+        let media_path = PathBuf::from(media_path);
+        let result = match ffmpeg::extract_audio_from_video_to_file(&media_path, &audio_path) {
+            Ok(_) => self.transcribe_audio(&audio_path, options),
+            Err(e) => Err(e.into()),
+        };
+
+        if let Err(e) = std::fs::remove_dir_all(&temp_dir) {
+            tracing::warn!("Failed to remove {}: {}", temp_dir.display(), e);
+        }
+
+        return result;
+    }
+
+    fn transcribe_audio(
+        &self,
+        audio_path: &PathBuf,
+        options: &TranscribeOptions,
+    ) -> Result<String, SyncError> {
+        std::fs::create_dir_all(&self.output_path)?;
+
+        let transcription_id = Uuid::new_v4().to_string();
+        // whisper adds the extension
+        let output_file = self.output_path.join(&transcription_id);
+
+        let mut cmd = Command::new(&self.binary);
+        cmd.arg("--model")
+            .arg(&self.model)
+            .arg("--file")
+            .arg(audio_path)
+            .arg("--language")
+            .arg(&self.language)
+            .arg("--output-json-full")
+            .arg("--output-file")
+            .arg(&output_file)
+            .arg("--no-prints");
+
+        if let Some(threads) = &self.threads {
+            cmd.arg("--threads").arg(threads);
+        }
+
+        // whisper has no word boost, the words on the prompt make it more likely to write them
+        if !options.word_boost.is_empty() {
+            cmd.arg("--prompt").arg(options.word_boost.join(", "));
+        }
+
+        let output = metrics::time_command("whisper", "transcribe", || cmd.output())?;
+
+        if !output.status.success() {
+            let error_message = String::from_utf8_lossy(&output.stderr);
+            tracing::error!(
+                "Whisper transcription failed. Error message: {}",
+                error_message
+            );
+            return Err(error_message.into());
+        }
+
+        return Ok(transcription_id);
+    }
+}
+
+impl ServiceProvider for WhisperClient {
+    fn id(&self) -> i32 {
+        return 7;
+    }
+}
+
+#[async_trait]
+impl TranscriberClient for WhisperClient {
+    /// ffmpeg reads the media straight from the url
+    async fn transcribe(
+        &self,
+        media_url: &str,
+        options: &TranscribeOptions,
+    ) -> Result<String, SyncError> {
+        return self.transcribe_media(media_url, options).await;
+    }
+
+    async fn transcribe_from_file(
+        &self,
+        file_path: &str,
+        options: &TranscribeOptions,
+    ) -> Result<String, SyncError> {
+        return self.transcribe_media(file_path, options).await;
+    }
+
+    /// The result is only read once, its file is removed so the output folder doesn't grow
+    async fn get_transcription_sentences(
+        &self,
+        transcription_id: &str,
+    ) -> Result<Vec<Sentence>, SyncError> {
+        let result_path = self.result_path(transcription_id);
+        let result = tokio::fs::read_to_string(&result_path).await?;
+        let output: WhisperOutput = serde_json::from_str(&result)?;
+
+        if let Err(e) = tokio::fs::remove_file(&result_path).await {
+            tracing::warn!("Failed to remove {}: {}", result_path.display(), e);
+        }
+
+        let sentences = output
+            .transcription
+            .into_iter()
+            .filter(|segment| segment.is_speech())
+            .map(|segment| segment.into())
+            .collect();
+
+        return Ok(sentences);
+    }
+
+    /// Nothing is left processing, a missing result means whisper never finished it
+    async fn status(&self, transcription_id: &str) -> Result<TranscriptionStatus, SyncError> {
+        if !self.result_path(transcription_id).exists() {
+            let error = format!("Transcription {} not found", transcription_id);
+            return Ok(TranscriptionStatus::Failed(error));
+        }
+
+        return Ok(TranscriptionStatus::Completed);
+    }
+
+    async fn pool(&self, transcription_id: &str) -> Result<(), SyncError> {
+        match self.status(transcription_id).await? {
+            TranscriptionStatus::Failed(error) => return Err(error.into()),
+            _ => return Ok(()),
+        }
+    }
+
+    fn authorize_webhook(&self, _authorization: &str) -> bool {
+        return false;
+    }
+
+    fn webhook_transcription_id(&self, _body: &[u8]) -> Result<String, SyncError> {
+        return Err("Whisper sends no webhooks".into());
+    }
+}
+
+#[cfg(all(test, unix))]
+mod test {
+    use std::os::unix::fs::PermissionsExt;
+
+    use super::*;
+
+    /// Part of the output of `whisper-cli --output-json-full` for a short video
+    const OUTPUT: &str = r#"{
+        "systeminfo": "AVX = 1 | AVX2 = 1 | NEON = 0",
+        "model": {"type": "base"},
+        "params": {"model": "models/ggml-base.bin", "language": "en", "translate": false},
+        "result": {"language": "en"},
+        "transcription": [
+            {
+                "timestamps": {"from": "00:00:00,000", "to": "00:00:01,200"},
+                "offsets": {"from": 0, "to": 1200},
+                "text": " [BLANK_AUDIO]",
+                "tokens": []
+            },
+            {
+                "timestamps": {"from": "00:00:01,200", "to": "00:00:03,900"},
+                "offsets": {"from": 1200, "to": 3900},
+                "text": " So today, FFmpeg works.",
+                "tokens": [
+                    {"text": "[_BEG_]", "timestamps": {"from": "00:00:01,200", "to": "00:00:01,200"}, "offsets": {"from": 1200, "to": 1200}, "id": 50364, "p": 0.98},
+                    {"text": " So", "timestamps": {"from": "00:00:01,200", "to": "00:00:01,380"}, "offsets": {"from": 1200, "to": 1380}, "id": 407, "p": 0.91},
+                    {"text": " today", "timestamps": {"from": "00:00:01,400", "to": "00:00:01,700"}, "offsets": {"from": 1400, "to": 1700}, "id": 965, "p": 0.99},
+                    {"text": ",", "timestamps": {"from": "00:00:01,700", "to": "00:00:01,720"}, "offsets": {"from": 1700, "to": 1720}, "id": 11, "p": 0.87},
+                    {"text": " FF", "timestamps": {"from": "00:00:02,000", "to": "00:00:02,400"}, "offsets": {"from": 2000, "to": 2400}, "id": 16675, "p": 0.76},
+                    {"text": "mpeg", "timestamps": {"from": "00:00:02,400", "to": "00:00:03,000"}, "offsets": {"from": 2400, "to": 3000}, "id": 43561, "p": 0.93},
+                    {"text": " works", "timestamps": {"from": "00:00:03,000", "to": "00:00:03,600"}, "offsets": {"from": 3000, "to": 3600}, "id": 1985, "p": 0.99},
+                    {"text": ".", "timestamps": {"from": "00:00:03,600", "to": "00:00:03,900"}, "offsets": {"from": 3600, "to": 3900}, "id": 13, "p": 0.95},
+                    {"text": "[_TT_195]", "timestamps": {"from": "00:00:03,900", "to": "00:00:03,900"}, "offsets": {"from": 3900, "to": 3900}, "id": 50559, "p": 0.12}
+                ]
+            }
+        ]
+    }"#;
+
+    /// A stand-in for the whisper binary, writing `output` where it is asked to
+    fn stub_client(name: &str, script: &str) -> WhisperClient {
+        let dir = std::env::temp_dir().join(format!("whisper-{}-{}", name, Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let binary = dir.join("whisper-cli");
+        std::fs::write(&binary, script).unwrap();
+        std::fs::set_permissions(&binary, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        WhisperClient {
+            binary: binary.to_str().unwrap().to_string(),
+            model: "models/ggml-base.bin".to_string(),
+            language: DEFAULT_LANGUAGE.to_string(),
+            threads: Some("2".to_string()),
+            output_path: dir.join("output"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_transcribe_with_stub_binary() {
+        let script = format!(
+            r#"#!/bin/sh
+[ "$2" = "models/ggml-base.bin" ] || exit 2
+while [ "$#" -gt 0 ]; do
+    [ "$1" = "--output-file" ] && output="$2"
+    [ "$1" = "--prompt" ] && prompt="$2"
+    shift
+done
+[ "$prompt" = "FFmpeg, libass" ] || exit 3
+cat > "$output.json" <<'EOF'
+{}
+EOF
+"#,
+            OUTPUT
+        );
+        let client = stub_client("transcribe", &script);
+        let options = TranscribeOptions {
+            word_boost: vec!["FFmpeg".to_string(), "libass".to_string()],
+            ..Default::default()
+        };
+
+        let transcription_id = client
+            .transcribe_audio(&PathBuf::from("audio.mp3"), &options)
+            .unwrap();
+        client.pool(&transcription_id).await.unwrap();
+
+        let sentences = client
+            .get_transcription_sentences(&transcription_id)
+            .await
+            .unwrap();
+
+        assert_eq!(sentences.len(), 1);
+        assert!(!client.result_path(&transcription_id).exists());
+        assert_eq!(sentences[0].text, "So today, FFmpeg works.");
+        assert_eq!(sentences[0].start_time, 1200);
+        assert_eq!(sentences[0].end_time, 3900);
+
+        let words = sentences[0].words.as_ref().unwrap();
+        let texts: Vec<&str> = words.iter().map(|word| word.text.as_str()).collect();
+        assert_eq!(texts, vec!["So", "today,", "FFmpeg", "works."]);
+        assert_eq!((words[2].start_time, words[2].end_time), (2000, 3000));
+        assert_eq!((words[3].start_time, words[3].end_time), (3000, 3900));
+    }
+
+    #[tokio::test]
+    async fn test_transcribe_failure() {
+        let script = "#!/bin/sh\necho 'failed to load model' >&2\nexit 1\n";
+        let client = stub_client("failure", script);
+
+        let error = client
+            .transcribe_audio(&PathBuf::from("audio.mp3"), &TranscribeOptions::default())
+            .unwrap_err();
+        assert!(error.to_string().contains("failed to load model"));
+
+        assert!(client.pool("missing").await.is_err());
+        assert!(matches!(
+            client.status("missing").await.unwrap(),
+            TranscriptionStatus::Failed(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_failed_transcription_leaves_no_audio() {
+        let client = stub_client("cleanup", "#!/bin/sh\nexit 1\n");
+
+        let result = client
+            .transcribe_from_file("missing.mp4", &TranscribeOptions::default())
+            .await;
+        assert!(result.is_err());
+
+        let leftovers = std::fs::read_dir(create_temp_dir().unwrap())
+            .unwrap()
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_name().to_string_lossy().starts_with("whisper-"))
+            .count();
+        assert_eq!(leftovers, 0);
+    }
+}
@@ -0,0 +1,86 @@
+use serde::Deserialize;
+
+use crate::internals::transcriber::traits::{Sentence, Word};
+
+/// The file written by `whisper-cli --output-json-full`
+#[derive(Debug, Clone, Deserialize)]
+pub struct WhisperOutput {
+    pub transcription: Vec<Segment>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Segment {
+    pub offsets: Offsets,
+    pub text: String,
+    #[serde(default)]
+    pub tokens: Vec<Token>,
+}
+
+/// Milliseconds from the start of the audio
+#[derive(Debug, Clone, Deserialize)]
+pub struct Offsets {
+    pub from: i32,
+    pub to: i32,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Token {
+    pub text: String,
+    pub offsets: Offsets,
+}
+
+impl Segment {
+    /// Silences and noises are written between brackets, like `[BLANK_AUDIO]`
+    pub fn is_speech(&self) -> bool {
+        let text = self.text.trim();
+        let noise = text.starts_with('[') && text.ends_with(']');
+        return !(text.is_empty() || noise);
+    }
+}
+
+impl From<Segment> for Sentence {
+    fn from(segment: Segment) -> Self {
+        Sentence {
+            start_time: segment.offsets.from,
+            end_time: segment.offsets.to,
+            text: segment.text.trim().to_string(),
+            words: Some(words(segment.tokens)),
+            speaker: None,
+        }
+    }
+}
+
+/// Tokens are pieces of words, a token starting with a space starts a new word.
+/// The special tokens of the model, like `[_BEG_]`, are skipped
+fn words(tokens: Vec<Token>) -> Vec<Word> {
+    let mut words: Vec<Word> = vec![];
+    let mut new_word = true;
+
+    for token in tokens {
+        if token.text.starts_with("[_") {
+            continue;
+        }
+
+        let text = token.text.trim();
+        if text.is_empty() {
+            new_word = true;
+            continue;
+        }
+
+        match words.last_mut() {
+            Some(word) if !new_word && !token.text.starts_with(' ') => {
+                word.text.push_str(text);
+                word.end_time = token.offsets.to;
+            }
+            _ => words.push(Word {
+                start_time: token.offsets.from,
+                end_time: token.offsets.to,
+                text: text.to_string(),
+            }),
+        }
+
+        new_word = token.text.ends_with(' ');
+    }
+
+    return words;
+}
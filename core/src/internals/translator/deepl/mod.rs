@@ -1,12 +1,18 @@
 mod payload;
 
-use payload::DeeplResponse;
+use payload::{DeeplResponse, GlossaryResponse};
 
 use async_trait::async_trait;
 
-use crate::internals::ServiceProvider;
+use crate::{internals::ServiceProvider, SyncError};
 
-use super::traits::TranslatorClient;
+use super::{
+    glossary::{self, Glossary},
+    language::Language,
+    traits::TranslatorClient,
+};
+
+const MAX_SENTENCES_PER_REQUEST: usize = 50;
 
 #[derive(Debug, Clone)]
 pub struct DeeplClient {
@@ -17,7 +23,7 @@ pub struct DeeplClient {
 
 impl DeeplClient {
     pub fn new() -> Self {
-        println!("Creating Deepl client...");
+        tracing::info!("Creating Deepl client...");
         let api_key = std::env::var("DEEPL_API_KEY").expect("DEEPL_API_KEY not set");
 
         let api_key = format!("DeepL-Auth-Key {}", api_key);
@@ -32,6 +38,150 @@ impl DeeplClient {
             client,
         }
     }
+
+    /// `DEEPL_BASE_URL` is the url of the translations, the glossaries are next to it
+    fn glossaries_url(&self) -> String {
+        let base_url = self.api_base_url.trim_end_matches('/');
+        let base_url = base_url.strip_suffix("/translate").unwrap_or(base_url);
+        return format!("{}/glossaries", base_url);
+    }
+
+    async fn translate_texts(
+        &self,
+        texts: &[&str],
+        source_language: Language,
+        target_language: Language,
+        glossary_id: Option<&str>,
+    ) -> Result<Vec<String>, SyncError> {
+        let mut params = vec![
+            ("source_lang", source_code(source_language)),
+            ("target_lang", target_code(target_language)),
+            ("split_sentences", "0"),
+        ];
+        if let Some(glossary_id) = glossary_id {
+            params.push(("glossary_id", glossary_id));
+        }
+        params.extend(texts.iter().map(|text| ("text", *text)));
+
+        let res = self
+            .client
+            .post(&self.api_base_url)
+            .header("Authorization", &self.api_key)
+            .form(&params)
+            .send()
+            .await?;
+
+        let response_status = res.status();
+        let text = res.text().await?;
+
+        let response_body: DeeplResponse = match serde_json::from_str(&text) {
+            Ok(response_body) => response_body,
+            Err(e) => {
+                tracing::error!("status : {}", response_status);
+                tracing::error!("error : {}", e);
+                tracing::error!("text {}", text);
+                Err(e)?
+            }
+        };
+
+        let translations = response_body
+            .translations
+            .into_iter()
+            .map(|translation| translation.text)
+            .collect();
+
+        return Ok(translations);
+    }
+
+    /// Glossaries are made for one pair of languages, without the regional variants
+    async fn create_glossary(
+        &self,
+        glossary: &Glossary,
+        source_language: Language,
+        target_language: Language,
+    ) -> Result<String, SyncError> {
+        let entries = glossary
+            .entries
+            .iter()
+            .map(|entry| format!("{}\t{}", entry.term, entry.translation))
+            .collect::<Vec<String>>()
+            .join("\n");
+
+        let params = [
+            ("name", "marco-polo"),
+            ("source_lang", source_code(source_language)),
+            ("target_lang", source_code(target_language)),
+            ("entries", entries.as_str()),
+            ("entries_format", "tsv"),
+        ];
+
+        let res = self
+            .client
+            .post(self.glossaries_url())
+            .header("Authorization", &self.api_key)
+            .form(&params)
+            .send()
+            .await?;
+
+        if !res.status().is_success() {
+            let status = res.status();
+            let text = res.text().await?;
+            return Err(format!("Failed to create the glossary ({}): {}", status, text).into());
+        }
+
+        let response_body: GlossaryResponse = res.json().await?;
+        return Ok(response_body.glossary_id);
+    }
+
+    async fn delete_glossary(&self, glossary_id: &str) {
+        let url = format!("{}/{}", self.glossaries_url(), glossary_id);
+        let res = self
+            .client
+            .delete(url)
+            .header("Authorization", &self.api_key)
+            .send()
+            .await;
+
+        match res {
+            Ok(res) if res.status().is_success() => {}
+            Ok(res) => tracing::warn!(
+                "Failed to delete the glossary {}: {}",
+                glossary_id,
+                res.status()
+            ),
+            Err(e) => tracing::warn!("Failed to delete the glossary {}: {}", glossary_id, e),
+        }
+    }
+}
+
+/// DeepL does not accept regional variants on the source language
+fn source_code(language: Language) -> &'static str {
+    match language {
+        Language::English => "EN",
+        Language::PortugueseBrazil | Language::PortuguesePortugal => "PT",
+        Language::Spanish => "ES",
+        Language::French => "FR",
+        Language::German => "DE",
+        Language::Italian => "IT",
+        Language::Dutch => "NL",
+        Language::Polish => "PL",
+        Language::Russian => "RU",
+        Language::Ukrainian => "UK",
+        Language::Turkish => "TR",
+        Language::Indonesian => "ID",
+        Language::Japanese => "JA",
+        Language::Korean => "KO",
+        Language::Chinese => "ZH",
+    }
+}
+
+fn target_code(language: Language) -> &'static str {
+    match language {
+        Language::English => "EN-US",
+        Language::PortugueseBrazil => "PT-BR",
+        Language::PortuguesePortugal => "PT-PT",
+        _ => source_code(language),
+    }
 }
 
 impl ServiceProvider for DeeplClient {
@@ -45,13 +195,15 @@ impl TranslatorClient for DeeplClient {
     async fn translate_sentence(
         &self,
         text: &str,
+        source_language: Language,
+        target_language: Language,
     ) -> Result<String, Box<dyn std::error::Error + Sync + Send>> {
         let text = text;
         let url = &self.api_base_url;
 
         let params = [
-            ("source_lang", "EN"),
-            ("target_lang", "PT-BR"),
+            ("source_lang", source_code(source_language)),
+            ("target_lang", target_code(target_language)),
             ("split_sentences", "0"),
             ("text", text),
         ];
@@ -70,10 +222,10 @@ impl TranslatorClient for DeeplClient {
         let response_body: DeeplResponse = match serde_json::from_str(&text) {
             Ok(response_body) => response_body,
             Err(e) => {
-                println!("status : {}", response_status);
-                println!("error : {}", e);
+                tracing::error!("status : {}", response_status);
+                tracing::error!("error : {}", e);
                 print!("");
-                println!("text {}", text);
+                tracing::error!("text {}", text);
                 Err(e)?
             }
         };
@@ -90,8 +242,72 @@ impl TranslatorClient for DeeplClient {
 
     async fn translate_sentences(
         &self,
-        _sentences: Vec<&str>,
+        sentences: Vec<&str>,
+        source_language: Language,
+        target_language: Language,
     ) -> Result<Vec<String>, Box<dyn std::error::Error + Sync + Send>> {
-        todo!("Implement DeeplClient::translate_sentences")
+        let mut translations = vec![];
+        for chunk in sentences.chunks(MAX_SENTENCES_PER_REQUEST) {
+            let translated = self
+                .translate_texts(chunk, source_language, target_language, None)
+                .await?;
+            translations.extend(translated);
+        }
+
+        Ok(translations)
+    }
+
+    /// The glossary is created on DeepL for this translation only. Pairs of languages
+    /// DeepL has no glossaries for fall back to the placeholders
+    async fn translate_sentences_with_glossary(
+        &self,
+        sentences: Vec<&str>,
+        source_language: Language,
+        target_language: Language,
+        glossary: &Glossary,
+    ) -> Result<Vec<String>, Box<dyn std::error::Error + Sync + Send>> {
+        if glossary.is_empty() {
+            return self
+                .translate_sentences(sentences, source_language, target_language)
+                .await;
+        }
+
+        let glossary_id = match self
+            .create_glossary(glossary, source_language, target_language)
+            .await
+        {
+            Ok(glossary_id) => glossary_id,
+            Err(e) => {
+                tracing::warn!("{}, translating with placeholders", e);
+                return glossary::translate_with_placeholders(
+                    self,
+                    sentences,
+                    source_language,
+                    target_language,
+                    glossary,
+                )
+                .await;
+            }
+        };
+
+        let mut translations = vec![];
+        let mut result = Ok(());
+        for chunk in sentences.chunks(MAX_SENTENCES_PER_REQUEST) {
+            match self
+                .translate_texts(chunk, source_language, target_language, Some(&glossary_id))
+                .await
+            {
+                Ok(translated) => translations.extend(translated),
+                Err(e) => {
+                    result = Err(e);
+                    break;
+                }
+            }
+        }
+
+        self.delete_glossary(&glossary_id).await;
+        result?;
+
+        Ok(translations)
     }
 }
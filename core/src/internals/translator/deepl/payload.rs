@@ -13,4 +13,9 @@ pub struct Translation {
     #[serde(rename = "detected_source_language")]
     pub detected_source_language: String,
     pub text: String,
-}
\ No newline at end of file
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GlossaryResponse {
+    pub glossary_id: String,
+}
@@ -0,0 +1,229 @@
+use std::str::FromStr;
+
+use crate::{database::models::glossary_term::GlossaryTerm, SyncError};
+
+use super::{language::Language, traits::TranslatorClient};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct GlossaryEntry {
+    pub term: String,
+    pub translation: String,
+}
+
+/// The terms of a channel that apply to one target language
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Glossary {
+    pub entries: Vec<GlossaryEntry>,
+}
+
+impl Glossary {
+    /// Terms without a translation are kept as they are. A term translated to the target
+    /// language wins over the same term kept as it is, terms of other languages are left out
+    pub fn new(terms: &[GlossaryTerm], target_language: Language) -> Self {
+        let translated = terms.iter().filter_map(|term| {
+            let language = Language::from_str(term.language.as_deref()?).ok()?;
+            let translation = term.translation.as_ref()?;
+            (language == target_language).then_some((&term.term, translation))
+        });
+        let kept = terms
+            .iter()
+            .filter(|term| term.translation.is_none())
+            .map(|term| (&term.term, &term.term));
+
+        let mut entries: Vec<GlossaryEntry> = vec![];
+        for (term, translation) in translated.chain(kept) {
+            let term = term.trim();
+            if term.is_empty() || entries.iter().any(|e| e.term.eq_ignore_ascii_case(term)) {
+                continue;
+            }
+
+            entries.push(GlossaryEntry {
+                term: term.to_string(),
+                translation: translation.trim().to_string(),
+            });
+        }
+
+        return Self { entries };
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.entries.is_empty();
+    }
+
+    /// Swaps the terms for `[#index]` placeholders, which the translators leave alone.
+    /// Terms are matched as whole words, ignoring the case
+    pub fn protect(&self, text: &str) -> String {
+        let mut protected = String::new();
+        let mut position = 0;
+
+        while let Some((index, start, end)) = self.next_term(text, position) {
+            protected.push_str(&text[position..start]);
+            protected.push_str(&format!("[#{}]", index));
+            position = end;
+        }
+
+        protected.push_str(&text[position..]);
+        return protected;
+    }
+
+    /// Puts the translations of the terms where their placeholders ended up
+    pub fn restore(&self, text: &str) -> String {
+        let mut restored = String::new();
+        let mut rest = text;
+
+        while let Some(start) = rest.find("[#") {
+            restored.push_str(&rest[..start]);
+            let after = &rest[start + 2..];
+
+            match self.placeholder(after) {
+                Some((entry, length)) => {
+                    restored.push_str(&entry.translation);
+                    rest = &after[length..];
+                }
+                None => {
+                    restored.push_str("[#");
+                    rest = after;
+                }
+            }
+        }
+
+        restored.push_str(rest);
+        return restored;
+    }
+
+    /// The first term found from `position`, the longest one when they start together
+    fn next_term(&self, text: &str, position: usize) -> Option<(usize, usize, usize)> {
+        for (offset, _) in text[position..].char_indices() {
+            let start = position + offset;
+            let in_word = text[..start]
+                .chars()
+                .next_back()
+                .is_some_and(|c| c.is_alphanumeric());
+            if in_word {
+                continue;
+            }
+
+            let found = self
+                .entries
+                .iter()
+                .enumerate()
+                .filter_map(|(index, entry)| {
+                    let end = start + starts_with_term(&text[start..], &entry.term)?;
+                    Some((index, start, end))
+                })
+                .max_by_key(|(_, _, end)| *end);
+
+            if found.is_some() {
+                return found;
+            }
+        }
+
+        return None;
+    }
+
+    /// The entry of the placeholder `text` starts with, past its `[#`, and its length
+    fn placeholder(&self, text: &str) -> Option<(&GlossaryEntry, usize)> {
+        let end = text.find(']')?;
+        let index: usize = text[..end].trim().parse().ok()?;
+        let entry = self.entries.get(index)?;
+        return Some((entry, end + 1));
+    }
+}
+
+/// The length of the term at the start of the text, when it is a whole word there
+fn starts_with_term(text: &str, term: &str) -> Option<usize> {
+    let mut chars = text.char_indices();
+    let mut length = 0;
+
+    for term_char in term.chars() {
+        let (index, c) = chars.next()?;
+        if !c.to_lowercase().eq(term_char.to_lowercase()) {
+            return None;
+        }
+        length = index + c.len_utf8();
+    }
+
+    let in_word = chars.next().is_some_and(|(_, c)| c.is_alphanumeric());
+    return (!in_word).then_some(length);
+}
+
+/// Translates with the terms swapped for placeholders, for translators without glossaries
+pub async fn translate_with_placeholders<T>(
+    translator_client: &T,
+    sentences: Vec<&str>,
+    source_language: Language,
+    target_language: Language,
+    glossary: &Glossary,
+) -> Result<Vec<String>, SyncError>
+where
+    T: TranslatorClient + Sync + ?Sized,
+{
+    if glossary.is_empty() {
+        return translator_client
+            .translate_sentences(sentences, source_language, target_language)
+            .await;
+    }
+
+    let protected: Vec<String> = sentences.iter().map(|s| glossary.protect(s)).collect();
+    let protected = protected.iter().map(|s| s.as_str()).collect();
+
+    let translations = translator_client
+        .translate_sentences(protected, source_language, target_language)
+        .await?;
+
+    return Ok(translations.iter().map(|t| glossary.restore(t)).collect());
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::NaiveDateTime;
+
+    use super::*;
+
+    fn term(term: &str, language: Option<&str>, translation: Option<&str>) -> GlossaryTerm {
+        GlossaryTerm {
+            id: 1,
+            channel_id: 1,
+            term: term.to_string(),
+            language: language.map(|l| l.to_string()),
+            translation: translation.map(|t| t.to_string()),
+            created_at: NaiveDateTime::default(),
+            updated_at: NaiveDateTime::default(),
+        }
+    }
+
+    #[test]
+    fn test_new() {
+        let terms = vec![
+            term("Rust", None, None),
+            term("pull request", Some("pt-br"), Some("PR")),
+            term("pull request", Some("es"), Some("solicitud")),
+            term("Pull Request", None, None),
+        ];
+
+        let glossary = Glossary::new(&terms, Language::PortugueseBrazil);
+        let entries: Vec<(&str, &str)> = glossary
+            .entries
+            .iter()
+            .map(|e| (e.term.as_str(), e.translation.as_str()))
+            .collect();
+
+        assert_eq!(entries, vec![("pull request", "PR"), ("Rust", "Rust")]);
+    }
+
+    #[test]
+    fn test_protect_and_restore() {
+        let terms = vec![
+            term("Rust", None, None),
+            term("Rust Belt", None, None),
+            term("pull request", Some("pt-br"), Some("PR")),
+        ];
+        let glossary = Glossary::new(&terms, Language::PortugueseBrazil);
+
+        let protected = glossary.protect("Open a Pull Request on Trusty, rust and the Rust Belt.");
+        assert_eq!(protected, "Open a [#0] on Trusty, [#1] and the [#2].");
+
+        let restored = glossary.restore("Abra um [# 0] no Trusty, [#1] e o [#2]. [#9]");
+        assert_eq!(restored, "Abra um PR no Trusty, Rust e o Rust Belt. [#9]");
+    }
+}
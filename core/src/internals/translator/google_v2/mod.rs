@@ -6,7 +6,7 @@ use async_trait::async_trait;
 
 use crate::internals::ServiceProvider;
 
-use super::traits::TranslatorClient;
+use super::{language::Language, traits::TranslatorClient};
 
 const MAX_SENTENCES_PER_REQUEST: usize = 128;
 
@@ -19,7 +19,7 @@ pub struct GoogleTranslateV2Client {
 
 impl GoogleTranslateV2Client {
     pub fn new() -> Self {
-        println!("Creating Google Translator V2 client...");
+        tracing::info!("Creating Google Translator V2 client...");
         let api_key =
             std::env::var("GOOGLE_TRANSLATE_API_KEY").expect("GOOGLE_TRANSLATE_API_KEY not set");
 
@@ -36,6 +36,27 @@ impl GoogleTranslateV2Client {
     }
 }
 
+fn language_code(language: Language) -> &'static str {
+    match language {
+        Language::English => "en",
+        Language::PortugueseBrazil => "pt-BR",
+        Language::PortuguesePortugal => "pt-PT",
+        Language::Spanish => "es",
+        Language::French => "fr",
+        Language::German => "de",
+        Language::Italian => "it",
+        Language::Dutch => "nl",
+        Language::Polish => "pl",
+        Language::Russian => "ru",
+        Language::Ukrainian => "uk",
+        Language::Turkish => "tr",
+        Language::Indonesian => "id",
+        Language::Japanese => "ja",
+        Language::Korean => "ko",
+        Language::Chinese => "zh-CN",
+    }
+}
+
 impl ServiceProvider for GoogleTranslateV2Client {
     fn id(&self) -> i32 {
         return 6;
@@ -47,6 +68,8 @@ impl TranslatorClient for GoogleTranslateV2Client {
     async fn translate_sentence(
         &self,
         _text: &str,
+        _source_language: Language,
+        _target_language: Language,
     ) -> Result<String, Box<dyn std::error::Error + Sync + Send>> {
         todo!("Implement GoogleTranslateV2Client::translate_sentence")
     }
@@ -54,9 +77,13 @@ impl TranslatorClient for GoogleTranslateV2Client {
     async fn translate_sentences(
         &self,
         sentences: Vec<&str>,
+        source_language: Language,
+        target_language: Language,
     ) -> Result<Vec<String>, Box<dyn std::error::Error + Sync + Send>> {
         let url = &self.api_base_url;
         let api_key = &self.api_key;
+        let source = language_code(source_language);
+        let target = language_code(target_language);
 
         let mut final_translated_sentences: Vec<String> = vec![];
         let mut i: usize = 0;
@@ -71,12 +98,12 @@ impl TranslatorClient for GoogleTranslateV2Client {
 
             let buff = &sentences[i..x];
 
-            println!("Translating sentences {} to {}", i, x);
+            tracing::debug!("Translating sentences {} to {}", i, x);
 
             let request_body = serde_json::json!({
                 "q": buff,
-                "source": "en-US",
-                "target": "pt-BR"
+                "source": source,
+                "target": target
             });
 
             let res = self
@@ -93,10 +120,10 @@ impl TranslatorClient for GoogleTranslateV2Client {
             let response_body: GoogleTranslateResponse = match serde_json::from_str(&text) {
                 Ok(response_body) => response_body,
                 Err(e) => {
-                    println!("status : {}", response_status);
-                    println!("error : {}", e);
+                    tracing::error!("status : {}", response_status);
+                    tracing::error!("error : {}", e);
                     print!("");
-                    println!("text {}", text);
+                    tracing::error!("text {}", text);
                     Err(e)?
                 }
             };
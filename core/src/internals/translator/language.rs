@@ -0,0 +1,179 @@
+use std::{fmt::Display, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+/// Normalized language used across the pipeline.
+/// Each translator maps it to its own provider codes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum Language {
+    English,
+    PortugueseBrazil,
+    PortuguesePortugal,
+    Spanish,
+    French,
+    German,
+    Italian,
+    Dutch,
+    Polish,
+    Russian,
+    Ukrainian,
+    Turkish,
+    Indonesian,
+    Japanese,
+    Korean,
+    Chinese,
+}
+
+impl Language {
+    /// The code used to store the language on the database
+    pub fn code(&self) -> &'static str {
+        match self {
+            Language::English => "en",
+            Language::PortugueseBrazil => "pt-br",
+            Language::PortuguesePortugal => "pt-pt",
+            Language::Spanish => "es",
+            Language::French => "fr",
+            Language::German => "de",
+            Language::Italian => "it",
+            Language::Dutch => "nl",
+            Language::Polish => "pl",
+            Language::Russian => "ru",
+            Language::Ukrainian => "uk",
+            Language::Turkish => "tr",
+            Language::Indonesian => "id",
+            Language::Japanese => "ja",
+            Language::Korean => "ko",
+            Language::Chinese => "zh",
+        }
+    }
+
+    /// The three letter code players read from the metadata of subtitle tracks
+    pub fn iso_639_2(&self) -> &'static str {
+        match self {
+            Language::English => "eng",
+            Language::PortugueseBrazil | Language::PortuguesePortugal => "por",
+            Language::Spanish => "spa",
+            Language::French => "fra",
+            Language::German => "deu",
+            Language::Italian => "ita",
+            Language::Dutch => "nld",
+            Language::Polish => "pol",
+            Language::Russian => "rus",
+            Language::Ukrainian => "ukr",
+            Language::Turkish => "tur",
+            Language::Indonesian => "ind",
+            Language::Japanese => "jpn",
+            Language::Korean => "kor",
+            Language::Chinese => "zho",
+        }
+    }
+}
+
+impl Display for Language {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.code())
+    }
+}
+
+impl FromStr for Language {
+    type Err = String;
+
+    /// Accepts the stored code, regional variants and the english name of the language
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let value = s.trim().to_lowercase().replace('_', "-");
+        match value.as_str() {
+            "en" | "en-us" | "en-gb" | "english" => Ok(Language::English),
+            "pt" | "pt-br" | "portuguese" | "brazilian portuguese" => {
+                Ok(Language::PortugueseBrazil)
+            }
+            "pt-pt" | "european portuguese" => Ok(Language::PortuguesePortugal),
+            "es" | "spanish" => Ok(Language::Spanish),
+            "fr" | "french" => Ok(Language::French),
+            "de" | "german" => Ok(Language::German),
+            "it" | "italian" => Ok(Language::Italian),
+            "nl" | "dutch" => Ok(Language::Dutch),
+            "pl" | "polish" => Ok(Language::Polish),
+            "ru" | "russian" => Ok(Language::Russian),
+            "uk" | "ukrainian" => Ok(Language::Ukrainian),
+            "tr" | "turkish" => Ok(Language::Turkish),
+            "id" | "indonesian" => Ok(Language::Indonesian),
+            "ja" | "japanese" => Ok(Language::Japanese),
+            "ko" | "korean" => Ok(Language::Korean),
+            "zh" | "zh-cn" | "chinese" => Ok(Language::Chinese),
+            _ => Err(format!("{} is not a supported language", s)),
+        }
+    }
+}
+
+impl TryFrom<String> for Language {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Language::from_str(&value)
+    }
+}
+
+impl From<Language> for String {
+    fn from(value: Language) -> Self {
+        value.code().to_string()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use super::Language;
+
+    #[test]
+    fn test_from_code() {
+        assert_eq!(Language::from_str("en").unwrap(), Language::English);
+        assert_eq!(
+            Language::from_str("pt-br").unwrap(),
+            Language::PortugueseBrazil
+        );
+        assert_eq!(
+            Language::from_str("pt-PT").unwrap(),
+            Language::PortuguesePortugal
+        );
+    }
+
+    #[test]
+    fn test_from_name() {
+        assert_eq!(Language::from_str("English").unwrap(), Language::English);
+        assert_eq!(Language::from_str(" spanish ").unwrap(), Language::Spanish);
+    }
+
+    #[test]
+    fn test_from_regional_variant() {
+        assert_eq!(Language::from_str("en_US").unwrap(), Language::English);
+        assert_eq!(Language::from_str("zh-CN").unwrap(), Language::Chinese);
+    }
+
+    #[test]
+    fn test_invalid_language() {
+        assert!(Language::from_str("klingon").is_err());
+    }
+
+    #[test]
+    fn test_iso_639_2() {
+        assert_eq!(Language::PortugueseBrazil.iso_639_2(), "por");
+        assert_eq!(Language::from_str("en").unwrap().iso_639_2(), "eng");
+    }
+
+    #[test]
+    fn test_code_round_trip() {
+        let languages = vec![
+            Language::English,
+            Language::PortugueseBrazil,
+            Language::PortuguesePortugal,
+            Language::Chinese,
+        ];
+
+        for language in languages {
+            let code = language.to_string();
+            assert_eq!(Language::from_str(&code).unwrap(), language);
+        }
+    }
+}
@@ -1,3 +1,5 @@
 pub mod traits;
 pub mod deepl;
-pub mod google_v2;
\ No newline at end of file
+pub mod glossary;
+pub mod google_v2;
+pub mod language;
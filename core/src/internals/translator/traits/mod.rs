@@ -2,15 +2,43 @@ use async_trait::async_trait;
 
 use crate::internals::ServiceProvider;
 
+use super::{
+    glossary::{self, Glossary},
+    language::Language,
+};
+
 #[async_trait]
 pub trait TranslatorClient: ServiceProvider {
     async fn translate_sentence(
         &self,
         sentence: &str,
+        source_language: Language,
+        target_language: Language,
     ) -> Result<String, Box<dyn std::error::Error + Sync + Send>>;
 
     async fn translate_sentences(
         &self,
         sentences: Vec<&str>,
+        source_language: Language,
+        target_language: Language,
     ) -> Result<Vec<String>, Box<dyn std::error::Error + Sync + Send>>;
+
+    /// Keeps or forces the translation of the terms of the glossary. Translators without
+    /// glossaries swap the terms for placeholders around `translate_sentences`
+    async fn translate_sentences_with_glossary(
+        &self,
+        sentences: Vec<&str>,
+        source_language: Language,
+        target_language: Language,
+        glossary: &Glossary,
+    ) -> Result<Vec<String>, Box<dyn std::error::Error + Sync + Send>> {
+        return glossary::translate_with_placeholders(
+            self,
+            sentences,
+            source_language,
+            target_language,
+            glossary,
+        )
+        .await;
+    }
 }
@@ -1,5 +1,6 @@
 use crate::{
     database::models::{channel::Channel, video::Video, video_storage::VideosStorage},
+    util::progress::OnProgress,
     SyncError,
 };
 
@@ -13,6 +14,15 @@ pub struct UploadParams<'a> {
     pub video: &'a Video,
     pub storage: &'a VideosStorage,
     pub channel: &'a Channel,
+    pub on_progress: OnProgress<'a>,
+}
+
+/// A subtitle track of a published video, `video_id` is the id of the video on the platform
+pub struct SubtitlesUploadParams<'a> {
+    pub video_id: &'a str,
+    pub channel: &'a Channel,
+    pub language: &'a str,
+    pub subtitles: Vec<u8>,
 }
 
 #[async_trait::async_trait]
@@ -27,4 +37,12 @@ pub trait VideoPlatformClient {
         &self,
         channel: &'a Channel,
     ) -> Result<(), HeathCheckError<'a>>;
+
+    /// Platforms without captions only keep the tracks muxed into the video
+    async fn upload_subtitles<'a>(
+        &self,
+        _params: SubtitlesUploadParams<'a>,
+    ) -> Result<(), SyncError> {
+        return Ok(());
+    }
 }
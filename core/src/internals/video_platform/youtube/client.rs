@@ -1,16 +1,16 @@
 use crate::database::models::channel::{auth::AuthType, Channel};
 use crate::internals::video_platform::errors::HeathCheckError;
-use crate::internals::video_platform::{UploadParams, VideoPlatformClient};
+use crate::internals::video_platform::{SubtitlesUploadParams, UploadParams, VideoPlatformClient};
 use crate::util::fs::create_temp_dir;
 use async_trait::async_trait;
-use google_youtube3::api::{Video, VideoSnippet, VideoStatus};
+use google_youtube3::api::{Caption, CaptionSnippet, Video, VideoSnippet, VideoStatus};
 use google_youtube3::hyper::{Body, Client};
 use google_youtube3::oauth2::AccessTokenAuthenticator;
 use hyper_tls::HttpsConnector;
 use oauth2::{AuthorizationCode, CsrfToken, RefreshToken, Scope, TokenResponse};
 
 use std::fs::File;
-use std::io::Read;
+use std::io::{Cursor, Read};
 
 use crate::SyncError;
 
@@ -25,7 +25,7 @@ pub struct YoutubeClient {
 
 impl YoutubeClient {
     pub fn new() -> Self {
-        println!("starting YoutubeClient ...");
+        tracing::info!("starting YoutubeClient ...");
         let mut file = File::open("yt-client-secret.json").unwrap();
         let mut file_content = String::new();
         file.read_to_string(&mut file_content).unwrap();
@@ -54,7 +54,7 @@ impl YoutubeClient {
                         .error_description()
                         .unwrap_or(&fallback_description);
 
-                    println!("error description: {}", description);
+                    tracing::error!("error description: {}", description);
 
                     return Err(description.to_string().into());
                 }
@@ -76,6 +76,7 @@ impl VideoPlatformClient for YoutubeClient {
 
         let storage = video.storage;
         let channel = video.channel;
+        let on_progress = video.on_progress;
         let video = video.video;
 
         let auth = match &channel.auth.0 {
@@ -129,7 +130,7 @@ impl VideoPlatformClient for YoutubeClient {
             ..Default::default()
         };
 
-        let mut delegate = UploadDelegator::new(chunk_size);
+        let mut delegate = UploadDelegator::new(chunk_size, on_progress);
 
         let insert_call = hub.videos().insert(video).delegate(&mut delegate);
 
@@ -154,7 +155,7 @@ impl VideoPlatformClient for YoutubeClient {
         match std::fs::remove_file(path) {
             Ok(_) => {}
             Err(err) => {
-                println!("failed to remove file: {}", err);
+                tracing::warn!("failed to remove file: {}", err);
             }
         }
 
@@ -187,6 +188,64 @@ impl VideoPlatformClient for YoutubeClient {
 
         return Ok(());
     }
+
+    async fn upload_subtitles<'a>(
+        &self,
+        params: SubtitlesUploadParams<'a>,
+    ) -> Result<(), SyncError> {
+        let auth = match &params.channel.auth.0 {
+            AuthType::Oauth2(auth) => auth,
+            _ => {
+                return Err("invalid auth type".into());
+            }
+        };
+
+        let refresh_token = match &auth.refresh_token {
+            Some(refresh_token) => refresh_token.to_string(),
+            None => {
+                return Err("no refresh token".into());
+            }
+        };
+
+        let token = self.get_token(refresh_token).await?;
+
+        let https = HttpsConnector::new();
+        let client = Client::builder().build::<_, Body>(https);
+
+        let authenticator = AccessTokenAuthenticator::builder(token).build().await?;
+        let hub = google_youtube3::YouTube::new(client, authenticator);
+
+        let caption = Caption {
+            snippet: Some(CaptionSnippet {
+                video_id: Some(params.video_id.to_string()),
+                language: Some(params.language.to_string()),
+                name: Some(params.language.to_string()),
+                is_draft: Some(false),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        let (response, _) = hub
+            .captions()
+            .insert(caption)
+            .upload(
+                Cursor::new(params.subtitles),
+                "application/octet-stream".parse().unwrap(),
+            )
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(format!(
+                "request to {} error with status: {}",
+                "Youtube API",
+                response.status()
+            )
+            .into());
+        }
+
+        return Ok(());
+    }
 }
 
 #[async_trait]
@@ -203,6 +262,10 @@ impl YoutubeClientTrait for YoutubeClient {
             .add_scope(Scope::new(
                 "https://www.googleapis.com/auth/youtube.readonly".to_string(),
             ))
+            // captions can only be written with this scope
+            .add_scope(Scope::new(
+                "https://www.googleapis.com/auth/youtube.force-ssl".to_string(),
+            ))
             .url();
 
         return (auth_url.to_string(), csrf_token.secret().to_string());
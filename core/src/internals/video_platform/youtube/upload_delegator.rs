@@ -1,21 +1,24 @@
-use google_youtube3::Delegate;
+use google_youtube3::{client::ContentRange, Delegate};
 
-#[derive(Debug)]
-pub struct UploadDelegator {
+use crate::util::progress::{self, OnProgress};
+
+pub struct UploadDelegator<'a> {
     upload_url: Option<String>,
     chunk_size: u64,
+    on_progress: OnProgress<'a>,
 }
 
-impl UploadDelegator {
-    pub fn new(chunk_size: u64) -> Self {
+impl<'a> UploadDelegator<'a> {
+    pub fn new(chunk_size: u64, on_progress: OnProgress<'a>) -> Self {
         Self {
             upload_url: None,
             chunk_size,
+            on_progress,
         }
     }
 }
 
-impl Delegate for UploadDelegator {
+impl<'a> Delegate for UploadDelegator<'a> {
     fn upload_url(&mut self) -> Option<String> {
         return self.upload_url.clone();
     }
@@ -30,4 +33,16 @@ impl Delegate for UploadDelegator {
     fn chunk_size(&mut self) -> u64 {
         return self.chunk_size;
     }
+
+    /// Called before every chunk of the resumable upload, the chunks before it are already uploaded
+    fn cancel_chunk_upload(&mut self, chunk: &ContentRange) -> bool {
+        if let Some(range) = &chunk.range {
+            let uploaded = range.first as f64;
+            if let Some(percentage) = progress::percentage(uploaded, chunk.total_length as f64) {
+                (self.on_progress)(percentage);
+            }
+        }
+
+        return false;
+    }
 }
@@ -1,9 +1,9 @@
 use async_trait::async_trait;
 
-use crate::SyncError;
+use crate::{util::progress::OnProgress, SyncError};
 
 #[async_trait]
 pub trait YoutubeDownloader {
-    async fn download(&self, url: &str) -> Result<String, SyncError>;
+    async fn download(&self, url: &str, on_progress: OnProgress<'_>) -> Result<String, SyncError>;
     async fn estimate_time(&self, url: &str) -> Result<usize, SyncError>;
 }
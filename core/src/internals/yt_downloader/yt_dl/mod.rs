@@ -1,6 +1,14 @@
 use std::process::Command;
 
-use crate::{database::models::video_storage::VideoFormat, util::fs::create_temp_dir, SyncError};
+use crate::{
+    database::models::video_storage::VideoFormat,
+    util::{
+        fs::create_temp_dir,
+        metrics,
+        progress::{self, OnProgress},
+    },
+    SyncError,
+};
 
 use super::traits::YoutubeDownloader;
 use async_trait::async_trait;
@@ -14,16 +22,18 @@ impl YtDl {
     }
 
     fn get_video_duration(&self, url: &str) -> Result<String, SyncError> {
-        let output = Command::new("yt-dlp")
-            .arg("--skip-download")
-            .arg("--get-duration")
-            .arg(url)
-            .output()?;
+        let output = metrics::time_command("yt-dlp", "duration", || {
+            Command::new("yt-dlp")
+                .arg("--skip-download")
+                .arg("--get-duration")
+                .arg(url)
+                .output()
+        })?;
 
         if !output.status.success() {
             let error_message = String::from_utf8_lossy(&output.stderr);
 
-            println!(
+            tracing::error!(
                 "Video duration estimation failed. Error message: {}",
                 error_message
             );
@@ -47,9 +57,17 @@ impl YtDl {
     }
 }
 
+/// Percentage on a `[download]  42.3% of 10.00MiB at 1.00MiB/s ETA 00:05` line.
+/// The video and the audio are downloaded one after the other, each going up to 100%
+fn parse_progress_line(line: &str) -> Option<f32> {
+    let line = line.strip_prefix("[download]")?;
+    let percentage = line.split_whitespace().next()?.strip_suffix('%')?;
+    percentage.parse().ok()
+}
+
 #[async_trait]
 impl YoutubeDownloader for YtDl {
-    async fn download(&self, url: &str) -> Result<String, SyncError> {
+    async fn download(&self, url: &str, on_progress: OnProgress<'_>) -> Result<String, SyncError> {
         let format: String = VideoFormat::Mkv.into();
 
         let video_id = uuid::Uuid::new_v4();
@@ -60,19 +78,21 @@ impl YoutubeDownloader for YtDl {
 
         let mut cmd = Command::new("yt-dlp");
 
-        let output = cmd
+        cmd.arg("--newline")
             .arg("-o")
             .arg(&output_file)
             .arg("-f")
             .arg("bestvideo[height<=1080][fps<=30]+bestaudio/best[height<=1080][fps<=30]")
             .arg("--merge-output-format")
             .arg(format)
-            .arg(url)
-            .output()?;
+            .arg(url);
+        let output = metrics::time_command("yt-dlp", "download", || {
+            progress::output_with_progress(&mut cmd, parse_progress_line, on_progress)
+        })?;
 
         if !output.status.success() {
             let error_message = String::from_utf8_lossy(&output.stderr);
-            println!("Video download failed. Error message: {}", error_message);
+            tracing::error!("Video download failed. Error message: {}", error_message);
             return Err(error_message.into());
         }
 
@@ -91,3 +111,22 @@ impl YoutubeDownloader for YtDl {
         Ok(estimated_time)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::parse_progress_line;
+
+    #[test]
+    fn test_parse_progress_line() {
+        let line = "[download]  42.3% of   10.00MiB at    1.00MiB/s ETA 00:05";
+        assert_eq!(parse_progress_line(line), Some(42.3));
+        assert_eq!(
+            parse_progress_line("[download] Destination: video.mkv"),
+            None
+        );
+        assert_eq!(
+            parse_progress_line("[youtube] abc: Downloading webpage"),
+            None
+        );
+    }
+}
@@ -18,21 +18,22 @@ impl From<handlebars::RenderError> for super::EngineError {
     }
 }
 
+const TEMPLATES: [&str; 3] = ["forgot-password", "videos-finished", "video-failed"];
+
 pub struct HandleBarsEngine<'a> {
     handlebars: Handlebars<'a>,
 }
 
 impl HandleBarsEngine<'_> {
     pub fn new(base_path: &str) -> Self {
-        println!("Creating HandleBarsEngine...");
+        tracing::info!("Creating HandleBarsEngine...");
 
         let mut handlebars = Handlebars::new();
-        handlebars
-            .register_template_file(
-                "forgot-password",
-                format!("{}/forgot-password.hbs", base_path),
-            )
-            .unwrap();
+        for template in TEMPLATES {
+            handlebars
+                .register_template_file(template, format!("{}/{}.hbs", base_path, template))
+                .unwrap();
+        }
 
         return Self { handlebars };
     }
@@ -97,4 +98,26 @@ mod test {
             "<p>Click <a href=\"http://localhost:8080/reset-password/?token=test\">here</a> to reset your password.</p>\n</body>\n\n</html>"
         );
     }
+
+    #[test]
+    fn test_videos_finished_rendering() {
+        let engine = super::HandleBarsEngine::new("./templates");
+
+        let params = serde_json::json!({
+            "name": "John",
+            "original_url": "https://youtu.be/1",
+            "videos": [
+                {"title": "First Cut", "url": "https://youtu.be/2"},
+                {"title": "Second Cut", "url": null}
+            ]
+        });
+
+        let template = engine
+            .render("videos-finished", Some(params))
+            .expect("Failed to render template");
+
+        assert!(template.contains("<p>Hi John,</p>"));
+        assert!(template.contains("<a href=\"https://youtu.be/2\">First Cut</a>"));
+        assert!(template.contains("<li>Second Cut</li>"));
+    }
 }
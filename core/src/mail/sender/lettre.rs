@@ -40,7 +40,7 @@ pub struct LettreMailer {
 
 impl LettreMailer {
     pub fn new() -> Self {
-        println!("Creating LettreMailer...");
+        tracing::info!("Creating LettreMailer...");
         let username = std::env::var("SMTP_USERNAME").unwrap();
         let password = std::env::var("SMTP_PASSWORD").unwrap();
         let host = std::env::var("SMTP_HOST").unwrap();
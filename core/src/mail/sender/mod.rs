@@ -5,6 +5,7 @@ pub struct SenderError {
     pub message: String,
 }
 
+#[derive(Debug, Clone)]
 pub struct SendEmailOptions {
     pub to: String,
     pub subject: String,
@@ -6,6 +6,8 @@ pub enum FfmpegError {
     IoError(std::io::Error),
     ProbeError(String),
     CutError,
+    /// Containers without a subtitle codec for the soft tracks
+    UnsupportedContainer(String),
 }
 
 impl From<serde_json::Error> for FfmpegError {
@@ -27,6 +29,13 @@ impl Display for FfmpegError {
             FfmpegError::IoError(err) => write!(f, "IO error: {}", err),
             FfmpegError::ProbeError(msg) => write!(f, "Probe error: {}", msg),
             FfmpegError::CutError => write!(f, "Failed to cut video"),
+            FfmpegError::UnsupportedContainer(container) => {
+                write!(
+                    f,
+                    "Soft subtitles are not supported on {} videos",
+                    container
+                )
+            }
         }
     }
 }
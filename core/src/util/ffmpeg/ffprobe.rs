@@ -2,7 +2,7 @@ use std::process::Command;
 
 use serde::{Deserialize, Serialize};
 
-use crate::util::ffmpeg::SECONDS_TO_REDUCE;
+use crate::util::{ffmpeg::SECONDS_TO_REDUCE, metrics};
 
 use super::error::FfmpegError;
 
@@ -70,22 +70,24 @@ pub struct Frame {
 }
 
 pub fn get_nearest_keyframe_in_seconds(output_file: &str) -> Result<String, FfmpegError> {
-    let output = Command::new("ffprobe")
-        .arg("-select_streams")
-        .arg("v:0")
-        .arg("-show_frames")
-        .arg("-read_intervals")
-        .arg("%+#200")
-        .arg("-skip_frame")
-        .arg("nokey")
-        .arg("-print_format")
-        .arg("json")
-        .arg("-i")
-        .arg(&output_file)
-        .output()?;
+    let output = metrics::time_command("ffprobe", "keyframe", || {
+        Command::new("ffprobe")
+            .arg("-select_streams")
+            .arg("v:0")
+            .arg("-show_frames")
+            .arg("-read_intervals")
+            .arg("%+#200")
+            .arg("-skip_frame")
+            .arg("nokey")
+            .arg("-print_format")
+            .arg("json")
+            .arg("-i")
+            .arg(&output_file)
+            .output()
+    })?;
 
     if !output.status.success() {
-        println!(
+        tracing::error!(
             "get_nearest_keyframe_in_seconds failed. Error message: {}",
             String::from_utf8_lossy(&output.stderr)
         );
@@ -106,3 +108,57 @@ pub fn get_nearest_keyframe_in_seconds(output_file: &str) -> Result<String, Ffmp
 
     return Ok(keyframe.pkt_dts_time.to_string());
 }
+
+/// Width and height of the first video stream
+pub fn get_video_resolution(video_path: &str) -> Result<(u32, u32), FfmpegError> {
+    let output = metrics::time_command("ffprobe", "resolution", || {
+        Command::new("ffprobe")
+            .arg("-v")
+            .arg("error")
+            .arg("-select_streams")
+            .arg("v:0")
+            .arg("-show_entries")
+            .arg("stream=width,height")
+            .arg("-of")
+            .arg("csv=s=x:p=0")
+            .arg(video_path)
+            .output()
+    })?;
+
+    if !output.status.success() {
+        tracing::error!(
+            "get_video_resolution failed. Error message: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        return Err(FfmpegError::ProbeError(String::from(
+            "Failed to get the resolution of the video",
+        )));
+    }
+
+    let output = String::from_utf8_lossy(&output.stdout);
+    return parse_resolution(&output);
+}
+
+/// `1920x1080`, as printed by ffprobe
+fn parse_resolution(output: &str) -> Result<(u32, u32), FfmpegError> {
+    let invalid = || FfmpegError::ParseError(format!("Invalid resolution: {}", output.trim()));
+
+    let line = output.lines().next().ok_or_else(invalid)?;
+    let (width, height) = line.trim().split_once('x').ok_or_else(invalid)?;
+    let width = width.parse().map_err(|_| invalid())?;
+    let height = height.parse().map_err(|_| invalid())?;
+
+    return Ok((width, height));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_resolution() {
+        assert_eq!(parse_resolution("1080x1920\n").unwrap(), (1080, 1920));
+        assert!(parse_resolution("").is_err());
+        assert!(parse_resolution("N/Ax1080").is_err());
+    }
+}
@@ -1,6 +1,6 @@
 use std::io::{self, Read};
 use std::path::PathBuf;
-use std::process::{Command, Stdio};
+use std::process::{Command, Output, Stdio};
 use std::str::FromStr;
 
 use crate::SyncError;
@@ -8,7 +8,11 @@ use crate::SyncError;
 use self::error::FfmpegError;
 use self::time::Time;
 
-use super::fs::create_temp_dir;
+use super::{
+    fs::create_temp_dir,
+    metrics,
+    progress::{self, OnProgress},
+};
 
 pub mod error;
 pub mod ffprobe;
@@ -30,6 +34,12 @@ pub fn check() -> Result<(), io::Error> {
 }
 
 pub fn extract_audio_from_video_to_buff(video_path: &PathBuf) -> Result<Vec<u8>, io::Error> {
+    metrics::time_command("ffmpeg", "extract_audio", || {
+        extract_audio_to_buff(video_path)
+    })
+}
+
+fn extract_audio_to_buff(video_path: &PathBuf) -> Result<Vec<u8>, io::Error> {
     let mut ffmpeg_output = Command::new("ffmpeg")
         .arg("-hide_banner") // Hides FFmpeg banner
         .arg("-loglevel")
@@ -54,7 +64,7 @@ pub fn extract_audio_from_video_to_buff(video_path: &PathBuf) -> Result<Vec<u8>,
     stdout.read_to_end(&mut buffer)?;
 
     if let Err(err) = ffmpeg_output.wait() {
-        eprintln!("Error waiting for FFmpeg process: {}", err);
+        tracing::error!("Error waiting for FFmpeg process: {}", err);
         return Err(err);
     }
 
@@ -65,23 +75,25 @@ pub fn extract_audio_from_video_to_file(
     video_path: &PathBuf,
     audio_path: &PathBuf,
 ) -> Result<(), io::Error> {
-    let ffmpeg_output = Command::new("ffmpeg")
-        .arg("-i")
-        .arg(&video_path)
-        .arg("-vn")
-        .arg("-acodec")
-        .arg("libmp3lame")
-        .arg("-q:a")
-        .arg("2")
-        .arg(&audio_path)
-        .output()?;
+    let ffmpeg_output = metrics::time_command("ffmpeg", "extract_audio", || {
+        Command::new("ffmpeg")
+            .arg("-i")
+            .arg(&video_path)
+            .arg("-vn")
+            .arg("-acodec")
+            .arg("libmp3lame")
+            .arg("-q:a")
+            .arg("2")
+            .arg(&audio_path)
+            .output()
+    })?;
 
     if ffmpeg_output.status.success() {
-        println!("Audio extraction succeeded!");
+        tracing::info!("Audio extraction succeeded!");
         Ok(())
     } else {
         let error_message = String::from_utf8_lossy(&ffmpeg_output.stderr);
-        eprintln!("Audio extraction failed: {}", error_message);
+        tracing::error!("Audio extraction failed: {}", error_message);
         Err(std::io::Error::new(
             std::io::ErrorKind::Other,
             "Audio extraction failed",
@@ -93,8 +105,15 @@ pub fn subtitle_video_to_file(
     video_path: &PathBuf,
     srt_path: &PathBuf,
     output_path: &PathBuf,
+    on_progress: OnProgress<'_>,
 ) -> Result<(), SyncError> {
-    let output = Command::new("ffmpeg")
+    let duration = match get_video_duration(video_path) {
+        Ok(duration) => parse_seconds(&duration),
+        Err(_) => None,
+    };
+
+    let mut command = ffmpeg_with_progress();
+    command
         .arg("-i")
         .arg(&video_path)
         .arg("-vf")
@@ -102,17 +121,20 @@ pub fn subtitle_video_to_file(
         .arg("-c:a")
         .arg("copy")
         .arg(&output_path)
-        .arg("-y")
-        .output()?;
+        .arg("-y");
+
+    let output = metrics::time_command("ffmpeg", "subtitle", || {
+        output_with_progress(&mut command, duration, on_progress)
+    })?;
 
     match output.status.code() {
         Some(0) => {}
         Some(_) => {
-            println!("1:{:?}", output);
+            tracing::error!("1:{:?}", output);
             return Err("ffmpeg failed".into());
         }
         None => {
-            println!("2:{:?}", output);
+            tracing::error!("2:{:?}", output);
             return Err("ffmpeg failed".into());
         }
     }
@@ -120,18 +142,123 @@ pub fn subtitle_video_to_file(
     Ok(())
 }
 
+/// A subtitle file muxed as a track, `language` is its ISO 639-2 code
+pub struct SubtitleStream<'a> {
+    pub path: &'a str,
+    pub language: &'a str,
+}
+
+/// Muxes the subtitles as soft tracks, the first one is the default.
+/// The video is only encoded again when `burned` subtitles are drawn into it
+pub fn mux_subtitles_to_file(
+    video_path: &PathBuf,
+    streams: &[SubtitleStream<'_>],
+    burned: Option<&PathBuf>,
+    output_path: &PathBuf,
+    on_progress: OnProgress<'_>,
+) -> Result<(), SyncError> {
+    let duration = match get_video_duration(video_path) {
+        Ok(duration) => parse_seconds(&duration),
+        Err(_) => None,
+    };
+
+    let arguments = mux_arguments(
+        video_path.to_str().unwrap(),
+        streams,
+        burned.map(|path| path.to_str().unwrap()),
+        output_path.to_str().unwrap(),
+    )?;
+
+    let mut command = ffmpeg_with_progress();
+    command.args(arguments);
+
+    let output = metrics::time_command("ffmpeg", "mux_subtitles", || {
+        output_with_progress(&mut command, duration, on_progress)
+    })?;
+
+    if !output.status.success() {
+        tracing::error!("{:?}", output);
+        return Err("ffmpeg failed".into());
+    }
+
+    Ok(())
+}
+
+/// Arguments of the mux, also used to build the commands of remote subtitlers
+pub fn mux_arguments(
+    video: &str,
+    streams: &[SubtitleStream<'_>],
+    burned: Option<&str>,
+    output: &str,
+) -> Result<Vec<String>, FfmpegError> {
+    let container = output.rsplit_once('.').map(|(_, extension)| extension);
+    let container = container.unwrap_or_default().to_lowercase();
+
+    let mut arguments = vec!["-i".to_string(), video.to_string()];
+    for stream in streams {
+        arguments.extend(["-i".to_string(), stream.path.to_string()]);
+    }
+
+    arguments.extend(["-map", "0:v", "-map", "0:a?"].map(String::from));
+    for index in 0..streams.len() {
+        arguments.extend(["-map".to_string(), format!("{}:s", index + 1)]);
+    }
+
+    match burned {
+        Some(path) => arguments.extend(["-vf".to_string(), format!("subtitles={}", path)]),
+        None => arguments.extend(["-c:v", "copy"].map(String::from)),
+    };
+    arguments.extend(["-c:a", "copy"].map(String::from));
+
+    for (index, stream) in streams.iter().enumerate() {
+        let disposition = match index {
+            0 => "default",
+            _ => "0",
+        };
+
+        arguments.extend([
+            format!("-c:s:{}", index),
+            subtitle_codec(&container, stream.path)?.to_string(),
+            format!("-metadata:s:s:{}", index),
+            format!("language={}", stream.language),
+            format!("-disposition:s:{}", index),
+            disposition.to_string(),
+        ]);
+    }
+
+    arguments.extend([output.to_string(), "-y".to_string()]);
+    Ok(arguments)
+}
+
+/// mov_text for mp4, the srt or ass of the file for mkv and WebVTT for webm
+fn subtitle_codec(container: &str, subtitles_path: &str) -> Result<&'static str, FfmpegError> {
+    let is_ass = subtitles_path.ends_with(".ass") || subtitles_path.ends_with(".ssa");
+
+    match container {
+        "mp4" | "m4v" | "mov" => Ok("mov_text"),
+        "mkv" if is_ass => Ok("ass"),
+        "mkv" => Ok("srt"),
+        "webm" => Ok("webvtt"),
+        _ => Err(FfmpegError::UnsupportedContainer(container.to_string())),
+    }
+}
+
 pub fn get_video_duration(video_path: &PathBuf) -> Result<String, io::Error> {
-    let output = Command::new("ffmpeg").arg("-i").arg(&video_path).output()?;
+    let output = metrics::time_command("ffmpeg", "duration", || {
+        Command::new("ffmpeg").arg("-i").arg(&video_path).output()
+    })?;
 
     let output = String::from_utf8_lossy(&output.stderr); // ffmpeg will error cause because none output file is specified,this is ok
 
     parse_ffmpeg_output_duration(&output)
 }
 
+/// The cut runs twice, each run reports half of the progress
 pub fn cut_video(
     video_path: &PathBuf,
     start_time: &str,
     end_time: &str,
+    on_progress: OnProgress<'_>,
 ) -> Result<String, FfmpegError> {
     let temp_output_id = uuid::Uuid::new_v4();
     let temp_dir = create_temp_dir()?;
@@ -139,17 +266,24 @@ pub fn cut_video(
 
     let reduced_start_time = reduce_start_time(start_time)?;
 
+    let duration = match (parse_seconds(end_time), parse_seconds(&reduced_start_time)) {
+        (Some(end), Some(start)) => Some(end - start),
+        _ => None,
+    };
+
     call_cut_command(
         video_path,
         &reduced_start_time,
         Some(end_time),
         &temp_output_file,
+        duration,
+        &|percentage| on_progress(percentage / 2.0),
     )?;
 
     let start_time = match ffprobe::get_nearest_keyframe_in_seconds(&temp_output_file) {
         Ok(start_time) => start_time,
         Err(err) => {
-            eprintln!("Failed to get nearest keyframe in seconds: {}", err);
+            tracing::error!("Failed to get nearest keyframe in seconds: {}", err);
             std::fs::remove_file(&temp_output_file)?;
             return Err(std::io::Error::new(
                 std::io::ErrorKind::Other,
@@ -169,13 +303,15 @@ pub fn cut_video(
         &start_time.to_string(),
         None,
         &output_file,
+        duration,
+        &|percentage| on_progress(50.0 + percentage / 2.0),
     ) {
         Ok(_) => {
             std::fs::remove_file(&temp_output_file)?;
             Ok(output_file)
         }
         Err(err) => {
-            eprintln!("Failed to cut video: {}", err);
+            tracing::error!("Failed to cut video: {}", err);
             std::fs::remove_file(&temp_output_file)?;
             Err(err)
         }
@@ -210,8 +346,10 @@ fn call_cut_command(
     start_time: &str,
     end_time: Option<&str>,
     output_file: &str,
+    duration: Option<f64>,
+    on_progress: OnProgress<'_>,
 ) -> Result<(), FfmpegError> {
-    let mut command = Command::new("ffmpeg");
+    let mut command = ffmpeg_with_progress();
 
     command
         .arg("-i")
@@ -225,15 +363,13 @@ fn call_cut_command(
         }
         None => {}
     };
-    let output = command
-        .arg("-c")
-        .arg("copy")
-        .arg(&output_file)
-        .arg("-y")
-        .output()?;
+    command.arg("-c").arg("copy").arg(&output_file).arg("-y");
+    let output = metrics::time_command("ffmpeg", "cut", || {
+        output_with_progress(&mut command, duration, on_progress)
+    })?;
 
     if !output.status.success() {
-        println!(
+        tracing::error!(
             "Video cut failed. Error message: {}",
             String::from_utf8_lossy(&output.stderr)
         );
@@ -243,6 +379,43 @@ fn call_cut_command(
     return Ok(());
 }
 
+/// ffmpeg writing its progress to stdout, one `key=value` per line
+fn ffmpeg_with_progress() -> Command {
+    let mut command = Command::new("ffmpeg");
+    command.arg("-progress").arg("pipe:1").arg("-nostats");
+    command
+}
+
+/// Runs a command from `ffmpeg_with_progress`, reporting how much of the `duration` seconds was written
+fn output_with_progress(
+    command: &mut Command,
+    duration: Option<f64>,
+    on_progress: OnProgress<'_>,
+) -> Result<Output, io::Error> {
+    let parse_line = |line: &str| {
+        let written = parse_progress_line(line)?;
+        progress::percentage(written, duration?)
+    };
+
+    progress::output_with_progress(command, parse_line, on_progress)
+}
+
+/// Seconds of output on a `-progress` line, `out_time_us` is in microseconds
+fn parse_progress_line(line: &str) -> Option<f64> {
+    let micros = line.strip_prefix("out_time_us=")?;
+    let micros: f64 = micros.trim().parse().ok()?;
+    Some(micros / 1_000_000.0)
+}
+
+/// Seconds of a `HH:MM:SS` duration, the seconds may have a fraction
+fn parse_seconds(duration: &str) -> Option<f64> {
+    let mut seconds = 0.0;
+    for part in duration.trim().split(':') {
+        seconds = seconds * 60.0 + part.parse::<f64>().ok()?;
+    }
+    Some(seconds)
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -256,6 +429,54 @@ mod test {
         assert_eq!(duration, "00:00:00.04");
     }
 
+    #[test]
+    fn test_parse_progress_line() {
+        assert_eq!(parse_progress_line("out_time_us=1500000"), Some(1.5));
+        assert_eq!(parse_progress_line("out_time_us=N/A"), None);
+        assert_eq!(parse_progress_line("frame=10"), None);
+    }
+
+    #[test]
+    fn test_mux_arguments() {
+        let streams = [
+            SubtitleStream {
+                path: "id.pt-br.srt",
+                language: "por",
+            },
+            SubtitleStream {
+                path: "id.ass",
+                language: "eng",
+            },
+        ];
+
+        let arguments = mux_arguments("input.mkv", &streams, None, "output.mkv").unwrap();
+        let expected = "-i input.mkv -i id.pt-br.srt -i id.ass -map 0:v -map 0:a? -map 1:s -map 2:s -c:v copy -c:a copy -c:s:0 srt -metadata:s:s:0 language=por -disposition:s:0 default -c:s:1 ass -metadata:s:s:1 language=eng -disposition:s:1 0 output.mkv -y";
+        assert_eq!(arguments.join(" "), expected);
+
+        let arguments = mux_arguments(
+            "input.mp4",
+            &streams[..1],
+            Some("id.pt-br.srt"),
+            "output.mp4",
+        )
+        .unwrap();
+        let arguments = arguments.join(" ");
+        assert!(arguments.contains("-vf subtitles=id.pt-br.srt -c:a copy -c:s:0 mov_text"));
+        assert!(!arguments.contains("-c:v copy"));
+
+        let arguments = mux_arguments("input.webm", &streams[..1], None, "output.webm").unwrap();
+        assert!(arguments.join(" ").contains("-c:s:0 webvtt"));
+
+        assert!(mux_arguments("input.ogg", &streams, None, "output.ogg").is_err());
+    }
+
+    #[test]
+    fn test_parse_seconds() {
+        assert_eq!(parse_seconds("01:02:03.5"), Some(3723.5));
+        assert_eq!(parse_seconds("00:10:00"), Some(600.0));
+        assert_eq!(parse_seconds("invalid"), None);
+    }
+
     /* local test
     #[test]
     fn test_cut_video() {
@@ -117,7 +117,7 @@ impl FromStr for Time {
 
         // second time checking minutes to make sure we didn't overflow.
         if minutes > 60 {
-            println!("minutes: {}", minutes);
+            tracing::debug!("minutes: {}", minutes);
             hours = hours
                 .checked_add(minutes / 60)
                 .ok_or(FfmpegError::ParseError(
@@ -1,11 +1,25 @@
 use std::path::PathBuf;
 
-pub fn create_temp_dir() -> Result<PathBuf, std::io::Error> {
+fn temp_dir() -> PathBuf {
     let root = std::env::current_dir().unwrap();
-    let temp_dir = root.join("temp");
+    root.join("temp")
+}
+
+pub fn create_temp_dir() -> Result<PathBuf, std::io::Error> {
+    let temp_dir = temp_dir();
     std::fs::create_dir_all(&temp_dir)?;
     Ok(temp_dir)
 }
+
+/// Deletes the files every handler left on the temp dir
+pub fn remove_temp_dir() -> Result<(), std::io::Error> {
+    match std::fs::remove_dir_all(temp_dir()) {
+        Ok(_) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(e),
+    }
+}
+
 pub fn check_file_size(path: &PathBuf) -> Result<u64, std::io::Error> {
     let metadata = std::fs::metadata(path)?;
     let size = metadata.len();
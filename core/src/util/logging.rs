@@ -0,0 +1,14 @@
+use tracing_subscriber::EnvFilter;
+
+const DEFAULT_FILTER: &str = "info";
+
+/// Prints the logs of every crate with the span they happened in,
+/// filtered by `RUST_LOG` (e.g. `RUST_LOG=marco_polo_rs_queue=debug,info`)
+pub fn init() {
+    let filter = match EnvFilter::try_from_default_env() {
+        Ok(filter) => filter,
+        Err(_) => EnvFilter::new(DEFAULT_FILTER),
+    };
+
+    tracing_subscriber::fmt().with_env_filter(filter).init();
+}
@@ -0,0 +1,136 @@
+use std::time::{Duration, Instant};
+
+use metrics::{describe_counter, describe_histogram, histogram, increment_counter, Unit};
+use metrics_exporter_prometheus::{Matcher, PrometheusBuilder};
+
+pub const HANDLER_DURATION: &str = "marco_polo_handler_duration_seconds";
+pub const HANDLER_ERRORS: &str = "marco_polo_handler_errors_total";
+pub const QUEUE_RECEIVE_LAG: &str = "marco_polo_queue_receive_lag_seconds";
+pub const COMMAND_DURATION: &str = "marco_polo_command_duration_seconds";
+pub const HTTP_REQUEST_DURATION: &str = "marco_polo_http_request_duration_seconds";
+pub const OUTBOX_FAILURES: &str = "marco_polo_outbox_failures_total";
+
+/// Handlers and commands take from seconds to hours
+const LONG_BUCKETS: &[f64] = &[
+    0.5, 1.0, 5.0, 15.0, 30.0, 60.0, 300.0, 900.0, 1800.0, 3600.0, 7200.0,
+];
+const HTTP_BUCKETS: &[f64] = &[0.005, 0.01, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0];
+
+/// Prometheus exporter with the buckets of every histogram, install it once per process
+pub fn builder() -> PrometheusBuilder {
+    describe();
+
+    PrometheusBuilder::new()
+        .set_buckets_for_metric(Matcher::Full(HANDLER_DURATION.to_string()), LONG_BUCKETS)
+        .and_then(|builder| {
+            builder
+                .set_buckets_for_metric(Matcher::Full(QUEUE_RECEIVE_LAG.to_string()), LONG_BUCKETS)
+        })
+        .and_then(|builder| {
+            builder
+                .set_buckets_for_metric(Matcher::Full(COMMAND_DURATION.to_string()), LONG_BUCKETS)
+        })
+        .and_then(|builder| {
+            builder.set_buckets_for_metric(
+                Matcher::Full(HTTP_REQUEST_DURATION.to_string()),
+                HTTP_BUCKETS,
+            )
+        })
+        .expect("The histogram buckets are not empty")
+}
+
+fn describe() {
+    describe_histogram!(
+        HANDLER_DURATION,
+        Unit::Seconds,
+        "Time a worker took to handle a payload, by payload and result"
+    );
+    describe_counter!(
+        HANDLER_ERRORS,
+        "Payloads that failed, by payload and kind of error"
+    );
+    describe_histogram!(
+        QUEUE_RECEIVE_LAG,
+        Unit::Seconds,
+        "Time between a message being sent and received"
+    );
+    describe_histogram!(
+        COMMAND_DURATION,
+        Unit::Seconds,
+        "Time ffmpeg, ffprobe and yt-dlp took to run, by command and operation"
+    );
+    describe_histogram!(
+        HTTP_REQUEST_DURATION,
+        Unit::Seconds,
+        "Time the api took to answer, by method, route and status"
+    );
+    describe_counter!(
+        OUTBOX_FAILURES,
+        "Outbox messages the relay gave up on, kept as failed messages"
+    );
+}
+
+pub fn record_handler(payload: &'static str, result: &'static str, duration: Duration) {
+    histogram!(HANDLER_DURATION, duration, "payload" => payload, "result" => result);
+}
+
+pub fn record_handler_error(payload: &'static str, kind: &'static str) {
+    increment_counter!(HANDLER_ERRORS, "payload" => payload, "kind" => kind);
+}
+
+pub fn record_outbox_failure() {
+    increment_counter!(OUTBOX_FAILURES);
+}
+
+pub fn record_receive_lag(lag: Duration) {
+    histogram!(QUEUE_RECEIVE_LAG, lag);
+}
+
+pub fn record_http_request(method: String, route: String, status: u16, duration: Duration) {
+    histogram!(
+        HTTP_REQUEST_DURATION,
+        duration,
+        "method" => method,
+        "route" => route,
+        "status" => status.to_string()
+    );
+}
+
+/// Runs an external command, recording how long it took
+pub fn time_command<T>(
+    command: &'static str,
+    operation: &'static str,
+    run: impl FnOnce() -> T,
+) -> T {
+    let start = Instant::now();
+    let result = run();
+    histogram!(COMMAND_DURATION, start.elapsed(), "command" => command, "operation" => operation);
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use metrics::Recorder;
+
+    use super::*;
+
+    #[test]
+    fn test_recorder_renders_histograms_with_buckets() {
+        let recorder = builder().build_recorder();
+        let handle = recorder.handle();
+
+        let key = metrics::Key::from_parts(
+            COMMAND_DURATION,
+            vec![
+                metrics::Label::new("command", "ffmpeg"),
+                metrics::Label::new("operation", "cut"),
+            ],
+        );
+        recorder.register_histogram(&key).record(2.0);
+
+        let rendered = handle.render();
+        assert!(rendered.contains(
+            "marco_polo_command_duration_seconds_bucket{command=\"ffmpeg\",operation=\"cut\",le=\"5\"} 1"
+        ));
+    }
+}
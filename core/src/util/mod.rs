@@ -18,6 +18,10 @@ macro_rules! time_it {
 pub(crate) use time_it;
 pub mod ffmpeg;
 pub mod fs;
+pub mod logging;
+pub mod metrics;
+pub mod progress;
 pub mod queue;
 pub mod security;
 pub mod srt;
+pub mod subtitles;
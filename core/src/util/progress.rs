@@ -0,0 +1,96 @@
+use std::{
+    io::{self, BufRead, BufReader, Read},
+    process::{Command, Output, Stdio},
+};
+
+/// Called with how far a long step is, from 0 to 100
+pub type OnProgress<'a> = &'a (dyn Fn(f32) + Send + Sync);
+
+/// For the callers that don't report progress
+pub fn ignore(_: f32) {}
+
+/// Percentage of `done` out of `total`, `None` when the total is unknown
+pub fn percentage(done: f64, total: f64) -> Option<f32> {
+    if total <= 0.0 {
+        return None;
+    }
+
+    Some((done / total * 100.0).clamp(0.0, 100.0) as f32)
+}
+
+/// Runs the command like `Command::output`, reporting the progress `parse_line` finds on its stdout.
+/// The stdout itself is not kept
+pub fn output_with_progress(
+    command: &mut Command,
+    parse_line: impl Fn(&str) -> Option<f32>,
+    on_progress: OnProgress<'_>,
+) -> Result<Output, io::Error> {
+    let mut child = command
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+
+    let stdout = child
+        .stdout
+        .take()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::Other, "Failed to capture stdout"))?;
+    let mut stderr = child
+        .stderr
+        .take()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::Other, "Failed to capture stderr"))?;
+
+    // the command blocks once a pipe is full, so stderr is read while the progress is
+    let stderr_reader = std::thread::spawn(move || {
+        let mut buffer = Vec::new();
+        let _ = stderr.read_to_end(&mut buffer);
+        buffer
+    });
+
+    for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+        if let Some(percentage) = parse_line(&line) {
+            on_progress(percentage);
+        }
+    }
+
+    let status = child.wait()?;
+    let stderr = stderr_reader.join().unwrap_or_default();
+
+    Ok(Output {
+        status,
+        stdout: Vec::new(),
+        stderr,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use std::{process::Command, sync::Mutex};
+
+    use super::{output_with_progress, percentage};
+
+    #[test]
+    fn test_percentage() {
+        assert_eq!(percentage(5.0, 20.0), Some(25.0));
+        assert_eq!(percentage(30.0, 20.0), Some(100.0));
+        assert_eq!(percentage(5.0, 0.0), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_output_with_progress() {
+        let reported = Mutex::new(vec![]);
+        let mut command = Command::new("sh");
+        command
+            .arg("-c")
+            .arg("echo 10; echo other; echo 50; echo failed >&2; exit 1");
+
+        let output = output_with_progress(&mut command, |line| line.parse().ok(), &|percentage| {
+            reported.lock().unwrap().push(percentage)
+        })
+        .unwrap();
+
+        assert_eq!(*reported.lock().unwrap(), vec![10.0, 50.0]);
+        assert!(!output.status.success());
+        assert_eq!(output.stderr, b"failed\n");
+    }
+}
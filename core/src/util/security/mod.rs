@@ -1 +1,3 @@
 pub mod hash;
+pub mod signature;
+pub mod url_policy;
@@ -0,0 +1,26 @@
+use ring::hmac;
+
+/// Hex HMAC-SHA256 of the body, lets the receiver of a webhook check who sent it
+pub fn sign(secret: &str, body: &str) -> String {
+    let key = hmac::Key::new(hmac::HMAC_SHA256, secret.as_bytes());
+    let signature = hmac::sign(&key, body.as_bytes());
+
+    signature
+        .as_ref()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect::<String>()
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_sign() {
+        let signature = super::sign("test", "hello world");
+
+        assert_eq!(
+            signature,
+            "d1596e0d4280f2bd2d311ce0819f23bde0dc834d8254b92924088de94c38d922"
+        );
+    }
+}
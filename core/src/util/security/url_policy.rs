@@ -0,0 +1,167 @@
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+
+use reqwest::{redirect, ClientBuilder, Url};
+
+use crate::SyncError;
+
+/// Where requests may be sent on behalf of the users, like their webhooks.
+/// Only https urls of public addresses by default, the `allowed_hosts` are trusted
+/// as they are, e.g. a receiver on the same machine during development
+#[derive(Debug, Clone, Default)]
+pub struct UrlPolicy {
+    pub allowed_hosts: Vec<String>,
+}
+
+impl UrlPolicy {
+    /// The allowed hosts are the comma separated `WEBHOOK_ALLOWED_HOSTS`
+    pub fn from_env() -> Self {
+        let allowed_hosts = match std::env::var("WEBHOOK_ALLOWED_HOSTS") {
+            Ok(hosts) => hosts
+                .split(',')
+                .map(|host| host.trim().to_string())
+                .filter(|host| !host.is_empty())
+                .collect(),
+            Err(_) => vec![],
+        };
+
+        return Self { allowed_hosts };
+    }
+
+    /// Resolves the host of the url, refusing the ones that point inside the network.
+    /// Returns the addresses that were checked, none for the allowed hosts
+    pub async fn check(&self, url: &str) -> Result<Vec<SocketAddr>, SyncError> {
+        let url = Url::parse(url)?;
+        let host = url.host_str().ok_or("The url has no host")?;
+
+        if self.is_allowed(host) {
+            return Ok(vec![]);
+        }
+
+        if url.scheme() != "https" {
+            return Err("Only https urls are allowed".into());
+        }
+
+        // ipv6 hosts keep their brackets
+        let host = host.trim_start_matches('[').trim_end_matches(']');
+        let port = url.port_or_known_default().unwrap_or(443);
+        let addresses: Vec<SocketAddr> = tokio::net::lookup_host((host, port)).await?.collect();
+
+        if addresses.is_empty() {
+            return Err(format!("{} has no addresses", host).into());
+        }
+
+        if let Some(address) = addresses.iter().find(|address| !is_public(address.ip())) {
+            let error = format!("{} points to the private address {}", host, address.ip());
+            return Err(error.into());
+        }
+
+        return Ok(addresses);
+    }
+
+    /// A client that only connects to the addresses that were checked, so the host can't
+    /// resolve somewhere else in between, and that doesn't follow redirects
+    pub async fn client_builder(&self, url: &str) -> Result<ClientBuilder, SyncError> {
+        let addresses = self.check(url).await?;
+        let builder = reqwest::Client::builder().redirect(redirect::Policy::none());
+
+        let url = Url::parse(url)?;
+        return match url.domain() {
+            Some(domain) if !addresses.is_empty() => {
+                Ok(builder.resolve_to_addrs(domain, &addresses))
+            }
+            _ => Ok(builder),
+        };
+    }
+
+    fn is_allowed(&self, host: &str) -> bool {
+        self.allowed_hosts
+            .iter()
+            .any(|allowed| allowed.eq_ignore_ascii_case(host))
+    }
+}
+
+fn is_public(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_public_v4(ip),
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(ip) => is_public_v4(ip),
+            None => is_public_v6(ip),
+        },
+    }
+}
+
+fn is_public_v4(ip: Ipv4Addr) -> bool {
+    let [first, second, ..] = ip.octets();
+    let this_network = first == 0;
+    let shared = first == 100 && (64..128).contains(&second);
+
+    return !(ip.is_private()
+        || ip.is_loopback()
+        || ip.is_link_local()
+        || ip.is_unspecified()
+        || ip.is_broadcast()
+        || ip.is_documentation()
+        || this_network
+        || shared);
+}
+
+fn is_public_v6(ip: Ipv6Addr) -> bool {
+    let first = ip.segments()[0];
+    let unique_local = (first & 0xfe00) == 0xfc00;
+    let link_local = (first & 0xffc0) == 0xfe80;
+
+    return !(ip.is_loopback() || ip.is_unspecified() || unique_local || link_local);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::IpAddr;
+
+    use super::{is_public, UrlPolicy};
+
+    #[test]
+    fn test_is_public() {
+        let public = ["93.184.216.34", "2606:2800:220:1:248:1893:25c8:1946"];
+        let private = [
+            "127.0.0.1",
+            "10.0.0.1",
+            "172.16.5.4",
+            "192.168.0.10",
+            "169.254.169.254",
+            "100.64.0.1",
+            "0.0.0.0",
+            "::1",
+            "fd00::1",
+            "fe80::1",
+            "::ffff:127.0.0.1",
+        ];
+
+        for ip in public {
+            assert!(is_public(ip.parse::<IpAddr>().unwrap()), "{}", ip);
+        }
+        for ip in private {
+            assert!(!is_public(ip.parse::<IpAddr>().unwrap()), "{}", ip);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_check() {
+        let policy = UrlPolicy::default();
+
+        assert!(policy.check("https://93.184.216.34/hook").await.is_ok());
+        assert!(policy.check("http://93.184.216.34/hook").await.is_err());
+        assert!(policy.check("https://127.0.0.1/hook").await.is_err());
+        assert!(policy.check("https://[::1]/hook").await.is_err());
+        assert!(policy.check("https://169.254.169.254/").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_allowed_hosts_are_trusted() {
+        let policy = UrlPolicy {
+            allowed_hosts: vec!["127.0.0.1".to_string()],
+        };
+
+        let addresses = policy.check("http://127.0.0.1:9/hook").await.unwrap();
+        assert!(addresses.is_empty());
+    }
+}
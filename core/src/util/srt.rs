@@ -1,89 +1,60 @@
 use chrono::{Duration, NaiveTime};
 
-use crate::internals::transcriber::traits::Sentence;
-
-const MAX_SENTENCE_LENGTH: usize = 110; // including spaces
-
-pub fn create_based_on_sentences(sentences: Vec<Sentence>) -> String {
-    let mut srt_file_raw = String::new();
-
-    let sentences: Vec<Sentence> = sentences
-        .into_iter()
-        .flat_map(|sentence| split_sentence(sentence))
-        .collect();
-
-    for (index, sentence) in sentences.into_iter().enumerate() {
-        let start_time = sentence.start_time;
-        let end_time = sentence.end_time;
-        let text = sentence.text;
-
-        let index = format!("{}\n", index + 1);
-        srt_file_raw.push_str(&index);
-
-        let start_time = format_milliseconds(start_time as u32);
-        let end_time = format_milliseconds(end_time as u32);
-        let time = format!("{} --> {}\n", start_time, end_time);
-        srt_file_raw.push_str(&time);
-
-        let text = format!("{}\n\n", text);
-        srt_file_raw.push_str(&text);
+use crate::{
+    database::models::video::speaker_labels::SpeakerLabels,
+    internals::transcriber::traits::Sentence,
+};
+
+use super::subtitles::{
+    segmentation::{self, SegmentationOptions},
+    speakers, Document, Format,
+};
+
+/// Breaks the sentences into cues that fit on screen, timed by their words,
+/// and labels their speakers
+pub fn create_based_on_sentences(sentences: Vec<Sentence>, labels: SpeakerLabels) -> String {
+    let mut options = SegmentationOptions::default();
+    if labels == SpeakerLabels::Dashes {
+        // room for the dash
+        options.max_line_length -= 2;
     }
 
-    return srt_file_raw;
+    let mut cues = segmentation::segment(sentences, &options);
+    speakers::label(&mut cues, labels);
+    return write(cues);
 }
 
-fn split_sentence(sentence: Sentence) -> Vec<Sentence> {
-    let mut new_sentences: Vec<Sentence> = vec![];
-    let text = sentence.text;
-    let start_time = sentence.start_time;
-    let end_time = sentence.end_time;
-    let text_len = text.len();
-
-    if text_len <= MAX_SENTENCE_LENGTH {
-        new_sentences.push(Sentence {
-            text,
-            start_time,
-            end_time,
-        });
-        return new_sentences;
-    }
-
-    let mut sentences: Vec<Sentence> = vec![];
-    let time_diff = end_time - start_time;
-    let half_time = time_diff / 2;
-
-    let text = text.split_whitespace().collect::<Vec<&str>>();
-    let middle = text.len() / 2;
-    let first_half = text[..middle].join(" ");
-    let second_half = text[middle..].join(" ");
-
-    let first_half_sentence = Sentence {
-        text: first_half,
-        start_time,
-        end_time: start_time + half_time,
-    };
-
-    let second_half_sentence = Sentence {
-        text: second_half,
-        start_time: start_time + half_time,
-        end_time,
-    };
-
-    let first_half_sentences = split_sentence(first_half_sentence);
-    let second_half_sentences = split_sentence(second_half_sentence);
+/// Writes the sentences as they are, one srt line each
+pub fn write(sentences: Vec<Sentence>) -> String {
+    return Document::from_sentences(sentences).write(Format::Srt);
+}
 
-    sentences.extend(first_half_sentences);
-    sentences.extend(second_half_sentences);
+/// Reads the lines of a srt file, indexes are ignored and rewritten by `write`
+pub fn parse(srt: &str) -> Result<Vec<Sentence>, String> {
+    let document = Document::parse(srt, Format::Srt).map_err(|e| e.to_string())?;
+    return Ok(document.into_sentences());
+}
 
-    return sentences;
+/// Lines must have text, end after they start and follow each other without overlapping
+pub fn validate(sentences: &[Sentence]) -> Result<(), String> {
+    let document = Document::from_sentences(sentences.to_vec());
+    return document.validate().map_err(|e| e.to_string());
 }
 
-fn format_milliseconds(ms: u32) -> String {
+pub fn format_milliseconds(ms: u32) -> String {
     let duration = Duration::milliseconds(ms as i64);
     let time = NaiveTime::from_hms_opt(0, 0, 0).unwrap() + duration;
     return time.format("%H:%M:%S,%3f").to_string();
 }
 
+/// Parses the `HH:MM:SS,mmm` timestamps of the srt files
+pub fn parse_milliseconds(time: &str) -> Result<i32, String> {
+    let time = NaiveTime::parse_from_str(time, "%H:%M:%S,%3f")
+        .map_err(|_| format!("Invalid time: {}", time))?;
+    let midnight = NaiveTime::from_hms_opt(0, 0, 0).unwrap();
+    return Ok((time - midnight).num_milliseconds() as i32);
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -95,62 +66,90 @@ mod tests {
         assert_eq!(formatted, "00:00:01,000");
     }
 
-    #[test]
-    fn test_split_sentence() {
-        let sentence = Sentence {
-            text: "Ele é capaz de decodificar, codificar, transcodificar, multiplexar D, multiplexar fluxo, filtrar e reproduzir praticamente qualquer arquivo multimídia do mundo, com suporte a mais de 100 codecs diferentes.".to_string(),
-            start_time: 0,
-            end_time: 1000,
-        };
-
-        let sentences = split_sentence(sentence);
-        assert_eq!(sentences.len(), 2);
-        let first_sentence = &sentences[0];
-        let second_sentence = &sentences[1];
-
-        assert!(first_sentence.text.len() <= MAX_SENTENCE_LENGTH);
-        assert!(second_sentence.text.len() <= MAX_SENTENCE_LENGTH);
-
-        assert_eq!(first_sentence.start_time, 0);
-        assert_eq!(first_sentence.end_time, 500);
-
-        assert_eq!(second_sentence.start_time, 500);
-        assert_eq!(second_sentence.end_time, 1000);
-    }
-
     #[test]
     fn test_srt() {
-        let expected_text: &str = "1\n00:00:01,370 --> 00:00:02,654\nSua vida não é nada.\n\n2\n00:00:02,772 --> 00:00:04,750\nVocê não serve para nada.\n\n3\n00:00:05,170 --> 00:00:10,438\nVocê deveria se matar agora e dar a outra pessoa um pedaço da camada\n\n4\n00:00:10,438 --> 00:00:15,706\nde oxigênio e ozônio que está encoberta para que possamos respirar dentro dessa bolha azul.\n\n5\n00:00:15,818 --> 00:00:17,150\nPorque você está aqui para quê?\n\n6\n00:00:17,220 --> 00:00:17,340\nPara.\n\n";
+        let expected_text: &str = "1\n00:00:01,370 --> 00:00:02,654\nSua vida não é nada.\n\n2\n00:00:02,772 --> 00:00:04,750\nVocê não serve para nada.\n\n3\n00:00:05,170 --> 00:00:10,570\nVocê deveria se matar agora e dar a outra\npessoa um pedaço da camada de oxigênio e\n\n4\n00:00:10,636 --> 00:00:15,706\nozônio que está encoberta para que\npossamos respirar dentro dessa bolha azul.\n\n5\n00:00:15,818 --> 00:00:17,220\nPorque você está aqui para quê?\n\n6\n00:00:17,220 --> 00:00:18,220\nPara.\n\n";
 
         let senteces = vec![
             Sentence {
                 text: "Sua vida não é nada.".to_string(),
                 start_time: 1370,
                 end_time: 2654,
+                words: None,
+                speaker: None,
             },
             Sentence {
                 text: "Você não serve para nada.".to_string(),
                 start_time: 2772,
                 end_time: 4750,
+                words: None,
+                speaker: None,
             },
             Sentence {
                 text: "Você deveria se matar agora e dar a outra pessoa um pedaço da camada de oxigênio e ozônio que está encoberta para que possamos respirar dentro dessa bolha azul.".to_string(),
                 start_time: 5170,
                 end_time: 15706,
+                words: None,
+                speaker: None,
             },
             Sentence {
                 text: "Porque você está aqui para quê?".to_string(),
                 start_time: 15818,
                 end_time: 17150,
+                words: None,
+                speaker: None,
             },
             Sentence {
                 text: "Para.".to_string(),
                 start_time: 17220,
                 end_time: 17340,
+                words: None,
+                speaker: None,
             },
         ];
 
-        let srt = create_based_on_sentences(senteces);
+        let srt = create_based_on_sentences(senteces, SpeakerLabels::None);
         assert_eq!(srt, expected_text);
     }
+
+    #[test]
+    fn test_parse_milliseconds() {
+        assert_eq!(parse_milliseconds("01:02:03,456").unwrap(), 3723456);
+        assert!(parse_milliseconds("01:02").is_err());
+    }
+
+    #[test]
+    fn test_parse_and_write() {
+        let srt = "1\r\n00:00:01,370 --> 00:00:02,654\r\nSua vida não é nada.\r\n\r\n2\r\n00:00:02,772 --> 00:00:04,750\r\nVocê não serve\r\npara nada.\r\n";
+
+        let sentences = parse(srt).unwrap();
+        assert_eq!(sentences.len(), 2);
+        assert_eq!(sentences[0].start_time, 1370);
+        assert_eq!(sentences[0].end_time, 2654);
+        assert_eq!(sentences[1].text, "Você não serve\npara nada.");
+
+        let expected = "1\n00:00:01,370 --> 00:00:02,654\nSua vida não é nada.\n\n2\n00:00:02,772 --> 00:00:04,750\nVocê não serve\npara nada.\n\n";
+        assert_eq!(write(sentences), expected);
+    }
+
+    #[test]
+    fn test_parse_invalid_time() {
+        assert!(parse("1\n00:00:01 -> 00:00:02\nOi\n").is_err());
+    }
+
+    #[test]
+    fn test_validate() {
+        let sentence = |start_time, end_time, text: &str| Sentence {
+            start_time,
+            end_time,
+            text: text.to_string(),
+            words: None,
+            speaker: None,
+        };
+
+        assert!(validate(&[sentence(0, 1000, "Oi"), sentence(1000, 2000, "Tchau")]).is_ok());
+        assert!(validate(&[sentence(0, 1000, " ")]).is_err());
+        assert!(validate(&[sentence(1000, 1000, "Oi")]).is_err());
+        assert!(validate(&[sentence(0, 1500, "Oi"), sentence(1000, 2000, "Tchau")]).is_err());
+    }
 }
@@ -0,0 +1,651 @@
+use super::{to_millis, Cue, Document, Style, SubtitleError};
+
+const STYLE_FORMAT: &str = "Name, Fontname, Fontsize, PrimaryColour, SecondaryColour, OutlineColour, BackColour, Bold, Italic, Underline, StrikeOut, ScaleX, ScaleY, Spacing, Angle, BorderStyle, Outline, Shadow, Alignment, MarginL, MarginR, MarginV, Encoding";
+const EVENT_FORMAT: &str =
+    "Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text";
+
+enum Section {
+    ScriptInfo,
+    /// SSA files have `[V4 Styles]`, with other alignments and decimal colors
+    Styles {
+        ssa: bool,
+    },
+    Events,
+    Other,
+}
+
+/// Only the fields of the model are read, layers, names, effects and the
+/// margins of the dialogues are dropped
+pub(super) fn parse(input: &str) -> Result<Document, SubtitleError> {
+    let mut section = None;
+    let mut style_format: Option<Vec<String>> = None;
+    let mut event_format: Option<Vec<String>> = None;
+    let mut styles = vec![];
+    let mut cues = vec![];
+    let mut width = None;
+    let mut height = None;
+
+    for (index, line) in input.split('\n').enumerate() {
+        let number = index + 1;
+        let line = line.trim();
+
+        if line.is_empty() || line.starts_with(';') {
+            continue;
+        }
+
+        if line.starts_with('[') && line.ends_with(']') {
+            section = Some(match line.to_lowercase().as_str() {
+                "[script info]" => Section::ScriptInfo,
+                "[v4+ styles]" => Section::Styles { ssa: false },
+                "[v4 styles]" => Section::Styles { ssa: true },
+                "[events]" => Section::Events,
+                _ => Section::Other,
+            });
+            continue;
+        }
+
+        let ssa = match section {
+            None => {
+                return Err(SubtitleError::at(
+                    number,
+                    "Missing the [Script Info] section",
+                ))
+            }
+            Some(Section::Styles { ssa }) => ssa,
+            Some(Section::Events) => false,
+            Some(Section::ScriptInfo) => {
+                let resolution = |value: &str| {
+                    value.trim().parse::<u32>().map_err(|_| {
+                        SubtitleError::at(number, format!("Invalid resolution: {}", value.trim()))
+                    })
+                };
+
+                match line.split_once(':') {
+                    Some(("PlayResX", value)) => width = Some(resolution(value)?),
+                    Some(("PlayResY", value)) => height = Some(resolution(value)?),
+                    _ => {}
+                };
+                continue;
+            }
+            Some(Section::Other) => continue,
+        };
+
+        let (key, value) = match line.split_once(':') {
+            Some((key, value)) => (key.trim(), value.trim()),
+            None => return Err(SubtitleError::at(number, format!("Invalid line: {}", line))),
+        };
+
+        match (&section, key) {
+            (_, "Format") => {
+                let format = value
+                    .split(',')
+                    .map(|field| field.trim().to_lowercase())
+                    .collect();
+
+                match section {
+                    Some(Section::Styles { .. }) => style_format = Some(format),
+                    _ => event_format = Some(format),
+                }
+            }
+            (Some(Section::Styles { .. }), "Style") => {
+                let format = style_format.as_ref().ok_or_else(|| {
+                    SubtitleError::at(number, "Style before the Format of the styles")
+                })?;
+                styles.push(parse_style(format, value, ssa, number)?);
+            }
+            (Some(Section::Events), "Dialogue") => {
+                let format = event_format.as_ref().ok_or_else(|| {
+                    SubtitleError::at(number, "Dialogue before the Format of the events")
+                })?;
+                cues.push(parse_dialogue(format, value, number)?);
+            }
+            // comments, pictures, sounds and commands
+            _ => {}
+        }
+    }
+
+    if section.is_none() {
+        return Err(SubtitleError::at(1, "Missing the [Script Info] section"));
+    }
+
+    let resolution = match (width, height) {
+        (Some(width), Some(height)) => Some((width, height)),
+        _ => None,
+    };
+
+    return Ok(Document {
+        styles,
+        cues,
+        resolution,
+    });
+}
+
+fn fields<'a>(format: &[String], value: &'a str) -> impl Fn(&str) -> Option<&'a str> {
+    // the last field, the text of dialogues, may have commas
+    let values: Vec<&'a str> = value.splitn(format.len(), ',').collect();
+    let format = format.to_vec();
+
+    return move |name: &str| {
+        let position = format.iter().position(|field| field == name)?;
+        return values.get(position).map(|value| value.trim());
+    };
+}
+
+fn parse_style(
+    format: &[String],
+    value: &str,
+    ssa: bool,
+    line: usize,
+) -> Result<Style, SubtitleError> {
+    let field = fields(format, value);
+    let mut style = Style::default();
+
+    let invalid = |name: &str, value: &str| {
+        SubtitleError::at(line, format!("Invalid {} of the style: {}", name, value))
+    };
+    let number = |name: &str| -> Result<Option<f32>, SubtitleError> {
+        match field(name) {
+            Some(value) => value.parse().map(Some).map_err(|_| invalid(name, value)),
+            None => Ok(None),
+        }
+    };
+    let color = |name: &str| -> Result<Option<String>, SubtitleError> {
+        match field(name) {
+            Some(value) => parse_color(value)
+                .map(Some)
+                .ok_or_else(|| invalid(name, value)),
+            None => Ok(None),
+        }
+    };
+
+    match field("name") {
+        Some(name) if !name.is_empty() => style.name = name.to_string(),
+        _ => return Err(SubtitleError::at(line, "Style without a name")),
+    }
+
+    if let Some(font_name) = field("fontname") {
+        style.font_name = font_name.to_string();
+    }
+    if let Some(font_size) = number("fontsize")? {
+        style.font_size = font_size.round() as u32;
+    }
+    if let Some(primary_color) = color("primarycolour")? {
+        style.primary_color = primary_color;
+    }
+    if let Some(outline_color) = color("outlinecolour")? {
+        style.outline_color = outline_color;
+    }
+    if let Some(back_color) = color("backcolour")? {
+        style.back_color = back_color;
+    }
+    if let Some(bold) = field("bold") {
+        style.bold = bold != "0";
+    }
+    if let Some(italic) = field("italic") {
+        style.italic = italic != "0";
+    }
+    if let Some(underline) = field("underline") {
+        style.underline = underline != "0";
+    }
+    if let Some(border_style) = field("borderstyle") {
+        style.opaque_box = border_style == "3";
+    }
+    if let Some(outline) = number("outline")? {
+        style.outline = outline;
+    }
+    if let Some(shadow) = number("shadow")? {
+        style.shadow = shadow;
+    }
+    if let Some(alignment) = number("alignment")? {
+        let alignment = alignment as u8;
+        style.alignment = match (ssa, alignment) {
+            (true, 1..=3) => alignment,
+            (true, 5..=7) => alignment + 2,
+            (true, 9..=11) => alignment - 5,
+            (false, 1..=9) => alignment,
+            _ => return Err(invalid("alignment", &alignment.to_string())),
+        };
+    }
+    if let Some(margin_l) = number("marginl")? {
+        style.margin_l = margin_l as u32;
+    }
+    if let Some(margin_r) = number("marginr")? {
+        style.margin_r = margin_r as u32;
+    }
+    if let Some(margin_v) = number("marginv")? {
+        style.margin_v = margin_v as u32;
+    }
+
+    return Ok(style);
+}
+
+/// `&HAABBGGRR`, SSA colors are decimal numbers
+fn parse_color(value: &str) -> Option<String> {
+    let hex = value
+        .strip_prefix("&H")
+        .or_else(|| value.strip_prefix("&h"))
+        .map(|hex| hex.trim_end_matches('&'));
+
+    let color = match hex {
+        Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+        None => value.parse::<i64>().ok()? as u32,
+    };
+
+    return Some(format!("&H{:08X}", color));
+}
+
+fn parse_dialogue(format: &[String], value: &str, line: usize) -> Result<Cue, SubtitleError> {
+    let field = fields(format, value);
+    let missing = |name: &str| SubtitleError::at(line, format!("Dialogue without {}", name));
+
+    let start_time = parse_time(field("start").ok_or_else(|| missing("start"))?, line)?;
+    let end_time = parse_time(field("end").ok_or_else(|| missing("end"))?, line)?;
+    let text = field("text").ok_or_else(|| missing("text"))?;
+
+    let mut cue = Cue::new(start_time, end_time, text_from_ass(text));
+    cue.style = field("style")
+        .map(|style| style.trim_start_matches('*').to_string())
+        .filter(|style| !style.is_empty());
+    cue.line = Some(line);
+
+    return Ok(cue);
+}
+
+/// `H:MM:SS.cc`, in centiseconds
+fn parse_time(time: &str, line: usize) -> Result<i32, SubtitleError> {
+    let invalid = || SubtitleError::at(line, format!("Invalid time: {}", time));
+
+    let (clock, fraction) = time.split_once('.').ok_or_else(invalid)?;
+    let parts: Vec<&str> = clock.split(':').collect();
+    if parts.len() != 3 || fraction.is_empty() || fraction.len() > 3 {
+        return Err(invalid());
+    }
+
+    let mut values = vec![];
+    for part in parts.iter().chain([&fraction]) {
+        if part.is_empty() || !part.chars().all(|c| c.is_ascii_digit()) {
+            return Err(invalid());
+        }
+        values.push(part.parse::<i32>().map_err(|_| invalid())?);
+    }
+
+    let millis = values[3] * 10_i32.pow(3 - fraction.len() as u32);
+    return to_millis(values[0], values[1], values[2], millis)
+        .ok_or_else(|| SubtitleError::at(line, format!("Time out of range: {}", time)));
+}
+
+fn format_time(ms: i32) -> String {
+    let centis = (ms.max(0) + 5) / 10;
+
+    return format!(
+        "{}:{:02}:{:02}.{:02}",
+        centis / 360_000,
+        centis / 6000 % 60,
+        centis / 100 % 60,
+        centis % 100
+    );
+}
+
+/// Turns the italic, bold, underline and color overrides into tags, keeping the
+/// others as override blocks
+fn text_from_ass(text: &str) -> String {
+    let plain = |text: &str| {
+        text.replace("\\N", "\n")
+            .replace("\\n", "\n")
+            .replace("\\h", " ")
+    };
+
+    let mut converted = String::new();
+    let mut rest = text;
+
+    while let Some(start) = rest.find('{') {
+        let end = match rest[start..].find('}') {
+            Some(end) => start + end,
+            None => break,
+        };
+
+        converted.push_str(&plain(&rest[..start]));
+        converted.push_str(&overrides_to_tags(&rest[start + 1..end]));
+        rest = &rest[end + 1..];
+    }
+
+    converted.push_str(&plain(rest));
+    return converted;
+}
+
+fn overrides_to_tags(block: &str) -> String {
+    // blocks without overrides are comments
+    if !block.starts_with('\\') {
+        return String::new();
+    }
+
+    let mut tags = String::new();
+    let mut kept = vec![];
+
+    for tag in split_overrides(block) {
+        match tag {
+            "i1" => tags.push_str("<i>"),
+            "i0" => tags.push_str("</i>"),
+            "b0" => tags.push_str("</b>"),
+            "u1" => tags.push_str("<u>"),
+            "u0" => tags.push_str("</u>"),
+            "c" | "1c" => tags.push_str("</font>"),
+            _ if tag.len() > 1
+                && tag.starts_with('b')
+                && tag[1..].chars().all(|c| c.is_ascii_digit()) =>
+            {
+                tags.push_str("<b>")
+            }
+            _ => match font_color(tag) {
+                Some(color) => tags.push_str(&format!("<font color=\"{}\">", color)),
+                None => kept.push(tag),
+            },
+        }
+    }
+
+    if kept.is_empty() {
+        return tags;
+    }
+
+    return format!("{{\\{}}}{}", kept.join("\\"), tags);
+}
+
+/// `#RRGGBB` of a `c&HBBGGRR&` override
+fn font_color(tag: &str) -> Option<String> {
+    let hex = tag
+        .strip_prefix("1c&H")
+        .or_else(|| tag.strip_prefix("c&H"))?
+        .trim_end_matches('&');
+    if hex.len() != 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+
+    return Some(format!("#{}{}{}", &hex[4..6], &hex[2..4], &hex[0..2]).to_uppercase());
+}
+
+/// `c&HBBGGRR&` override of a `<font color="#RRGGBB">` tag
+fn color_override(tag: &str) -> Option<String> {
+    let (_, color) = tag.split_once("color=")?;
+    let hex = color
+        .trim_matches(|c| c == '"' || c == '\'')
+        .strip_prefix('#')?;
+    if hex.len() != 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+
+    let bgr = format!("{}{}{}", &hex[4..6], &hex[2..4], &hex[0..2]).to_uppercase();
+    return Some(format!("c&H{}&", bgr));
+}
+
+/// Splits `\pos(1,2)\t(\i1)\b1` by its backslashes, except the ones inside parentheses
+fn split_overrides(block: &str) -> Vec<&str> {
+    let mut tags = vec![];
+    let mut depth = 0;
+    let mut start = 1;
+
+    for (index, c) in block.char_indices().skip(1) {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            '\\' if depth <= 0 => {
+                tags.push(&block[start..index]);
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+
+    tags.push(&block[start..]);
+    return tags.into_iter().filter(|tag| !tag.is_empty()).collect();
+}
+
+/// Turns the italic, bold, underline and font color tags into overrides, other tags are dropped
+fn text_to_ass(text: &str) -> String {
+    let mut converted = String::new();
+    let mut rest = text;
+
+    while let Some(start) = rest.find('<') {
+        let end = match rest[start..].find('>') {
+            Some(end) => start + end,
+            None => break,
+        };
+
+        converted.push_str(&rest[..start]);
+        let tag = rest[start + 1..end].trim().to_lowercase();
+        let override_tag = match tag.as_str() {
+            "i" => "{\\i1}".to_string(),
+            "/i" => "{\\i0}".to_string(),
+            "b" => "{\\b1}".to_string(),
+            "/b" => "{\\b0}".to_string(),
+            "u" => "{\\u1}".to_string(),
+            "/u" => "{\\u0}".to_string(),
+            "/font" => "{\\c}".to_string(),
+            _ if tag.starts_with("font") => match color_override(&tag) {
+                Some(color) => format!("{{\\{}}}", color),
+                None => String::new(),
+            },
+            _ => String::new(),
+        };
+        converted.push_str(&override_tag);
+        rest = &rest[end + 1..];
+    }
+
+    converted.push_str(rest);
+    return converted.replace('\n', "\\N");
+}
+
+fn flag(value: bool) -> &'static str {
+    match value {
+        true => "-1",
+        false => "0",
+    }
+}
+
+/// 1 is the outline and the shadow, 3 the opaque box
+fn border_style(opaque_box: bool) -> u8 {
+    match opaque_box {
+        true => 3,
+        false => 1,
+    }
+}
+
+pub(super) fn write(document: &Document) -> String {
+    let default_styles = vec![Style::default()];
+    let styles = match document.styles.is_empty() {
+        true => &default_styles,
+        false => &document.styles,
+    };
+
+    let mut ass = String::from(
+        "[Script Info]\nScriptType: v4.00+\nWrapStyle: 0\nScaledBorderAndShadow: yes\n",
+    );
+    if let Some((width, height)) = document.resolution {
+        ass.push_str(&format!("PlayResX: {}\nPlayResY: {}\n", width, height));
+    }
+    ass.push('\n');
+
+    ass.push_str(&format!("[V4+ Styles]\nFormat: {}\n", STYLE_FORMAT));
+    for style in styles {
+        ass.push_str(&format!(
+            "Style: {},{},{},{},&H000000FF,{},{},{},{},{},0,100,100,0,0,{},{},{},{},{},{},{},1\n",
+            style.name,
+            style.font_name,
+            style.font_size,
+            style.primary_color,
+            style.outline_color,
+            style.back_color,
+            flag(style.bold),
+            flag(style.italic),
+            flag(style.underline),
+            border_style(style.opaque_box),
+            style.outline,
+            style.shadow,
+            style.alignment,
+            style.margin_l,
+            style.margin_r,
+            style.margin_v
+        ));
+    }
+
+    ass.push_str(&format!("\n[Events]\nFormat: {}\n", EVENT_FORMAT));
+    for cue in &document.cues {
+        let style = cue.style.as_deref().unwrap_or(&styles[0].name);
+
+        ass.push_str(&format!(
+            "Dialogue: 0,{},{},{},,0,0,0,,{}\n",
+            format_time(cue.start_time),
+            format_time(cue.end_time),
+            style,
+            text_to_ass(&cue.text)
+        ));
+    }
+
+    return ass;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ASS: &str = "[Script Info]\n; comentário\nTitle: Legendas\nScriptType: v4.00+\n\n[V4+ Styles]\nFormat: Name, Fontname, Fontsize, PrimaryColour, Bold, Italic, Alignment, MarginV\nStyle: Default,Roboto,48,&H0000FFFF,-1,0,2,30\nStyle: Top,Arial,32,&H00FFFFFF,0,0,8,10\n\n[Events]\nFormat: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text\nComment: 0,0:00:00.00,0:00:01.00,Default,,0,0,0,,ignorado\nDialogue: 0,0:00:01.37,0:00:02.65,Default,,0,0,0,,Sua vida, não é nada.\nDialogue: 0,0:00:02.77,0:00:04.75,Top,,0,0,0,,{\\pos(10,20)\\i1}Você não serve\\Npara nada{\\i0}.\n";
+
+    #[test]
+    fn test_parse() {
+        let document = parse(ASS).unwrap();
+
+        assert_eq!(document.styles.len(), 2);
+        assert_eq!(document.styles[0].font_name, "Roboto");
+        assert_eq!(document.styles[0].font_size, 48);
+        assert_eq!(document.styles[0].primary_color, "&H0000FFFF");
+        assert!(document.styles[0].bold);
+        assert_eq!(document.styles[0].margin_v, 30);
+        assert_eq!(document.styles[1].alignment, 8);
+
+        assert_eq!(document.cues.len(), 2);
+        assert_eq!(document.cues[0].start_time, 1370);
+        assert_eq!(document.cues[0].end_time, 2650);
+        assert_eq!(document.cues[0].text, "Sua vida, não é nada.");
+        assert_eq!(document.cues[0].line, Some(14));
+        assert_eq!(document.cues[1].style.as_deref(), Some("Top"));
+        assert_eq!(
+            document.cues[1].text,
+            "{\\pos(10,20)}<i>Você não serve\npara nada</i>."
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let document = parse(ASS).unwrap();
+        let written = write(&document);
+
+        assert!(written.contains("Style: Top,Arial,32,&H00FFFFFF,&H000000FF,&H00000000,&H00000000,0,0,0,0,100,100,0,0,1,1,0,8,10,10,10,1\n"));
+        assert!(written.contains("Dialogue: 0,0:00:02.77,0:00:04.75,Top,,0,0,0,,{\\pos(10,20)}{\\i1}Você não serve\\Npara nada{\\i0}.\n"));
+
+        let mut parsed = parse(&written).unwrap();
+        for cue in parsed.cues.iter_mut() {
+            cue.line = None;
+        }
+        let mut expected = document;
+        for cue in expected.cues.iter_mut() {
+            cue.line = None;
+        }
+        assert_eq!(parsed, expected);
+    }
+
+    #[test]
+    fn test_resolution_and_box() {
+        let mut document = parse(ASS).unwrap();
+        assert_eq!(document.resolution, None);
+        assert!(!document.styles[0].opaque_box);
+
+        document.resolution = Some((1080, 1920));
+        document.styles[0].opaque_box = true;
+        let written = write(&document);
+        assert!(written.contains("PlayResX: 1080\nPlayResY: 1920\n"));
+
+        let parsed = parse(&written).unwrap();
+        assert_eq!(parsed.resolution, Some((1080, 1920)));
+        assert!(parsed.styles[0].opaque_box);
+        assert!(!parsed.styles[1].opaque_box);
+    }
+
+    #[test]
+    fn test_parse_ssa() {
+        let ssa = "[Script Info]\nScriptType: v4.00\n\n[V4 Styles]\nFormat: Name, Fontname, Fontsize, PrimaryColour, Alignment\nStyle: Default,Arial,20,16777215,6\n\n[Events]\nFormat: Marked, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text\nDialogue: Marked=0,0:00:01.00,0:00:02.00,*Default,,0,0,0,,Oi\n";
+
+        let document = parse(ssa).unwrap();
+        assert_eq!(document.styles[0].primary_color, "&H00FFFFFF");
+        assert_eq!(document.styles[0].alignment, 8);
+        assert_eq!(document.cues[0].style.as_deref(), Some("Default"));
+
+        let bottom = ssa.replace("16777215,6", "16777215,2");
+        assert_eq!(parse(&bottom).unwrap().styles[0].alignment, 2);
+
+        // 4 and 8 are not alignments of ssa, even though they are of ass
+        for alignment in ["4", "8"] {
+            let invalid = ssa.replace("16777215,6", &format!("16777215,{}", alignment));
+            assert!(parse(&invalid).is_err(), "{}", alignment);
+        }
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let error = parse("1\n00:00:01,000 --> 00:00:02,000\nOi\n").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Line 1: Missing the [Script Info] section"
+        );
+
+        let error = parse(
+            "[Script Info]\n\n[Events]\nDialogue: 0,0:00:01.00,0:00:02.00,Default,,0,0,0,,Oi\n",
+        )
+        .unwrap_err();
+        assert_eq!(error.line, Some(4));
+
+        let invalid_time = ASS.replace("0:00:04.75", "0:00:4.75x");
+        let error = parse(&invalid_time).unwrap_err();
+        assert_eq!(error.line, Some(15));
+
+        let invalid_size = ASS.replace("Roboto,48", "Roboto,big");
+        let error = parse(&invalid_size).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Line 8: Invalid fontsize of the style: big"
+        );
+    }
+
+    #[test]
+    fn test_text_conversions() {
+        assert_eq!(
+            text_to_ass("<i>Oi</i>\n<v Ana>Tchau"),
+            "{\\i1}Oi{\\i0}\\NTchau"
+        );
+        assert_eq!(
+            text_from_ass("{\\b700}Oi{\\b0}\\htchau{comentário}"),
+            "<b>Oi</b> tchau"
+        );
+        assert_eq!(text_from_ass("{\\t(\\i1)\\i1}Oi"), "{\\t(\\i1)}<i>Oi");
+    }
+
+    #[test]
+    fn test_font_colors() {
+        let text = "<font color=\"#ffff00\">Oi\ntchau</font>";
+        assert_eq!(text_to_ass(text), "{\\c&H00FFFF&}Oi\\Ntchau{\\c}");
+        assert_eq!(
+            text_from_ass("{\\c&H00FFFF&}Oi\\Ntchau{\\c}"),
+            "<font color=\"#FFFF00\">Oi\ntchau</font>"
+        );
+        assert_eq!(
+            text_from_ass("{\\1c&H00FF00&}Oi"),
+            "<font color=\"#00FF00\">Oi"
+        );
+    }
+
+    #[test]
+    fn test_parse_time() {
+        assert_eq!(parse_time("1:02:03.45", 1).unwrap(), 3723450);
+        assert_eq!(parse_time("0:00:01.5", 1).unwrap(), 1500);
+        assert!(parse_time("0:00:01", 1).is_err());
+        assert!(parse_time("999999:00:00.00", 1).is_err());
+    }
+}
@@ -0,0 +1,583 @@
+use std::{fmt::Display, str::FromStr};
+
+use crate::internals::transcriber::traits::Sentence;
+
+mod ass;
+mod srt;
+mod vtt;
+
+pub mod segmentation;
+pub mod speakers;
+
+/// Subtitle file formats that can be read and written
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Srt,
+    WebVtt,
+    /// ASS, SSA files are read as well but always written as ASS
+    Ass,
+}
+
+impl Format {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Format::Srt => "srt",
+            Format::WebVtt => "vtt",
+            Format::Ass => "ass",
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            Format::Srt => "application/x-subrip",
+            Format::WebVtt => "text/vtt",
+            Format::Ass => "text/x-ssa",
+        }
+    }
+
+    /// Format of a file by its extension, works with paths and uris
+    pub fn from_path(path: &str) -> Option<Self> {
+        let (_, extension) = path.rsplit_once('.')?;
+        return Self::from_str(extension).ok();
+    }
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "srt" => Ok(Format::Srt),
+            "vtt" | "webvtt" => Ok(Format::WebVtt),
+            "ass" | "ssa" => Ok(Format::Ass),
+            _ => Err(format!("Unknown subtitle format: {}", s)),
+        }
+    }
+}
+
+impl Display for Format {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.extension())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SubtitleError {
+    /// Line of the file, cues that were not parsed have none
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+impl SubtitleError {
+    fn at(line: usize, message: impl Into<String>) -> Self {
+        return Self {
+            line: Some(line),
+            message: message.into(),
+        };
+    }
+}
+
+impl Display for SubtitleError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "Line {}: {}", line, self.message),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
+impl std::error::Error for SubtitleError {}
+
+/// Styling of the cues, modeled after the ASS styles
+#[derive(Debug, Clone, PartialEq)]
+pub struct Style {
+    pub name: String,
+    pub font_name: String,
+    pub font_size: u32,
+    /// Colors use the `&HAABBGGRR` notation of ASS
+    pub primary_color: String,
+    pub outline_color: String,
+    pub back_color: String,
+    pub bold: bool,
+    pub italic: bool,
+    pub underline: bool,
+    pub outline: f32,
+    pub shadow: f32,
+    /// Draws a box behind the text instead of the outline, filled with the outline color
+    pub opaque_box: bool,
+    /// Position on the numpad, 2 is the bottom center
+    pub alignment: u8,
+    pub margin_l: u32,
+    pub margin_r: u32,
+    pub margin_v: u32,
+}
+
+impl Default for Style {
+    fn default() -> Self {
+        return Self {
+            name: "Default".to_string(),
+            font_name: "Arial".to_string(),
+            font_size: 20,
+            primary_color: "&H00FFFFFF".to_string(),
+            outline_color: "&H00000000".to_string(),
+            back_color: "&H00000000".to_string(),
+            bold: false,
+            italic: false,
+            underline: false,
+            outline: 1.0,
+            shadow: 0.0,
+            opaque_box: false,
+            alignment: 2,
+            margin_l: 10,
+            margin_r: 10,
+            margin_v: 10,
+        };
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cue {
+    /// Milliseconds
+    pub start_time: i32,
+    pub end_time: i32,
+    /// Lines are split by `\n` and styled with the `<i>`, `<b>` and `<u>` tags,
+    /// other ASS overrides are kept as `{\...}` blocks for the ASS files
+    pub text: String,
+    /// Name of the style of the document
+    pub style: Option<String>,
+    /// WebVTT cue settings, like `align:start line:0`
+    pub settings: Option<String>,
+    /// Line of the file where the cue starts
+    pub line: Option<usize>,
+}
+
+impl Cue {
+    pub fn new(start_time: i32, end_time: i32, text: impl Into<String>) -> Self {
+        return Self {
+            start_time,
+            end_time,
+            text: text.into(),
+            style: None,
+            settings: None,
+            line: None,
+        };
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Document {
+    pub styles: Vec<Style>,
+    pub cues: Vec<Cue>,
+    /// Width and height the sizes of the styles are relative to, the PlayResX and PlayResY of ASS
+    pub resolution: Option<(u32, u32)>,
+}
+
+impl Document {
+    pub fn parse(input: &str, format: Format) -> Result<Self, SubtitleError> {
+        let input = normalize(input);
+
+        match format {
+            Format::Srt => srt::parse(&input),
+            Format::WebVtt => vtt::parse(&input),
+            Format::Ass => ass::parse(&input),
+        }
+    }
+
+    pub fn write(&self, format: Format) -> String {
+        match format {
+            Format::Srt => srt::write(self),
+            Format::WebVtt => vtt::write(self),
+            Format::Ass => ass::write(self),
+        }
+    }
+
+    /// Reads the subtitles in one format and writes them in another
+    pub fn convert(input: &str, from: Format, to: Format) -> Result<String, SubtitleError> {
+        let document = Self::parse(input, from)?;
+        return Ok(document.write(to));
+    }
+
+    pub fn from_sentences(sentences: Vec<Sentence>) -> Self {
+        let cues = sentences
+            .into_iter()
+            .map(|sentence| Cue::new(sentence.start_time, sentence.end_time, sentence.text))
+            .collect();
+
+        return Self {
+            styles: vec![],
+            cues,
+            resolution: None,
+        };
+    }
+
+    pub fn into_sentences(self) -> Vec<Sentence> {
+        return self
+            .cues
+            .into_iter()
+            .map(|cue| Sentence {
+                start_time: cue.start_time,
+                end_time: cue.end_time,
+                text: cue.text,
+                words: None,
+                speaker: None,
+            })
+            .collect();
+    }
+
+    /// Breaks the lines of the cues again so none is longer than `max_width` characters,
+    /// words longer than that stay on a line of their own
+    pub fn wrap_lines(&mut self, max_width: usize) {
+        for cue in &mut self.cues {
+            let mut lines: Vec<String> = vec![];
+            let mut width = 0;
+
+            for word in words(&cue.text) {
+                let word_width = strip_tags(word).chars().count();
+
+                match lines.last_mut() {
+                    Some(line) if width + 1 + word_width <= max_width => {
+                        line.push(' ');
+                        line.push_str(word);
+                        width += 1 + word_width;
+                    }
+                    _ => {
+                        lines.push(word.to_string());
+                        width = word_width;
+                    }
+                }
+            }
+
+            cue.text = lines.join("\n");
+        }
+    }
+
+    /// Cues must have text, end after they start, follow each other without
+    /// overlapping and use the styles of the document
+    pub fn validate(&self) -> Result<(), SubtitleError> {
+        let mut previous_end = 0;
+
+        for (index, cue) in self.cues.iter().enumerate() {
+            let error = |message: String| SubtitleError {
+                line: cue.line,
+                message,
+            };
+            let number = index + 1;
+
+            if strip_tags(&cue.text).trim().is_empty() {
+                return Err(error(format!("Cue {} has no text", number)));
+            }
+
+            if cue.start_time < 0 || cue.start_time >= cue.end_time {
+                return Err(error(format!("Cue {} must start before it ends", number)));
+            }
+
+            if cue.start_time < previous_end {
+                return Err(error(format!(
+                    "Cue {} starts before the previous cue ends",
+                    number
+                )));
+            }
+
+            if let Some(style) = &cue.style {
+                if !self.styles.is_empty() && !self.styles.iter().any(|s| &s.name == style) {
+                    return Err(error(format!(
+                        "Cue {} uses the unknown style {}",
+                        number, style
+                    )));
+                }
+            }
+
+            previous_end = cue.end_time;
+        }
+
+        return Ok(());
+    }
+}
+
+/// Removes the byte order mark and the windows line endings
+fn normalize(input: &str) -> String {
+    return input
+        .trim_start_matches('\u{feff}')
+        .replace("\r\n", "\n")
+        .replace('\r', "\n");
+}
+
+/// `HH:MM:SS,mmm` for srt and `HH:MM:SS.mmm` for WebVTT
+fn format_time(ms: i32, separator: char) -> String {
+    let ms = ms.max(0);
+    let hours = ms / 3_600_000;
+    let minutes = ms / 60_000 % 60;
+    let seconds = ms / 1000 % 60;
+    let millis = ms % 1000;
+
+    return format!(
+        "{:02}:{:02}:{:02}{}{:03}",
+        hours, minutes, seconds, separator, millis
+    );
+}
+
+/// Reads `HH:MM:SS,mmm`, `HH:MM:SS.mmm` and `MM:SS.mmm` timestamps
+fn parse_time(time: &str, line: usize) -> Result<i32, SubtitleError> {
+    let invalid = || SubtitleError::at(line, format!("Invalid time: {}", time));
+
+    let (clock, millis) = time.split_once([',', '.']).ok_or_else(invalid)?;
+    if millis.len() != 3 {
+        return Err(invalid());
+    }
+
+    let parts: Vec<&str> = clock.split(':').collect();
+    let (hours, minutes, seconds) = match parts.as_slice() {
+        [hours, minutes, seconds] => (*hours, *minutes, *seconds),
+        [minutes, seconds] => ("0", *minutes, *seconds),
+        _ => return Err(invalid()),
+    };
+
+    let number = |value: &str| -> Result<i32, SubtitleError> {
+        if value.is_empty() || !value.chars().all(|c| c.is_ascii_digit()) {
+            return Err(invalid());
+        }
+        return value.parse().map_err(|_| invalid());
+    };
+
+    let (hours, minutes, seconds, millis) = (
+        number(hours)?,
+        number(minutes)?,
+        number(seconds)?,
+        number(millis)?,
+    );
+    if minutes > 59 || seconds > 59 {
+        return Err(invalid());
+    }
+
+    return to_millis(hours, minutes, seconds, millis)
+        .ok_or_else(|| SubtitleError::at(line, format!("Time out of range: {}", time)));
+}
+
+/// None when the time doesn't fit the milliseconds of a cue, like a made up `999999:00:00`
+fn to_millis(hours: i32, minutes: i32, seconds: i32, millis: i32) -> Option<i32> {
+    return hours
+        .checked_mul(60)?
+        .checked_add(minutes)?
+        .checked_mul(60)?
+        .checked_add(seconds)?
+        .checked_mul(1000)?
+        .checked_add(millis);
+}
+
+/// Reads the `start --> end settings` line of srt and WebVTT cues
+fn parse_timing(timing: &str, line: usize) -> Result<(i32, i32, Option<String>), SubtitleError> {
+    let (start, rest) = timing
+        .split_once("-->")
+        .ok_or_else(|| SubtitleError::at(line, format!("Invalid timing: {}", timing)))?;
+
+    let rest = rest.trim();
+    let (end, settings) = match rest.split_once(char::is_whitespace) {
+        Some((end, settings)) => (end, Some(settings.trim().to_string())),
+        None => (rest, None),
+    };
+
+    let start = parse_time(start.trim(), line)?;
+    let end = parse_time(end, line)?;
+
+    return Ok((start, end, settings));
+}
+
+/// Drops the `{\...}` ASS override blocks, which srt and WebVTT don't have
+fn strip_overrides(text: &str) -> String {
+    let mut stripped = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find("{\\") {
+        stripped.push_str(&rest[..start]);
+        match rest[start..].find('}') {
+            Some(end) => rest = &rest[start + end + 1..],
+            None => {
+                rest = &rest[start..];
+                break;
+            }
+        }
+    }
+
+    stripped.push_str(rest);
+    return stripped;
+}
+
+/// Splits the text on the whitespace outside of the tags, so `<font color="#FFFF00">` stays whole
+fn words(text: &str) -> Vec<&str> {
+    let mut words = vec![];
+    let mut start = None;
+    let mut inside_tag = false;
+
+    for (index, c) in text.char_indices() {
+        match c {
+            '<' => inside_tag = true,
+            '>' => inside_tag = false,
+            _ => {}
+        }
+
+        if c.is_whitespace() && !inside_tag {
+            if let Some(from) = start.take() {
+                words.push(&text[from..index]);
+            }
+        } else if start.is_none() {
+            start = Some(index);
+        }
+    }
+
+    if let Some(from) = start {
+        words.push(&text[from..]);
+    }
+
+    return words;
+}
+
+/// Text without the styling tags and overrides
+fn strip_tags(text: &str) -> String {
+    let text = strip_overrides(text);
+    let mut stripped = String::with_capacity(text.len());
+    let mut inside_tag = false;
+
+    for c in text.chars() {
+        match c {
+            '<' => inside_tag = true,
+            '>' if inside_tag => inside_tag = false,
+            _ if !inside_tag => stripped.push(c),
+            _ => {}
+        }
+    }
+
+    return stripped;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_from_path() {
+        assert_eq!(
+            Format::from_path("srt_revisions/1/id.pt.srt"),
+            Some(Format::Srt)
+        );
+        assert_eq!(Format::from_path("./output.VTT"), Some(Format::WebVtt));
+        assert_eq!(Format::from_path("old.ssa"), Some(Format::Ass));
+        assert_eq!(Format::from_path("video.mkv"), None);
+        assert_eq!(Format::from_path("no_extension"), None);
+    }
+
+    #[test]
+    fn test_parse_time() {
+        assert_eq!(parse_time("01:02:03,456", 1).unwrap(), 3723456);
+        assert_eq!(parse_time("01:02:03.456", 1).unwrap(), 3723456);
+        assert_eq!(parse_time("02:03.456", 1).unwrap(), 123456);
+        assert_eq!(parse_time("100:00:00,000", 1).unwrap(), 360000000);
+        assert!(parse_time("01:02", 1).is_err());
+        assert!(parse_time("00:61:00,000", 1).is_err());
+        assert!(parse_time("00:00:01,5", 1).is_err());
+    }
+
+    #[test]
+    fn test_parse_time_out_of_range() {
+        assert_eq!(parse_time("596:31:23,647", 1).unwrap(), i32::MAX);
+
+        let error = parse_time("596:31:23,648", 3).unwrap_err();
+        assert_eq!(error.line, Some(3));
+        assert!(error.message.contains("out of range"));
+        assert!(parse_time("999999:00:00,000", 1).is_err());
+    }
+
+    #[test]
+    fn test_format_time() {
+        assert_eq!(format_time(3723456, ','), "01:02:03,456");
+        assert_eq!(format_time(1000, '.'), "00:00:01.000");
+        assert_eq!(format_time(360000000, ','), "100:00:00,000");
+    }
+
+    #[test]
+    fn test_strip_tags() {
+        assert_eq!(strip_tags("{\\pos(10,10)}<i>Oi</i>"), "Oi");
+        assert_eq!(strip_tags("<b></b>"), "");
+    }
+
+    #[test]
+    fn test_convert() {
+        let srt = "1\n00:00:01,000 --> 00:00:02,500\n<i>Oi</i>\nTudo bem?\n\n";
+
+        let vtt = Document::convert(srt, Format::Srt, Format::WebVtt).unwrap();
+        assert_eq!(
+            vtt,
+            "WEBVTT\n\n00:00:01.000 --> 00:00:02.500\n<i>Oi</i>\nTudo bem?\n\n"
+        );
+
+        let ass = Document::convert(&vtt, Format::WebVtt, Format::Ass).unwrap();
+        assert!(ass.contains(
+            "Dialogue: 0,0:00:01.00,0:00:02.50,Default,,0,0,0,,{\\i1}Oi{\\i0}\\NTudo bem?\n"
+        ));
+
+        let back = Document::convert(&ass, Format::Ass, Format::Srt).unwrap();
+        assert_eq!(back, srt);
+    }
+
+    #[test]
+    fn test_validate() {
+        let document = |cues: Vec<Cue>| Document {
+            styles: vec![],
+            cues,
+            resolution: None,
+        };
+
+        assert!(
+            document(vec![Cue::new(0, 1000, "Oi"), Cue::new(1000, 2000, "Tchau")])
+                .validate()
+                .is_ok()
+        );
+        assert!(document(vec![Cue::new(0, 1000, "<i> </i>")])
+            .validate()
+            .is_err());
+        assert!(document(vec![Cue::new(1000, 1000, "Oi")])
+            .validate()
+            .is_err());
+
+        let error = Document::parse(
+            "1\n00:00:00,000 --> 00:00:01,500\nOi\n\n2\n00:00:01,000 --> 00:00:02,000\nTchau\n",
+            Format::Srt,
+        )
+        .unwrap()
+        .validate()
+        .unwrap_err();
+        assert_eq!(error.line, Some(5));
+        assert_eq!(
+            error.to_string(),
+            "Line 5: Cue 2 starts before the previous cue ends"
+        );
+
+        let mut styled = document(vec![Cue::new(0, 1000, "Oi")]);
+        styled.styles.push(Style::default());
+        styled.cues[0].style = Some("Top".to_string());
+        assert!(styled.validate().is_err());
+    }
+
+    #[test]
+    fn test_wrap_lines() {
+        let mut document = Document::from_sentences(vec![]);
+        document
+            .cues
+            .push(Cue::new(0, 1000, "Sua vida\nnão é <i>nada</i>, você"));
+        document
+            .cues
+            .push(Cue::new(1000, 2000, "inconstitucionalissimamente"));
+        document.cues.push(Cue::new(
+            2000,
+            3000,
+            "<font color=\"#FFFF00\">Sua vida\nnão é nada</font>",
+        ));
+
+        document.wrap_lines(12);
+        assert_eq!(document.cues[0].text, "Sua vida não\né <i>nada</i>, você");
+        assert_eq!(document.cues[1].text, "inconstitucionalissimamente");
+        assert_eq!(
+            document.cues[2].text,
+            "<font color=\"#FFFF00\">Sua vida não\né nada</font>"
+        );
+    }
+}
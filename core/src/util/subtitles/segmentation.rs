@@ -0,0 +1,473 @@
+use crate::internals::transcriber::traits::{Sentence, Word};
+
+/// Limits the cues are built within, times are in milliseconds
+#[derive(Debug, Clone, PartialEq)]
+pub struct SegmentationOptions {
+    /// Characters per line, a single word longer than this gets a line of its own
+    pub max_line_length: usize,
+    pub max_lines: usize,
+    /// Characters per second a viewer is expected to read
+    pub max_cps: f32,
+    /// Silence between two words that always starts a new cue
+    pub min_pause: i32,
+    /// Time a cue stays on screen when the next one doesn't start before
+    pub min_duration: i32,
+    pub max_duration: i32,
+}
+
+impl Default for SegmentationOptions {
+    fn default() -> Self {
+        Self {
+            max_line_length: 42,
+            max_lines: 2,
+            max_cps: 17.0,
+            min_pause: 500,
+            min_duration: 1000,
+            max_duration: 7000,
+        }
+    }
+}
+
+/// Breaks the sentences into cues that fit on screen, following the timings of the words.
+/// Sentences without word timings, or whose text was translated, have their words
+/// placed on the timeline by their position in the text. A cue never takes words of two
+/// sentences, so it has a single speaker
+pub fn segment(sentences: Vec<Sentence>, options: &SegmentationOptions) -> Vec<Sentence> {
+    let mut cues: Vec<Sentence> = sentences
+        .iter()
+        .flat_map(|sentence| {
+            let mut cues = split_words(&timed_words(sentence), options);
+            for cue in cues.iter_mut() {
+                cue.speaker = sentence.speaker.clone();
+            }
+            cues
+        })
+        .collect();
+
+    extend_for_reading(&mut cues, options);
+    return cues;
+}
+
+/// The words of the text of the sentence with their timings
+fn timed_words(sentence: &Sentence) -> Vec<Word> {
+    let texts: Vec<&str> = sentence.text.split_whitespace().collect();
+
+    let spoken: Vec<Word> = match &sentence.words {
+        Some(words) => words
+            .iter()
+            .filter(|word| !word.text.trim().is_empty())
+            .cloned()
+            .collect(),
+        None => vec![],
+    };
+
+    let matches_text = spoken.len() == texts.len()
+        && spoken
+            .iter()
+            .zip(&texts)
+            .all(|(word, text)| word.text.trim() == *text);
+
+    if matches_text {
+        return spoken;
+    }
+
+    let timeline = match spoken.is_empty() {
+        true => vec![Word {
+            start_time: sentence.start_time,
+            end_time: sentence.end_time,
+            text: sentence.text.clone(),
+        }],
+        false => spoken,
+    };
+
+    return project(&texts, &timeline);
+}
+
+/// Places the texts on the timeline of the spoken words, a text that is at a
+/// fraction of the sentence is said at the same fraction of the spoken words
+fn project(texts: &[&str], timeline: &[Word]) -> Vec<Word> {
+    let spoken_spans = spans(timeline.iter().map(|word| word.text.as_str()));
+
+    let time_at = |position: f64, is_start: bool| -> i32 {
+        for (index, (from, to)) in spoken_spans.iter().enumerate() {
+            let word = &timeline[index];
+
+            // between two words, a start waits for the next one and an end keeps the previous one
+            if position < *from {
+                return match (is_start, index) {
+                    (false, index) if index > 0 => timeline[index - 1].end_time,
+                    _ => word.start_time,
+                };
+            }
+
+            if position <= *to {
+                let progress = match to - from {
+                    length if length > 0.0 => (position - from) / length,
+                    _ => 0.0,
+                };
+                let duration = (word.end_time - word.start_time) as f64;
+                return word.start_time + (progress * duration).round() as i32;
+            }
+        }
+
+        return timeline.last().map(|word| word.end_time).unwrap_or(0);
+    };
+
+    return spans(texts.iter().copied())
+        .into_iter()
+        .zip(texts)
+        .map(|((from, to), text)| Word {
+            start_time: time_at(from, true),
+            end_time: time_at(to, false),
+            text: text.to_string(),
+        })
+        .collect();
+}
+
+/// Where each word starts and ends in the text they make, as fractions of its length
+fn spans<'a>(words: impl Iterator<Item = &'a str>) -> Vec<(f64, f64)> {
+    let mut spans = vec![];
+    let mut position = 0;
+
+    for word in words {
+        if position > 0 {
+            position += 1;
+        }
+        let length = word.trim().chars().count();
+        spans.push((position, position + length));
+        position += length;
+    }
+
+    let total = position.max(1) as f64;
+    return spans
+        .into_iter()
+        .map(|(from, to)| (from as f64 / total, to as f64 / total))
+        .collect();
+}
+
+fn split_words(words: &[Word], options: &SegmentationOptions) -> Vec<Sentence> {
+    let mut cues = vec![];
+    let mut current: Vec<Word> = vec![];
+
+    for word in words {
+        if let Some(last) = current.last() {
+            if word.start_time - last.end_time >= options.min_pause {
+                cues.push(cue(&current, options));
+                current.clear();
+            }
+        }
+
+        while !current.is_empty() && !fits(&current, word, options) {
+            let at = natural_break(&current, word);
+            cues.push(cue(&current[..at], options));
+            current.drain(..at);
+        }
+
+        current.push(word.clone());
+    }
+
+    if !current.is_empty() {
+        cues.push(cue(&current, options));
+    }
+
+    return cues;
+}
+
+/// Whether the cue can take one more word
+fn fits(current: &[Word], word: &Word, options: &SegmentationOptions) -> bool {
+    let first = &current[0];
+    if word.end_time - first.start_time > options.max_duration {
+        return false;
+    }
+
+    let texts: Vec<&str> = current
+        .iter()
+        .chain(std::iter::once(word))
+        .map(|word| word.text.as_str())
+        .collect();
+
+    return wrap(&texts, options.max_line_length).len() <= options.max_lines;
+}
+
+/// How many words of the cue to keep when it is full. The break goes after the
+/// last punctuation of the second half of the cue, or else on its longest silence
+fn natural_break(current: &[Word], next: &Word) -> usize {
+    let half = current.len().div_ceil(2);
+
+    let punctuation = (half..=current.len())
+        .rev()
+        .find(|at| ends_clause(&current[at - 1].text));
+
+    if let Some(at) = punctuation {
+        return at;
+    }
+
+    let silence = |at: usize| {
+        let next_start = current.get(at).unwrap_or(next).start_time;
+        next_start - current[at - 1].end_time
+    };
+
+    return (half..=current.len())
+        .max_by_key(|at| silence(*at))
+        .unwrap_or(current.len());
+}
+
+fn ends_clause(text: &str) -> bool {
+    let text = text.trim_end_matches(['"', '\'', ')', '”', '’']);
+    return text.ends_with(['.', ',', ';', ':', '?', '!', '…']);
+}
+
+fn cue(words: &[Word], options: &SegmentationOptions) -> Sentence {
+    let texts: Vec<&str> = words.iter().map(|word| word.text.as_str()).collect();
+
+    return Sentence {
+        start_time: words[0].start_time,
+        end_time: words[words.len() - 1].end_time,
+        text: balanced_wrap(&texts, options.max_line_length).join("\n"),
+        words: Some(words.to_vec()),
+        speaker: None,
+    };
+}
+
+/// Fills each line before going to the next one
+fn wrap(words: &[&str], max_line_length: usize) -> Vec<String> {
+    let mut lines: Vec<String> = vec![];
+
+    for word in words {
+        match lines.last_mut() {
+            Some(line) if line.chars().count() + 1 + word.chars().count() <= max_line_length => {
+                line.push(' ');
+                line.push_str(word);
+            }
+            _ => lines.push(word.to_string()),
+        }
+    }
+
+    return lines;
+}
+
+/// Same number of lines as `wrap`, but as even as they can be
+fn balanced_wrap(words: &[&str], max_line_length: usize) -> Vec<String> {
+    let lines = wrap(words, max_line_length);
+    let longest_word = words.iter().map(|word| word.chars().count()).max();
+
+    return longest_word
+        .and_then(|longest_word| {
+            (longest_word..max_line_length)
+                .map(|length| wrap(words, length))
+                .find(|balanced| balanced.len() <= lines.len())
+        })
+        .unwrap_or(lines);
+}
+
+/// Cues stay on screen long enough to be read, as long as they don't reach the next one
+fn extend_for_reading(cues: &mut [Sentence], options: &SegmentationOptions) {
+    for index in 0..cues.len() {
+        let next_start = cues.get(index + 1).map(|next| next.start_time);
+        let cue = &mut cues[index];
+
+        let characters = cue.text.chars().filter(|c| *c != '\n').count();
+        let reading_time = (characters as f32 * 1000.0 / options.max_cps).ceil() as i32;
+        let end_time = cue.start_time + reading_time.max(options.min_duration);
+
+        if end_time > cue.end_time {
+            cue.end_time = match next_start {
+                Some(next_start) => end_time.min(next_start).max(cue.end_time),
+                None => end_time,
+            };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::internals::transcriber::assembly_ai::payload::response::AssemblyAiSentence;
+
+    /// Sentences of an AssemblyAI transcription, as the `/sentences` endpoint returns them
+    const TRANSCRIPT: &str = r#"[
+        {
+            "text": "So today we are going to talk about how FFmpeg works under the hood, and why it is still the most important piece of software in video.",
+            "start": 1200, "end": 8940, "confidence": 0.95,
+            "words": [
+                {"text": "So", "start": 1200, "end": 1380, "confidence": 0.98},
+                {"text": "today", "start": 1400, "end": 1720, "confidence": 0.99},
+                {"text": "we", "start": 1740, "end": 1850, "confidence": 0.99},
+                {"text": "are", "start": 1850, "end": 1960, "confidence": 0.97},
+                {"text": "going", "start": 1960, "end": 2150, "confidence": 0.99},
+                {"text": "to", "start": 2150, "end": 2230, "confidence": 0.99},
+                {"text": "talk", "start": 2240, "end": 2480, "confidence": 0.99},
+                {"text": "about", "start": 2480, "end": 2700, "confidence": 0.99},
+                {"text": "how", "start": 2720, "end": 2900, "confidence": 0.99},
+                {"text": "FFmpeg", "start": 2920, "end": 3600, "confidence": 0.81},
+                {"text": "works", "start": 3620, "end": 3900, "confidence": 0.99},
+                {"text": "under", "start": 3920, "end": 4120, "confidence": 0.99},
+                {"text": "the", "start": 4120, "end": 4200, "confidence": 0.99},
+                {"text": "hood,", "start": 4200, "end": 4560, "confidence": 0.97},
+                {"text": "and", "start": 4900, "end": 5040, "confidence": 0.99},
+                {"text": "why", "start": 5060, "end": 5280, "confidence": 0.99},
+                {"text": "it", "start": 5300, "end": 5380, "confidence": 0.99},
+                {"text": "is", "start": 5380, "end": 5480, "confidence": 0.99},
+                {"text": "still", "start": 5500, "end": 5760, "confidence": 0.99},
+                {"text": "the", "start": 5780, "end": 5860, "confidence": 0.99},
+                {"text": "most", "start": 5880, "end": 6160, "confidence": 0.99},
+                {"text": "important", "start": 6180, "end": 6680, "confidence": 0.99},
+                {"text": "piece", "start": 6700, "end": 6980, "confidence": 0.99},
+                {"text": "of", "start": 7000, "end": 7080, "confidence": 0.99},
+                {"text": "software", "start": 7100, "end": 7600, "confidence": 0.99},
+                {"text": "in", "start": 7620, "end": 7720, "confidence": 0.99},
+                {"text": "video.", "start": 7740, "end": 8940, "confidence": 0.99}
+            ]
+        },
+        {
+            "text": "Right?",
+            "start": 10460, "end": 10700, "confidence": 0.9,
+            "words": [
+                {"text": "Right?", "start": 10460, "end": 10700, "confidence": 0.9}
+            ]
+        },
+        {
+            "text": "It decodes, encodes, transcodes and filters basically anything. Seriously, anything.",
+            "start": 11020, "end": 16300, "confidence": 0.93,
+            "words": [
+                {"text": "It", "start": 11020, "end": 11100, "confidence": 0.99},
+                {"text": "decodes,", "start": 11120, "end": 11700, "confidence": 0.95},
+                {"text": "encodes,", "start": 11820, "end": 12400, "confidence": 0.95},
+                {"text": "transcodes", "start": 12520, "end": 13180, "confidence": 0.91},
+                {"text": "and", "start": 13200, "end": 13300, "confidence": 0.99},
+                {"text": "filters", "start": 13320, "end": 13740, "confidence": 0.98},
+                {"text": "basically", "start": 13760, "end": 14220, "confidence": 0.99},
+                {"text": "anything.", "start": 14240, "end": 14700, "confidence": 0.99},
+                {"text": "Seriously,", "start": 15300, "end": 15800, "confidence": 0.97},
+                {"text": "anything.", "start": 15820, "end": 16300, "confidence": 0.99}
+            ]
+        }
+    ]"#;
+
+    fn transcript() -> Vec<Sentence> {
+        let sentences: Vec<AssemblyAiSentence> = serde_json::from_str(TRANSCRIPT).unwrap();
+        return sentences.into_iter().map(Into::into).collect();
+    }
+
+    fn assert_within(cues: &[Sentence], options: &SegmentationOptions) {
+        let mut previous_end = 0;
+
+        for cue in cues {
+            let lines: Vec<&str> = cue.text.lines().collect();
+            assert!(lines.len() <= options.max_lines, "{:?}", cue.text);
+            assert!(lines
+                .iter()
+                .all(|line| line.chars().count() <= options.max_line_length));
+
+            assert!(cue.start_time >= previous_end, "{:?}", cue);
+            assert!(cue.end_time > cue.start_time, "{:?}", cue);
+            previous_end = cue.end_time;
+        }
+    }
+
+    #[test]
+    fn test_segment_transcript() {
+        let options = SegmentationOptions::default();
+        let cues = segment(transcript(), &options);
+        assert_within(&cues, &options);
+
+        let texts: Vec<&str> = cues.iter().map(|cue| cue.text.as_str()).collect();
+        assert_eq!(
+            texts,
+            vec![
+                "So today we are going to talk about\nhow FFmpeg works under the hood,",
+                "and why it is still the most\nimportant piece of software in video.",
+                "Right?",
+                "It decodes, encodes, transcodes\nand filters basically anything.",
+                "Seriously, anything.",
+            ]
+        );
+
+        // cues start and end with the words, unless they need more time to be read
+        assert_eq!((cues[0].start_time, cues[0].end_time), (1200, 4900));
+        assert_eq!((cues[1].start_time, cues[1].end_time), (4900, 8940));
+        assert_eq!((cues[2].start_time, cues[2].end_time), (10460, 11020));
+        assert_eq!((cues[3].start_time, cues[3].end_time), (11020, 14700));
+        assert_eq!((cues[4].start_time, cues[4].end_time), (15300, 16477));
+    }
+
+    #[test]
+    fn test_segment_translated_transcript() {
+        let options = SegmentationOptions::default();
+        let mut sentences = transcript();
+        sentences[0].text = "Então hoje nós vamos falar sobre como o FFmpeg funciona por dentro, e por que ele ainda é o software mais importante do vídeo.".to_string();
+
+        let cues = segment(sentences, &options);
+        assert_within(&cues, &options);
+
+        // the translation is spread over the spoken words, not over the whole sentence
+        assert_eq!(cues[0].start_time, 1200);
+        assert!(cues[0].text.ends_with("por dentro,"), "{:?}", cues[0].text);
+        assert!((4200..=5100).contains(&cues[0].end_time), "{:?}", cues[0]);
+        assert!((4900..=5300).contains(&cues[1].start_time), "{:?}", cues[1]);
+        assert_eq!(cues[1].end_time, 8940);
+    }
+
+    #[test]
+    fn test_segment_without_words() {
+        let options = SegmentationOptions::default();
+        let sentence = Sentence {
+            text: "Você deveria se matar agora e dar a outra pessoa um pedaço da camada de oxigênio e ozônio que está encoberta para que possamos respirar dentro dessa bolha azul.".to_string(),
+            start_time: 5170,
+            end_time: 15706,
+            words: None,
+            speaker: None,
+        };
+
+        let cues = segment(vec![sentence], &options);
+        assert_within(&cues, &options);
+        assert_eq!(cues.len(), 2);
+        assert_eq!(cues[0].start_time, 5170);
+        assert!(cues[0].end_time <= cues[1].start_time);
+        assert_eq!(cues[1].end_time, 15706);
+    }
+
+    #[test]
+    fn test_segment_on_pauses_and_duration() {
+        let options = SegmentationOptions {
+            max_duration: 1200,
+            ..Default::default()
+        };
+        let word = |start_time, end_time, text: &str| Word {
+            start_time,
+            end_time,
+            text: text.to_string(),
+        };
+        let sentence = Sentence {
+            text: "Um dois três quatro cinco".to_string(),
+            start_time: 0,
+            end_time: 4000,
+            words: Some(vec![
+                word(0, 400, "Um"),
+                word(1200, 1500, "dois"),
+                word(1500, 2000, "três"),
+                word(2000, 2600, "quatro"),
+                word(3700, 4000, "cinco"),
+            ]),
+            speaker: Some("B".to_string()),
+        };
+
+        let cues = segment(vec![sentence], &options);
+        let texts: Vec<&str> = cues.iter().map(|cue| cue.text.as_str()).collect();
+        assert_eq!(texts, vec!["Um", "dois três", "quatro", "cinco"]);
+        assert_eq!((cues[0].start_time, cues[0].end_time), (0, 1000));
+        assert_eq!((cues[2].start_time, cues[2].end_time), (2000, 3000));
+        assert_eq!((cues[3].start_time, cues[3].end_time), (3700, 4700));
+        assert!(cues.iter().all(|cue| cue.speaker == Some("B".to_string())));
+    }
+
+    #[test]
+    fn test_balanced_wrap() {
+        let words = ["Você", "não", "serve", "para", "nada", "nenhuma", "mesmo"];
+        assert_eq!(
+            balanced_wrap(&words, 30),
+            vec!["Você não serve para", "nada nenhuma mesmo"]
+        );
+        assert_eq!(balanced_wrap(&["Oi"], 30), vec!["Oi"]);
+    }
+}
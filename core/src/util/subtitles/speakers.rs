@@ -0,0 +1,103 @@
+use crate::{
+    database::models::video::speaker_labels::SpeakerLabels,
+    internals::transcriber::traits::Sentence,
+};
+
+/// Colors of the speakers after the first one, which keeps the color of the style
+const COLORS: [&str; 5] = ["#FFFF00", "#00FFFF", "#00FF00", "#FF80FF", "#FFA040"];
+
+/// Marks who says each cue, with `<font>` tags for the colors so they are kept by the
+/// srt files. Nothing changes when the cues have less than two speakers
+pub fn label(cues: &mut [Sentence], labels: SpeakerLabels) {
+    let mut speakers: Vec<String> = vec![];
+    for speaker in cues.iter().filter_map(|cue| cue.speaker.as_ref()) {
+        if !speakers.contains(speaker) {
+            speakers.push(speaker.clone());
+        }
+    }
+
+    if speakers.len() < 2 {
+        return;
+    }
+
+    let mut previous: Option<String> = None;
+    for cue in cues.iter_mut() {
+        let speaker = match &cue.speaker {
+            Some(speaker) => speaker.clone(),
+            None => continue,
+        };
+
+        match labels {
+            SpeakerLabels::None => return,
+            SpeakerLabels::Dashes => {
+                let changed = previous.as_ref().is_some_and(|p| *p != speaker);
+                if changed {
+                    cue.text = format!("- {}", cue.text);
+                }
+            }
+            SpeakerLabels::Colors => {
+                let index = speakers.iter().position(|s| *s == speaker).unwrap_or(0);
+                if index > 0 {
+                    let color = COLORS[(index - 1) % COLORS.len()];
+                    cue.text = format!("<font color=\"{}\">{}</font>", color, cue.text);
+                }
+            }
+        }
+
+        previous = Some(speaker);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cues(speakers: &[Option<&str>]) -> Vec<Sentence> {
+        return speakers
+            .iter()
+            .enumerate()
+            .map(|(index, speaker)| Sentence {
+                start_time: index as i32 * 1000,
+                end_time: index as i32 * 1000 + 900,
+                text: format!("Fala {}", index),
+                words: None,
+                speaker: speaker.map(|speaker| speaker.to_string()),
+            })
+            .collect();
+    }
+
+    fn texts(cues: &[Sentence]) -> Vec<&str> {
+        return cues.iter().map(|cue| cue.text.as_str()).collect();
+    }
+
+    #[test]
+    fn test_dashes() {
+        let mut cues = cues(&[Some("A"), Some("A"), Some("B"), None, Some("A")]);
+        label(&mut cues, SpeakerLabels::Dashes);
+
+        assert_eq!(
+            texts(&cues),
+            vec!["Fala 0", "Fala 1", "- Fala 2", "Fala 3", "- Fala 4"]
+        );
+    }
+
+    #[test]
+    fn test_colors() {
+        let mut cues = cues(&[Some("B"), Some("A"), Some("B")]);
+        label(&mut cues, SpeakerLabels::Colors);
+
+        assert_eq!(
+            texts(&cues),
+            vec!["Fala 0", "<font color=\"#FFFF00\">Fala 1</font>", "Fala 2"]
+        );
+    }
+
+    #[test]
+    fn test_single_speaker() {
+        let mut cues = cues(&[Some("A"), Some("A")]);
+        label(&mut cues, SpeakerLabels::Dashes);
+        label(&mut cues, SpeakerLabels::Colors);
+
+        assert_eq!(texts(&cues), vec!["Fala 0", "Fala 1"]);
+    }
+}
@@ -0,0 +1,99 @@
+use super::{format_time, parse_timing, strip_overrides, Cue, Document, SubtitleError};
+
+/// Cue numbers are ignored, `write` numbers the cues again
+pub(super) fn parse(input: &str) -> Result<Document, SubtitleError> {
+    let lines: Vec<&str> = input.split('\n').collect();
+    let mut cues = vec![];
+    let mut index = 0;
+
+    while index < lines.len() {
+        if lines[index].trim().is_empty() {
+            index += 1;
+            continue;
+        }
+
+        let cue_line = index + 1;
+        if !lines[index].contains("-->") {
+            index += 1;
+            if lines.get(index).map_or(true, |line| line.trim().is_empty()) {
+                return Err(SubtitleError::at(cue_line, "Missing the time of the cue"));
+            }
+        }
+
+        let (start_time, end_time, _) = parse_timing(lines[index], index + 1)?;
+        index += 1;
+
+        let mut text = vec![];
+        while index < lines.len() && !lines[index].trim().is_empty() {
+            text.push(lines[index]);
+            index += 1;
+        }
+
+        let mut cue = Cue::new(start_time, end_time, text.join("\n"));
+        cue.line = Some(cue_line);
+        cues.push(cue);
+    }
+
+    return Ok(Document {
+        styles: vec![],
+        cues,
+        resolution: None,
+    });
+}
+
+pub(super) fn write(document: &Document) -> String {
+    let mut srt = String::new();
+
+    for (index, cue) in document.cues.iter().enumerate() {
+        srt.push_str(&format!("{}\n", index + 1));
+        srt.push_str(&format!(
+            "{} --> {}\n",
+            format_time(cue.start_time, ','),
+            format_time(cue.end_time, ',')
+        ));
+        srt.push_str(&format!("{}\n\n", strip_overrides(&cue.text)));
+    }
+
+    return srt;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_write() {
+        let srt = "1\n00:00:01,370 --> 00:00:02,654\nSua vida não é nada.\n\n2\n00:00:02,772 --> 00:00:04,750\n<i>Você não serve</i>\npara nada.\n\n";
+
+        let document = parse(srt).unwrap();
+        assert_eq!(document.cues.len(), 2);
+        assert_eq!(document.cues[0].start_time, 1370);
+        assert_eq!(document.cues[0].end_time, 2654);
+        assert_eq!(document.cues[0].line, Some(1));
+        assert_eq!(document.cues[1].text, "<i>Você não serve</i>\npara nada.");
+        assert_eq!(document.cues[1].line, Some(5));
+
+        assert_eq!(write(&document), srt);
+    }
+
+    #[test]
+    fn test_parse_without_numbers() {
+        let document = parse("\n\n00:00:01,000 --> 00:00:02,000\nOi\n\n\n\n").unwrap();
+        assert_eq!(document.cues.len(), 1);
+        assert_eq!(document.cues[0].line, Some(3));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let error =
+            parse("1\n00:00:01,000 --> 00:00:02,000\nOi\n\n2\n00:00:03 -> 00:00:04\nTchau\n")
+                .unwrap_err();
+        assert_eq!(error.line, Some(6));
+
+        let error = parse("1\n00:00:01,000 --> 00:00:0x,000\nOi\n").unwrap_err();
+        assert_eq!(error.to_string(), "Line 2: Invalid time: 00:00:0x,000");
+
+        let error = parse("1\n\n00:00:01,000 --> 00:00:02,000\nOi\n").unwrap_err();
+        assert_eq!(error.line, Some(1));
+    }
+}
@@ -0,0 +1,126 @@
+use super::{format_time, parse_timing, strip_overrides, Cue, Document, SubtitleError};
+
+/// Cue identifiers and the NOTE, STYLE and REGION blocks are skipped
+pub(super) fn parse(input: &str) -> Result<Document, SubtitleError> {
+    let lines: Vec<&str> = input.split('\n').collect();
+
+    let header = lines.first().copied().unwrap_or_default();
+    let is_header =
+        header == "WEBVTT" || header.starts_with("WEBVTT ") || header.starts_with("WEBVTT\t");
+    if !is_header {
+        return Err(SubtitleError::at(1, "Missing the WEBVTT header"));
+    }
+
+    let mut cues = vec![];
+    let mut index = 1;
+
+    // the header may continue until the first blank line
+    while index < lines.len() && !lines[index].trim().is_empty() {
+        index += 1;
+    }
+
+    while index < lines.len() {
+        if lines[index].trim().is_empty() {
+            index += 1;
+            continue;
+        }
+
+        let block = lines[index];
+        if block.starts_with("NOTE") || block.starts_with("STYLE") || block.starts_with("REGION") {
+            while index < lines.len() && !lines[index].trim().is_empty() {
+                index += 1;
+            }
+            continue;
+        }
+
+        let cue_line = index + 1;
+        if !block.contains("-->") {
+            index += 1;
+            if lines.get(index).map_or(true, |line| line.trim().is_empty()) {
+                return Err(SubtitleError::at(cue_line, "Missing the time of the cue"));
+            }
+        }
+
+        let (start_time, end_time, settings) = parse_timing(lines[index], index + 1)?;
+        index += 1;
+
+        let mut text = vec![];
+        while index < lines.len() && !lines[index].trim().is_empty() {
+            text.push(lines[index]);
+            index += 1;
+        }
+
+        let mut cue = Cue::new(start_time, end_time, text.join("\n"));
+        cue.settings = settings.filter(|settings| !settings.is_empty());
+        cue.line = Some(cue_line);
+        cues.push(cue);
+    }
+
+    return Ok(Document {
+        styles: vec![],
+        cues,
+        resolution: None,
+    });
+}
+
+pub(super) fn write(document: &Document) -> String {
+    let mut vtt = String::from("WEBVTT\n\n");
+
+    for cue in &document.cues {
+        let settings = match &cue.settings {
+            Some(settings) => format!(" {}", settings),
+            None => String::new(),
+        };
+
+        vtt.push_str(&format!(
+            "{} --> {}{}\n",
+            format_time(cue.start_time, '.'),
+            format_time(cue.end_time, '.'),
+            settings
+        ));
+        vtt.push_str(&format!("{}\n\n", strip_overrides(&cue.text)));
+    }
+
+    return vtt;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_write() {
+        let vtt = "WEBVTT\n\n00:00:01.370 --> 00:00:02.654\nSua vida não é nada.\n\n00:00:02.772 --> 00:00:04.750 align:start line:0\n<b>Você não serve</b>\npara nada.\n\n";
+
+        let document = parse(vtt).unwrap();
+        assert_eq!(document.cues.len(), 2);
+        assert_eq!(document.cues[0].start_time, 1370);
+        assert_eq!(document.cues[1].line, Some(6));
+        assert_eq!(
+            document.cues[1].settings.as_deref(),
+            Some("align:start line:0")
+        );
+
+        assert_eq!(write(&document), vtt);
+    }
+
+    #[test]
+    fn test_parse_skips_blocks() {
+        let vtt = "WEBVTT - Legendas\nKind: captions\n\nNOTE revisado\npor alguém\n\nSTYLE\n::cue { color: yellow }\n\nintro\n01:02.000 --> 01:03.500\nOi\n";
+
+        let document = parse(vtt).unwrap();
+        assert_eq!(document.cues.len(), 1);
+        assert_eq!(document.cues[0].start_time, 62000);
+        assert_eq!(document.cues[0].end_time, 63500);
+        assert_eq!(document.cues[0].line, Some(10));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let error = parse("1\n00:00:01,000 --> 00:00:02,000\nOi\n").unwrap_err();
+        assert_eq!(error.to_string(), "Line 1: Missing the WEBVTT header");
+
+        let error = parse("WEBVTT\n\n00:00:01.000 --> 00:00:02\nOi\n").unwrap_err();
+        assert_eq!(error.line, Some(3));
+    }
+}
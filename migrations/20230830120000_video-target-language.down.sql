@@ -0,0 +1,2 @@
+-- Add down migration script here
+ALTER TABLE videos DROP COLUMN target_language;
@@ -0,0 +1,3 @@
+-- Add up migration script here
+ALTER TABLE videos
+ADD COLUMN target_language varchar(255) NOT NULL DEFAULT 'pt-br';
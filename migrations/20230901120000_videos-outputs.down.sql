@@ -0,0 +1,13 @@
+-- Add down migration script here
+ALTER TABLE
+  videos_storages DROP COLUMN language;
+
+ALTER TABLE
+  videos_translations DROP CONSTRAINT videos_translations_pkey;
+
+ALTER TABLE
+  videos_translations
+ADD
+  PRIMARY KEY (video_id);
+
+DROP TABLE videos_outputs;
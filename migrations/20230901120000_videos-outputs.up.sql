@@ -0,0 +1,38 @@
+-- Add up migration script here
+CREATE TABLE IF NOT EXISTS videos_outputs (
+  id SERIAL PRIMARY KEY,
+  video_id UUID NOT NULL REFERENCES videos(id),
+  channel_id INTEGER NOT NULL REFERENCES channels(id),
+  language VARCHAR(255) NOT NULL,
+  stage videos_video_stages NOT NULL DEFAULT 'DOWNLOADING',
+  url VARCHAR(255),
+  created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+  updated_at TIMESTAMP NOT NULL DEFAULT NOW(),
+  uploaded_at TIMESTAMP,
+  UNIQUE (video_id, language)
+);
+
+INSERT INTO
+  videos_outputs (video_id, channel_id, language, stage, url, uploaded_at)
+SELECT
+  id,
+  channel_id,
+  target_language,
+  stage,
+  url,
+  uploaded_at
+FROM
+  videos;
+
+ALTER TABLE
+  videos_translations DROP CONSTRAINT videos_translations_pkey;
+
+ALTER TABLE
+  videos_translations
+ADD
+  PRIMARY KEY (video_id, language);
+
+ALTER TABLE
+  videos_storages
+ADD
+  COLUMN language VARCHAR(255);
@@ -0,0 +1,4 @@
+-- Add down migration script here
+DELETE FROM service_providers_types WHERE service_provider_id = 1 AND service_type_id = 1;
+
+DROP TABLE IF EXISTS queue_messages;
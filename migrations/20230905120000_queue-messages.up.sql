@@ -0,0 +1,13 @@
+-- Add up migration script here
+CREATE TABLE IF NOT EXISTS queue_messages (
+  id BIGSERIAL PRIMARY KEY,
+  body TEXT NOT NULL,
+  receipt_handle UUID UNIQUE,
+  visible_at TIMESTAMP NOT NULL DEFAULT NOW(),
+  created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+  updated_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS queue_messages_visible_at_idx ON queue_messages (visible_at);
+
+INSERT INTO service_providers_types (service_provider_id, service_type_id) VALUES (1, 1); -- LOCAL - STORAGE
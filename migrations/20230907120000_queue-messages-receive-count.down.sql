@@ -0,0 +1,5 @@
+-- Add down migration script here
+DROP INDEX IF EXISTS queue_messages_dead_lettered_at_idx;
+
+ALTER TABLE queue_messages DROP COLUMN dead_lettered_at;
+ALTER TABLE queue_messages DROP COLUMN receive_count;
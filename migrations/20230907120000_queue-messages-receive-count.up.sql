@@ -0,0 +1,5 @@
+-- Add up migration script here
+ALTER TABLE queue_messages ADD COLUMN receive_count INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE queue_messages ADD COLUMN dead_lettered_at TIMESTAMP;
+
+CREATE INDEX IF NOT EXISTS queue_messages_dead_lettered_at_idx ON queue_messages (dead_lettered_at);
@@ -0,0 +1,12 @@
+-- Add up migration script here
+CREATE TABLE IF NOT EXISTS failed_messages (
+  id SERIAL PRIMARY KEY,
+  payload TEXT NOT NULL,
+  video_ids UUID[] NOT NULL DEFAULT '{}',
+  error TEXT NOT NULL,
+  stage videos_video_stages,
+  attempts INTEGER NOT NULL DEFAULT 1,
+  replayed_at TIMESTAMP,
+  created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+  updated_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
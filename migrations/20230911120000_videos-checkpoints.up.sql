@@ -0,0 +1,8 @@
+-- Add up migration script here
+CREATE TABLE IF NOT EXISTS videos_checkpoints (
+  video_id UUID NOT NULL REFERENCES videos(id),
+  stage videos_video_stages NOT NULL,
+  language VARCHAR(255) NOT NULL DEFAULT '',
+  created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+  PRIMARY KEY (video_id, stage, language)
+);
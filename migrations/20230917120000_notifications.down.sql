@@ -0,0 +1,5 @@
+-- Add down migration script here
+DROP INDEX IF EXISTS idx_email_notifications_pending;
+DROP TABLE IF EXISTS email_notifications;
+DROP TYPE IF EXISTS notification_kinds;
+DROP TABLE IF EXISTS notification_preferences;
@@ -0,0 +1,29 @@
+-- Add up migration script here
+CREATE TABLE IF NOT EXISTS notification_preferences (
+  user_id INTEGER PRIMARY KEY REFERENCES users (id),
+  videos_finished BOOLEAN NOT NULL DEFAULT TRUE,
+  video_failed BOOLEAN NOT NULL DEFAULT TRUE,
+  created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+  updated_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+CREATE TYPE notification_kinds AS ENUM ('VIDEOS_FINISHED', 'VIDEO_FAILED');
+
+CREATE TABLE IF NOT EXISTS email_notifications (
+  id BIGSERIAL PRIMARY KEY,
+  user_id INTEGER NOT NULL REFERENCES users (id),
+  kind notification_kinds NOT NULL,
+  -- what the email is about, the same thing is never notified twice
+  reference VARCHAR(255) NOT NULL,
+  params TEXT NOT NULL,
+  attempts INTEGER NOT NULL DEFAULT 0,
+  last_error TEXT,
+  sent_at TIMESTAMP,
+  created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+  updated_at TIMESTAMP NOT NULL DEFAULT NOW(),
+  UNIQUE (kind, reference)
+);
+
+CREATE INDEX idx_email_notifications_pending ON email_notifications (id)
+WHERE
+  sent_at IS NULL;
@@ -0,0 +1,44 @@
+-- Add down migration script here
+DROP TABLE IF EXISTS videos_reviews;
+
+ALTER TABLE videos DROP COLUMN IF EXISTS review;
+
+UPDATE videos SET stage = 'TRANSLATING' WHERE stage = 'REVIEWING';
+UPDATE videos_outputs SET stage = 'TRANSLATING' WHERE stage = 'REVIEWING';
+UPDATE videos_errors SET stage = 'TRANSLATING' WHERE stage = 'REVIEWING';
+UPDATE failed_messages SET stage = 'TRANSLATING' WHERE stage = 'REVIEWING';
+DELETE FROM videos_checkpoints WHERE stage = 'REVIEWING';
+
+CREATE TYPE videos_video_stages_temp AS ENUM (
+  'DOWNLOADING',
+  'TRANSCRIBING',
+  'TRANSLATING',
+  'SUBTITLING',
+  'DONE',
+  'UPLOADING',
+  'CUTTING',
+  'RAW_UPLOADING'
+);
+ALTER TABLE videos
+ALTER COLUMN stage DROP DEFAULT;
+ALTER TABLE videos_outputs
+ALTER COLUMN stage DROP DEFAULT;
+ALTER TABLE videos
+ALTER COLUMN stage TYPE videos_video_stages_temp USING stage::text::videos_video_stages_temp;
+ALTER TABLE videos_outputs
+ALTER COLUMN stage TYPE videos_video_stages_temp USING stage::text::videos_video_stages_temp;
+ALTER TABLE videos_errors
+ALTER COLUMN stage TYPE videos_video_stages_temp USING stage::text::videos_video_stages_temp;
+ALTER TABLE failed_messages
+ALTER COLUMN stage TYPE videos_video_stages_temp USING stage::text::videos_video_stages_temp;
+ALTER TABLE videos_checkpoints
+ALTER COLUMN stage TYPE videos_video_stages_temp USING stage::text::videos_video_stages_temp;
+DROP TYPE videos_video_stages;
+ALTER TYPE videos_video_stages_temp
+RENAME TO videos_video_stages;
+ALTER TABLE videos
+ALTER COLUMN stage
+SET DEFAULT 'DOWNLOADING';
+ALTER TABLE videos_outputs
+ALTER COLUMN stage
+SET DEFAULT 'DOWNLOADING';
@@ -0,0 +1,17 @@
+-- Add up migration script here
+ALTER TYPE videos_video_stages
+ADD VALUE 'REVIEWING' AFTER 'TRANSLATING';
+
+ALTER TABLE videos
+ADD COLUMN review BOOLEAN NOT NULL DEFAULT FALSE;
+
+CREATE TABLE IF NOT EXISTS videos_reviews (
+  id SERIAL PRIMARY KEY,
+  video_id UUID NOT NULL REFERENCES videos(id),
+  language VARCHAR(255) NOT NULL,
+  srt TEXT NOT NULL,
+  approved_at TIMESTAMP,
+  created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+  updated_at TIMESTAMP NOT NULL DEFAULT NOW(),
+  UNIQUE (video_id, language)
+);
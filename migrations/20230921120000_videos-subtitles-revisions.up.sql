@@ -0,0 +1,12 @@
+-- Add up migration script here
+CREATE TABLE IF NOT EXISTS videos_subtitles_revisions (
+  id SERIAL PRIMARY KEY,
+  video_id UUID NOT NULL REFERENCES videos(id),
+  language VARCHAR(255) NOT NULL,
+  revision INTEGER NOT NULL,
+  storage_id INTEGER NOT NULL REFERENCES service_providers(id),
+  path VARCHAR(255) NOT NULL,
+  user_id INTEGER NOT NULL REFERENCES users(id),
+  created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+  UNIQUE (video_id, language, revision)
+);
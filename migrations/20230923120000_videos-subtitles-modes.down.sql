@@ -0,0 +1,4 @@
+-- Add down migration script here
+ALTER TABLE videos DROP COLUMN IF EXISTS subtitles_mode;
+
+DROP TYPE IF EXISTS videos_subtitles_modes;
@@ -0,0 +1,5 @@
+-- Add up migration script here
+CREATE TYPE videos_subtitles_modes AS ENUM ('BURNED', 'SOFT', 'BOTH');
+
+ALTER TABLE videos
+ADD COLUMN subtitles_mode videos_subtitles_modes NOT NULL DEFAULT 'BURNED';
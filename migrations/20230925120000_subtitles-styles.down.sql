@@ -0,0 +1,5 @@
+-- Add down migration script here
+ALTER TABLE videos DROP COLUMN IF EXISTS subtitles_style_id;
+ALTER TABLE channels DROP COLUMN IF EXISTS subtitles_style_id;
+DROP TABLE IF EXISTS subtitles_styles;
+DROP TYPE IF EXISTS subtitles_positions;
@@ -0,0 +1,26 @@
+-- Add up migration script here
+CREATE TYPE subtitles_positions AS ENUM ('TOP', 'MIDDLE', 'BOTTOM');
+
+CREATE TABLE IF NOT EXISTS subtitles_styles (
+  id SERIAL PRIMARY KEY,
+  user_id INTEGER NOT NULL REFERENCES users (id),
+  name VARCHAR(255) NOT NULL,
+  font_name VARCHAR(255) NOT NULL,
+  font_size INTEGER NOT NULL,
+  primary_color VARCHAR(9) NOT NULL,
+  outline_color VARCHAR(9) NOT NULL,
+  outline INTEGER NOT NULL,
+  background_color VARCHAR(9),
+  position subtitles_positions NOT NULL DEFAULT 'BOTTOM',
+  margin INTEGER NOT NULL,
+  max_line_width INTEGER,
+  created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+  updated_at TIMESTAMP NOT NULL DEFAULT NOW(),
+  UNIQUE (user_id, name)
+);
+
+ALTER TABLE channels
+ADD COLUMN subtitles_style_id INTEGER REFERENCES subtitles_styles (id) ON DELETE SET NULL;
+
+ALTER TABLE videos
+ADD COLUMN subtitles_style_id INTEGER REFERENCES subtitles_styles (id) ON DELETE SET NULL;
@@ -0,0 +1,3 @@
+-- Add down migration script here
+DELETE FROM service_providers_types WHERE service_provider_id = 7 AND service_type_id = 2;
+DELETE FROM service_providers WHERE name = 'Whisper';
@@ -0,0 +1,3 @@
+-- Add up migration script here
+INSERT INTO service_providers (name, created_at, updated_at) VALUES ('Whisper', NOW(), NOW());
+INSERT INTO service_providers_types (service_provider_id, service_type_id) VALUES (7, 2); -- Whisper - TRANSCRIPTION
@@ -0,0 +1,4 @@
+-- Add down migration script here
+ALTER TABLE videos DROP COLUMN IF EXISTS speaker_labels;
+
+DROP TYPE IF EXISTS videos_speaker_labels;
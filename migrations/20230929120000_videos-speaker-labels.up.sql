@@ -0,0 +1,5 @@
+-- Add up migration script here
+CREATE TYPE videos_speaker_labels AS ENUM ('NONE', 'DASHES', 'COLORS');
+
+ALTER TABLE videos
+ADD COLUMN speaker_labels videos_speaker_labels NOT NULL DEFAULT 'NONE';
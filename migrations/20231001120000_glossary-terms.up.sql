@@ -0,0 +1,13 @@
+-- Add up migration script here
+CREATE TABLE IF NOT EXISTS glossary_terms (
+  id SERIAL PRIMARY KEY,
+  channel_id INTEGER NOT NULL REFERENCES channels (id) ON DELETE CASCADE,
+  term VARCHAR(255) NOT NULL,
+  language VARCHAR(10),
+  translation VARCHAR(255),
+  created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+  updated_at TIMESTAMP NOT NULL DEFAULT NOW(),
+  CHECK ((language IS NULL) = (translation IS NULL))
+);
+
+CREATE INDEX IF NOT EXISTS glossary_terms_channel_id_idx ON glossary_terms (channel_id);
@@ -0,0 +1,2 @@
+-- Add down migration script here
+ALTER TABLE outbox_messages DROP COLUMN IF EXISTS locked_until;
@@ -0,0 +1,2 @@
+-- Add up migration script here
+ALTER TABLE outbox_messages ADD COLUMN locked_until TIMESTAMP;
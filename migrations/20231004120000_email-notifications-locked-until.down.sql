@@ -0,0 +1,2 @@
+-- Add down migration script here
+ALTER TABLE email_notifications DROP COLUMN IF EXISTS locked_until;
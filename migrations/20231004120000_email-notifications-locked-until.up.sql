@@ -0,0 +1,2 @@
+-- Add up migration script here
+ALTER TABLE email_notifications ADD COLUMN locked_until TIMESTAMP;
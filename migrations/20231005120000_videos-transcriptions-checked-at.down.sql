@@ -0,0 +1,2 @@
+-- Add down migration script here
+ALTER TABLE videos_transcriptions DROP COLUMN IF EXISTS checked_at;
@@ -0,0 +1,2 @@
+-- Add up migration script here
+ALTER TABLE videos_transcriptions ADD COLUMN checked_at TIMESTAMP;
@@ -0,0 +1,198 @@
+use std::collections::HashSet;
+
+use marco_polo_rs_core::internals::cloud::models::payload::PayloadType;
+use serde::Deserialize;
+
+/// Heavy workers subtitle videos, light workers handle every other payload
+const HEAVY_PAYLOAD_NAMES: [&str; 1] = [PayloadType::SRT_TRANSLATION_UPLOAD];
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WorkerKind {
+    Light,
+    Heavy,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct WorkerClassConfig {
+    pub name: String,
+    pub kind: WorkerKind,
+    pub concurrency: usize,
+    pub payloads: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct WorkersConfig {
+    pub classes: Vec<WorkerClassConfig>,
+}
+
+impl WorkersConfig {
+    /// Reads the file on `QUEUE_WORKERS_CONFIG`, without it a heavy worker
+    /// subtitles and a light worker per remaining physical cpu handles the rest
+    pub fn load() -> Self {
+        let config = match std::env::var("QUEUE_WORKERS_CONFIG") {
+            Ok(path) => {
+                let file = std::fs::read_to_string(&path)
+                    .unwrap_or_else(|e| panic!("Failed to read {}: {}", path, e));
+                Self::from_json(&file).unwrap_or_else(|e| panic!("Invalid {}: {}", path, e))
+            }
+            Err(_) => Self::default_for(num_cpus::get_physical()),
+        };
+
+        if let Err(e) = config.validate() {
+            panic!("Invalid workers config: {}", e);
+        }
+
+        return config;
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+
+    pub fn default_for(thread_count: usize) -> Self {
+        let light_payloads = PayloadType::NAMES
+            .iter()
+            .filter(|name| !HEAVY_PAYLOAD_NAMES.contains(name))
+            .map(|name| name.to_string())
+            .collect();
+
+        WorkersConfig {
+            classes: vec![
+                WorkerClassConfig {
+                    name: "heavy".to_string(),
+                    kind: WorkerKind::Heavy,
+                    concurrency: 1,
+                    payloads: HEAVY_PAYLOAD_NAMES.iter().map(|n| n.to_string()).collect(),
+                },
+                WorkerClassConfig {
+                    name: "light".to_string(),
+                    kind: WorkerKind::Light,
+                    concurrency: thread_count.saturating_sub(1).max(1),
+                    payloads: light_payloads,
+                },
+            ],
+        }
+    }
+
+    /// Every payload must be routed to exactly one class able to handle it
+    pub fn validate(&self) -> Result<(), String> {
+        let mut routed = HashSet::new();
+
+        for class in &self.classes {
+            if class.concurrency == 0 {
+                return Err(format!("Class {} has no workers", class.name));
+            }
+
+            for payload in &class.payloads {
+                if !PayloadType::NAMES.contains(&payload.as_str()) {
+                    return Err(format!("Unknown payload {}", payload));
+                }
+
+                let heavy = HEAVY_PAYLOAD_NAMES.contains(&payload.as_str());
+                if heavy != (class.kind == WorkerKind::Heavy) {
+                    return Err(format!(
+                        "Class {} can't handle {} with {:?} workers",
+                        class.name, payload, class.kind
+                    ));
+                }
+
+                if !routed.insert(payload.as_str()) {
+                    return Err(format!("Payload {} is routed more than once", payload));
+                }
+            }
+        }
+
+        for payload in PayloadType::NAMES {
+            if !routed.contains(payload) {
+                return Err(format!("Payload {} is not routed to any class", payload));
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn total_concurrency(&self) -> usize {
+        self.classes.iter().map(|class| class.concurrency).sum()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{WorkerKind, WorkersConfig};
+
+    const CONFIG: &str = r#"
+    {
+        "classes": [
+            {
+                "name": "subtitler",
+                "kind": "heavy",
+                "concurrency": 1,
+                "payloads": ["BatukaSrtTranslationUpload"]
+            },
+            {
+                "name": "downloader",
+                "kind": "light",
+                "concurrency": 2,
+                "payloads": ["BatukaDownloadVideo", "BatukaCutVideo"]
+            },
+            {
+                "name": "light",
+                "kind": "light",
+                "concurrency": 4,
+                "payloads": [
+                    "BatukaVideoRawUpload",
+                    "BatukaSrtTranscriptionUpload",
+                    "BatukaVideoProcessedUpload"
+                ]
+            }
+        ]
+    }
+    "#;
+
+    #[test]
+    fn test_parse_config() {
+        let config = WorkersConfig::from_json(CONFIG).unwrap();
+
+        assert!(config.validate().is_ok());
+        assert_eq!(config.classes.len(), 3);
+        assert_eq!(config.classes[1].kind, WorkerKind::Light);
+        assert_eq!(config.total_concurrency(), 7);
+    }
+
+    #[test]
+    fn test_default_config() {
+        let config = WorkersConfig::default_for(4);
+
+        assert!(config.validate().is_ok());
+        assert_eq!(config.classes[0].concurrency, 1);
+        assert_eq!(config.classes[1].concurrency, 3);
+    }
+
+    #[test]
+    fn test_unrouted_payload() {
+        let mut config = WorkersConfig::from_json(CONFIG).unwrap();
+        config.classes.remove(1);
+
+        let error = config.validate().unwrap_err();
+        assert!(error.contains("BatukaDownloadVideo"));
+    }
+
+    #[test]
+    fn test_payload_on_wrong_kind() {
+        let mut config = WorkersConfig::from_json(CONFIG).unwrap();
+        config.classes[0].kind = WorkerKind::Light;
+
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_payload_routed_twice() {
+        let mut config = WorkersConfig::from_json(CONFIG).unwrap();
+        config.classes[2]
+            .payloads
+            .push("BatukaCutVideo".to_string());
+
+        assert!(config.validate().is_err());
+    }
+}
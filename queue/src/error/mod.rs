@@ -17,6 +17,16 @@ impl Display for HandlerError {
     }
 }
 
+impl HandlerError {
+    /// Label of the variant on the metrics
+    pub fn kind(&self) -> &'static str {
+        match self {
+            HandlerError::Retrievable(_) => "retrievable",
+            HandlerError::Final(_) => "final",
+        }
+    }
+}
+
 impl From<SyncError> for HandlerError {
     fn from(error: SyncError) -> Self {
         HandlerError::Retrievable(error)
@@ -2,12 +2,14 @@ use std::path::PathBuf;
 
 use marco_polo_rs_core::{
     database::{
-        models::{video::stage::VideoStage, video_storage::StorageVideoStage},
+        models::{
+            video::stage::VideoStage, video_event::ProgressStep, video_storage::StorageVideoStage,
+        },
         queries::{self, storage::CreateStorageDto},
     },
     internals::{
         cloud::{
-            models::payload::VideoCutPayload,
+            models::payload::{PayloadType, VideoCutPayload, VideoDownloadPayload},
             traits::{BucketClient, CloudService, QueueClient},
         },
         ServiceProvider,
@@ -15,7 +17,7 @@ use marco_polo_rs_core::{
     util::{ffmpeg, fs},
 };
 
-use crate::error::HandlerError;
+use crate::{error::HandlerError, progress::ProgressNotifier};
 
 pub async fn handle<CS: CloudService>(
     payload: VideoCutPayload,
@@ -32,13 +34,39 @@ pub async fn handle<CS: CloudService>(
     let video = match queries::video::find_by_id(pool, &video_id).await {
         Ok(video) => video,
         Err(e) => {
-            eprintln!("Failed to find video: {}", e);
+            tracing::error!("Failed to find video: {}", e);
             return Err(HandlerError::Final(e.into()));
         }
     };
 
     let original_id = video.original_video_id;
 
+    // a redelivered message for a video that was already cut and uploaded
+    if queries::checkpoint::exists(pool, &video_id, VideoStage::Cutting, None).await? {
+        tracing::info!("Video {} was already cut, skipping", video_id);
+        delete_original_file(pool, original_id, &original_file_path).await?;
+        return Ok(());
+    }
+
+    // the download was lost, e.g. the temp dir was cleaned when the queue shut down
+    if !PathBuf::from(&original_file_path).exists() {
+        tracing::warn!(
+            "Original file of video {} is missing, downloading it again",
+            video_id
+        );
+        let payload = VideoDownloadPayload {
+            original_video_id: original_id,
+            video_ids: vec![video_id],
+        };
+
+        let mut trx = pool.begin().await?;
+        queries::checkpoint::delete(&mut *trx, &video_id, VideoStage::Downloading, None).await?;
+        queries::outbox::enqueue(&mut *trx, &PayloadType::BatukaDownloadVideo(payload)).await?;
+        trx.commit().await?;
+
+        return Ok(());
+    }
+
     queries::video::change_stage(pool, &video_id, VideoStage::Cutting).await?;
 
     let end_time = match video.end_time {
@@ -46,7 +74,7 @@ pub async fn handle<CS: CloudService>(
         None => {
             queries::video::change_error_state(pool, &video.id, true).await?; //Need to change this so for the delete_original_file function
             delete_original_file(pool, original_id, &original_file_path).await?;
-            eprintln!("Video {} has no end time", video.id);
+            tracing::error!("Video {} has no end time", video.id);
             return Err(HandlerError::Final("Video has no end time".into()));
         }
     };
@@ -60,7 +88,13 @@ pub async fn handle<CS: CloudService>(
         .change_message_visibility(message, 2000) // TODO: Make this configurable
         .await?;
 
-    let cut_output = match ffmpeg::cut_video(&raw_path, &start_time, &end_time) {
+    let notifier = ProgressNotifier::start(pool, vec![video_id], ProgressStep::Cut);
+    let cut_output = ffmpeg::cut_video(&raw_path, &start_time, &end_time, &|percentage| {
+        notifier.report(percentage)
+    });
+    notifier.finish().await;
+
+    let cut_output = match cut_output {
         Ok(output) => output,
         Err(e) => {
             queries::video::change_error_state(pool, &video.id, true).await?; //Need to change this so for the delete_original_file function
@@ -74,11 +108,16 @@ pub async fn handle<CS: CloudService>(
     let cut_size = match fs::check_file_size(&cut_path) {
         Ok(size) => size,
         Err(e) => {
-            eprintln!("Failed to check file size: {}", e);
+            tracing::error!("Failed to check file size: {}", e);
             0
         }
     };
 
+    cloud_service
+        .bucket_client()
+        .upload_file_from_path(&video_uri, &cut_output)
+        .await?;
+
     let mut trx = pool.begin().await?;
 
     let storage_dto = CreateStorageDto {
@@ -88,15 +127,16 @@ pub async fn handle<CS: CloudService>(
         storage_id: cloud_service.bucket_client().id(),
         stage: StorageVideoStage::Raw,
         size: cut_size as i64, //if some day a negative value appears on the database, this is the reason
+        language: None,
     };
 
     queries::storage::create(&mut *trx, storage_dto).await?;
 
-    queries::video::change_stage(&mut *trx, &video_id, VideoStage::RawUploading).await?;
+    queries::video::change_stage_with(&mut *trx, &video_id, VideoStage::RawUploading).await?;
 
-    cloud_service
-        .bucket_client()
-        .upload_file_from_path(&video_uri, &cut_output)
+    queries::checkpoint::create(&mut *trx, &video_id, VideoStage::Cutting, None).await?;
+
+    queries::outbox::enqueue_upload_event(&mut *trx, cloud_service.bucket_client(), &video_uri)
         .await?;
 
     trx.commit().await?;
@@ -122,7 +162,7 @@ async fn delete_original_file(
             Err(e) => match e.kind() {
                 std::io::ErrorKind::NotFound => (),
                 _ => {
-                    eprintln!("Failed to delete original file: {}", e);
+                    tracing::error!("Failed to delete original file: {}", e);
                     return Err(HandlerError::Final(e.into()));
                 }
             },
@@ -1,6 +1,8 @@
-use futures::future::try_join_all;
 use marco_polo_rs_core::{
-    database::{models::video_storage::VideoFormat, queries},
+    database::{
+        models::{video::stage::VideoStage, video_event::ProgressStep, video_storage::VideoFormat},
+        queries,
+    },
     internals::{
         cloud::{
             models::payload::{PayloadType, VideoCutPayload, VideoDownloadPayload},
@@ -11,7 +13,7 @@ use marco_polo_rs_core::{
     util::ffmpeg,
 };
 
-use crate::error::HandlerError;
+use crate::{error::HandlerError, progress::ProgressNotifier};
 
 pub async fn handle<CS: CloudService>(
     payload: VideoDownloadPayload,
@@ -26,7 +28,24 @@ pub async fn handle<CS: CloudService>(
         queries::original_video::with_video::find_with_videos(pool, id).await?;
 
     let original_video = original_with_video.original_video;
-    let videos = original_with_video.videos;
+
+    let mut videos = vec![];
+    for video in original_with_video.videos {
+        if !payload.video_ids.contains(&video.id) {
+            continue;
+        }
+
+        if queries::checkpoint::exists(pool, &video.id, VideoStage::Downloading, None).await? {
+            tracing::info!("Video {} was already downloaded, skipping", video.id);
+            continue;
+        }
+
+        videos.push(video);
+    }
+
+    if videos.is_empty() {
+        return Ok(());
+    }
 
     let estimated_time = video_downloader.estimate_time(&original_video.url).await?;
 
@@ -35,7 +54,15 @@ pub async fn handle<CS: CloudService>(
         .change_message_visibility(message, estimated_time) // TODO: Make this configurable
         .await?;
 
-    let output_file = video_downloader.download(&original_video.url).await?;
+    let video_ids = videos.iter().map(|video| video.id).collect();
+    let notifier = ProgressNotifier::start(pool, video_ids, ProgressStep::Download);
+    let output_file = video_downloader
+        .download(&original_video.url, &|percentage| {
+            notifier.report(percentage)
+        })
+        .await;
+    notifier.finish().await;
+    let output_file = output_file?;
 
     let raw_path = std::path::PathBuf::from(&output_file);
 
@@ -48,36 +75,37 @@ pub async fn handle<CS: CloudService>(
     };
 
     let mut without_end_time_ids = vec![];
-    let mut messages = vec![];
-
-    for mut video in videos {
-        match video.end_time {
-            Some(_) => {}
-            None => {
-                video.end_time = Some(original_video_duration.to_string());
-                without_end_time_ids.push(video.id);
-            }
+
+    for video in &videos {
+        if video.end_time.is_none() {
+            without_end_time_ids.push(video.id);
         }
+    }
+
+    let mut trx = pool.begin().await?;
+
+    queries::video::bulk_update_end_time(&mut *trx, without_end_time_ids, &original_video_duration)
+        .await?;
+
+    queries::original_video::update_duration(
+        &mut *trx,
+        original_video.id,
+        &original_video_duration,
+    )
+    .await?;
 
+    for video in videos {
         let payload: VideoCutPayload = VideoCutPayload {
             video_id: video.id,
             video_format: VideoFormat::Mkv,
             file_path: output_file.clone(),
         };
 
-        let payload = PayloadType::BatukaCutVideo(payload);
-
-        let handler = cloud_service.queue_client().send_message(payload);
-        messages.push(handler);
+        queries::outbox::enqueue(&mut *trx, &PayloadType::BatukaCutVideo(payload)).await?;
+        queries::checkpoint::create(&mut *trx, &video.id, VideoStage::Downloading, None).await?;
     }
 
-    queries::video::bulk_update_end_time(pool, without_end_time_ids, &original_video_duration)
-        .await?;
-
-    queries::original_video::update_duration(pool, original_video.id, &original_video_duration)
-        .await?;
-
-    try_join_all(messages).await?;
+    trx.commit().await?;
 
     Ok(())
 }
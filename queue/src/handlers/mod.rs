@@ -4,3 +4,6 @@ pub mod processed_upload;
 pub mod raw_upload;
 pub mod transcription;
 pub mod translation;
+
+#[cfg(test)]
+mod test;
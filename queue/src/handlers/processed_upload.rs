@@ -1,69 +1,216 @@
 use marco_polo_rs_core::{
     database::{
         models::{
-            channel::platform::Platform, video::stage::VideoStage, video_storage::StorageVideoStage,
+            channel::{platform::Platform, Channel},
+            video::stage::VideoStage,
+            video_event::ProgressStep,
+            video_output::VideoOutput,
         },
         queries::{self},
     },
     internals::{
-        cloud::models::payload::VideoPayload,
-        video_platform::{UploadParams, VideoPlatformClient},
+        cloud::{models::payload::VideoPayload, traits::BucketClient},
+        video_platform::{SubtitlesUploadParams, UploadParams, VideoPlatformClient},
     },
+    SyncError,
 };
-use sqlx::PgPool;
+use sqlx::{types::Uuid, PgPool};
 
-use crate::{error::HandlerError, YoutubeClientInUse};
+use crate::{
+    error::HandlerError, handlers::translation::sidecar_uri, notifications,
+    progress::ProgressNotifier, YoutubeClientInUse,
+};
+
+/// What the youtube client answers with, the platform id of the video is read from it
+type YoutubeVideo = <YoutubeClientInUse as VideoPlatformClient>::VideoResult;
 
-pub async fn handle(
+pub async fn handle<BC, YC>(
     pool: &PgPool,
-    youtube_client: &YoutubeClientInUse,
+    youtube_client: &YC,
+    bucket_client: &BC,
     payload: VideoPayload,
-) -> Result<(), HandlerError> {
-    let video = queries::video::find_by_id_with_storage_and_channel(
+) -> Result<(), HandlerError>
+where
+    BC: BucketClient,
+    YC: VideoPlatformClient<VideoResult = YoutubeVideo> + Sync,
+{
+    let video = queries::video::find_by_id(pool, &payload.video_id).await?;
+
+    // videos processed before the fan-out don't carry a language
+    let language = payload
+        .language
+        .clone()
+        .unwrap_or_else(|| video.target_language.clone());
+
+    let output =
+        queries::output::find_by_video_id_and_language(pool, &payload.video_id, &language).await?;
+
+    let storage = queries::storage::find_processed_by_video_id_and_language(
         pool,
         &payload.video_id,
-        StorageVideoStage::Processed,
+        &language,
     )
     .await?;
 
-    queries::video::change_stage(pool, &payload.video_id, VideoStage::Uploading).await?;
+    // the output was already published, only the video may be left to finish
+    let uploaded =
+        queries::checkpoint::exists(pool, &video.id, VideoStage::Uploading, Some(&language))
+            .await?;
+    if uploaded || output.url.is_some() {
+        tracing::info!(
+            "Video {} was already uploaded in {}, skipping",
+            video.id,
+            language
+        );
+        finish_video(pool, &video.id, &video.target_language).await?;
+        return Ok(());
+    }
 
-    let storage = video.storage;
-    let channel = video.channel;
-    let video = video.video;
+    let channel = queries::channel::find_by_id(pool, output.channel_id).await?;
+
+    queries::video::change_stage(pool, &payload.video_id, VideoStage::Uploading).await?;
+    queries::output::change_stage(pool, output.id, VideoStage::Uploading).await?;
 
+    let notifier = ProgressNotifier::start(pool, vec![video.id], ProgressStep::Upload);
+    let on_progress = |percentage| notifier.report(percentage);
     let upload_params = UploadParams {
         video: &video,
         storage: &storage,
         channel: &channel,
+        on_progress: &on_progress,
     };
 
-    match channel.platform {
+    let result = match channel.platform {
         Platform::Youtube => {
-            youtube_upload(upload_params, youtube_client, pool).await?;
-        }
-        _ => {
-            return Err(HandlerError::Final("Unsupported platform".into()));
+            youtube_upload(upload_params, &output, youtube_client, bucket_client, pool).await
         }
+        _ => Err(HandlerError::Final("Unsupported platform".into())),
     };
+    notifier.finish().await;
+    result?;
+
+    finish_video(pool, &video.id, &video.target_language).await?;
 
     Ok(())
 }
 
-async fn youtube_upload(
+async fn youtube_upload<BC, YC>(
     video: UploadParams<'_>,
-    youtube_client: &YoutubeClientInUse,
+    output: &VideoOutput,
+    youtube_client: &YC,
+    bucket_client: &BC,
     pool: &PgPool,
-) -> Result<(), HandlerError> {
-    let video_id = video.video.id;
+) -> Result<(), HandlerError>
+where
+    BC: BucketClient,
+    YC: VideoPlatformClient<VideoResult = YoutubeVideo> + Sync,
+{
+    let channel = video.channel;
+    let original_language = video.video.language.clone();
     let youtube_video = youtube_client.upload_video(video).await?;
+    let youtube_video_id = youtube_video.id.unwrap();
+
+    let video_url = format!("https://www.youtube.com/watch?v={}", youtube_video_id);
+
+    let mut trx = pool.begin().await?;
+    queries::output::set_url(&mut *trx, output.id, &video_url).await?;
+    queries::checkpoint::create(
+        &mut *trx,
+        &output.video_id,
+        VideoStage::Uploading,
+        Some(&output.language),
+    )
+    .await?;
+    trx.commit().await?;
 
-    let video_url = format!(
-        "https://www.youtube.com/watch?v={}",
-        youtube_video.id.unwrap()
-    );
+    // the video is published already, missing captions are not worth uploading it again
+    let mode = queries::video::find_subtitles_mode(pool, &output.video_id).await?;
+    if mode.has_soft_tracks() {
+        let mut languages = vec![output.language.as_str()];
+        if original_language != output.language {
+            languages.push(&original_language);
+        }
+
+        for language in languages {
+            let result = upload_sidecar(
+                youtube_client,
+                bucket_client,
+                output,
+                channel,
+                &youtube_video_id,
+                language,
+            )
+            .await;
+
+            if let Err(e) = result {
+                tracing::error!(
+                    "Failed to upload the {} captions of video {}: {}",
+                    language,
+                    output.video_id,
+                    e
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+async fn upload_sidecar<BC, YC>(
+    youtube_client: &YC,
+    bucket_client: &BC,
+    output: &VideoOutput,
+    channel: &Channel,
+    platform_video_id: &str,
+    language: &str,
+) -> Result<(), SyncError>
+where
+    BC: BucketClient,
+    YC: VideoPlatformClient + Sync,
+{
+    let sidecar_uri = sidecar_uri(&output.video_id, &output.language, language);
+    let subtitles = bucket_client.download_file(&sidecar_uri).await?;
+
+    youtube_client
+        .upload_subtitles(SubtitlesUploadParams {
+            video_id: platform_video_id,
+            channel,
+            language,
+            subtitles,
+        })
+        .await?;
+
+    Ok(())
+}
+
+/// Marks the video as done once every output was published,
+/// the url of the video is the one of its primary language.
+/// Outputs finishing together both get here, only the one that marks the video notifies it
+async fn finish_video(
+    pool: &PgPool,
+    video_id: &Uuid,
+    primary_language: &str,
+) -> Result<(), HandlerError> {
+    let unfinished = queries::output::count_unfinished(pool, video_id).await?;
+    if unfinished > 0 {
+        return Ok(());
+    }
+
+    let primary =
+        queries::output::find_by_video_id_and_language(pool, video_id, primary_language).await?;
+
+    let finished = match primary.url {
+        Some(url) => queries::video::set_url(pool, *video_id, &url).await?,
+        None => queries::video::finish(pool, *video_id).await?,
+    };
+    if !finished {
+        return Ok(());
+    }
 
-    queries::video::set_url(pool, video_id, &video_url).await?;
+    // the video is published already, a missing email is not worth uploading it again
+    if let Err(e) = notifications::notify_finished(pool, video_id).await {
+        tracing::error!("Failed to notify the finished videos: {}", e);
+    }
 
     Ok(())
 }
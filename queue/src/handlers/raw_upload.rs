@@ -8,7 +8,7 @@ use marco_polo_rs_core::{
             models::payload::VideoPayload,
             traits::{BucketClient, CloudService, QueueClient},
         },
-        transcriber::traits::TranscriberClient,
+        transcriber::traits::{TranscribeOptions, TranscriberClient},
     },
 };
 
@@ -21,6 +21,15 @@ pub async fn handle<CS: CloudService>(
     message: &<<CS as CloudService>::QC as QueueClient>::M,
     payload: VideoPayload,
 ) -> Result<(), HandlerError> {
+    // the transcription was already requested, requesting it again would be paid twice
+    if queries::checkpoint::exists(pool, &payload.video_id, VideoStage::Transcribing, None).await? {
+        tracing::info!(
+            "Video {} is already being transcribed, skipping",
+            payload.video_id
+        );
+        return Ok(());
+    }
+
     let bucket_client = cloud_service.bucket_client();
 
     let queue_client = cloud_service.queue_client();
@@ -33,7 +42,14 @@ pub async fn handle<CS: CloudService>(
 
     queries::video::change_stage(pool, &payload.video_id, VideoStage::Transcribing).await?;
 
-    let transcribe_id = transcriber_client.transcribe(&signed_url).await?;
+    let speaker_labels = queries::video::find_speaker_labels(pool, &payload.video_id).await?;
+    let glossary = queries::glossary::find_all_by_video(pool, &payload.video_id).await?;
+    let options = TranscribeOptions {
+        speaker_labels: speaker_labels.is_enabled(),
+        word_boost: glossary.into_iter().map(|term| term.term).collect(),
+    };
+
+    let transcribe_id = transcriber_client.transcribe(&signed_url, &options).await?;
 
     queries::transcription::create(
         pool,
@@ -45,5 +61,7 @@ pub async fn handle<CS: CloudService>(
     )
     .await?;
 
+    queries::checkpoint::create(pool, &payload.video_id, VideoStage::Transcribing, None).await?;
+
     Ok(())
 }
@@ -0,0 +1,60 @@
+INSERT INTO users (id, name, email, password)
+VALUES (
+    666,
+    'TestUser',
+    'teste@gmail.com',
+    '$2b$12$.jvb858VF4tanKNd11Vp4eDYyhg.KuFgOG8AhgJCvj/cJV47Sqtby'
+  );
+--99020711Aa@
+INSERT INTO channels (id, name, creator_id)
+VALUES (666, 'TestChannel', 666);
+INSERT INTO original_videos(id, url, duration)
+VALUES (
+    1000,
+    'https://www.youtube.com/watch?v=1234567890',
+    '00:10:00'
+  );
+INSERT INTO videos (
+    id,
+    title,
+    description,
+    user_id,
+    channel_id,
+    language,
+    target_language,
+    original_video_id,
+    start_time,
+    end_time
+  )
+VALUES(
+    '806b5a48-f221-11ed-a05b-0242ac120096',
+    'Test Video',
+    'This is a test video',
+    666,
+    666,
+    'en',
+    'pt-br',
+    1000,
+    '00:00:00',
+    '00:10:00'
+  );
+INSERT INTO videos_outputs (video_id, channel_id, language)
+VALUES (
+    '806b5a48-f221-11ed-a05b-0242ac120096',
+    666,
+    'pt-br'
+  ),
+  (
+    '806b5a48-f221-11ed-a05b-0242ac120096',
+    666,
+    'es'
+  );
+INSERT INTO videos_storages (video_id, storage_id, video_path, format, stage, size)
+VALUES (
+    '806b5a48-f221-11ed-a05b-0242ac120096',
+    1,
+    'videos/raw/806b5a48-f221-11ed-a05b-0242ac120096.mkv',
+    'MKV',
+    'RAW',
+    1000
+  );
@@ -0,0 +1,543 @@
+use std::{str::FromStr, sync::Arc};
+
+use marco_polo_rs_core::{
+    database::{
+        models::{
+            subtitles_style::SubtitlesPosition,
+            video::{
+                speaker_labels::SpeakerLabels, stage::VideoStage, subtitles_mode::SubtitlesMode,
+            },
+            video_storage::{StorageVideoStage, VideoFormat},
+        },
+        queries::{
+            self, glossary::CreateGlossaryTermDto, storage::CreateStorageDto,
+            subtitles_style::CreateSubtitlesStyleDto,
+        },
+    },
+    internals::{
+        cloud::models::payload::{SrtPayload, VideoCutPayload, VideoPayload},
+        transcriber::traits::TranscriberClient,
+        translator::{glossary::Glossary, language::Language},
+    },
+};
+use sqlx::{types::Uuid, PgPool};
+
+use crate::{
+    handlers::{cut_video, processed_upload, raw_upload, transcription, translation},
+    test::mock::{
+        CloudServiceMock, MessageMock, SubtitlerClientMock, TranscriberClientMock,
+        TranslatorClientMock, VideoPlatformClientMock,
+    },
+};
+
+const VIDEO_ID: &str = "806b5a48-f221-11ed-a05b-0242ac120096";
+
+fn video_payload() -> VideoPayload {
+    VideoPayload {
+        video_uri: format!("videos/raw/{}.mkv", VIDEO_ID),
+        video_id: Uuid::from_str(VIDEO_ID).unwrap(),
+        language: None,
+    }
+}
+
+fn srt_payload() -> SrtPayload {
+    SrtPayload {
+        video_id: Uuid::from_str(VIDEO_ID).unwrap(),
+        srt_uri: format!("srt_transcriptions/{}.srt", VIDEO_ID),
+        language: None,
+    }
+}
+
+#[sqlx::test(migrations = "../migrations", fixtures("video"))]
+async fn test_raw_upload_delivered_twice(pool: PgPool) {
+    let cloud_service = CloudServiceMock::default();
+    let transcriber_client = TranscriberClientMock::default();
+    let message = MessageMock;
+
+    for _ in 0..2 {
+        raw_upload::handle(
+            &cloud_service,
+            &transcriber_client,
+            &pool,
+            &message,
+            video_payload(),
+        )
+        .await
+        .unwrap();
+    }
+
+    assert_eq!(transcriber_client.requests(), 1);
+
+    let id = Uuid::from_str(VIDEO_ID).unwrap();
+    let transcription = queries::transcription::find_by_video_id(&pool, &id)
+        .await
+        .unwrap();
+    assert_eq!(transcription.transcription_id, "transcription_0");
+
+    let checkpoint = queries::checkpoint::exists(&pool, &id, VideoStage::Transcribing, None)
+        .await
+        .unwrap();
+    assert!(checkpoint);
+}
+
+/// A short clip in place of a download, cutting needs ffmpeg
+fn sample_video() -> String {
+    let path = std::env::temp_dir().join(format!("{}.mkv", Uuid::new_v4()));
+    let output = std::process::Command::new("ffmpeg")
+        .args([
+            "-y",
+            "-f",
+            "lavfi",
+            "-i",
+            "testsrc=duration=2:size=64x64:rate=10",
+        ])
+        .arg(&path)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    path.to_string_lossy().to_string()
+}
+
+#[sqlx::test(migrations = "../migrations", fixtures("video"))]
+async fn test_cut_video_delivered_twice(pool: PgPool) {
+    let cloud_service = CloudServiceMock::default();
+    let id = Uuid::from_str(VIDEO_ID).unwrap();
+    let payload = VideoCutPayload {
+        video_id: id,
+        video_format: VideoFormat::Mkv,
+        file_path: sample_video(),
+    };
+
+    for _ in 0..2 {
+        cut_video::handle(payload.clone(), &cloud_service, &pool, &MessageMock)
+            .await
+            .unwrap();
+    }
+
+    assert_eq!(cloud_service.bucket_client.uploads(), 1);
+
+    // the one of the fixture and the cut
+    let storages = sqlx::query_scalar!(
+        r#"SELECT COUNT(*) as "count!" FROM videos_storages WHERE video_id = $1 AND stage = 'RAW'"#,
+        id
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(storages, 2);
+
+    let events = queries::outbox::find_unsent(&pool).await.unwrap();
+    assert_eq!(events.len(), 1);
+}
+
+#[sqlx::test(migrations = "../migrations", fixtures("video"))]
+async fn test_processed_upload_delivered_twice(pool: PgPool) {
+    let cloud_service = CloudServiceMock::default();
+    let youtube_client = VideoPlatformClientMock::default();
+    let id = Uuid::from_str(VIDEO_ID).unwrap();
+    let video_uri = format!("videos/processed/{}.pt-br.mkv", VIDEO_ID);
+
+    queries::storage::create(
+        &pool,
+        CreateStorageDto {
+            video_id: &id,
+            format: VideoFormat::Mkv,
+            video_uri: &video_uri,
+            storage_id: 1,
+            stage: StorageVideoStage::Processed,
+            size: 1000,
+            language: Some("pt-br"),
+        },
+    )
+    .await
+    .unwrap();
+
+    for _ in 0..2 {
+        let payload = VideoPayload {
+            video_uri: video_uri.clone(),
+            video_id: id,
+            language: Some("pt-br".to_string()),
+        };
+        processed_upload::handle(
+            &pool,
+            &youtube_client,
+            &cloud_service.bucket_client,
+            payload,
+        )
+        .await
+        .unwrap();
+    }
+
+    assert_eq!(youtube_client.uploads(), 1);
+
+    let output = queries::output::find_by_video_id_and_language(&pool, &id, "pt-br")
+        .await
+        .unwrap();
+    assert_eq!(
+        output.url,
+        Some("https://www.youtube.com/watch?v=video_0".to_string())
+    );
+
+    let storages = sqlx::query_scalar!(
+        r#"SELECT COUNT(*) as "count!" FROM videos_storages WHERE video_id = $1 AND stage = 'PROCESSED'"#,
+        id
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(storages, 1);
+}
+
+#[sqlx::test(migrations = "../migrations", fixtures("video"))]
+async fn test_raw_upload_with_speaker_labels(pool: PgPool) {
+    let cloud_service = CloudServiceMock::default();
+    let transcriber_client = TranscriberClientMock::default();
+    let id = Uuid::from_str(VIDEO_ID).unwrap();
+
+    sqlx::query!(
+        "UPDATE videos SET speaker_labels = 'DASHES' WHERE id = $1",
+        id
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    raw_upload::handle(
+        &cloud_service,
+        &transcriber_client,
+        &pool,
+        &MessageMock,
+        video_payload(),
+    )
+    .await
+    .unwrap();
+
+    assert!(transcriber_client.speaker_labels());
+}
+
+async fn create_glossary(pool: &PgPool) {
+    let terms = [
+        ("Hello", Some("pt-br"), Some("Olá")),
+        ("FFmpeg", None, None),
+    ];
+    for (term, language, translation) in terms {
+        let dto = CreateGlossaryTermDto {
+            channel_id: 666,
+            term,
+            language,
+            translation,
+        };
+        queries::glossary::create(pool, dto).await.unwrap();
+    }
+}
+
+#[sqlx::test(migrations = "../migrations", fixtures("video"))]
+async fn test_raw_upload_with_glossary(pool: PgPool) {
+    let cloud_service = CloudServiceMock::default();
+    let transcriber_client = TranscriberClientMock::default();
+    create_glossary(&pool).await;
+
+    raw_upload::handle(
+        &cloud_service,
+        &transcriber_client,
+        &pool,
+        &MessageMock,
+        video_payload(),
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(transcriber_client.word_boost(), vec!["Hello", "FFmpeg"]);
+}
+
+#[sqlx::test(migrations = "../migrations", fixtures("video"))]
+async fn test_transcription_with_glossary(pool: PgPool) {
+    let pool = Arc::new(pool);
+    let cloud_service = CloudServiceMock::default();
+    let transcriber_client = TranscriberClientMock::default();
+    let translator_client = TranslatorClientMock;
+    let id = Uuid::from_str(VIDEO_ID).unwrap();
+    create_glossary(&pool).await;
+
+    let handler = transcription::Handler::new(
+        &transcriber_client,
+        &cloud_service,
+        &translator_client,
+        pool.clone(),
+    );
+
+    let sentences = transcriber_client
+        .get_transcription_sentences("transcription_0")
+        .await
+        .unwrap();
+    let outputs = queries::output::find_by_video_id(pool.as_ref(), &id)
+        .await
+        .unwrap();
+    let terms = queries::glossary::find_all_by_channel(&pool, outputs[0].channel_id)
+        .await
+        .unwrap();
+
+    let glossary = Glossary::new(&terms, Language::PortugueseBrazil);
+    let (srt, _) = handler
+        .translate(
+            sentences.clone(),
+            Language::English,
+            Language::PortugueseBrazil,
+            SpeakerLabels::None,
+            &glossary,
+        )
+        .await
+        .unwrap();
+    assert!(srt.contains("Olá (pt-br)"));
+
+    // the term is only translated to the language it was given for
+    let glossary = Glossary::new(&terms, Language::Spanish);
+    let (srt, _) = handler
+        .translate(
+            sentences.clone(),
+            Language::English,
+            Language::Spanish,
+            SpeakerLabels::None,
+            &glossary,
+        )
+        .await
+        .unwrap();
+    assert!(srt.contains("Hello (es)"));
+
+    // the glossary of another channel is not used
+    let terms = queries::glossary::find_all_by_channel(&pool, 667)
+        .await
+        .unwrap();
+    let glossary = Glossary::new(&terms, Language::PortugueseBrazil);
+    let (srt, _) = handler
+        .translate(
+            sentences,
+            Language::English,
+            Language::PortugueseBrazil,
+            SpeakerLabels::None,
+            &glossary,
+        )
+        .await
+        .unwrap();
+    assert!(srt.contains("Hello (pt-br)"));
+}
+
+#[sqlx::test(migrations = "../migrations", fixtures("video"))]
+async fn test_transcription_delivered_twice(pool: PgPool) {
+    let pool = Arc::new(pool);
+    let cloud_service = CloudServiceMock::default();
+    let transcriber_client = TranscriberClientMock::default();
+    let translator_client = TranslatorClientMock;
+
+    raw_upload::handle(
+        &cloud_service,
+        &transcriber_client,
+        &pool,
+        &MessageMock,
+        video_payload(),
+    )
+    .await
+    .unwrap();
+
+    let handler = transcription::Handler::new(
+        &transcriber_client,
+        &cloud_service,
+        &translator_client,
+        pool.clone(),
+    );
+
+    for _ in 0..2 {
+        handler.handle(srt_payload()).await.unwrap();
+    }
+
+    // one translation per output, the redelivery uploads nothing
+    assert_eq!(cloud_service.bucket_client.uploads(), 2);
+
+    let id = Uuid::from_str(VIDEO_ID).unwrap();
+    let translations = queries::translation::find_by_video_id(&pool, &id)
+        .await
+        .unwrap();
+    assert_eq!(translations.len(), 2);
+}
+
+#[sqlx::test(migrations = "../migrations", fixtures("video"))]
+async fn test_transcription_resumes_missing_languages(pool: PgPool) {
+    let pool = Arc::new(pool);
+    let cloud_service = CloudServiceMock::default();
+    let transcriber_client = TranscriberClientMock::default();
+    let translator_client = TranslatorClientMock;
+
+    raw_upload::handle(
+        &cloud_service,
+        &transcriber_client,
+        &pool,
+        &MessageMock,
+        video_payload(),
+    )
+    .await
+    .unwrap();
+
+    let id = Uuid::from_str(VIDEO_ID).unwrap();
+    queries::checkpoint::create(pool.as_ref(), &id, VideoStage::Translating, Some("pt-br"))
+        .await
+        .unwrap();
+
+    let handler = transcription::Handler::new(
+        &transcriber_client,
+        &cloud_service,
+        &translator_client,
+        pool.clone(),
+    );
+    handler.handle(srt_payload()).await.unwrap();
+
+    assert_eq!(cloud_service.bucket_client.uploads(), 1);
+
+    let translations = queries::translation::find_by_video_id(&pool, &id)
+        .await
+        .unwrap();
+    assert_eq!(translations.len(), 1);
+    assert_eq!(translations[0].language, "es");
+}
+
+#[sqlx::test(migrations = "../migrations", fixtures("video"))]
+async fn test_translation_waits_for_review(pool: PgPool) {
+    let pool = Arc::new(pool);
+    let cloud_service = CloudServiceMock::default();
+    let subtitler_client = SubtitlerClientMock::default();
+    let id = Uuid::from_str(VIDEO_ID).unwrap();
+
+    sqlx::query!("UPDATE videos SET review = true WHERE id = $1", id)
+        .execute(pool.as_ref())
+        .await
+        .unwrap();
+
+    let handler = translation::Handler::new(
+        &cloud_service,
+        &subtitler_client,
+        pool.clone(),
+        &MessageMock,
+    );
+    let payload = || SrtPayload {
+        video_id: id,
+        srt_uri: format!("srt_translations/{}.pt-br.srt", VIDEO_ID),
+        language: Some("pt-br".to_string()),
+    };
+
+    // the redelivery finds the review still open
+    for _ in 0..2 {
+        handler.handle(payload()).await.unwrap();
+    }
+    assert!(subtitler_client.srt_uris().is_empty());
+
+    let video = queries::video::find_by_id(pool.as_ref(), &id)
+        .await
+        .unwrap();
+    assert_eq!(video.stage, VideoStage::Reviewing);
+
+    let review = queries::review::find_by_video_id_and_language(pool.as_ref(), &id, "pt-br")
+        .await
+        .unwrap()
+        .unwrap();
+    queries::review::approve(pool.as_ref(), review.id)
+        .await
+        .unwrap();
+
+    handler.handle(payload()).await.unwrap();
+
+    let reviewed_uri = format!("srt_reviews/{}.pt-br.srt", VIDEO_ID);
+    assert_eq!(subtitler_client.srt_uris(), vec![reviewed_uri]);
+
+    let subtitled = queries::checkpoint::exists(&pool, &id, VideoStage::Subtitling, Some("pt-br"))
+        .await
+        .unwrap();
+    assert!(subtitled);
+}
+
+#[sqlx::test(migrations = "../migrations", fixtures("video"))]
+async fn test_translation_with_soft_subtitles(pool: PgPool) {
+    let pool = Arc::new(pool);
+    let cloud_service = CloudServiceMock::default();
+    let subtitler_client = SubtitlerClientMock::default();
+    let id = Uuid::from_str(VIDEO_ID).unwrap();
+
+    sqlx::query!(
+        "UPDATE videos SET subtitles_mode = 'SOFT' WHERE id = $1",
+        id
+    )
+    .execute(pool.as_ref())
+    .await
+    .unwrap();
+
+    let handler = translation::Handler::new(
+        &cloud_service,
+        &subtitler_client,
+        pool.clone(),
+        &MessageMock,
+    );
+    let translated_uri = format!("srt_translations/{}.pt-br.srt", VIDEO_ID);
+    handler
+        .handle(SrtPayload {
+            video_id: id,
+            srt_uri: translated_uri.clone(),
+            language: Some("pt-br".to_string()),
+        })
+        .await
+        .unwrap();
+
+    let transcribed_uri = format!("srt_transcriptions/{}.srt", VIDEO_ID);
+    assert_eq!(
+        subtitler_client.tracks(),
+        vec![(vec![translated_uri, transcribed_uri], SubtitlesMode::Soft)]
+    );
+
+    // both sidecars and the processed video
+    assert_eq!(cloud_service.bucket_client.uploads(), 3);
+}
+
+#[sqlx::test(migrations = "../migrations", fixtures("video"))]
+async fn test_translation_with_channel_style(pool: PgPool) {
+    let pool = Arc::new(pool);
+    let cloud_service = CloudServiceMock::default();
+    let subtitler_client = SubtitlerClientMock::default();
+    let id = Uuid::from_str(VIDEO_ID).unwrap();
+
+    let style = queries::subtitles_style::create(
+        pool.as_ref(),
+        CreateSubtitlesStyleDto {
+            user_id: 666,
+            name: "Shorts",
+            font_name: "Roboto",
+            font_size: 64,
+            primary_color: "#FFFFFF",
+            outline_color: "#000000",
+            outline: 3,
+            background_color: None,
+            position: SubtitlesPosition::Middle,
+            margin: 0,
+            max_line_width: Some(24),
+        },
+    )
+    .await
+    .unwrap();
+    queries::subtitles_style::set_channel_style(pool.as_ref(), 666, Some(style.id))
+        .await
+        .unwrap();
+
+    let handler = translation::Handler::new(
+        &cloud_service,
+        &subtitler_client,
+        pool.clone(),
+        &MessageMock,
+    );
+    handler
+        .handle(SrtPayload {
+            video_id: id,
+            srt_uri: format!("srt_translations/{}.pt-br.srt", VIDEO_ID),
+            language: Some("pt-br".to_string()),
+        })
+        .await
+        .unwrap();
+
+    assert_eq!(subtitler_client.styles(), vec![Some("Shorts".to_string())]);
+}
@@ -1,8 +1,8 @@
-use std::sync::Arc;
+use std::{str::FromStr, sync::Arc};
 
 use marco_polo_rs_core::{
     database::{
-        models::video::stage::VideoStage,
+        models::video::{speaker_labels::SpeakerLabels, stage::VideoStage},
         queries::{self, translation::CreateTranslationDto},
     },
     internals::{
@@ -11,7 +11,7 @@ use marco_polo_rs_core::{
             traits::{BucketClient, CloudService},
         },
         transcriber::traits::{Sentence, TranscriberClient},
-        translator::traits::TranslatorClient,
+        translator::{glossary::Glossary, language::Language, traits::TranslatorClient},
         ServiceProvider,
     },
 };
@@ -24,7 +24,7 @@ pub struct Handler<'a, TC, CS, TLC>
 where
     TC: TranscriberClient,
     CS: CloudService,
-    TLC: TranslatorClient,
+    TLC: TranslatorClient + Sync,
 {
     transcriber_client: &'a TC,
     cloud_service: &'a CS,
@@ -36,7 +36,7 @@ impl<'a, TC, CS, TLC> Handler<'a, TC, CS, TLC>
 where
     TC: TranscriberClient,
     CS: CloudService,
-    TLC: TranslatorClient,
+    TLC: TranslatorClient + Sync,
 {
     pub fn new(
         transcriber_client: &'a TC,
@@ -62,6 +62,18 @@ where
 
         queries::video::change_stage(pool, &payload.video_id, VideoStage::Translating).await?;
 
+        let video = queries::video::find_by_id(pool, &payload.video_id).await?;
+        let speaker_labels = queries::video::find_speaker_labels(pool, &payload.video_id).await?;
+
+        let source_language =
+            Language::from_str(&video.language).map_err(|e| HandlerError::Final(e.into()))?;
+
+        let outputs = queries::output::find_by_video_id(pool, &payload.video_id).await?;
+
+        if outputs.is_empty() {
+            return Err(HandlerError::Final("Video has no outputs".into()));
+        }
+
         let transcription =
             queries::transcription::find_by_video_id(&self.pool, &payload.video_id).await?;
 
@@ -69,25 +81,77 @@ where
             .get_transcription_sentences(&transcription.transcription_id)
             .await?;
 
-        let (translation_raw, id) = self.translate(transcription_sentences).await?;
-
-        let file_path = format!("srt_translations/{}.srt", payload.video_id);
-
-        bucket_client
-            .upload_file(&file_path, translation_raw.into())
+        for output in outputs {
+            let target_language =
+                Language::from_str(&output.language).map_err(|e| HandlerError::Final(e.into()))?;
+
+            let language = Some(target_language.code());
+            if queries::checkpoint::exists(
+                pool,
+                &payload.video_id,
+                VideoStage::Translating,
+                language,
+            )
+            .await?
+            {
+                tracing::info!(
+                    "Video {} was already translated to {}, skipping",
+                    payload.video_id,
+                    target_language
+                );
+                continue;
+            }
+
+            queries::output::change_stage(pool, output.id, VideoStage::Translating).await?;
+
+            // each output goes to its own channel, translated with the glossary of that channel
+            let glossary_terms =
+                queries::glossary::find_all_by_channel(pool, output.channel_id).await?;
+            let glossary = Glossary::new(&glossary_terms, target_language);
+            let (translation_raw, id) = self
+                .translate(
+                    transcription_sentences.clone(),
+                    source_language,
+                    target_language,
+                    speaker_labels,
+                    &glossary,
+                )
+                .await?;
+
+            let file_path = format!(
+                "srt_translations/{}.{}.srt",
+                payload.video_id,
+                target_language.code()
+            );
+
+            bucket_client
+                .upload_file(&file_path, translation_raw.into())
+                .await?;
+
+            let mut trx = pool.begin().await?;
+            queries::outbox::enqueue_upload_event(&mut *trx, bucket_client, &file_path).await?;
+            queries::translation::create(
+                &mut *trx,
+                CreateTranslationDto {
+                    video_id: &payload.video_id,
+                    translator_id,
+                    translation_id: id,
+                    storage_id: bucket_id,
+                    path: &file_path,
+                    language: target_language.code(),
+                },
+            )
             .await?;
 
-        queries::translation::create(
-            &self.pool,
-            CreateTranslationDto {
-                video_id: &payload.video_id,
-                translator_id,
-                translation_id: id,
-                storage_id: bucket_id,
-                path: &file_path,
-            },
-        )
-        .await?;
+            queries::checkpoint::create(
+                &mut *trx,
+                &payload.video_id,
+                VideoStage::Translating,
+                language,
+            )
+            .await?;
+            trx.commit().await?;
+        }
 
         Ok(())
     }
@@ -95,10 +159,16 @@ where
     pub async fn translate(
         &self,
         sentences: Vec<Sentence>,
+        source_language: Language,
+        target_language: Language,
+        speaker_labels: SpeakerLabels,
+        glossary: &Glossary,
     ) -> Result<(String, Option<String>), Box<dyn std::error::Error + Sync + Send>> {
-        let translated_sentences = self.get_translated_sentences(sentences).await?;
+        let translated_sentences = self
+            .get_translated_sentences(sentences, source_language, target_language, glossary)
+            .await?;
 
-        let new_srt_buffer = srt::create_based_on_sentences(translated_sentences);
+        let new_srt_buffer = srt::create_based_on_sentences(translated_sentences, speaker_labels);
 
         Ok((new_srt_buffer, None))
     }
@@ -106,6 +176,9 @@ where
     async fn get_translated_sentences(
         &self,
         mut payload: Vec<Sentence>,
+        source_language: Language,
+        target_language: Language,
+        glossary: &Glossary,
     ) -> Result<Vec<Sentence>, Box<dyn std::error::Error + Sync + Send>> {
         let translator_client = &self.translator_client;
 
@@ -115,7 +188,12 @@ where
         }
 
         let translations = translator_client
-            .translate_sentences(texts_from_sentences)
+            .translate_sentences_with_glossary(
+                texts_from_sentences,
+                source_language,
+                target_language,
+                glossary,
+            )
             .await?;
         for (i, translation) in translations.into_iter().enumerate() {
             payload[i].text = translation.to_string();
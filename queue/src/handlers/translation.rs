@@ -3,24 +3,25 @@ use std::{path::PathBuf, sync::Arc};
 use marco_polo_rs_core::{
     database::{
         models::{
-            video::stage::VideoStage,
+            video::{stage::VideoStage, subtitles_mode::SubtitlesMode},
+            video_event::ProgressStep,
             video_storage::{StorageVideoStage, VideoFormat},
         },
-        queries::{self, storage::CreateStorageDto},
+        queries::{self, review::CreateReviewDto, storage::CreateStorageDto},
     },
     internals::{
         cloud::{
             models::payload::SrtPayload,
             traits::{BucketClient, CloudService, QueueClient},
         },
-        subtitler::traits::SubtitlerClient,
+        subtitler::traits::{SubtitleTrack, SubtitlerClient},
         ServiceProvider,
     },
     util::fs,
 };
-use sqlx::PgPool;
+use sqlx::{types::Uuid, PgPool};
 
-use crate::error::HandlerError;
+use crate::{error::HandlerError, progress::ProgressNotifier};
 
 pub struct Handler<'a, CS, SC>
 where
@@ -64,6 +65,47 @@ where
         )
         .await?;
 
+        // srts uploaded before the fan-out don't carry a language
+        let language = payload
+            .language
+            .clone()
+            .unwrap_or_else(|| video.video.target_language.clone());
+
+        let output =
+            queries::output::find_by_video_id_and_language(pool, &payload.video_id, &language)
+                .await?;
+
+        if queries::checkpoint::exists(
+            pool,
+            &payload.video_id,
+            VideoStage::Subtitling,
+            Some(&language),
+        )
+        .await?
+        {
+            tracing::info!(
+                "Video {} was already subtitled in {}, skipping",
+                payload.video_id,
+                language
+            );
+            return Ok(());
+        }
+
+        let srt_uri = match self
+            .reviewed_srt_uri(&payload, &language, output.id)
+            .await?
+        {
+            Some(srt_uri) => srt_uri,
+            None => return Ok(()),
+        };
+
+        let mode = queries::video::find_subtitles_mode(pool, &payload.video_id).await?;
+        let style =
+            queries::subtitles_style::find_by_output(pool, &payload.video_id, output.channel_id)
+                .await?;
+        let tracks =
+            self.subtitle_tracks(&payload, &language, &video.video.language, srt_uri, mode);
+
         let estimation = self.subtitler_client.estimate_time(&video, bucket_client);
 
         queue_client
@@ -71,15 +113,37 @@ where
             .await?;
 
         queries::video::change_stage(pool, &payload.video_id, VideoStage::Subtitling).await?;
+        queries::output::change_stage(pool, output.id, VideoStage::Subtitling).await?;
 
+        let notifier =
+            ProgressNotifier::start(pool, vec![payload.video_id], ProgressStep::Subtitle);
         let subtitle_path = self
             .subtitler_client
-            .subtitle(&video, bucket_client)
-            .await?; // this is a path only because of the local client,would be a uri otherwise
+            .subtitle(
+                &video,
+                &tracks,
+                mode,
+                style.as_ref(),
+                bucket_client,
+                &|percentage| notifier.report(percentage),
+            )
+            .await;
+        notifier.finish().await;
+        let subtitle_path = subtitle_path?; // this is a path only because of the local client,would be a uri otherwise
+
+        // the upload of the processed video publishes it, the sidecars must be there before
+        if mode.has_soft_tracks() {
+            for track in &tracks {
+                let sidecar_uri = sidecar_uri(&payload.video_id, &language, &track.language);
+                let srt = bucket_client.download_file(&track.uri).await?;
+                bucket_client.upload_file(&sidecar_uri, srt).await?;
+            }
+        }
 
         let video_uri = format!(
-            "videos/processed/{}.{}",
+            "videos/processed/{}.{}.{}",
             payload.video_id,
+            language,
             video.storage.format.to_string()
         );
 
@@ -98,8 +162,12 @@ where
 
         let size = fs::check_file_size(&sub_path)? as i64;
 
+        let mut trx = pool.begin().await?;
+
+        queries::outbox::enqueue_upload_event(&mut *trx, bucket_client, &video_uri).await?;
+
         queries::storage::create(
-            pool,
+            &mut *trx,
             CreateStorageDto {
                 format: VideoFormat::Mkv,
                 storage_id: self.cloud_service.bucket_client().id(),
@@ -107,10 +175,119 @@ where
                 video_uri: &video_uri,
                 stage: StorageVideoStage::Processed,
                 size,
+                language: Some(&language),
             },
         )
         .await?;
 
+        queries::checkpoint::create(
+            &mut *trx,
+            &payload.video_id,
+            VideoStage::Subtitling,
+            Some(&language),
+        )
+        .await?;
+
+        trx.commit().await?;
+
         return Ok(());
     }
+
+    /// The output srt comes first, the soft modes also carry the transcription when it is
+    /// in another language
+    fn subtitle_tracks(
+        &self,
+        payload: &SrtPayload,
+        language: &str,
+        original_language: &str,
+        srt_uri: String,
+        mode: SubtitlesMode,
+    ) -> Vec<SubtitleTrack> {
+        let mut tracks = vec![SubtitleTrack {
+            uri: srt_uri,
+            language: language.to_string(),
+        }];
+
+        if mode.has_soft_tracks() && original_language != language {
+            tracks.push(SubtitleTrack {
+                uri: format!("srt_transcriptions/{}.srt", payload.video_id),
+                language: original_language.to_string(),
+            });
+        }
+
+        return tracks;
+    }
+
+    /// Videos created with `review` stop on `Reviewing` until the user approves the subtitles,
+    /// then they are subtitled with the reviewed srt instead of the translated one.
+    /// Revisions rendered from the api skip the review
+    async fn reviewed_srt_uri(
+        &self,
+        payload: &SrtPayload,
+        language: &str,
+        output_id: i32,
+    ) -> Result<Option<String>, HandlerError> {
+        let bucket_client = self.cloud_service.bucket_client();
+        let pool: &PgPool = &self.pool;
+
+        // revisions were written by the user, they are rendered as they are
+        if queries::subtitles_revision::is_revision_path(&payload.srt_uri) {
+            return Ok(Some(payload.srt_uri.clone()));
+        }
+
+        if !queries::video::requires_review(pool, &payload.video_id).await? {
+            return Ok(Some(payload.srt_uri.clone()));
+        }
+
+        let review =
+            queries::review::find_by_video_id_and_language(pool, &payload.video_id, language)
+                .await?;
+
+        match review {
+            Some(review) if review.approved_at.is_some() => {
+                let srt_uri = format!("srt_reviews/{}.{}.srt", payload.video_id, language);
+                bucket_client
+                    .upload_file(&srt_uri, review.srt.into_bytes())
+                    .await?;
+                return Ok(Some(srt_uri));
+            }
+            Some(_) => {
+                tracing::info!(
+                    "Video {} is still being reviewed in {}, skipping",
+                    payload.video_id,
+                    language
+                );
+                return Ok(None);
+            }
+            None => {}
+        };
+
+        let srt = bucket_client.download_file(&payload.srt_uri).await?;
+        let srt = String::from_utf8(srt).map_err(|e| HandlerError::Final(e.into()))?;
+
+        let mut trx = pool.begin().await?;
+        queries::review::create(
+            &mut *trx,
+            CreateReviewDto {
+                video_id: &payload.video_id,
+                language,
+                srt: &srt,
+            },
+        )
+        .await?;
+        queries::video::change_stage_with(&mut *trx, &payload.video_id, VideoStage::Reviewing)
+            .await?;
+        queries::output::change_stage(&mut *trx, output_id, VideoStage::Reviewing).await?;
+        trx.commit().await?;
+
+        return Ok(None);
+    }
+}
+
+/// Copies of the tracks muxed into an output, uploaded to the platform along with the video
+pub fn sidecar_uri(video_id: &Uuid, language: &str, track_language: &str) -> String {
+    return format!(
+        "srt_outputs/{}.{}.{}.srt",
+        video_id, language, track_language
+    );
 }
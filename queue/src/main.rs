@@ -1,35 +1,58 @@
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
+
+use chrono::Utc;
 
 use marco_polo_rs_core::{
     database::create_pool,
     env,
     internals::{
         cloud::{
-            aws::AwsCloudService,
+            default_cloud_service,
             models::payload::PayloadType,
             traits::{CloudService, QueueClient, QueueMessage},
+            DefaultCloudService, MAX_DELIVERIES,
         },
         subtitler::local::LocalClient,
-        transcriber::assembly_ai::AssemblyAiClient,
+        transcriber::{self, assembly_ai::AssemblyAiClient},
         translator::google_v2::GoogleTranslateV2Client,
         video_platform::youtube::client::YoutubeClient,
         yt_downloader::yt_dl::YtDl,
     },
+    mail::Mailer,
+    util::{fs, logging, metrics, security::url_policy::UrlPolicy},
 };
 use sqlx::PgPool;
 use tokio::{
     runtime::{Builder, Runtime},
-    sync::Mutex,
+    sync::{Mutex, Semaphore},
 };
+use tokio_util::sync::CancellationToken;
 use workers::{heavy::HeavyWorker, light::LightWorker};
 
-use crate::workers::Worker;
+use crate::{
+    config::{WorkerKind, WorkersConfig},
+    notifications::Notifications,
+    relay::Relay,
+    transcriptions::Transcriptions,
+    webhooks::Webhooks,
+    workers::{requeue_message, WorkerClass, WorkerPool},
+};
 
+mod config;
 mod error;
 mod handlers;
+mod notifications;
+mod poller;
+mod progress;
+mod relay;
+mod shutdown;
+#[cfg(test)]
+mod test;
+mod transcriptions;
+mod webhooks;
 mod workers;
 
-pub type CloudServiceInUse = AwsCloudService;
+pub type CloudServiceInUse = DefaultCloudService;
 pub type TranscriberClientInUse = AssemblyAiClient;
 pub type TranslatorClientInUse = GoogleTranslateV2Client;
 pub type SubtitlerClientInUse = LocalClient;
@@ -38,14 +61,19 @@ pub type YoutubeClientInUse = YoutubeClient;
 
 pub type Message = <<CloudServiceInUse as CloudService>::QC as QueueClient>::M;
 
-const ERROR_COUNT_THRESHOLD: i64 = 3;
-const HEAVY_WORKER_CAPACITY: usize = 1;
+const ERROR_COUNT_THRESHOLD: i64 = MAX_DELIVERIES;
+const DEFAULT_METRICS_PORT: u16 = 9000;
+const SATURATED_INTERVAL: Duration = Duration::from_secs(1);
 
 struct ServerState {
     cloud_service: CloudServiceInUse,
+    pool: Arc<PgPool>,
     runtime: Runtime,
-    inactive_light_workers: Arc<Mutex<Vec<LightWorker>>>,
-    inactive_heavy_workers: Arc<Mutex<Vec<HeavyWorker>>>,
+    classes: Vec<WorkerClass>,
+    capacity: Arc<Semaphore>,
+    total_workers: u32,
+    abort: CancellationToken,
+    shutdown_timeout: Duration,
 }
 
 #[tokio::main]
@@ -53,15 +81,48 @@ async fn main() {
     let state = start_server_state().await;
 
     let queue_client = state.cloud_service.queue_client();
-    let inactive_light_workers = state.inactive_light_workers;
-    let inactive_heavy_workers = state.inactive_heavy_workers;
+    let classes = state.classes;
+    let capacity = state.capacity;
     let runtime = state.runtime;
 
-    loop {
-        let message_result = match queue_client.receive_message().await {
+    let shutdown = CancellationToken::new();
+    tokio::spawn(shutdown::listen(shutdown.clone()));
+
+    let relay = Relay {
+        cloud_service: state.cloud_service.clone(),
+    };
+    runtime.spawn(poller::run(relay, state.pool.clone()));
+    let webhooks = Webhooks {
+        policy: UrlPolicy::from_env(),
+    };
+    runtime.spawn(poller::run(webhooks, state.pool.clone()));
+    if transcriber::polling_enabled() {
+        let transcriptions = Transcriptions {
+            cloud_service: state.cloud_service.clone(),
+            transcriber_client: TranscriberClientInUse::new(),
+        };
+        runtime.spawn(poller::run(transcriptions, state.pool.clone()));
+    }
+    let notifications = Notifications {
+        mailer: Mailer::default(),
+    };
+    runtime.spawn(poller::run(notifications, state.pool));
+
+    while !shutdown.is_cancelled() {
+        // nothing is received while every worker is busy
+        let receive_result = tokio::select! {
+            biased;
+            _ = shutdown.cancelled() => break,
+            result = async {
+                drop(capacity.clone().acquire_owned().await.unwrap());
+                queue_client.receive_message().await
+            } => result,
+        };
+
+        let message_result = match receive_result {
             Ok(messages) => messages,
             Err(e) => {
-                eprintln!("Error receiving message:{}", e);
+                tracing::error!("Error receiving message:{}", e);
                 continue;
             }
         };
@@ -73,26 +134,81 @@ async fn main() {
             }
         };
 
-        println!("Enqueuing {} messages", messages.len());
+        tracing::info!("Enqueuing {} messages", messages.len());
+        let received = messages.len();
+        let mut requeued = 0;
         for message in messages {
             let (message, payload_type) = match get_payload(message, queue_client).await {
                 Ok((message, payload_type)) => (message, payload_type),
                 Err(_) => continue,
             };
-            match payload_type {
-                PayloadType::BatukaSrtTranslationUpload(_) => {
-                    let pool = inactive_heavy_workers.clone();
-                    let message = (message, payload_type);
-                    handle_message(&runtime, message, pool).await;
+
+            if let Some(sent_at) = message.sent_at() {
+                let lag = Utc::now().naive_utc() - sent_at;
+                metrics::record_receive_lag(lag.to_std().unwrap_or_default());
+            }
+
+            let class = match classes.iter().find(|class| class.accepts(&payload_type)) {
+                Some(class) => class,
+                None => {
+                    // left on the queue, it will be received again after the visibility timeout
+                    tracing::error!("No worker class accepts {}", payload_type.name());
+                    continue;
+                }
+            };
+
+            // shutting down or every worker of the class is busy, waiting for one would hold
+            // the rest of the batch past its visibility timeout. Another consumer can take the copy
+            let permits = match shutdown.is_cancelled() {
+                true => None,
+                false => class.try_acquire(capacity.clone()),
+            };
+
+            match permits {
+                Some(permits) => {
+                    let dispatched = class
+                        .dispatch(&runtime, (message, payload_type), permits)
+                        .await;
+                    if let Err((message, payload_type)) = dispatched {
+                        tracing::error!("No idle worker in class {}", class.name);
+                        requeue_message(queue_client, message, payload_type).await;
+                        requeued += 1;
+                    }
                 }
-                _ => {
-                    let pool = inactive_light_workers.clone();
-                    let message = (message, payload_type);
-                    handle_message(&runtime, message, pool).await;
+                None => {
+                    requeue_message(queue_client, message, payload_type).await;
+                    requeued += 1;
                 }
             }
         }
+
+        // the batch only had messages for busy classes, they would be received right back
+        if requeued == received {
+            tokio::select! {
+                _ = shutdown.cancelled() => {},
+                _ = tokio::time::sleep(SATURATED_INTERVAL) => {},
+            }
+        }
+    }
+
+    let drained = shutdown::drain(
+        capacity,
+        state.total_workers,
+        &state.abort,
+        state.shutdown_timeout,
+    )
+    .await;
+
+    // a worker that didn't stop may still be writing to the temp dir
+    if drained {
+        if let Err(e) = fs::remove_temp_dir() {
+            tracing::error!("Failed to remove temp dir: {}", e);
+        }
     }
+
+    // dropping a runtime inside another one panics
+    runtime.shutdown_background();
+    tracing::info!("Queue stopped");
 }
 
 async fn get_payload<QC: QueueClient>(
@@ -103,10 +219,10 @@ async fn get_payload<QC: QueueClient>(
     let payload = match payload_result {
         Ok(payload) => payload,
         Err(_) => {
-            println!("Invalid payload");
+            tracing::warn!("Invalid payload");
             let result = queue_client.delete_message(message).await;
             if result.is_err() {
-                println!("Failed to delete message");
+                tracing::error!("Failed to delete message");
             }
             return Err(());
         }
@@ -114,102 +230,90 @@ async fn get_payload<QC: QueueClient>(
     return Ok((message, payload));
 }
 
-fn instantiate_worker(
-    thread_count: usize,
+fn instantiate_workers(
+    config: &WorkersConfig,
     pool: Arc<PgPool>,
     cloud_service: CloudServiceInUse,
-) -> (Vec<LightWorker>, Vec<HeavyWorker>) {
-    let mut inactive_light_workers: Vec<LightWorker> =
-        Vec::with_capacity(thread_count - HEAVY_WORKER_CAPACITY);
-
-    let mut inactive_heavy_workers: Vec<HeavyWorker> = Vec::with_capacity(HEAVY_WORKER_CAPACITY);
-
-    for id in 0..thread_count {
-        {
-            if id < HEAVY_WORKER_CAPACITY {
-                let subtitler_client = SubtitlerClientInUse::new();
-                let heavy_worker = HeavyWorker {
-                    id,
-                    pool: pool.clone(),
-                    cloud_service: cloud_service.clone(),
-                    subtitler_client,
-                };
-                inactive_heavy_workers.push(heavy_worker);
-            } else {
-                let translator_client = TranslatorClientInUse::new();
-                let transcriber_client = TranscriberClientInUse::new();
-                let video_downloader = VideoDownloaderInUse::new();
-                let youtube_client = YoutubeClientInUse::new();
-                let light_worker = LightWorker {
-                    id,
-                    pool: pool.clone(),
-                    cloud_service: cloud_service.clone(),
-                    translator_client,
-                    transcriber_client,
-                    video_downloader,
-                    youtube_client,
-                };
-                inactive_light_workers.push(light_worker);
-            }
-        }
-    }
-
-    let total_workers = inactive_heavy_workers.len() + inactive_light_workers.len();
-    let total_light_workers = inactive_light_workers.len();
-    let total_heavy_workers = inactive_heavy_workers.len();
+    abort: &CancellationToken,
+) -> Vec<WorkerClass> {
+    let mut classes = Vec::with_capacity(config.classes.len());
+    let mut id = 0;
 
-    println!(
-        "Created {} workers ({} light, {} heavy)",
-        total_workers, total_light_workers, total_heavy_workers
-    );
+    for class_config in &config.classes {
+        let worker_pool = match class_config.kind {
+            WorkerKind::Heavy => {
+                let mut workers = Vec::with_capacity(class_config.concurrency);
+                for _ in 0..class_config.concurrency {
+                    let subtitler_client = SubtitlerClientInUse::new();
+                    workers.push(HeavyWorker {
+                        id,
+                        pool: pool.clone(),
+                        cloud_service: cloud_service.clone(),
+                        subtitler_client,
+                        abort: abort.clone(),
+                    });
+                    id += 1;
+                }
+                WorkerPool::Heavy(Arc::new(Mutex::new(workers)))
+            }
+            WorkerKind::Light => {
+                let mut workers = Vec::with_capacity(class_config.concurrency);
+                for _ in 0..class_config.concurrency {
+                    let translator_client = TranslatorClientInUse::new();
+                    let transcriber_client = TranscriberClientInUse::new();
+                    let video_downloader = VideoDownloaderInUse::new();
+                    let youtube_client = YoutubeClientInUse::new();
+                    workers.push(LightWorker {
+                        id,
+                        pool: pool.clone(),
+                        cloud_service: cloud_service.clone(),
+                        translator_client,
+                        transcriber_client,
+                        video_downloader,
+                        youtube_client,
+                        abort: abort.clone(),
+                    });
+                    id += 1;
+                }
+                WorkerPool::Light(Arc::new(Mutex::new(workers)))
+            }
+        };
 
-    return (inactive_light_workers, inactive_heavy_workers);
-}
+        tracing::info!(
+            "Created {} {:?} workers for class {} ({})",
+            class_config.concurrency,
+            class_config.kind,
+            class_config.name,
+            class_config.payloads.join(", ")
+        );
 
-async fn handle_message<T: Worker>(
-    runtime: &Runtime,
-    message: (Message, PayloadType),
-    inactive_worker_pool: Arc<Mutex<Vec<T>>>,
-) {
-    let mut lock = inactive_worker_pool.lock().await;
-    let lock_option = lock.pop();
-    drop(lock);
-    match lock_option {
-        Some(worker) => {
-            runtime.spawn(async move { worker.handle(message, inactive_worker_pool).await });
-        }
-        None => {
-            return;
-        }
+        classes.push(WorkerClass::new(class_config, worker_pool));
     }
+
+    return classes;
 }
 
 async fn start_server_state() -> ServerState {
-    println!("Starting workers...");
     dotenv::dotenv().ok();
+    logging::init();
+    tracing::info!("Starting workers...");
     env::check_envs();
+    install_metrics();
     let thread_count = num_cpus::get_physical();
 
-    println!("Using {} threads", thread_count);
+    tracing::info!("Using {} threads", thread_count);
 
-    if thread_count < HEAVY_WORKER_CAPACITY + 1 {
-        panic!(
-            "Thread count must be at least {}",
-            HEAVY_WORKER_CAPACITY + 1
-        );
-    }
+    let config = WorkersConfig::load();
+    let shutdown_timeout = shutdown::timeout();
 
     let pool = create_pool().await;
     let pool = Arc::new(pool);
 
-    let queue_url = std::env::var("AWS_QUEUE_URL").expect("QUEUE_URL not found");
-    let cloud_service = CloudServiceInUse::new(queue_url).unwrap();
+    let cloud_service = default_cloud_service(&pool);
 
-    let (inactive_light_workers, inactive_heavy_workers) =
-        instantiate_worker(thread_count, pool.clone(), cloud_service.clone());
-
-    let inactive_light_workers = Arc::new(Mutex::new(inactive_light_workers));
-    let inactive_heavy_workers = Arc::new(Mutex::new(inactive_heavy_workers));
+    let abort = CancellationToken::new();
+    let classes = instantiate_workers(&config, pool.clone(), cloud_service.clone(), &abort);
+    let capacity = Arc::new(Semaphore::new(config.total_concurrency()));
 
     let runtime = Builder::new_multi_thread()
         .worker_threads(thread_count)
@@ -219,8 +323,27 @@ async fn start_server_state() -> ServerState {
 
     return ServerState {
         cloud_service,
+        pool,
         runtime,
-        inactive_light_workers,
-        inactive_heavy_workers,
+        classes,
+        capacity,
+        total_workers: config.total_concurrency() as u32,
+        abort,
+        shutdown_timeout,
+    };
+}
+
+/// Serves the metrics of the workers on `QUEUE_METRICS_PORT`, for prometheus to scrape
+fn install_metrics() {
+    let port = match std::env::var("QUEUE_METRICS_PORT") {
+        Ok(port) => port.parse().expect("QUEUE_METRICS_PORT must be a port"),
+        Err(_) => DEFAULT_METRICS_PORT,
     };
+
+    metrics::builder()
+        .with_http_listener(([0, 0, 0, 0], port))
+        .install()
+        .expect("Failed to install the metrics exporter");
+
+    tracing::info!("Serving metrics on port {}", port);
 }
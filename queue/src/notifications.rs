@@ -0,0 +1,254 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use marco_polo_rs_core::{
+    database::{
+        models::{
+            notification::{
+                NotificationKind, NotifiedVideo, VideoFailedParams, VideosFinishedParams,
+            },
+            video::{stage::VideoStage, Video},
+        },
+        queries::{
+            self,
+            notification::{CreateNotificationDto, PendingNotification},
+        },
+    },
+    mail::{engine::MailEngine, sender::MailSender, Mailer},
+    SyncError,
+};
+use sqlx::{types::Uuid, PgPool};
+
+use crate::poller::Poller;
+
+const NOTIFICATION_BATCH_SIZE: i64 = 10;
+const NOTIFICATION_MAX_ATTEMPTS: i32 = 5;
+/// Well over the time smtp takes to refuse a whole batch
+const NOTIFICATION_LEASE_SECONDS: i32 = 5 * 60;
+
+/// Emails the notifications the handlers queued, through the same mailer of the api
+pub struct Notifications<E: MailEngine, S: MailSender> {
+    pub mailer: Mailer<E, S>,
+}
+
+#[async_trait]
+impl<E, S> Poller for Notifications<E, S>
+where
+    E: MailEngine + Send + Sync,
+    S: MailSender + Send + Sync,
+{
+    type Item = PendingNotification;
+
+    const NAME: &'static str = "email notifications";
+    const IDLE_INTERVAL: Duration = Duration::from_secs(5);
+
+    async fn claim(&self, pool: &PgPool) -> Result<Vec<PendingNotification>, SyncError> {
+        let notifications = queries::notification::claim_pending(
+            pool,
+            NOTIFICATION_BATCH_SIZE,
+            NOTIFICATION_MAX_ATTEMPTS,
+            NOTIFICATION_LEASE_SECONDS,
+        )
+        .await?;
+
+        return Ok(notifications);
+    }
+
+    async fn work(
+        &self,
+        pool: &PgPool,
+        notification: PendingNotification,
+    ) -> Result<bool, SyncError> {
+        match send(&self.mailer, &notification).await {
+            Ok(_) => {
+                queries::notification::mark_sent(pool, notification.id).await?;
+                return Ok(true);
+            }
+            Err(e) => {
+                tracing::error!("Failed to send notification {}: {}", notification.id, e);
+                queries::notification::mark_failed(pool, notification.id, &e).await?;
+                return Ok(false);
+            }
+        }
+    }
+}
+
+async fn send<E: MailEngine, S: MailSender>(
+    mailer: &Mailer<E, S>,
+    notification: &PendingNotification,
+) -> Result<(), String> {
+    let mut params: serde_json::Value =
+        serde_json::from_str(&notification.params).map_err(|e| e.to_string())?;
+    params["name"] = serde_json::Value::String(notification.name.clone());
+
+    let kind = notification.kind;
+    mailer
+        .send(
+            notification.email.clone(),
+            kind.subject().to_string(),
+            kind.template(),
+            Some(params),
+        )
+        .await
+        .map_err(|e| e.message)
+}
+
+/// Queues the email of a batch of cuts once every video of its original video is done
+pub async fn notify_finished(pool: &PgPool, video_id: &Uuid) -> Result<(), SyncError> {
+    let video = queries::video::find_by_id(pool, video_id).await?;
+    let batch =
+        queries::original_video::with_video::find_with_videos(pool, video.original_video_id)
+            .await?;
+
+    if batch.videos.iter().any(|v| v.stage != VideoStage::Done) {
+        return Ok(());
+    }
+
+    let kind = NotificationKind::VideosFinished;
+    if !queries::notification::wants(pool, video.user_id, kind).await? {
+        return Ok(());
+    }
+
+    let params = VideosFinishedParams {
+        original_url: batch.original_video.url,
+        videos: notified_videos(batch.videos),
+    };
+
+    let dto = CreateNotificationDto {
+        user_id: video.user_id,
+        kind,
+        reference: &video.original_video_id.to_string(),
+        params: &serde_json::to_string(&params)?,
+    };
+    queries::notification::enqueue(pool, dto).await?;
+
+    return Ok(());
+}
+
+/// Queues the email of the videos of a message the queue gave up on
+pub async fn notify_failed(
+    pool: &PgPool,
+    failed_message_id: i32,
+    video_ids: &[Uuid],
+    stage: Option<VideoStage>,
+    error: &str,
+) -> Result<(), SyncError> {
+    let mut videos = Vec::with_capacity(video_ids.len());
+    for video_id in video_ids {
+        videos.push(queries::video::find_by_id(pool, video_id).await?);
+    }
+
+    // the videos of a message are always created together, by the same user
+    let user_id = match videos.first() {
+        Some(video) => video.user_id,
+        None => return Ok(()),
+    };
+
+    let kind = NotificationKind::VideoFailed;
+    if !queries::notification::wants(pool, user_id, kind).await? {
+        return Ok(());
+    }
+
+    let params = VideoFailedParams {
+        stage,
+        error: error.to_string(),
+        videos: notified_videos(videos),
+    };
+
+    let dto = CreateNotificationDto {
+        user_id,
+        kind,
+        reference: &failed_message_id.to_string(),
+        params: &serde_json::to_string(&params)?,
+    };
+    queries::notification::enqueue(pool, dto).await?;
+
+    return Ok(());
+}
+
+fn notified_videos(videos: Vec<Video>) -> Vec<NotifiedVideo> {
+    videos
+        .into_iter()
+        .map(|video| NotifiedVideo {
+            title: video.title,
+            url: video.url,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use marco_polo_rs_core::{
+        database::{models::video::stage::VideoStage, queries},
+        mail::{engine::handlebars::HandleBarsEngine, Mailer},
+    };
+    use sqlx::{types::Uuid, PgPool};
+
+    use crate::{poller::poll_once, test::mock::MailSenderMock};
+
+    use super::{notify_failed, notify_finished, Notifications};
+
+    fn video_id() -> Uuid {
+        Uuid::from_str("806b5a48-f221-11ed-a05b-0242ac120096").unwrap()
+    }
+
+    #[sqlx::test(
+        migrations = "../migrations",
+        fixtures("../handlers/test/fixtures/video")
+    )]
+    async fn test_finished_videos_are_notified_once(pool: PgPool) {
+        let notifications = Notifications {
+            mailer: Mailer::new(
+                HandleBarsEngine::new("../core/templates"),
+                MailSenderMock::default(),
+            ),
+        };
+
+        notify_finished(&pool, &video_id()).await.unwrap();
+        assert_eq!(poll_once(&notifications, &pool).await.unwrap(), 0);
+
+        queries::video::set_url(&pool, video_id(), "https://youtu.be/1")
+            .await
+            .unwrap();
+        notify_finished(&pool, &video_id()).await.unwrap();
+        notify_finished(&pool, &video_id()).await.unwrap();
+
+        assert_eq!(poll_once(&notifications, &pool).await.unwrap(), 1);
+
+        let sent = notifications.mailer.sender.sent();
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].to, "teste@gmail.com");
+        assert!(sent[0].body.contains("https://youtu.be/1"));
+    }
+
+    #[sqlx::test(
+        migrations = "../migrations",
+        fixtures("../handlers/test/fixtures/video")
+    )]
+    async fn test_failed_videos_respect_preferences(pool: PgPool) {
+        let notifications = Notifications {
+            mailer: Mailer::new(
+                HandleBarsEngine::new("../core/templates"),
+                MailSenderMock::default(),
+            ),
+        };
+
+        let dto = queries::notification::UpdatePreferencesDto {
+            user_id: 666,
+            videos_finished: true,
+            video_failed: false,
+        };
+        queries::notification::upsert_preferences(&pool, dto)
+            .await
+            .unwrap();
+
+        notify_failed(&pool, 1, &[video_id()], Some(VideoStage::Cutting), "Boom")
+            .await
+            .unwrap();
+
+        assert_eq!(poll_once(&notifications, &pool).await.unwrap(), 0);
+        assert!(notifications.mailer.sender.sent().is_empty());
+    }
+}
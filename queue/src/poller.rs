@@ -0,0 +1,59 @@
+use std::{sync::Arc, time::Duration};
+
+use async_trait::async_trait;
+use marco_polo_rs_core::SyncError;
+use sqlx::PgPool;
+
+/// A background job over rows of the database, like the outbox or the webhook deliveries.
+/// A batch is claimed with a lease in a statement of its own, then every row is worked on
+/// with no transaction open while the outside world answers
+#[async_trait]
+pub trait Poller: Send + Sync {
+    type Item: Send;
+
+    /// Shown on the logs
+    const NAME: &'static str;
+    /// How long to wait after a round where nothing was done
+    const IDLE_INTERVAL: Duration;
+
+    /// Claims the next batch, committed before any of it is worked on
+    async fn claim(&self, pool: &PgPool) -> Result<Vec<Self::Item>, SyncError>;
+
+    /// Works on a claimed item, returning whether it is done.
+    /// An error leaves the item to be claimed again once its lease ends
+    async fn work(&self, pool: &PgPool, item: Self::Item) -> Result<bool, SyncError>;
+}
+
+pub async fn run<P: Poller>(poller: P, pool: Arc<PgPool>) {
+    tracing::info!("Starting {}...", P::NAME);
+    loop {
+        let done = match poll_once(&poller, &pool).await {
+            Ok(done) => done,
+            Err(e) => {
+                tracing::error!("Error claiming the {}: {}", P::NAME, e);
+                0
+            }
+        };
+
+        if done == 0 {
+            tokio::time::sleep(P::IDLE_INTERVAL).await;
+        }
+    }
+}
+
+/// Works on one claimed batch, returning how many items are done.
+/// A failing item doesn't stop the rest of the batch
+pub async fn poll_once<P: Poller>(poller: &P, pool: &PgPool) -> Result<usize, SyncError> {
+    let items = poller.claim(pool).await?;
+
+    let mut done = 0;
+    for item in items {
+        match poller.work(pool, item).await {
+            Ok(true) => done += 1,
+            Ok(false) => {}
+            Err(e) => tracing::error!("Error on the {}: {}", P::NAME, e),
+        }
+    }
+
+    return Ok(done);
+}
@@ -0,0 +1,80 @@
+use marco_polo_rs_core::database::{models::video_event::ProgressStep, queries};
+use sqlx::{types::Uuid, PgPool};
+use tokio::{sync::mpsc, task::JoinHandle};
+
+/// Percentage points between two notifications, the commands report way more often
+const NOTIFY_EVERY: f32 = 5.0;
+
+/// Sends the progress a blocking command reports to the video events.
+/// `report` can be called from the command's callback, the notifications run on their own task
+pub struct ProgressNotifier {
+    sender: mpsc::UnboundedSender<f32>,
+    task: JoinHandle<()>,
+}
+
+impl ProgressNotifier {
+    pub fn start(pool: &PgPool, video_ids: Vec<Uuid>, step: ProgressStep) -> Self {
+        let (sender, mut receiver) = mpsc::unbounded_channel::<f32>();
+        let pool = pool.clone();
+
+        let task = tokio::spawn(async move {
+            let mut last_notified = None;
+            while let Some(percentage) = receiver.recv().await {
+                if !should_notify(last_notified, percentage) {
+                    continue;
+                }
+                last_notified = Some(percentage);
+
+                for video_id in &video_ids {
+                    let result =
+                        queries::video_event::notify_progress(&pool, video_id, step, percentage)
+                            .await;
+                    if let Err(e) = result {
+                        tracing::warn!(
+                            "Failed to notify the progress of video {}: {}",
+                            video_id,
+                            e
+                        );
+                    }
+                }
+            }
+        });
+
+        Self { sender, task }
+    }
+
+    pub fn report(&self, percentage: f32) {
+        // the task only stops after the sender is dropped
+        let _ = self.sender.send(percentage);
+    }
+
+    /// Waits for the pending notifications, so they arrive before the next stage
+    pub async fn finish(self) {
+        drop(self.sender);
+        let _ = self.task.await;
+    }
+}
+
+fn should_notify(last_notified: Option<f32>, percentage: f32) -> bool {
+    match last_notified {
+        None => true,
+        Some(last) if percentage >= 100.0 => last < 100.0,
+        // a command can start over, e.g. yt-dlp downloads the video and then the audio
+        Some(last) => (percentage - last).abs() >= NOTIFY_EVERY,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::should_notify;
+
+    #[test]
+    fn test_should_notify() {
+        assert!(should_notify(None, 0.5));
+        assert!(!should_notify(Some(0.5), 3.0));
+        assert!(should_notify(Some(0.5), 6.0));
+        assert!(should_notify(Some(98.0), 100.0));
+        assert!(!should_notify(Some(100.0), 100.0));
+        assert!(should_notify(Some(100.0), 0.0));
+    }
+}
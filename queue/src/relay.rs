@@ -0,0 +1,214 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use marco_polo_rs_core::{
+    database::{
+        models::outbox_message::OutboxMessage,
+        queries::{self, failed_message::CreateFailedMessageDto},
+    },
+    internals::cloud::{
+        models::payload::PayloadType,
+        traits::{CloudService, QueueClient},
+    },
+    util::metrics,
+    SyncError,
+};
+use sqlx::PgPool;
+
+use crate::{
+    poller::Poller,
+    workers::{find_failed_stage, notify_failed_message},
+};
+
+const RELAY_BATCH_SIZE: i64 = 10;
+const RELAY_MAX_ATTEMPTS: i32 = 5;
+const RELAY_LEASE_SECONDS: i32 = 60;
+
+/// Publishes the messages the api and the handlers wrote to the outbox
+pub struct Relay<CS: CloudService> {
+    pub cloud_service: CS,
+}
+
+#[async_trait]
+impl<CS> Poller for Relay<CS>
+where
+    CS: CloudService + Send + Sync,
+    CS::QC: Sync,
+{
+    type Item = OutboxMessage;
+
+    const NAME: &'static str = "outbox relay";
+    // short, the handlers wait on these messages, but a down queue isn't hammered either
+    const IDLE_INTERVAL: Duration = Duration::from_secs(1);
+
+    async fn claim(&self, pool: &PgPool) -> Result<Vec<OutboxMessage>, SyncError> {
+        give_up_exhausted(pool).await?;
+
+        let messages = queries::outbox::claim_pending(
+            pool,
+            RELAY_BATCH_SIZE,
+            RELAY_MAX_ATTEMPTS,
+            RELAY_LEASE_SECONDS,
+        )
+        .await?;
+
+        return Ok(messages);
+    }
+
+    async fn work(&self, pool: &PgPool, message: OutboxMessage) -> Result<bool, SyncError> {
+        let queue_client = self.cloud_service.queue_client();
+        let result = match PayloadType::from_json(&message.payload) {
+            Ok(payload) => queue_client.send_message(payload).await,
+            Err(e) => Err(e),
+        };
+
+        match result {
+            Ok(_) => {
+                queries::outbox::mark_sent(pool, message.id).await?;
+                return Ok(true);
+            }
+            Err(e) => {
+                tracing::error!("Failed to relay outbox message {}: {}", message.id, e);
+                queries::outbox::mark_failed(pool, message.id, &e.to_string()).await?;
+                return Ok(false);
+            }
+        }
+    }
+}
+
+/// Moves the messages that failed every attempt to the failed messages, where they can be replayed.
+/// A message only leaves the outbox along with its failed message
+async fn give_up_exhausted(pool: &PgPool) -> Result<(), SyncError> {
+    let mut trx = pool.begin().await?;
+    let messages = queries::outbox::take_exhausted(&mut *trx, RELAY_MAX_ATTEMPTS).await?;
+
+    let mut failed = Vec::with_capacity(messages.len());
+    for message in messages {
+        tracing::error!(
+            "Giving up on outbox message {} after {} attempts",
+            message.id,
+            message.attempts
+        );
+
+        let video_ids = match PayloadType::from_json(&message.payload) {
+            Ok(payload) => payload.video_ids(),
+            Err(_) => vec![],
+        };
+        let error = message.last_error.unwrap_or_default();
+        let stage = find_failed_stage(pool, &video_ids).await;
+
+        let dto = CreateFailedMessageDto {
+            payload: &message.payload,
+            video_ids: &video_ids,
+            error: &error,
+            stage: stage.clone(),
+            attempts: message.attempts,
+        };
+        let id = queries::failed_message::create(&mut *trx, dto).await?;
+        failed.push((id, video_ids, stage, error));
+    }
+
+    trx.commit().await?;
+
+    for (id, video_ids, stage, error) in failed {
+        metrics::record_outbox_failure();
+        notify_failed_message(pool, id, &video_ids, stage, &error).await;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use marco_polo_rs_core::{
+        database::queries,
+        internals::cloud::models::payload::{PayloadType, VideoDownloadPayload},
+    };
+    use sqlx::{types::Uuid, PgPool};
+
+    use crate::{
+        poller::poll_once,
+        test::mock::{CloudServiceMock, QueueClientMock},
+    };
+
+    use super::{Relay, RELAY_MAX_ATTEMPTS};
+
+    fn download_payload() -> PayloadType {
+        PayloadType::BatukaDownloadVideo(VideoDownloadPayload {
+            original_video_id: 1,
+            video_ids: vec![Uuid::new_v4()],
+        })
+    }
+
+    #[sqlx::test(migrations = "../migrations")]
+    async fn test_relay_publishes_once(pool: PgPool) {
+        let relay = Relay {
+            cloud_service: CloudServiceMock::default(),
+        };
+
+        queries::outbox::enqueue(&pool, &download_payload())
+            .await
+            .unwrap();
+        queries::outbox::enqueue(&pool, &download_payload())
+            .await
+            .unwrap();
+
+        let published = poll_once(&relay, &pool).await.unwrap();
+        assert_eq!(published, 2);
+
+        let published = poll_once(&relay, &pool).await.unwrap();
+        assert_eq!(published, 0);
+
+        assert_eq!(relay.cloud_service.queue_client.sent(), 2);
+    }
+
+    #[sqlx::test(migrations = "../migrations")]
+    async fn test_relay_keeps_unsent_messages(pool: PgPool) {
+        let relay = Relay {
+            cloud_service: CloudServiceMock {
+                queue_client: QueueClientMock::unavailable(),
+                ..Default::default()
+            },
+        };
+
+        queries::outbox::enqueue(&pool, &download_payload())
+            .await
+            .unwrap();
+
+        let published = poll_once(&relay, &pool).await.unwrap();
+        assert_eq!(published, 0);
+
+        let pending = queries::outbox::find_unsent(&pool).await.unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].attempts, 1);
+        assert_eq!(pending[0].last_error, Some("Queue unavailable".to_string()));
+        assert!(pending[0].locked_until.is_none());
+    }
+
+    #[sqlx::test(migrations = "../migrations")]
+    async fn test_relay_gives_up_after_max_attempts(pool: PgPool) {
+        let relay = Relay {
+            cloud_service: CloudServiceMock {
+                queue_client: QueueClientMock::unavailable(),
+                ..Default::default()
+            },
+        };
+
+        let payload = download_payload();
+        queries::outbox::enqueue(&pool, &payload).await.unwrap();
+
+        for _ in 0..RELAY_MAX_ATTEMPTS + 1 {
+            poll_once(&relay, &pool).await.unwrap();
+        }
+
+        let pending = queries::outbox::find_unsent(&pool).await.unwrap();
+        assert!(pending.is_empty());
+
+        let failed = queries::failed_message::find_all(&pool).await.unwrap();
+        assert_eq!(failed.len(), 1);
+        assert_eq!(failed[0].payload, payload.to_json());
+        assert_eq!(failed[0].attempts, RELAY_MAX_ATTEMPTS);
+        assert_eq!(failed[0].error, "Queue unavailable");
+        assert_eq!(failed[0].video_ids, payload.video_ids());
+    }
+}
@@ -0,0 +1,123 @@
+use std::{sync::Arc, time::Duration};
+
+use tokio::sync::Semaphore;
+use tokio_util::sync::CancellationToken;
+
+const DEFAULT_SHUTDOWN_TIMEOUT: u64 = 60;
+/// Aborted workers only notice it on their next await, ffmpeg keeps them busy until it exits
+const ABORT_GRACE_PERIOD: Duration = Duration::from_secs(10);
+
+/// Cancels the token on SIGINT or SIGTERM
+pub async fn listen(shutdown: CancellationToken) {
+    wait_for_signal().await;
+    tracing::info!("Shutting down, no more messages will be received");
+    shutdown.cancel();
+}
+
+#[cfg(unix)]
+async fn wait_for_signal() {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    let mut terminate = signal(SignalKind::terminate()).expect("Failed to listen to SIGTERM");
+
+    tokio::select! {
+        _ = tokio::signal::ctrl_c() => {}
+        _ = terminate.recv() => {}
+    }
+}
+
+#[cfg(not(unix))]
+async fn wait_for_signal() {
+    if let Err(e) = tokio::signal::ctrl_c().await {
+        tracing::error!("Failed to listen to ctrl-c: {}", e);
+    }
+}
+
+/// Seconds the busy workers have to finish, from `QUEUE_SHUTDOWN_TIMEOUT`.
+/// Read at startup, an invalid value must not wait for the first SIGTERM to panic
+pub fn timeout() -> Duration {
+    let seconds = match std::env::var("QUEUE_SHUTDOWN_TIMEOUT") {
+        Ok(seconds) => seconds
+            .parse()
+            .expect("QUEUE_SHUTDOWN_TIMEOUT must be a number of seconds"),
+        Err(_) => DEFAULT_SHUTDOWN_TIMEOUT,
+    };
+
+    Duration::from_secs(seconds)
+}
+
+/// Waits until every worker is idle, `capacity` has a permit per idle worker.
+/// Workers still busy after the deadline are aborted and give their messages back.
+/// Returns whether every worker stopped
+pub async fn drain(
+    capacity: Arc<Semaphore>,
+    total_workers: u32,
+    abort: &CancellationToken,
+    deadline: Duration,
+) -> bool {
+    let busy = total_workers as usize - capacity.available_permits();
+    tracing::info!("Waiting for {} busy workers", busy);
+
+    let idle = capacity.clone().acquire_many_owned(total_workers);
+    if tokio::time::timeout(deadline, idle).await.is_ok() {
+        tracing::info!("Every worker finished");
+        return true;
+    }
+
+    tracing::warn!("Shutdown deadline reached, aborting the busy workers");
+    abort.cancel();
+
+    let idle = capacity.acquire_many_owned(total_workers);
+    match tokio::time::timeout(ABORT_GRACE_PERIOD, idle).await {
+        Ok(_) => true,
+        Err(_) => {
+            tracing::error!("Some workers did not stop, their messages will be received again");
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{sync::Arc, time::Duration};
+
+    use tokio::sync::Semaphore;
+    use tokio_util::sync::CancellationToken;
+
+    use super::drain;
+
+    #[tokio::test]
+    async fn test_drain_waits_for_busy_workers() {
+        let capacity = Arc::new(Semaphore::new(2));
+        let abort = CancellationToken::new();
+
+        let busy = capacity.clone().acquire_owned().await.unwrap();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            drop(busy);
+        });
+
+        let drained = drain(capacity, 2, &abort, Duration::from_secs(5)).await;
+
+        assert!(drained);
+        assert!(!abort.is_cancelled());
+    }
+
+    #[tokio::test]
+    async fn test_drain_aborts_after_deadline() {
+        let capacity = Arc::new(Semaphore::new(1));
+        let abort = CancellationToken::new();
+
+        let busy = capacity.clone().acquire_owned().await.unwrap();
+        let worker_abort = abort.clone();
+        tokio::spawn(async move {
+            worker_abort.cancelled().await;
+            drop(busy);
+        });
+
+        let drained = drain(capacity, 1, &abort, Duration::from_millis(50)).await;
+
+        assert!(drained);
+        assert!(abort.is_cancelled());
+    }
+}
@@ -0,0 +1,432 @@
+use std::sync::{
+    atomic::{AtomicBool, AtomicUsize, Ordering},
+    Mutex,
+};
+
+use async_trait::async_trait;
+use marco_polo_rs_core::{
+    database::models::{
+        channel::Channel,
+        subtitles_style::SubtitlesStyle,
+        video::{subtitles_mode::SubtitlesMode, with::VideoWithStorage},
+    },
+    internals::{
+        cloud::{
+            local::bucket::upload_event,
+            models::payload::PayloadType,
+            traits::{BucketClient, CloudService, QueueClient, QueueMessage},
+        },
+        subtitler::traits::{SubtitleTrack, SubtitlerClient},
+        transcriber::traits::{
+            Sentence, TranscribeOptions, TranscriberClient, TranscriptionStatus,
+        },
+        translator::{language::Language, traits::TranslatorClient},
+        video_platform::{errors::HeathCheckError, UploadParams, VideoPlatformClient},
+        ServiceProvider,
+    },
+    mail::sender::{MailSender, SendEmailOptions, SenderError},
+    util::progress::OnProgress,
+    SyncError,
+};
+
+use crate::YoutubeClientInUse;
+
+/// Counts the calls that produce side effects, so tests can
+/// check a redelivered message did not repeat them
+#[derive(Default)]
+pub struct CloudServiceMock {
+    pub bucket_client: BucketClientMock,
+    pub queue_client: QueueClientMock,
+}
+
+impl ServiceProvider for CloudServiceMock {
+    fn id(&self) -> i32 {
+        return 1;
+    }
+}
+
+impl CloudService for CloudServiceMock {
+    type BC = BucketClientMock;
+    type QC = QueueClientMock;
+
+    fn bucket_client(&self) -> &Self::BC {
+        &self.bucket_client
+    }
+
+    fn queue_client(&self) -> &Self::QC {
+        &self.queue_client
+    }
+}
+
+/// Announces the uploads through the outbox, like the local bucket
+#[derive(Default)]
+pub struct BucketClientMock {
+    pub uploads: AtomicUsize,
+}
+
+impl BucketClientMock {
+    pub fn uploads(&self) -> usize {
+        self.uploads.load(Ordering::SeqCst)
+    }
+}
+
+impl ServiceProvider for BucketClientMock {
+    fn id(&self) -> i32 {
+        return 1;
+    }
+}
+
+#[async_trait]
+impl BucketClient for BucketClientMock {
+    async fn create_signed_upload_url(&self, expires_in: u16) -> Result<String, SyncError> {
+        Ok(format!("https://storage.test/{}", expires_in))
+    }
+
+    async fn create_signed_upload_url_with_uri(
+        &self,
+        file_uri: &str,
+        _expires_in: u16,
+    ) -> Result<String, SyncError> {
+        Ok(format!("https://storage.test/{}", file_uri))
+    }
+
+    async fn create_signed_download_url(
+        &self,
+        file_uri: &str,
+        _expires_in: Option<u16>,
+    ) -> Result<String, SyncError> {
+        Ok(format!("https://storage.test/{}", file_uri))
+    }
+
+    async fn upload_file(&self, _file_uri: &str, _file: Vec<u8>) -> Result<(), SyncError> {
+        self.uploads.fetch_add(1, Ordering::SeqCst);
+        Ok(())
+    }
+
+    async fn upload_file_from_path(
+        &self,
+        _file_uri: &str,
+        _file_path: &str,
+    ) -> Result<(), SyncError> {
+        self.uploads.fetch_add(1, Ordering::SeqCst);
+        Ok(())
+    }
+
+    async fn download_file(&self, _file_uri: &str) -> Result<Vec<u8>, SyncError> {
+        Ok(vec![])
+    }
+
+    async fn download_file_to_path(
+        &self,
+        _file_uri: &str,
+        _destination_path: &str,
+    ) -> Result<(), SyncError> {
+        Ok(())
+    }
+
+    fn upload_event(&self, file_uri: &str) -> Option<PayloadType> {
+        let body = upload_event(file_uri)?;
+        PayloadType::from_json(&body).ok()
+    }
+}
+
+/// Publishes nothing, counting the videos that would have been published
+#[derive(Default)]
+pub struct VideoPlatformClientMock {
+    pub uploads: AtomicUsize,
+}
+
+impl VideoPlatformClientMock {
+    pub fn uploads(&self) -> usize {
+        self.uploads.load(Ordering::SeqCst)
+    }
+}
+
+#[async_trait]
+impl VideoPlatformClient for VideoPlatformClientMock {
+    type VideoResult = <YoutubeClientInUse as VideoPlatformClient>::VideoResult;
+
+    async fn upload_video<'a>(
+        &self,
+        _video: UploadParams<'a>,
+    ) -> Result<Self::VideoResult, SyncError> {
+        let count = self.uploads.fetch_add(1, Ordering::SeqCst);
+        let mut video: Self::VideoResult = Default::default();
+        video.id = Some(format!("video_{}", count));
+        Ok(video)
+    }
+
+    async fn check_channel_health<'a>(
+        &self,
+        _channel: &'a Channel,
+    ) -> Result<(), HeathCheckError<'a>> {
+        Ok(())
+    }
+}
+
+/// Records the srt each video was subtitled with
+#[derive(Default)]
+pub struct SubtitlerClientMock {
+    pub srt_uris: Mutex<Vec<String>>,
+    pub tracks: Mutex<Vec<(Vec<String>, SubtitlesMode)>>,
+    pub styles: Mutex<Vec<Option<String>>>,
+}
+
+impl SubtitlerClientMock {
+    pub fn srt_uris(&self) -> Vec<String> {
+        self.srt_uris.lock().unwrap().clone()
+    }
+
+    /// The uris of every track and the mode of each call
+    pub fn tracks(&self) -> Vec<(Vec<String>, SubtitlesMode)> {
+        self.tracks.lock().unwrap().clone()
+    }
+
+    /// The name of the style of each call
+    pub fn styles(&self) -> Vec<Option<String>> {
+        self.styles.lock().unwrap().clone()
+    }
+}
+
+impl ServiceProvider for SubtitlerClientMock {
+    fn id(&self) -> i32 {
+        return 1;
+    }
+}
+
+#[async_trait]
+impl<BC: BucketClient> SubtitlerClient<BC> for SubtitlerClientMock {
+    fn estimate_time(&self, _payload: &VideoWithStorage, _bucket_client: &BC) -> u32 {
+        0
+    }
+
+    async fn subtitle(
+        &self,
+        payload: &VideoWithStorage,
+        tracks: &[SubtitleTrack],
+        mode: SubtitlesMode,
+        style: Option<&SubtitlesStyle>,
+        _bucket_client: &BC,
+        _on_progress: OnProgress<'_>,
+    ) -> Result<String, SyncError> {
+        let uris: Vec<String> = tracks.iter().map(|track| track.uri.clone()).collect();
+        self.srt_uris.lock().unwrap().push(uris[0].clone());
+        self.tracks.lock().unwrap().push((uris, mode));
+        self.styles
+            .lock()
+            .unwrap()
+            .push(style.map(|style| style.name.clone()));
+
+        let path = std::env::temp_dir().join(format!("{}.subtitled.mkv", payload.video.id));
+        std::fs::write(&path, b"subtitled")?;
+
+        Ok(path.to_string_lossy().to_string())
+    }
+}
+
+#[derive(Debug)]
+pub struct MessageMock;
+
+impl QueueMessage for MessageMock {
+    fn get_message(&self) -> String {
+        String::from("test")
+    }
+
+    fn get_handle(&self) -> String {
+        String::from("test")
+    }
+
+    fn to_payload(&self) -> Result<PayloadType, SyncError> {
+        Err("The mock message has no payload".into())
+    }
+}
+
+#[derive(Default)]
+pub struct QueueClientMock {
+    pub sent: AtomicUsize,
+    pub unavailable: bool,
+}
+
+impl QueueClientMock {
+    pub fn unavailable() -> Self {
+        Self {
+            unavailable: true,
+            ..Default::default()
+        }
+    }
+
+    pub fn sent(&self) -> usize {
+        self.sent.load(Ordering::SeqCst)
+    }
+}
+
+#[async_trait]
+impl QueueClient for QueueClientMock {
+    type M = MessageMock;
+
+    async fn receive_message(&self) -> Result<Option<Vec<Self::M>>, SyncError> {
+        Ok(None)
+    }
+
+    async fn send_message(&self, _payload: PayloadType) -> Result<(), SyncError> {
+        if self.unavailable {
+            return Err("Queue unavailable".into());
+        }
+        self.sent.fetch_add(1, Ordering::SeqCst);
+        Ok(())
+    }
+
+    async fn delete_message(&self, _message: Self::M) -> Result<(), SyncError> {
+        Ok(())
+    }
+
+    async fn change_message_visibility(
+        &self,
+        _message: &Self::M,
+        _visibility_timeout: usize,
+    ) -> Result<(), SyncError> {
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+pub struct TranscriberClientMock {
+    pub requests: AtomicUsize,
+    /// Completed when not set
+    pub status: Option<TranscriptionStatus>,
+    /// Whether the last transcription asked for the speakers
+    pub speaker_labels: AtomicBool,
+    /// The words boosted on the last transcription
+    pub word_boost: Mutex<Vec<String>>,
+}
+
+impl TranscriberClientMock {
+    pub fn requests(&self) -> usize {
+        self.requests.load(Ordering::SeqCst)
+    }
+
+    pub fn speaker_labels(&self) -> bool {
+        self.speaker_labels.load(Ordering::SeqCst)
+    }
+
+    pub fn word_boost(&self) -> Vec<String> {
+        self.word_boost.lock().unwrap().clone()
+    }
+}
+
+impl ServiceProvider for TranscriberClientMock {
+    fn id(&self) -> i32 {
+        return 3;
+    }
+}
+
+#[async_trait]
+impl TranscriberClient for TranscriberClientMock {
+    async fn transcribe(
+        &self,
+        _media_url: &str,
+        options: &TranscribeOptions,
+    ) -> Result<String, SyncError> {
+        self.speaker_labels
+            .store(options.speaker_labels, Ordering::SeqCst);
+        *self.word_boost.lock().unwrap() = options.word_boost.clone();
+        let count = self.requests.fetch_add(1, Ordering::SeqCst);
+        Ok(format!("transcription_{}", count))
+    }
+
+    async fn transcribe_from_file(
+        &self,
+        file_path: &str,
+        options: &TranscribeOptions,
+    ) -> Result<String, SyncError> {
+        self.transcribe(file_path, options).await
+    }
+
+    async fn get_transcription_sentences(
+        &self,
+        _transcription_id: &str,
+    ) -> Result<Vec<Sentence>, SyncError> {
+        Ok(vec![Sentence {
+            start_time: 0,
+            end_time: 1000,
+            text: String::from("Hello"),
+            words: None,
+            speaker: None,
+        }])
+    }
+
+    async fn status(&self, _transcription_id: &str) -> Result<TranscriptionStatus, SyncError> {
+        Ok(self
+            .status
+            .clone()
+            .unwrap_or(TranscriptionStatus::Completed))
+    }
+
+    async fn pool(&self, _transcription_id: &str) -> Result<(), SyncError> {
+        Ok(())
+    }
+
+    fn authorize_webhook(&self, _authorization: &str) -> bool {
+        false
+    }
+
+    fn webhook_transcription_id(&self, _body: &[u8]) -> Result<String, SyncError> {
+        Err("The mock sends no webhooks".into())
+    }
+}
+
+pub struct TranslatorClientMock;
+
+impl ServiceProvider for TranslatorClientMock {
+    fn id(&self) -> i32 {
+        return 4;
+    }
+}
+
+#[async_trait]
+impl TranslatorClient for TranslatorClientMock {
+    async fn translate_sentence(
+        &self,
+        sentence: &str,
+        _source_language: Language,
+        target_language: Language,
+    ) -> Result<String, SyncError> {
+        Ok(format!("{} ({})", sentence, target_language.code()))
+    }
+
+    async fn translate_sentences(
+        &self,
+        sentences: Vec<&str>,
+        source_language: Language,
+        target_language: Language,
+    ) -> Result<Vec<String>, SyncError> {
+        let mut translations = vec![];
+        for sentence in sentences {
+            let translation = self
+                .translate_sentence(sentence, source_language, target_language)
+                .await?;
+            translations.push(translation);
+        }
+        Ok(translations)
+    }
+}
+
+/// Keeps the emails instead of sending them
+#[derive(Default)]
+pub struct MailSenderMock {
+    sent: Mutex<Vec<SendEmailOptions>>,
+}
+
+impl MailSenderMock {
+    pub fn sent(&self) -> Vec<SendEmailOptions> {
+        self.sent.lock().unwrap().clone()
+    }
+}
+
+#[async_trait]
+impl MailSender for MailSenderMock {
+    async fn send(&self, options: SendEmailOptions) -> Result<(), SenderError> {
+        self.sent.lock().unwrap().push(options);
+        Ok(())
+    }
+}
@@ -0,0 +1,229 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use marco_polo_rs_core::{
+    database::{
+        models::video_transcription::VideosTranscription,
+        queries::{self, transcription::UpdateVideoTranscriptionDto, video::CreateErrorsDto},
+    },
+    internals::{
+        cloud::traits::{BucketClient, CloudService},
+        transcriber::traits::{TranscriberClient, TranscriptionStatus},
+        ServiceProvider,
+    },
+    util::srt,
+    SyncError,
+};
+use sqlx::PgPool;
+
+use crate::poller::Poller;
+
+const POLLING_BATCH_SIZE: i64 = 10;
+/// A transcription still processing is asked about again after this
+const POLLING_CHECK_INTERVAL_SECONDS: i32 = 30;
+
+/// Asks the transcriber for the transcriptions the webhook would have delivered,
+/// so the api doesn't need to be reachable from the internet.
+/// Completed ones are uploaded to `srt_transcriptions/` like the webhook does, the upload
+/// notification of the bucket is what enqueues the translation
+pub struct Transcriptions<CS: CloudService, TC: TranscriberClient> {
+    pub cloud_service: CS,
+    pub transcriber_client: TC,
+}
+
+#[async_trait]
+impl<CS, TC> Poller for Transcriptions<CS, TC>
+where
+    CS: CloudService + Send + Sync,
+    TC: TranscriberClient + Send + Sync,
+{
+    type Item = VideosTranscription;
+
+    const NAME: &'static str = "transcription polling";
+    const IDLE_INTERVAL: Duration = Duration::from_secs(10);
+
+    async fn claim(&self, pool: &PgPool) -> Result<Vec<VideosTranscription>, SyncError> {
+        let transcriptions = queries::transcription::claim_pending(
+            pool,
+            self.transcriber_client.id(),
+            POLLING_BATCH_SIZE,
+            POLLING_CHECK_INTERVAL_SECONDS,
+        )
+        .await?;
+
+        return Ok(transcriptions);
+    }
+
+    /// Each transcription is saved in a transaction of its own, one that fails is only
+    /// checked again after the interval, without holding back the rest of the batch
+    async fn work(
+        &self,
+        pool: &PgPool,
+        transcription: VideosTranscription,
+    ) -> Result<bool, SyncError> {
+        let video_id = transcription.video_id;
+        let status = self
+            .transcriber_client
+            .status(&transcription.transcription_id)
+            .await
+            .map_err(|e| {
+                format!(
+                    "Failed to get the transcription of video {}: {}",
+                    video_id, e
+                )
+            })?;
+
+        match status {
+            TranscriptionStatus::Processing => return Ok(false),
+            TranscriptionStatus::Completed => {
+                let bucket_client = self.cloud_service.bucket_client();
+                let sentences = self
+                    .transcriber_client
+                    .get_transcription_sentences(&transcription.transcription_id)
+                    .await?;
+                let speaker_labels = queries::video::find_speaker_labels(pool, &video_id).await?;
+                let srt = srt::create_based_on_sentences(sentences, speaker_labels);
+
+                let file_name = format!("srt_transcriptions/{}.srt", video_id);
+                bucket_client
+                    .upload_file(&file_name, srt.as_bytes().to_vec())
+                    .await?;
+
+                let dto = UpdateVideoTranscriptionDto {
+                    video_id,
+                    storage_id: bucket_client.id(),
+                    path: file_name,
+                };
+                let mut trx = pool.begin().await?;
+                queries::outbox::enqueue_upload_event(&mut *trx, bucket_client, &dto.path).await?;
+                queries::transcription::update(&mut *trx, dto).await?;
+                trx.commit().await?;
+            }
+            TranscriptionStatus::Failed(error) => {
+                tracing::error!("Transcription of video {} failed: {}", video_id, error);
+                let error = format!("Transcription error: {}", error);
+                let dto = CreateErrorsDto {
+                    video_ids: vec![video_id],
+                    error: &error,
+                };
+                let mut trx = pool.begin().await?;
+                queries::video::create_errors(&mut *trx, dto).await?;
+                queries::transcription::mark_failed(&mut *trx, &video_id).await?;
+                trx.commit().await?;
+            }
+        }
+
+        return Ok(true);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use marco_polo_rs_core::{
+        database::queries::{self, transcription::CreateTranscriptionDto},
+        internals::transcriber::traits::TranscriptionStatus,
+    };
+    use sqlx::{types::Uuid, PgPool};
+
+    use crate::{
+        poller::poll_once,
+        test::mock::{CloudServiceMock, TranscriberClientMock},
+    };
+
+    use super::Transcriptions;
+
+    const VIDEO_ID: &str = "806b5a48-f221-11ed-a05b-0242ac120096";
+
+    async fn request_transcription(pool: &PgPool) -> Uuid {
+        let video_id = Uuid::parse_str(VIDEO_ID).unwrap();
+        let dto = CreateTranscriptionDto {
+            video_id,
+            transcription_id: "transcription_0".to_string(),
+            transcriber_id: 3,
+        };
+        queries::transcription::create(pool, dto).await.unwrap();
+        return video_id;
+    }
+
+    #[sqlx::test(
+        migrations = "../migrations",
+        fixtures("../handlers/test/fixtures/video")
+    )]
+    async fn test_completed_transcription_is_uploaded_once(pool: PgPool) {
+        let transcriptions = Transcriptions {
+            cloud_service: CloudServiceMock::default(),
+            transcriber_client: TranscriberClientMock::default(),
+        };
+        let video_id = request_transcription(&pool).await;
+
+        let finished = poll_once(&transcriptions, &pool).await.unwrap();
+        assert_eq!(finished, 1);
+
+        let finished = poll_once(&transcriptions, &pool).await.unwrap();
+        assert_eq!(finished, 0);
+
+        assert_eq!(transcriptions.cloud_service.bucket_client.uploads(), 1);
+
+        let transcription = queries::transcription::find_by_video_id(&pool, &video_id)
+            .await
+            .unwrap();
+        assert_eq!(
+            transcription.path,
+            Some(format!("srt_transcriptions/{}.srt", VIDEO_ID))
+        );
+    }
+
+    #[sqlx::test(
+        migrations = "../migrations",
+        fixtures("../handlers/test/fixtures/video")
+    )]
+    async fn test_processing_transcription_is_kept(pool: PgPool) {
+        let transcriptions = Transcriptions {
+            cloud_service: CloudServiceMock::default(),
+            transcriber_client: TranscriberClientMock {
+                status: Some(TranscriptionStatus::Processing),
+                ..Default::default()
+            },
+        };
+        request_transcription(&pool).await;
+
+        let finished = poll_once(&transcriptions, &pool).await.unwrap();
+        assert_eq!(finished, 0);
+        assert_eq!(transcriptions.cloud_service.bucket_client.uploads(), 0);
+
+        let pending = queries::transcription::claim_pending(&pool, 3, 10, 0)
+            .await
+            .unwrap();
+        assert_eq!(pending.len(), 1);
+    }
+
+    #[sqlx::test(
+        migrations = "../migrations",
+        fixtures("../handlers/test/fixtures/video")
+    )]
+    async fn test_failed_transcription_records_error(pool: PgPool) {
+        let transcriptions = Transcriptions {
+            cloud_service: CloudServiceMock::default(),
+            transcriber_client: TranscriberClientMock {
+                status: Some(TranscriptionStatus::Failed("Audio too short".to_string())),
+                ..Default::default()
+            },
+        };
+        let video_id = request_transcription(&pool).await;
+
+        let finished = poll_once(&transcriptions, &pool).await.unwrap();
+        assert_eq!(finished, 1);
+        assert_eq!(transcriptions.cloud_service.bucket_client.uploads(), 0);
+
+        let pending = queries::transcription::claim_pending(&pool, 3, 10, 0)
+            .await
+            .unwrap();
+        assert!(pending.is_empty());
+
+        let errors = queries::video_error::find_by_video_id(&pool, &video_id)
+            .await
+            .unwrap();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].error, "Transcription error: Audio too short");
+    }
+}
@@ -0,0 +1,220 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::Utc;
+use marco_polo_rs_core::{
+    database::queries::{
+        self,
+        webhook::{FailedDeliveryDto, PendingDelivery},
+    },
+    util::security::{signature, url_policy::UrlPolicy},
+    SyncError,
+};
+use sqlx::PgPool;
+
+use crate::poller::Poller;
+
+const DELIVERY_BATCH_SIZE: i64 = 10;
+/// About eight hours of retries with the backoff below
+const DELIVERY_MAX_ATTEMPTS: i32 = 10;
+const DELIVERY_TIMEOUT: Duration = Duration::from_secs(10);
+/// Longer than a whole batch of timed out deliveries
+const DELIVERY_LEASE_SECONDS: i32 = 5 * 60;
+const BACKOFF_BASE: Duration = Duration::from_secs(30);
+const BACKOFF_MAX: Duration = Duration::from_secs(6 * 60 * 60);
+
+const EVENT_HEADER: &str = "X-Marco-Polo-Event";
+const DELIVERY_HEADER: &str = "X-Marco-Polo-Delivery";
+const SIGNATURE_HEADER: &str = "X-Marco-Polo-Signature";
+
+/// Sends the video events to the webhooks of the users, retrying the failed ones with a backoff
+pub struct Webhooks {
+    pub policy: UrlPolicy,
+}
+
+#[async_trait]
+impl Poller for Webhooks {
+    type Item = PendingDelivery;
+
+    const NAME: &'static str = "webhook deliveries";
+    const IDLE_INTERVAL: Duration = Duration::from_secs(5);
+
+    async fn claim(&self, pool: &PgPool) -> Result<Vec<PendingDelivery>, SyncError> {
+        let deliveries = queries::webhook::claim_due_deliveries(
+            pool,
+            DELIVERY_BATCH_SIZE,
+            DELIVERY_MAX_ATTEMPTS,
+            DELIVERY_LEASE_SECONDS,
+        )
+        .await?;
+
+        return Ok(deliveries);
+    }
+
+    async fn work(&self, pool: &PgPool, delivery: PendingDelivery) -> Result<bool, SyncError> {
+        match send(&self.policy, &delivery).await {
+            Ok(status) => {
+                queries::webhook::mark_delivered(pool, delivery.id, status).await?;
+                return Ok(true);
+            }
+            Err((status, error)) => {
+                tracing::warn!("Failed to deliver webhook {}: {}", delivery.id, error);
+                // the claim already counted this attempt
+                let backoff = backoff(delivery.attempts - 1).as_secs() as i64;
+                let next_attempt_at = Utc::now().naive_utc() + chrono::Duration::seconds(backoff);
+                let dto = FailedDeliveryDto {
+                    id: delivery.id,
+                    response_status: status,
+                    error: &error,
+                    next_attempt_at,
+                };
+                queries::webhook::mark_failed(pool, dto).await?;
+                return Ok(false);
+            }
+        }
+    }
+}
+
+/// Any 2xx accepts the delivery, the status is kept on the log either way.
+/// The url is checked again on every attempt, its host may resolve somewhere else by now
+async fn send(
+    policy: &UrlPolicy,
+    delivery: &PendingDelivery,
+) -> Result<i32, (Option<i32>, String)> {
+    let client = policy
+        .client_builder(&delivery.url)
+        .await
+        .map_err(|e| (None, e.to_string()))?
+        .timeout(DELIVERY_TIMEOUT)
+        .build()
+        .map_err(|e| (None, e.to_string()))?;
+
+    let signature = signature::sign(&delivery.secret, &delivery.payload);
+
+    let response = client
+        .post(&delivery.url)
+        .header("Content-Type", "application/json")
+        .header(EVENT_HEADER, delivery.event.name())
+        .header(DELIVERY_HEADER, delivery.id.to_string())
+        .header(SIGNATURE_HEADER, format!("sha256={}", signature))
+        .body(delivery.payload.clone())
+        .send()
+        .await
+        .map_err(|e| (None, e.to_string()))?;
+
+    let status = response.status();
+    if !status.is_success() {
+        return Err((Some(status.as_u16() as i32), status.to_string()));
+    }
+
+    return Ok(status.as_u16() as i32);
+}
+
+/// Doubles with every failed attempt
+fn backoff(attempts: i32) -> Duration {
+    let factor = 2u32.saturating_pow(attempts.max(0) as u32);
+    BACKOFF_BASE.saturating_mul(factor).min(BACKOFF_MAX)
+}
+
+#[cfg(test)]
+mod test {
+    use std::{str::FromStr, time::Duration};
+
+    use marco_polo_rs_core::{
+        database::{
+            models::{video::stage::VideoStage, webhook::WebhookEvent},
+            queries::{self, webhook::CreateWebhookDto},
+        },
+        util::security::url_policy::UrlPolicy,
+    };
+    use sqlx::{types::Uuid, PgPool};
+
+    use crate::poller::poll_once;
+
+    use super::{backoff, Webhooks};
+
+    #[test]
+    fn test_backoff() {
+        assert_eq!(backoff(0), Duration::from_secs(30));
+        assert_eq!(backoff(1), Duration::from_secs(60));
+        assert_eq!(backoff(3), Duration::from_secs(240));
+        assert_eq!(backoff(20), Duration::from_secs(6 * 60 * 60));
+        assert_eq!(backoff(100), Duration::from_secs(6 * 60 * 60));
+    }
+
+    #[sqlx::test(
+        migrations = "../migrations",
+        fixtures("../handlers/test/fixtures/video")
+    )]
+    async fn test_unreachable_webhook_is_retried_later(pool: PgPool) {
+        let video_id = Uuid::from_str("806b5a48-f221-11ed-a05b-0242ac120096").unwrap();
+        let dto = CreateWebhookDto {
+            user_id: 666,
+            // nothing listens on the discard port
+            url: "http://127.0.0.1:9/hook",
+            secret: "secret",
+            events: &[WebhookEvent::Stage],
+        };
+        let webhook = queries::webhook::create(&pool, dto).await.unwrap();
+
+        queries::video::change_stage(&pool, &video_id, VideoStage::Cutting)
+            .await
+            .unwrap();
+
+        let webhooks = Webhooks {
+            policy: UrlPolicy {
+                allowed_hosts: vec!["127.0.0.1".to_string()],
+            },
+        };
+        let delivered = poll_once(&webhooks, &pool).await.unwrap();
+        assert_eq!(delivered, 0);
+
+        let deliveries = queries::webhook::find_deliveries(&pool, webhook.id, 0, 10)
+            .await
+            .unwrap();
+        assert_eq!(deliveries[0].attempts, 1);
+        assert!(deliveries[0].last_error.is_some());
+        assert!(deliveries[0].delivered_at.is_none());
+
+        // the next attempt is not due yet
+        let delivered = poll_once(&webhooks, &pool).await.unwrap();
+        assert_eq!(delivered, 0);
+
+        let deliveries = queries::webhook::find_deliveries(&pool, webhook.id, 0, 10)
+            .await
+            .unwrap();
+        assert_eq!(deliveries[0].attempts, 1);
+    }
+
+    #[sqlx::test(
+        migrations = "../migrations",
+        fixtures("../handlers/test/fixtures/video")
+    )]
+    async fn test_private_webhook_is_not_delivered(pool: PgPool) {
+        let video_id = Uuid::from_str("806b5a48-f221-11ed-a05b-0242ac120096").unwrap();
+        let dto = CreateWebhookDto {
+            user_id: 666,
+            url: "https://169.254.169.254/latest/meta-data",
+            secret: "secret",
+            events: &[WebhookEvent::Stage],
+        };
+        let webhook = queries::webhook::create(&pool, dto).await.unwrap();
+
+        queries::video::change_stage(&pool, &video_id, VideoStage::Cutting)
+            .await
+            .unwrap();
+
+        let webhooks = Webhooks {
+            policy: UrlPolicy::default(),
+        };
+        let delivered = poll_once(&webhooks, &pool).await.unwrap();
+        assert_eq!(delivered, 0);
+
+        let deliveries = queries::webhook::find_deliveries(&pool, webhook.id, 0, 10)
+            .await
+            .unwrap();
+        let error = deliveries[0].last_error.as_ref().unwrap();
+        assert!(error.contains("private address"));
+        assert!(deliveries[0].response_status.is_none());
+    }
+}
@@ -1,27 +1,31 @@
-use std::sync::Arc;
+use std::{sync::Arc, time::Instant};
 
 use marco_polo_rs_core::{
     database::queries::{self, video::CreateErrorsDto},
     internals::cloud::{
         models::payload::PayloadType,
-        traits::{CloudService, QueueClient},
+        traits::{CloudService, QueueClient, QueueMessage},
     },
+    util::metrics,
     SyncError,
 };
-use tokio::sync::Mutex;
+use tokio_util::sync::CancellationToken;
+use tracing::Instrument;
 
 use crate::{
     error::HandlerError, handlers, CloudServiceInUse, Message, SubtitlerClientInUse,
     ERROR_COUNT_THRESHOLD,
 };
 
-use super::Worker;
+use super::{message_span, record_result, release_message, save_failed_message, Worker};
 
 pub struct HeavyWorker {
     pub id: usize,
     pub cloud_service: CloudServiceInUse,
     pub subtitler_client: SubtitlerClientInUse,
     pub pool: Arc<sqlx::PgPool>,
+    /// Cancelled when the queue shuts down and can't wait for the worker anymore
+    pub abort: CancellationToken,
 }
 
 impl HeavyWorker {
@@ -35,7 +39,7 @@ impl HeavyWorker {
         match result {
             Ok(_) => {}
             Err(e) => {
-                println!("Heavy Worker {} delete error: {:?}", self.id, e);
+                tracing::error!("Heavy Worker {} delete error: {:?}", self.id, e);
                 return;
             }
         }
@@ -45,31 +49,61 @@ impl HeavyWorker {
         let queue_client = self.cloud_service.queue_client();
 
         let video_id = payload_type.video_ids();
-        let result = self.handle_payload(payload_type, &message).await;
+        let payload = payload_type.name();
+        let start = Instant::now();
+        let result: Result<(), HandlerError> = tokio::select! {
+            result = self.handle_payload(payload_type, &message) => result,
+            _ = self.abort.cancelled() => {
+                tracing::warn!("Heavy Worker {} aborted, releasing message", self.id);
+                metrics::record_handler(payload, "aborted", start.elapsed());
+                release_message(queue_client, &message).await;
+                return;
+            }
+        };
+        record_result(payload, &result, start.elapsed());
 
         match result {
             Ok(_) => {}
             Err(e) => {
-                println!("Heavy Worker {} error: {:?}", self.id, e);
+                tracing::error!("Heavy Worker {} error: {:?}", self.id, e);
                 let dto = CreateErrorsDto {
-                    video_ids: video_id,
+                    video_ids: video_id.clone(),
                     error: &e.to_string(),
                 };
-                let error_count = queries::video::create_errors(&self.pool, dto)
+                let error_count = queries::video::create_errors(self.pool.as_ref(), dto)
                     .await
                     .unwrap(); //TODO: unwrap
                 match e {
                     HandlerError::Retrievable(_) => {
-                        if error_count >= ERROR_COUNT_THRESHOLD {
-                            println!(
+                        // queues that count deliveries also catch the attempts that crashed the worker
+                        let attempts = message.receive_count().unwrap_or(error_count);
+                        if attempts >= ERROR_COUNT_THRESHOLD {
+                            tracing::warn!(
                                 "Heavy Worker {} error count threshold reached, deleting message",
                                 self.id
                             );
+                            save_failed_message(
+                                &self.pool,
+                                &message.get_message(),
+                                &video_id,
+                                &e.to_string(),
+                                attempts,
+                            )
+                            .await;
                             self.delete_message(queue_client, message).await;
                         }
                         return;
                     }
                     HandlerError::Final(_) => {
+                        let attempts = message.receive_count().unwrap_or(error_count);
+                        save_failed_message(
+                            &self.pool,
+                            &message.get_message(),
+                            &video_id,
+                            &e.to_string(),
+                            attempts,
+                        )
+                        .await;
                         self.delete_message(queue_client, message).await;
                         return;
                     }
@@ -88,7 +122,7 @@ impl HeavyWorker {
     ) -> Result<(), HandlerError> {
         match payload_type {
             PayloadType::BatukaSrtTranslationUpload(payload) => {
-                println!("Heavy Worker {} handling translation upload...", self.id);
+                tracing::info!("Heavy Worker {} handling translation upload...", self.id);
 
                 let handler = handlers::translation::Handler::new(
                     &self.cloud_service,
@@ -110,20 +144,15 @@ impl HeavyWorker {
 
 #[async_trait::async_trait]
 impl Worker for HeavyWorker {
-    async fn handle(
-        self,
-        message: (Message, PayloadType),
-        inactive_worker_pool: Arc<Mutex<Vec<HeavyWorker>>>,
-    ) {
-        println!("Heavy Worker {} is now active", self.id);
+    async fn handle(&self, message: (Message, PayloadType)) {
         let (message, payload_type) = message;
-        self.handle_message(message, payload_type).await;
-        println!("Heavy Worker {} is now inactive", self.id);
-        let mut pool = inactive_worker_pool.lock().await;
-        println!(
-            "Heavy Worker {} is now putting itself back in the pool",
-            self.id
-        );
-        pool.push(self);
+        let span = message_span("heavy", self.id, &payload_type);
+        async {
+            tracing::info!("Heavy Worker {} is now active", self.id);
+            self.handle_message(message, payload_type).await;
+        }
+        .instrument(span)
+        .await;
+        tracing::info!("Heavy Worker {} is now inactive", self.id);
     }
 }
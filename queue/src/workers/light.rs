@@ -1,14 +1,16 @@
-use std::sync::Arc;
+use std::{sync::Arc, time::Instant};
 
 use marco_polo_rs_core::{
     database::queries::{self, video::CreateErrorsDto},
     internals::cloud::{
         models::payload::PayloadType,
-        traits::{CloudService, QueueClient},
+        traits::{CloudService, QueueClient, QueueMessage},
     },
+    util::metrics,
     SyncError,
 };
-use tokio::sync::Mutex;
+use tokio_util::sync::CancellationToken;
+use tracing::Instrument;
 
 use crate::{
     error::HandlerError,
@@ -17,7 +19,7 @@ use crate::{
     VideoDownloaderInUse, YoutubeClientInUse, ERROR_COUNT_THRESHOLD,
 };
 
-use super::Worker;
+use super::{message_span, record_result, release_message, save_failed_message, Worker};
 
 pub struct LightWorker {
     pub id: usize,
@@ -25,6 +27,8 @@ pub struct LightWorker {
     pub transcriber_client: TranscriberClientInUse,
     pub translator_client: TranslatorClientInUse,
     pub pool: Arc<sqlx::PgPool>,
+    /// Cancelled when the queue shuts down and can't wait for the worker anymore
+    pub abort: CancellationToken,
     pub video_downloader: VideoDownloaderInUse,
     pub youtube_client: YoutubeClientInUse,
 }
@@ -34,36 +38,76 @@ impl LightWorker {
         let queue_client = self.cloud_service.queue_client();
         let video_id = payload_type.video_ids();
 
-        let result: Result<(), HandlerError> = self.handle_payload(payload_type, &message).await;
+        let payload = payload_type.name();
+        let start = Instant::now();
+        let result: Result<(), HandlerError> = tokio::select! {
+            result = self.handle_payload(payload_type, &message) => result,
+            _ = self.abort.cancelled() => {
+                tracing::warn!("Light Worker {} aborted, releasing message", self.id);
+                metrics::record_handler(payload, "aborted", start.elapsed());
+                release_message(queue_client, &message).await;
+                return;
+            }
+        };
+        record_result(payload, &result, start.elapsed());
 
         match result {
             Ok(_) => {}
             Err(e) => {
-                println!("Light Worker {} error: {:?}", self.id, e);
+                tracing::error!("Light Worker {} error: {:?}", self.id, e);
                 let dto = CreateErrorsDto {
-                    video_ids: video_id,
+                    video_ids: video_id.clone(),
                     error: &e.to_string(),
                 };
-                let error_count = match queries::video::create_errors(&self.pool, dto).await {
+                let error_count = match queries::video::create_errors(self.pool.as_ref(), dto).await
+                {
                     Ok(count) => count,
-                    Err(e) => {
-                        println!("Light Worker {} error: {:?}", self.id, e);
+                    Err(error) => {
+                        tracing::error!("Light Worker {} error: {:?}", self.id, error);
+                        let attempts = message.receive_count().unwrap_or(1);
+                        save_failed_message(
+                            &self.pool,
+                            &message.get_message(),
+                            &video_id,
+                            &e.to_string(),
+                            attempts,
+                        )
+                        .await;
                         self.delete_message(queue_client, message).await;
                         return;
                     }
                 };
                 match e {
                     HandlerError::Retrievable(_) => {
-                        if error_count >= ERROR_COUNT_THRESHOLD {
-                            println!(
+                        // queues that count deliveries also catch the attempts that crashed the worker
+                        let attempts = message.receive_count().unwrap_or(error_count);
+                        if attempts >= ERROR_COUNT_THRESHOLD {
+                            tracing::warn!(
                                 "Light Worker {} error count threshold reached, deleting message",
                                 self.id
                             );
+                            save_failed_message(
+                                &self.pool,
+                                &message.get_message(),
+                                &video_id,
+                                &e.to_string(),
+                                attempts,
+                            )
+                            .await;
                             self.delete_message(queue_client, message).await;
                         }
                         return;
                     }
                     HandlerError::Final(_) => {
+                        let attempts = message.receive_count().unwrap_or(error_count);
+                        save_failed_message(
+                            &self.pool,
+                            &message.get_message(),
+                            &video_id,
+                            &e.to_string(),
+                            attempts,
+                        )
+                        .await;
                         self.delete_message(queue_client, message).await;
                         return;
                     }
@@ -81,7 +125,7 @@ impl LightWorker {
     ) -> Result<(), HandlerError> {
         match payload_type {
             PayloadType::BatukaVideoRawUpload(payload) => {
-                println!("Light Worker {} handling raw upload...", self.id);
+                tracing::info!("Light Worker {} handling raw upload...", self.id);
                 let result: Result<(), HandlerError> = raw_upload::handle(
                     &self.cloud_service,
                     &self.transcriber_client,
@@ -95,7 +139,7 @@ impl LightWorker {
             }
 
             PayloadType::BatukaSrtTranscriptionUpload(payload) => {
-                println!("Light Worker {} handling transcription upload...", self.id);
+                tracing::info!("Light Worker {} handling transcription upload...", self.id);
                 let handler = transcription::Handler::new(
                     &self.transcriber_client,
                     &self.cloud_service,
@@ -107,12 +151,18 @@ impl LightWorker {
             }
 
             PayloadType::BatukaVideoProcessedUpload(payload) => {
-                println!("Light Worker {} handling processed upload...", self.id);
-                return processed_upload::handle(&self.pool, &self.youtube_client, payload).await;
+                tracing::info!("Light Worker {} handling processed upload...", self.id);
+                return processed_upload::handle(
+                    &self.pool,
+                    &self.youtube_client,
+                    self.cloud_service.bucket_client(),
+                    payload,
+                )
+                .await;
             }
 
             PayloadType::BatukaDownloadVideo(payload) => {
-                println!("Light Worker {} handling video download...", self.id);
+                tracing::info!("Light Worker {} handling video download...", self.id);
                 let download_result: Result<(), HandlerError> = download_video::handle(
                     payload,
                     &self.cloud_service,
@@ -126,7 +176,7 @@ impl LightWorker {
             }
 
             PayloadType::BatukaCutVideo(payload) => {
-                println!("Light Worker {} handling video cut...", self.id);
+                tracing::info!("Light Worker {} handling video cut...", self.id);
                 let cut_result: Result<(), HandlerError> =
                     cut_video::handle(payload, &self.cloud_service, &self.pool, message).await;
 
@@ -149,7 +199,7 @@ impl LightWorker {
         match result {
             Ok(_) => {}
             Err(e) => {
-                println!("Light Worker {} delete error: {:?}", self.id, e);
+                tracing::error!("Light Worker {} delete error: {:?}", self.id, e);
                 return;
             }
         }
@@ -158,21 +208,16 @@ impl LightWorker {
 
 #[async_trait::async_trait]
 impl Worker for LightWorker {
-    async fn handle(
-        self,
-        message: (Message, PayloadType),
-        inactive_worker_pool: Arc<Mutex<Vec<LightWorker>>>,
-    ) {
-        println!("Light Worker {} is now active", self.id);
+    async fn handle(&self, message: (Message, PayloadType)) {
         let (message, payload_type) = message;
-        self.handle_message(message, payload_type).await;
-
-        println!("Light Worker {} is now inactive", self.id);
-        let mut pool = inactive_worker_pool.lock().await;
-        println!(
-            "Light Worker {} is now putting itself back in the pool",
-            self.id
-        );
-        pool.push(self);
+        let span = message_span("light", self.id, &payload_type);
+        async {
+            tracing::info!("Light Worker {} is now active", self.id);
+            self.handle_message(message, payload_type).await;
+        }
+        .instrument(span)
+        .await;
+
+        tracing::info!("Light Worker {} is now inactive", self.id);
     }
 }
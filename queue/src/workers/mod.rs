@@ -1,18 +1,268 @@
-use std::sync::Arc;
+use std::{panic::AssertUnwindSafe, sync::Arc, time::Duration};
 
-use marco_polo_rs_core::internals::cloud::models::payload::PayloadType;
-use tokio::sync::Mutex;
+use futures::FutureExt;
+use marco_polo_rs_core::{
+    database::{
+        models::video::stage::VideoStage,
+        queries::{self, failed_message::CreateFailedMessageDto},
+    },
+    internals::cloud::{models::payload::PayloadType, traits::QueueClient},
+    util::metrics,
+};
+use sqlx::{types::Uuid, PgPool};
+use tokio::{
+    runtime::Runtime,
+    sync::{Mutex, OwnedSemaphorePermit, Semaphore},
+};
+use tracing::Span;
 
-use crate::Message;
+use crate::{config::WorkerClassConfig, error::HandlerError, notifications, Message};
+
+use self::{heavy::HeavyWorker, light::LightWorker};
 
 pub mod heavy;
 pub mod light;
 
 #[async_trait::async_trait]
 pub trait Worker: Sized + Send + Sync + 'static {
-    async fn handle(
-        self,
+    async fn handle(&self, message: (Message, PayloadType));
+}
+
+pub type WorkerPermits = (OwnedSemaphorePermit, OwnedSemaphorePermit);
+
+pub enum WorkerPool {
+    Light(Arc<Mutex<Vec<LightWorker>>>),
+    Heavy(Arc<Mutex<Vec<HeavyWorker>>>),
+}
+
+/// Workers of the same kind sharing the payloads they accept.
+/// A permit is held for every busy worker, so messages wait for a free one
+pub struct WorkerClass {
+    pub name: String,
+    pub payloads: Vec<String>,
+    permits: Arc<Semaphore>,
+    pool: WorkerPool,
+}
+
+impl WorkerClass {
+    pub fn new(config: &WorkerClassConfig, pool: WorkerPool) -> Self {
+        Self {
+            name: config.name.clone(),
+            payloads: config.payloads.clone(),
+            permits: Arc::new(Semaphore::new(config.concurrency)),
+            pool,
+        }
+    }
+
+    pub fn accepts(&self, payload_type: &PayloadType) -> bool {
+        let name = payload_type.name();
+        self.payloads.iter().any(|payload| payload == name)
+    }
+
+    /// Takes an idle worker of the class, none when every worker of the class is busy.
+    /// `capacity` is shared by every class and tells when all workers are busy
+    pub fn try_acquire(&self, capacity: Arc<Semaphore>) -> Option<WorkerPermits> {
+        let class_permit = self.permits.clone().try_acquire_owned().ok()?;
+        let capacity_permit = capacity.try_acquire_owned().ok()?;
+        Some((class_permit, capacity_permit))
+    }
+
+    /// Hands the message to the idle worker the permits were acquired for,
+    /// the message is given back when there is none
+    pub async fn dispatch(
+        &self,
+        runtime: &Runtime,
         message: (Message, PayloadType),
-        inactive_worker_pool: Arc<Mutex<Vec<Self>>>,
-    );
+        permits: WorkerPermits,
+    ) -> Result<(), (Message, PayloadType)> {
+        match &self.pool {
+            WorkerPool::Light(pool) => spawn_worker(runtime, message, pool.clone(), permits).await,
+            WorkerPool::Heavy(pool) => spawn_worker(runtime, message, pool.clone(), permits).await,
+        }
+    }
+}
+
+async fn spawn_worker<T: Worker>(
+    runtime: &Runtime,
+    message: (Message, PayloadType),
+    inactive_worker_pool: Arc<Mutex<Vec<T>>>,
+    permits: WorkerPermits,
+) -> Result<(), (Message, PayloadType)> {
+    let worker = match inactive_worker_pool.lock().await.pop() {
+        Some(worker) => worker,
+        None => return Err(message),
+    };
+
+    runtime.spawn(async move {
+        // a handler that panics must not take its worker out of the pool
+        let handled = AssertUnwindSafe(worker.handle(message))
+            .catch_unwind()
+            .await;
+        if handled.is_err() {
+            tracing::error!("A worker panicked, putting it back in the pool");
+        }
+
+        inactive_worker_pool.lock().await.push(worker);
+        // the worker is back in the pool, it can receive messages again
+        drop(permits);
+    });
+
+    return Ok(());
+}
+
+/// Span every log of the message is written in, so they can be found by video id
+fn message_span(worker: &'static str, worker_id: usize, payload_type: &PayloadType) -> Span {
+    let video_ids: Vec<String> = payload_type
+        .video_ids()
+        .iter()
+        .map(|video_id| video_id.to_string())
+        .collect();
+
+    tracing::info_span!(
+        "message",
+        worker,
+        worker_id,
+        payload = payload_type.name(),
+        video_ids = %video_ids.join(",")
+    )
+}
+
+/// Records how long the payload took and, when it failed, the kind of error
+fn record_result(payload: &'static str, result: &Result<(), HandlerError>, duration: Duration) {
+    match result {
+        Ok(_) => metrics::record_handler(payload, "ok", duration),
+        Err(e) => {
+            metrics::record_handler(payload, e.kind(), duration);
+            metrics::record_handler_error(payload, e.kind());
+        }
+    }
+}
+
+/// Makes the message visible again, so another worker can take it right away
+pub async fn release_message<QC: QueueClient>(queue_client: &QC, message: &QC::M) {
+    if let Err(e) = queue_client.change_message_visibility(message, 0).await {
+        tracing::error!("Failed to release message: {:?}", e);
+    }
+}
+
+/// Sends the payload again and deletes the delivered message, the copy waits for a free worker
+/// without counting as a delivery. Released instead when it can't be sent
+pub async fn requeue_message<QC: QueueClient>(
+    queue_client: &QC,
+    message: QC::M,
+    payload_type: PayloadType,
+) {
+    if let Err(e) = queue_client.send_message(payload_type).await {
+        tracing::error!("Failed to requeue message: {:?}", e);
+        release_message(queue_client, &message).await;
+        return;
+    }
+
+    if let Err(e) = queue_client.delete_message(message).await {
+        tracing::error!("Failed to delete requeued message: {:?}", e);
+    }
+}
+
+/// Keeps a message the queue is giving up on, so admins can replay it later
+pub async fn save_failed_message(
+    pool: &PgPool,
+    payload: &str,
+    video_ids: &[Uuid],
+    error: &str,
+    attempts: i64,
+) {
+    let stage = find_failed_stage(pool, video_ids).await;
+
+    let dto = CreateFailedMessageDto {
+        payload,
+        video_ids,
+        error,
+        stage: stage.clone(),
+        attempts: attempts as i32,
+    };
+
+    let id = match queries::failed_message::create(pool, dto).await {
+        Ok(id) => id,
+        Err(e) => {
+            tracing::error!("Failed to save failed message: {:?}", e);
+            return;
+        }
+    };
+
+    notify_failed_message(pool, id, video_ids, stage, error).await;
+}
+
+/// The stage the videos of a failed message were in, none when they are gone
+pub async fn find_failed_stage(pool: &PgPool, video_ids: &[Uuid]) -> Option<VideoStage> {
+    let video_id = video_ids.first()?;
+    return queries::video::find_by_id(pool, video_id)
+        .await
+        .ok()
+        .map(|video| video.stage);
+}
+
+/// Emails the owner of the videos, the failed message is kept when it can't be sent
+pub async fn notify_failed_message(
+    pool: &PgPool,
+    id: i32,
+    video_ids: &[Uuid],
+    stage: Option<VideoStage>,
+    error: &str,
+) {
+    if let Err(e) = notifications::notify_failed(pool, id, video_ids, stage, error).await {
+        tracing::error!("Failed to notify the failed videos: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use marco_polo_rs_core::internals::cloud::models::payload::{
+        PayloadType, VideoDownloadPayload,
+    };
+    use sqlx::types::Uuid;
+    use tokio::sync::{Mutex, Semaphore};
+
+    use crate::{
+        config::{WorkerClassConfig, WorkerKind},
+        test::mock::{MessageMock, QueueClientMock},
+    };
+
+    use super::{requeue_message, WorkerClass, WorkerPool};
+
+    #[test]
+    fn test_try_acquire_does_not_wait_for_a_busy_class() {
+        let config = WorkerClassConfig {
+            name: "subtitler".to_string(),
+            kind: WorkerKind::Heavy,
+            concurrency: 1,
+            payloads: vec!["BatukaSrtTranslationUpload".to_string()],
+        };
+        let class = WorkerClass::new(&config, WorkerPool::Heavy(Arc::new(Mutex::new(vec![]))));
+        let capacity = Arc::new(Semaphore::new(2));
+
+        let permits = class.try_acquire(capacity.clone());
+        assert!(permits.is_some());
+
+        // the class is busy, the capacity left is kept for the other classes
+        assert!(class.try_acquire(capacity.clone()).is_none());
+        assert_eq!(capacity.available_permits(), 1);
+
+        drop(permits);
+        assert!(class.try_acquire(capacity).is_some());
+    }
+
+    #[tokio::test]
+    async fn test_requeue_sends_a_copy() {
+        let queue_client = QueueClientMock::default();
+        let payload_type = PayloadType::BatukaDownloadVideo(VideoDownloadPayload {
+            original_video_id: 1,
+            video_ids: vec![Uuid::new_v4()],
+        });
+
+        requeue_message(&queue_client, MessageMock, payload_type).await;
+
+        assert_eq!(queue_client.sent(), 1);
+    }
 }